@@ -0,0 +1,2 @@
+-- NULL until the opt-in transcode check has looked at the file.
+ALTER TABLE songs ADD COLUMN suspected_transcode BOOLEAN;
@@ -0,0 +1,2 @@
+-- Set when a scan can't find the file but the missing-songs policy kept the row.
+ALTER TABLE songs ADD COLUMN unavailable BOOLEAN NOT NULL DEFAULT 0;
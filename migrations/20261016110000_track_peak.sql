@@ -0,0 +1,2 @@
+-- Linear sample peak from the ReplayGain tags, 1.0 is full scale. Untagged files get computed_peak.
+ALTER TABLE songs ADD COLUMN track_peak REAL;
@@ -0,0 +1,6 @@
+-- Last column sort picked in a song list, keyed by view (e.g. "songs", "album:<id>").
+CREATE TABLE IF NOT EXISTS view_sorts (
+    view_key TEXT PRIMARY KEY,
+    sort_column TEXT NOT NULL,
+    ascending BOOLEAN NOT NULL
+);
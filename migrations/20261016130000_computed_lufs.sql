@@ -0,0 +1,2 @@
+-- Integrated loudness measured while a song played through once. Cleared when the file changes.
+ALTER TABLE songs ADD COLUMN computed_lufs REAL;
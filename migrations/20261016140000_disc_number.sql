@@ -0,0 +1,2 @@
+-- Disc number from tags, so multi-disc albums play disc by disc.
+ALTER TABLE songs ADD COLUMN disc_number INTEGER;
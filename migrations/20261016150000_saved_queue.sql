@@ -0,0 +1,13 @@
+-- Queue left behind by the last session, restored on the next launch.
+CREATE TABLE IF NOT EXISTS saved_queue (
+    queue_index INTEGER PRIMARY KEY,
+    song_id TEXT NOT NULL,
+    -- Set for files played from outside the library, which have no songs row.
+    file_path TEXT
+);
+
+CREATE TABLE IF NOT EXISTS saved_queue_state (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    current_index INTEGER,
+    position_secs REAL NOT NULL DEFAULT 0
+);
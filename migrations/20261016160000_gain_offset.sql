@@ -0,0 +1,3 @@
+-- Manual gain in dB applied on top of normalization, for songs that still sound off. Kept across
+-- rescans.
+ALTER TABLE songs ADD COLUMN gain_offset_db REAL NOT NULL DEFAULT 0;
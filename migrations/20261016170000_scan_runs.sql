@@ -0,0 +1,10 @@
+-- Counts from each completed full scan, newest last. Trimmed to a short history on insert.
+CREATE TABLE IF NOT EXISTS scan_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    finished_at TEXT NOT NULL DEFAULT (DATETIME('now')),
+    scanned INTEGER NOT NULL,
+    added INTEGER NOT NULL,
+    updated INTEGER NOT NULL,
+    removed INTEGER NOT NULL,
+    missing INTEGER NOT NULL
+);
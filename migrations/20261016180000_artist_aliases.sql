@@ -0,0 +1,8 @@
+-- Names of artists merged into another one, so rescanning files still tagged with the old name
+-- adds their songs to the artist they were merged into instead of splitting it again.
+CREATE TABLE IF NOT EXISTS artist_aliases (
+    name TEXT PRIMARY KEY,
+    artist_id TEXT NOT NULL,
+    FOREIGN KEY (artist_id) REFERENCES artists(id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS idx_artist_aliases_artist ON artist_aliases(artist_id);
@@ -0,0 +1,2 @@
+-- Set by the scanner when a file had no title tag and its name was used instead.
+ALTER TABLE songs ADD COLUMN metadata_from_filename BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+-- The track count the file's tags declare for its disc, NULL when untagged.
+ALTER TABLE songs ADD COLUMN track_total INTEGER;
@@ -0,0 +1,8 @@
+-- Tracks from a cue sheet share their album's file. `cue_track` tells them apart (0 for a
+-- whole file) and the seconds mark the stretch each one plays; no end means the file's end.
+ALTER TABLE songs ADD COLUMN cue_track INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE songs ADD COLUMN start_secs REAL NOT NULL DEFAULT 0;
+ALTER TABLE songs ADD COLUMN end_secs REAL;
+
+DROP INDEX IF EXISTS idx_songs_file_path_unique;
+CREATE UNIQUE INDEX idx_songs_file_path_unique ON songs(file_path, cue_track);
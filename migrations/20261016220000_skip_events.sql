@@ -0,0 +1,19 @@
+-- Adds SKIP to the allowed event types. SQLite can't alter a CHECK constraint, so the table is
+-- rebuilt; nothing references events, so the copy keeps every row and id as they were.
+CREATE TABLE events_new (
+    id TEXT PRIMARY KEY,
+    event_type TEXT CHECK(
+        event_type IN ('PLAY', 'STOP', 'PAUSE', 'RESUME', 'SKIP')
+    ) NOT NULL,
+    context_id TEXT,
+    timestamp TEXT DEFAULT (DATETIME('now')),
+    FOREIGN KEY (context_id) REFERENCES event_contexts(id) ON DELETE CASCADE
+);
+INSERT INTO events_new (rowid, id, event_type, context_id, timestamp)
+    SELECT rowid, id, event_type, context_id, timestamp FROM events;
+DROP TABLE events;
+ALTER TABLE events_new RENAME TO events;
+
+CREATE INDEX IF NOT EXISTS idx_events_type ON events(event_type);
+CREATE INDEX IF NOT EXISTS idx_events_timestamp ON events(timestamp);
+CREATE INDEX IF NOT EXISTS idx_events_context ON events(context_id);
@@ -0,0 +1,2 @@
+-- Linear sample peak measured along with computed_lufs, 1.0 is full scale. Cleared when the file changes.
+ALTER TABLE songs ADD COLUMN computed_peak REAL;
@@ -42,6 +42,9 @@ pub struct EqualizerSettings {
     pub gains: Vec<f32>,
     #[serde(default)]
     pub q_values: Vec<f32>,
+    /// Gain applied ahead of the bands, so boosts can be made room for without clipping.
+    #[serde(default)]
+    pub preamp_db: f32,
 }
 
 impl Default for EqualizerSettings {
@@ -51,6 +54,7 @@ impl Default for EqualizerSettings {
             frequencies: vec![32, 64, 125, 250, 500, 1000, 2000, 4000, 8000, 16000],
             gains: vec![0.0; 10],
             q_values: vec![1.461; 10],
+            preamp_db: 0.0,
         }
     }
 }
@@ -59,6 +63,24 @@ impl Default for EqualizerSettings {
 pub struct ScanSettings {
     #[serde(default)]
     pub paths: Vec<String>,
+    #[serde(default = "defaults::watch_debounce_ms")]
+    pub watch_debounce_ms: u64,
+    #[serde(default = "defaults::watch_max_wait_ms")]
+    pub watch_max_wait_ms: u64,
+    #[serde(default)]
+    pub detect_transcodes: bool,
+    #[serde(default)]
+    pub remove_missing: MissingSongsPolicy,
+    /// Also index dotfiles and files inside hidden folders, like `._` AppleDouble forks.
+    #[serde(default)]
+    pub include_hidden: bool,
+    /// Tried in order on untagged files, matched against the file name and the folders above it.
+    #[serde(default = "defaults::filename_patterns")]
+    pub filename_patterns: Vec<String>,
+    /// How many files a scan reads at once. `None` uses one per CPU core; spinning disks
+    /// usually want 1 or 2.
+    #[serde(default)]
+    pub concurrency: Option<usize>,
 }
 
 impl Default for ScanSettings {
@@ -68,23 +90,150 @@ impl Default for ScanSettings {
                 .map(|p| p.to_string_lossy().to_string())
                 .into_iter()
                 .collect(),
+            watch_debounce_ms: defaults::watch_debounce_ms(),
+            watch_max_wait_ms: defaults::watch_max_wait_ms(),
+            detect_transcodes: false,
+            remove_missing: MissingSongsPolicy::default(),
+            include_hidden: false,
+            filename_patterns: defaults::filename_patterns(),
+            concurrency: None,
+        }
+    }
+}
+
+/// Which songs a scan deletes when their file is gone. Kept songs are marked unavailable.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingSongsPolicy {
+    #[default]
+    Never,
+    OnlyInScanPaths,
+    Always,
+}
+
+/// What happens to playback while another app is playing audio.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioFocusBehavior {
+    #[default]
+    Ignore,
+    Duck,
+    Pause,
+}
+
+/// Which loudness normalization trusts when a song has both ReplayGain tags and a measurement.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoudnessSource {
+    #[default]
+    TagsFirst,
+    MeasuredFirst,
+}
+
+/// How tracks are converted when their sample rate differs from the output's.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResamplerQuality {
+    Fast,
+    #[default]
+    Balanced,
+    High,
+}
+
+/// How long play history is kept before it's purged.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryRetention {
+    ThreeMonths,
+    OneYear,
+    #[default]
+    Forever,
+}
+
+impl HistoryRetention {
+    pub fn days(self) -> Option<u32> {
+        match self {
+            HistoryRetention::ThreeMonths => Some(90),
+            HistoryRetention::OneYear => Some(365),
+            HistoryRetention::Forever => None,
         }
     }
 }
 
+/// Play state after launch when the last session's queue is restored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResumeOnLaunch {
+    Playing,
+    #[default]
+    Paused,
+    Stopped,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioSettings {
     #[serde(default = "defaults::visualizer")]
     pub visualizer: bool,
+    /// Shows a spectrum analyzer next to the now-playing song. Needs `visualizer` on.
+    #[serde(default)]
+    pub spectrum_analyzer: bool,
     #[serde(default = "defaults::volume")]
     pub volume: f32,
+    #[serde(default)]
+    pub normalization_preamp_db: f32,
+    #[serde(default = "defaults::limiter")]
+    pub limiter: bool,
+    #[serde(default = "defaults::pause_on_sleep")]
+    pub pause_on_sleep: bool,
+    #[serde(default)]
+    pub focus_behavior: AudioFocusBehavior,
+    #[serde(default)]
+    pub loudness_source: LoudnessSource,
+    #[serde(default)]
+    pub resampler_quality: ResamplerQuality,
+    #[serde(default)]
+    pub output_sample_rate: Option<u32>,
+    #[serde(default)]
+    pub autoplay: bool,
+    #[serde(default)]
+    pub resume_on_launch: ResumeOnLaunch,
+    #[serde(default = "defaults::clipping_warning")]
+    pub clipping_warning: bool,
+    /// Songs shorter than this many seconds are left out when shuffle picks the next song.
+    #[serde(default)]
+    pub shuffle_min_duration_secs: Option<u32>,
+    /// Length of the fade out and back in around a seek, 0 to cut straight over.
+    #[serde(default = "defaults::seek_fade_ms")]
+    pub seek_fade_ms: u32,
+    /// Queues the next song on the output before the current one ends, so they play back to
+    /// back without a gap.
+    #[serde(default = "defaults::gapless")]
+    pub gapless: bool,
+    /// Closes the output device after this many seconds paused, `None` to keep it open.
+    #[serde(default)]
+    pub release_output_after_secs: Option<u32>,
 }
 
 impl Default for AudioSettings {
     fn default() -> Self {
         Self {
             visualizer: true,
+            spectrum_analyzer: false,
             volume: 0.5,
+            normalization_preamp_db: 0.0,
+            limiter: true,
+            pause_on_sleep: true,
+            focus_behavior: AudioFocusBehavior::default(),
+            loudness_source: LoudnessSource::default(),
+            resampler_quality: ResamplerQuality::default(),
+            output_sample_rate: None,
+            autoplay: false,
+            resume_on_launch: ResumeOnLaunch::default(),
+            clipping_warning: true,
+            shuffle_min_duration_secs: None,
+            seek_fade_ms: defaults::seek_fade_ms(),
+            gapless: defaults::gapless(),
+            release_output_after_secs: None,
         }
     }
 }
@@ -101,6 +250,192 @@ impl Default for UpdaterSettings {
     }
 }
 
+/// What a click on a song row (or grid card / search result) does.
+///
+/// Defaults to `Cover`: only the cover or track number starts playback and a click on the
+/// row itself selects it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClickBehavior {
+    #[default]
+    Cover,
+    SingleClick,
+    DoubleClick,
+}
+
+impl ClickBehavior {
+    pub fn plays_on(self, click_count: usize) -> bool {
+        match self {
+            ClickBehavior::Cover => false,
+            ClickBehavior::SingleClick => click_count == 1,
+            ClickBehavior::DoubleClick => click_count == 2,
+        }
+    }
+}
+
+/// What playing the song that is already current does, instead of rebuilding the queue from it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayingSongClick {
+    #[default]
+    Restart,
+    Nothing,
+    TogglePause,
+}
+
+/// What a click on a scrollbar track outside the thumb does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollbarClick {
+    #[default]
+    Page,
+    Jump,
+}
+
+/// Whether scrollbars fade out when idle. `System` follows the OS preference where there is one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScrollbarVisibility {
+    #[default]
+    System,
+    AutoHide,
+    Always,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+/// Cover used for songs and albums that have no artwork of their own.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackArtwork {
+    #[default]
+    None,
+    Bundled,
+    Custom(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterfaceSettings {
+    #[serde(default)]
+    pub click_behavior: ClickBehavior,
+    #[serde(default)]
+    pub playing_song_click: PlayingSongClick,
+    #[serde(default)]
+    pub group_recently_added: bool,
+    #[serde(default)]
+    pub row_density: RowDensity,
+    #[serde(default = "defaults::recently_added_limit")]
+    pub recently_added_limit: i64,
+    #[serde(default = "defaults::index_rail")]
+    pub index_rail: bool,
+    #[serde(default = "defaults::descending_sort_columns")]
+    pub descending_sort_columns: Vec<String>,
+    #[serde(default)]
+    pub scrollbar_click: ScrollbarClick,
+    #[serde(default)]
+    pub scrollbar_visibility: ScrollbarVisibility,
+    #[serde(default)]
+    pub momentum_scroll: bool,
+    #[serde(default = "defaults::font_family")]
+    pub font_family: String,
+    #[serde(default = "defaults::font_size")]
+    pub font_size: f32,
+    #[serde(default)]
+    pub fallback_artwork: FallbackArtwork,
+    /// Pin playlists to the sidebar as soon as they are created.
+    #[serde(default)]
+    pub pin_new_playlists: bool,
+    /// Sort artists and albums without a leading "The ", "A " or "An ".
+    #[serde(default = "defaults::ignore_articles")]
+    pub ignore_articles: bool,
+}
+
+impl Default for InterfaceSettings {
+    fn default() -> Self {
+        Self {
+            click_behavior: ClickBehavior::default(),
+            playing_song_click: PlayingSongClick::default(),
+            group_recently_added: false,
+            row_density: RowDensity::default(),
+            recently_added_limit: defaults::recently_added_limit(),
+            index_rail: defaults::index_rail(),
+            descending_sort_columns: defaults::descending_sort_columns(),
+            scrollbar_click: ScrollbarClick::default(),
+            scrollbar_visibility: ScrollbarVisibility::default(),
+            momentum_scroll: false,
+            font_family: defaults::font_family(),
+            font_size: defaults::font_size(),
+            fallback_artwork: FallbackArtwork::default(),
+            pin_new_playlists: false,
+            ignore_articles: defaults::ignore_articles(),
+        }
+    }
+}
+
+pub const MIN_FONT_SIZE: f32 = 10.0;
+pub const MAX_FONT_SIZE: f32 = 24.0;
+
+impl InterfaceSettings {
+    /// Base UI text size, kept within a range the fixed-height panes can hold.
+    pub fn ui_font_size(&self) -> f32 {
+        self.font_size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    #[default]
+    InPlace,
+    Copy,
+    Move,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrganizeSettings {
+    #[serde(default)]
+    pub import_mode: ImportMode,
+    #[serde(default)]
+    pub library_root: String,
+    #[serde(default = "defaults::organize_pattern")]
+    pub pattern: String,
+}
+
+impl Default for OrganizeSettings {
+    fn default() -> Self {
+        Self {
+            import_mode: ImportMode::default(),
+            library_root: String::new(),
+            pattern: defaults::organize_pattern(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Where the main window was last, restored on the next launch.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowSettings {
+    #[serde(default)]
+    pub bounds: Option<WindowRect>,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub display: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettingsConfig {
     #[serde(default = "defaults::version")]
@@ -110,6 +445,11 @@ pub struct SettingsConfig {
     #[serde(default)]
     pub discord_rpc: bool,
     #[serde(default)]
+    pub history_retention: HistoryRetention,
+    /// Hours of listening to aim for each calendar year, shown with progress in the stats.
+    #[serde(default)]
+    pub yearly_listening_goal_hours: Option<u32>,
+    #[serde(default)]
     pub equalizer: EqualizerSettings,
     #[serde(default)]
     pub scan: ScanSettings,
@@ -117,6 +457,12 @@ pub struct SettingsConfig {
     pub audio: AudioSettings,
     #[serde(default)]
     pub updater: UpdaterSettings,
+    #[serde(default)]
+    pub interface: InterfaceSettings,
+    #[serde(default)]
+    pub organize: OrganizeSettings,
+    #[serde(default)]
+    pub window: WindowSettings,
 }
 
 mod defaults {
@@ -124,6 +470,34 @@ mod defaults {
     pub fn visualizer() -> bool { true }
     pub fn volume() -> f32 { 0.5 }
     pub fn auto_check() -> bool { true }
+    pub fn limiter() -> bool { true }
+    pub fn pause_on_sleep() -> bool { true }
+    pub fn clipping_warning() -> bool { true }
+    pub fn seek_fade_ms() -> u32 { 5 }
+    pub fn gapless() -> bool { true }
+    pub fn ignore_articles() -> bool { true }
+    pub fn watch_debounce_ms() -> u64 { 2000 }
+    pub fn watch_max_wait_ms() -> u64 { 30_000 }
+    pub fn recently_added_limit() -> i64 { 100 }
+    pub fn index_rail() -> bool { true }
+    pub fn descending_sort_columns() -> Vec<String> {
+        vec!["duration".to_string(), "last_played".to_string()]
+    }
+    pub fn organize_pattern() -> String { "{artist}/{album}/{track} {title}".to_string() }
+    pub fn filename_patterns() -> Vec<String> {
+        [
+            "{artist}/{album}/{track}. {title}",
+            "{artist}/{album}/{track} - {title}",
+            "{track} - {title}",
+            "{track}. {title}",
+            "{artist} - {title}",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect()
+    }
+    pub fn font_family() -> String { "Feature Mono".to_string() }
+    pub fn font_size() -> f32 { 14.0 }
 }
 
 impl Default for SettingsConfig {
@@ -132,10 +506,14 @@ impl Default for SettingsConfig {
             version: defaults::version(),
             telemetry: false,
             discord_rpc: false,
+            history_retention: HistoryRetention::default(),
             equalizer: EqualizerSettings::default(),
             scan: ScanSettings::default(),
             audio: AudioSettings::default(),
             updater: UpdaterSettings::default(),
+            interface: InterfaceSettings::default(),
+            organize: OrganizeSettings::default(),
+            window: WindowSettings::default(),
         }
     }
 }
@@ -145,6 +523,7 @@ pub struct Config {
     config: SettingsConfig,
     config_path: PathBuf,
     pub parse_warning: Option<String>,
+    read_only: bool,
 }
 
 impl Global for Config {}
@@ -194,6 +573,7 @@ impl Config {
             config,
             config_path,
             parse_warning,
+            read_only: false,
         };
 
         if needs_save {
@@ -245,6 +625,10 @@ impl Config {
             warn!("Config has parse errors, skipping write to preserve file");
             return;
         }
+        if self.read_only {
+            warn!("Read-only mode, ignoring settings change");
+            return;
+        }
         f(&mut self.config);
         self.config.audio.volume = self.config.audio.volume.clamp(0.0, 1.0);
         let scan_paths: Vec<String> = self
@@ -285,6 +669,14 @@ impl Config {
         Ok(())
     }
 
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
     pub fn get(&self) -> &SettingsConfig {
         &self.config
     }
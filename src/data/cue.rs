@@ -0,0 +1,194 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const FRAMES_PER_SECOND: f32 = 75.0;
+
+/// A cue sheet, which splits one or more audio files into the tracks of an album.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CueSheet {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub files: Vec<CueFile>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueFile {
+    pub name: String,
+    pub tracks: Vec<CueTrack>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CueTrack {
+    pub number: u32,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub start_secs: f32,
+    /// Where the next track in the same file starts, `None` for the last one.
+    pub end_secs: Option<f32>,
+}
+
+impl CueSheet {
+    /// The tracks the sheet lays out in `audio`, matched by file name. A sheet with a single
+    /// file also matches when the names differ, since it was found by the audio's own name and
+    /// the file was likely converted after the sheet was written.
+    pub fn tracks_for(&self, audio: &Path) -> Option<&[CueTrack]> {
+        let file_name = audio.file_name()?.to_str()?;
+        let stem = audio.file_stem()?.to_str()?;
+        let matches = |name: &str| {
+            let name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+            let name_stem = Path::new(name).file_stem().and_then(|s| s.to_str());
+            name.eq_ignore_ascii_case(file_name)
+                || name_stem.is_some_and(|name_stem| name_stem.eq_ignore_ascii_case(stem))
+        };
+
+        let file = match self.files.iter().find(|file| matches(&file.name)) {
+            Some(file) => file,
+            None if self.files.len() == 1 => &self.files[0],
+            None => return None,
+        };
+        Some(file.tracks.as_slice()).filter(|tracks| !tracks.is_empty())
+    }
+}
+
+// Where a sheet for `audio` would be, named either `album.cue` or `album.flac.cue`.
+fn sheet_paths(audio: &Path) -> [PathBuf; 2] {
+    let mut with_suffix = audio.as_os_str().to_owned();
+    with_suffix.push(".cue");
+    [audio.with_extension("cue"), PathBuf::from(with_suffix)]
+}
+
+/// Finds the sheet next to `audio`, named either `album.cue` or `album.flac.cue`.
+pub fn find_for(audio: &Path) -> Option<CueSheet> {
+    sheet_paths(audio)
+        .iter()
+        .filter_map(|candidate| std::fs::read(candidate).ok())
+        .map(|bytes| parse(&String::from_utf8_lossy(&bytes)))
+        .find(|sheet| sheet.tracks_for(audio).is_some())
+}
+
+/// When a sheet next to `audio` last changed, `None` when there is none.
+pub fn sheet_modified(audio: &Path) -> Option<SystemTime> {
+    sheet_paths(audio)
+        .iter()
+        .filter_map(|candidate| std::fs::metadata(candidate).ok()?.modified().ok())
+        .max()
+}
+
+pub fn is_sheet(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cue"))
+}
+
+/// The files a sheet could split: the ones it names and the ones sharing its name. The names
+/// still count when the sheet can't be read, since it may just have been deleted.
+pub fn audio_for(sheet: &Path) -> Vec<PathBuf> {
+    let (Some(dir), Some(stem)) = (sheet.parent(), sheet.file_stem()) else {
+        return Vec::new();
+    };
+    // `album.flac.cue` names its file outright.
+    let mut files = vec![dir.join(stem)];
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        files.extend(
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.file_stem() == Some(stem) && path != sheet),
+        );
+    }
+    if let Ok(bytes) = std::fs::read(sheet) {
+        let named = parse(&String::from_utf8_lossy(&bytes)).files;
+        files.extend(named.iter().map(|file| {
+            let name = file.name.rsplit(['/', '\\']).next().unwrap_or(&file.name);
+            dir.join(name)
+        }));
+    }
+    files.sort();
+    files.dedup();
+    files
+}
+
+pub fn parse(text: &str) -> CueSheet {
+    let mut sheet = CueSheet::default();
+    let mut in_track = false;
+
+    for line in text.trim_start_matches('\u{feff}').lines() {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let track = sheet
+            .files
+            .last_mut()
+            .and_then(|file| file.tracks.last_mut())
+            .filter(|_| in_track);
+
+        match command.to_ascii_uppercase().as_str() {
+            "TITLE" => match track {
+                Some(track) => track.title = Some(unquote(rest)),
+                None => sheet.title = Some(unquote(rest)),
+            },
+            "PERFORMER" => match track {
+                Some(track) => track.performer = Some(unquote(rest)),
+                None => sheet.performer = Some(unquote(rest)),
+            },
+            "FILE" => {
+                // The file type follows the name, which is only quoted when it has spaces.
+                let name = match rest.strip_prefix('"') {
+                    Some(quoted) => quoted.split('"').next().unwrap_or(quoted),
+                    None => rest.split_whitespace().next().unwrap_or(rest),
+                };
+                sheet.files.push(CueFile {
+                    name: name.to_string(),
+                    tracks: Vec::new(),
+                });
+                in_track = false;
+            }
+            "TRACK" => {
+                let number = rest.split_whitespace().next().and_then(|n| n.parse().ok());
+                if let (Some(number), Some(file)) = (number, sheet.files.last_mut()) {
+                    // Stays NaN until the track's INDEX 01 says where it starts.
+                    file.tracks.push(CueTrack {
+                        number,
+                        title: None,
+                        performer: None,
+                        start_secs: f32::NAN,
+                        end_secs: None,
+                    });
+                    in_track = true;
+                }
+            }
+            "INDEX" => {
+                let mut parts = rest.split_whitespace();
+                if parts.next().and_then(|n| n.parse::<u32>().ok()) == Some(1)
+                    && let (Some(track), Some(start)) = (track, parts.next().and_then(timestamp))
+                {
+                    track.start_secs = start;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for file in &mut sheet.files {
+        file.tracks.retain(|track| !track.start_secs.is_nan());
+        let starts: Vec<f32> = file.tracks.iter().map(|track| track.start_secs).collect();
+        for (track, next_start) in file.tracks.iter_mut().zip(starts.into_iter().skip(1)) {
+            track.end_secs = Some(next_start);
+        }
+    }
+    sheet
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches('"').to_string()
+}
+
+// Cue times are `mm:ss:ff`, counting frames at 75 per second.
+fn timestamp(value: &str) -> Option<f32> {
+    let mut parts = value.split(':').map(|part| part.parse::<u32>().ok());
+    let (Some(Some(minutes)), Some(Some(seconds)), Some(Some(frames)), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Some((minutes * 60 + seconds) as f32 + frames as f32 / FRAMES_PER_SECOND)
+}
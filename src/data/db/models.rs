@@ -34,11 +34,19 @@ pub struct SongRow {
     pub duration: i32,
     pub image_id: Option<String>,
     pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
     pub favorite: bool,
     pub lufs: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub computed_lufs: Option<f32>,
+    pub computed_peak: Option<f32>,
+    pub gain_offset_db: f32,
     pub pinned: bool,
     pub date_added: String,
     pub date_updated: String,
+    pub start_secs: f32,
+    pub end_secs: Option<f32>,
+    pub unavailable: bool,
 }
 
 fn split_concat(s: Option<String>) -> Vec<String> {
@@ -66,11 +74,19 @@ impl SongRow {
             duration: row.get("duration")?,
             image_id: row.get("image_id")?,
             track_number: row.get("track_number")?,
+            disc_number: row.get("disc_number")?,
             favorite: row.get("favorite")?,
             lufs: row.get("lufs")?,
+            track_peak: row.get("track_peak")?,
+            computed_lufs: row.get("computed_lufs")?,
+            computed_peak: row.get("computed_peak")?,
+            gain_offset_db: row.get("gain_offset_db")?,
             pinned: row.get("pinned")?,
             date_added: row.get("date_added")?,
             date_updated: row.get("date_updated")?,
+            start_secs: row.get("start_secs")?,
+            end_secs: row.get("end_secs")?,
+            unavailable: row.get("unavailable")?,
         })
     }
 }
@@ -85,6 +101,8 @@ pub struct SongListRow {
     pub duration: i32,
     pub image_id: Option<String>,
     pub genres: Option<String>,
+    pub last_played: Option<String>,
+    pub unavailable: bool,
 }
 
 impl SongListRow {
@@ -98,6 +116,8 @@ impl SongListRow {
             duration: row.get("duration")?,
             image_id: row.get("image_id")?,
             genres: row.get("genres")?,
+            last_played: row.get("last_played")?,
+            unavailable: row.get("unavailable")?,
         })
     }
 }
@@ -128,6 +148,8 @@ pub struct ArtistListRow {
     pub id: Cuid,
     pub name: String,
     pub image_id: Option<String>,
+    pub album_count: i64,
+    pub song_count: i64,
 }
 
 impl ArtistListRow {
@@ -136,6 +158,8 @@ impl ArtistListRow {
             id: row.get("id")?,
             name: row.get("name")?,
             image_id: row.get("image_id")?,
+            album_count: row.get("album_count")?,
+            song_count: row.get("song_count")?,
         })
     }
 }
@@ -169,6 +193,7 @@ pub struct PlaylistListRow {
     pub name: String,
     pub image_id: Option<String>,
     pub song_count: i64,
+    pub duration_secs: i64,
 }
 
 impl PlaylistListRow {
@@ -178,6 +203,7 @@ impl PlaylistListRow {
             name: row.get("name")?,
             image_id: row.get("image_id")?,
             song_count: row.get("song_count")?,
+            duration_secs: row.get("duration_secs")?,
         })
     }
 }
@@ -277,6 +303,7 @@ impl EventRow {
                 "STOP" => EventType::Stop,
                 "PAUSE" => EventType::Pause,
                 "RESUME" => EventType::Resume,
+                "SKIP" => EventType::Skip,
                 other => {
                     tracing::error!("Unknown event type in DB: {}; defaulting to PLAY", other);
                     EventType::Play
@@ -356,6 +383,7 @@ pub struct RecentItemRow {
     pub album_id: Option<Cuid>,
     pub album_title: Option<String>,
     pub artist_name: Option<String>,
+    pub most_recent_date: Option<String>,
 }
 
 impl RecentItemRow {
@@ -369,6 +397,7 @@ impl RecentItemRow {
             album_id: row.get("album_id")?,
             album_title: row.get("album_title")?,
             artist_name: row.get("artist_name")?,
+            most_recent_date: row.get("most_recent_date")?,
         })
     }
 
@@ -1,9 +1,10 @@
 use crate::data::{
     db::models::*,
     models::{
-        Album, AlbumListItem, Artist, ArtistListItem, Cuid, Event, EventContext, EventType, Image,
-        PinnedItem, Playlist, PlaylistListItem, PlaylistTrack, RecentItem, Song, SongListItem,
-        SongSort,
+        Album, AlbumListItem, AlbumSort, Artist, ArtistListItem, Cuid, Event, EventContext,
+        EventType, Image, LibraryTotals, PinnedItem, Playlist, PlaylistListItem, PlaylistTrack,
+        RecentItem, SavedQueue, SavedQueueItem, ScanRun, SkippedSong, Song, SongListItem, SongSort,
+        SongUpsert, artist_match_key,
     },
 };
 use anyhow::Result;
@@ -12,8 +13,13 @@ use parking_lot::Mutex;
 use rusqlite::{Connection, OptionalExtension, ToSql, params};
 use rusqlite_migration::Migrations;
 use rust_embed::RustEmbed;
+use std::collections::{BTreeMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::warn;
+
+const SCAN_RUNS_KEPT: i64 = 50;
 
 #[derive(RustEmbed)]
 #[folder = "./migrations"]
@@ -50,6 +56,92 @@ fn run_migrations(conn: &mut rusqlite::Connection) -> Result<()> {
     Ok(())
 }
 
+/// Ranked matches for every item type, shared by the library search queries.
+/// Expects `?1` = raw query, `?2` = FTS query and `?3` = per-type candidate limit.
+const SEARCH_MATCHES_SQL: &str = r#"
+    WITH
+    search_params AS (SELECT ?1 AS query_text),
+    song_matches AS (
+        SELECT DISTINCT
+            s.id, s.title AS name, s.image_id AS image, 'Song' AS item_type,
+            CASE
+                WHEN s.title = sp.query_text COLLATE NOCASE THEN 400
+                WHEN s.title LIKE sp.query_text || '%' COLLATE NOCASE THEN 300
+                WHEN EXISTS (SELECT 1 FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id WHERE sa.song_id = s.id AND ar.name LIKE sp.query_text || '%' COLLATE NOCASE) THEN 220
+                WHEN EXISTS (SELECT 1 FROM albums al WHERE al.id = s.album_id AND al.title LIKE sp.query_text || '%' COLLATE NOCASE) THEN 200
+                ELSE 100
+            END AS score
+        FROM songs_fts
+        JOIN songs s ON s.id = songs_fts.song_id
+        CROSS JOIN search_params sp
+        WHERE songs_fts MATCH ?2
+        ORDER BY score DESC, s.title COLLATE NOCASE ASC
+        LIMIT ?3
+    ),
+    path_matches AS (
+        SELECT
+            s.id, s.title AS name, s.image_id AS image, 'Song' AS item_type, 50 AS score
+        FROM songs s
+        CROSS JOIN search_params sp
+        WHERE s.file_path LIKE '%' || sp.query_text || '%' COLLATE NOCASE
+          AND s.id NOT IN (SELECT id FROM song_matches)
+        ORDER BY s.title COLLATE NOCASE ASC
+        LIMIT ?3
+    ),
+    album_matches AS (
+        SELECT
+            al.id, al.title AS name, al.image_id AS image, 'Album' AS item_type,
+            CASE
+                WHEN al.title = sp.query_text COLLATE NOCASE THEN 350
+                WHEN al.title LIKE sp.query_text || '%' COLLATE NOCASE THEN 260
+                WHEN EXISTS (SELECT 1 FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id AND ar.name LIKE sp.query_text || '%' COLLATE NOCASE) THEN 180
+                ELSE 90
+            END AS score
+        FROM albums al
+        CROSS JOIN search_params sp
+        WHERE al.title LIKE '%' || sp.query_text || '%' COLLATE NOCASE
+           OR EXISTS (SELECT 1 FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id AND ar.name LIKE '%' || sp.query_text || '%' COLLATE NOCASE)
+        ORDER BY score DESC, al.title COLLATE NOCASE ASC
+        LIMIT ?3
+    ),
+    artist_matches AS (
+        SELECT
+            ar.id, ar.name AS name, ar.image_id AS image, 'Artist' AS item_type,
+            CASE
+                WHEN ar.name = sp.query_text COLLATE NOCASE THEN 320
+                WHEN ar.name LIKE sp.query_text || '%' COLLATE NOCASE THEN 250
+                ELSE 80
+            END AS score
+        FROM artists ar
+        CROSS JOIN search_params sp
+        WHERE ar.name LIKE '%' || sp.query_text || '%' COLLATE NOCASE
+        ORDER BY score DESC, ar.name COLLATE NOCASE ASC
+        LIMIT ?3
+    ),
+    playlist_matches AS (
+        SELECT
+            p.id, p.name AS name, p.image_id AS image, 'Playlist' AS item_type,
+            CASE
+                WHEN p.name = sp.query_text COLLATE NOCASE THEN 300
+                WHEN p.name LIKE sp.query_text || '%' COLLATE NOCASE THEN 240
+                ELSE 70
+            END AS score
+        FROM playlists p
+        CROSS JOIN search_params sp
+        WHERE p.name LIKE '%' || sp.query_text || '%' COLLATE NOCASE
+        ORDER BY score DESC, p.name COLLATE NOCASE ASC
+        LIMIT ?3
+    ),
+    all_matches AS (
+        SELECT * FROM song_matches
+        UNION ALL SELECT * FROM path_matches
+        UNION ALL SELECT * FROM album_matches
+        UNION ALL SELECT * FROM artist_matches
+        UNION ALL SELECT * FROM playlist_matches
+    )"#;
+
+// Contention with the scanner is absorbed by busy_timeout. Retrying on top of it would keep the
+// connection mutex held for several timeouts in a row, freezing any UI read waiting on it.
 fn collect_mapped<T, U, F>(
     conn: &rusqlite::Connection,
     sql: &str,
@@ -71,6 +163,8 @@ where
 pub struct Database {
     conn: Arc<Mutex<Connection>>,
     pub image_conn: Arc<Mutex<Connection>>,
+    read_only: Arc<AtomicBool>,
+    ignore_articles: Arc<AtomicBool>,
 }
 
 impl Global for Database {}
@@ -86,9 +180,39 @@ impl Database {
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
             image_conn: Arc::new(Mutex::new(image_conn)),
+            read_only: Arc::new(AtomicBool::new(false)),
+            ignore_articles: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// Read-only mode turns user edits (favorites, pins, playlists, removal, sorts) into no-ops
+    /// that still return `Ok`. Scanning and play history keep writing.
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.store(read_only, Ordering::Relaxed);
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
+    }
+
+    /// Sort artists and albums as if a leading "The ", "A " or "An " weren't there.
+    pub fn set_ignore_articles(&self, ignore_articles: bool) {
+        self.ignore_articles
+            .store(ignore_articles, Ordering::Relaxed);
+    }
+
+    fn ignore_articles(&self) -> bool {
+        self.ignore_articles.load(Ordering::Relaxed)
+    }
+
+    fn skip_write(&self, what: &str) -> bool {
+        let read_only = self.read_only();
+        if read_only {
+            warn!("Read-only mode, ignoring {}", what);
+        }
+        read_only
+    }
+
     pub fn get_song(&self, id: &Cuid) -> Result<Option<Song>> {
         let conn = self.conn.lock();
         let mut stmt = conn.prepare_cached(
@@ -122,37 +246,47 @@ impl Database {
         collect_mapped::<SongRow, Song, _>(&conn, &sql, params.as_slice(), SongRow::from_row)
     }
 
+    /// The song stored for a file, or its first track when a cue sheet splits it.
     pub fn get_song_by_path(&self, file_path: &str) -> Result<Option<Song>> {
+        Ok(self.get_songs_by_path(file_path)?.into_iter().next())
+    }
+
+    /// Every song stored for a file in track order, several when a cue sheet splits it.
+    pub fn get_songs_by_path(&self, file_path: &str) -> Result<Vec<Song>> {
         let conn = self.conn.lock();
-        let mut stmt = conn.prepare_cached(
+        collect_mapped::<SongRow, Song, _>(
+            &conn,
             "SELECT s.*,
                     (SELECT GROUP_CONCAT(name, ',') FROM (SELECT ar.name FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id WHERE sa.song_id = s.id ORDER BY sa.position)) AS artists,
                     (SELECT GROUP_CONCAT(g.name, ',') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres
              FROM songs s
-             WHERE s.file_path = ?1",
-        )?;
-        let row = stmt
-            .query_row(params![file_path], SongRow::from_row)
-            .optional()?;
-        Ok(row.map(Into::into))
+             WHERE s.file_path = ?1
+             ORDER BY s.cue_track",
+            params![file_path],
+            SongRow::from_row,
+        )
     }
 
-    #[allow(clippy::too_many_arguments)]
-    pub fn upsert_song(
-        &self,
-        title: &str,
-        artists: &[&str],
-        album_id: Option<&Cuid>,
-        file_path: &str,
-        duration: i32,
-        track_number: Option<i32>,
-        year: Option<i32>,
-        genres: &[&str],
-        image_id: Option<&str>,
-        file_size: i64,
-        file_modified: i64,
-        lufs: Option<f32>,
-    ) -> Result<()> {
+    pub fn upsert_song(&self, song: &SongUpsert) -> Result<()> {
+        let SongUpsert {
+            title,
+            artists,
+            album_id,
+            file_path,
+            duration,
+            track_number,
+            track_total,
+            disc_number,
+            year,
+            genres,
+            image_id,
+            file_size,
+            file_modified,
+            lufs,
+            track_peak,
+            metadata_from_filename,
+            segment,
+        } = *song;
         let year_str = year.map(|y| y.to_string());
         let id = Cuid::new();
         let mut conn = self.conn.lock();
@@ -160,9 +294,9 @@ impl Database {
 
         let song_id: Cuid = tx
             .prepare_cached(
-                "INSERT INTO songs (id, title, album_id, file_path, file_size, file_modified, date, duration, image_id, track_number, lufs)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)
-                 ON CONFLICT(file_path) DO UPDATE SET
+                "INSERT INTO songs (id, title, album_id, file_path, file_size, file_modified, date, duration, image_id, track_number, disc_number, lufs, track_peak, cue_track, start_secs, end_secs, track_total, metadata_from_filename)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+                 ON CONFLICT(file_path, cue_track) DO UPDATE SET
                     title = excluded.title,
                     album_id = excluded.album_id,
                     file_size = excluded.file_size,
@@ -171,11 +305,33 @@ impl Database {
                     duration = excluded.duration,
                     image_id = excluded.image_id,
                     track_number = excluded.track_number,
-                    lufs = excluded.lufs
+                    disc_number = excluded.disc_number,
+                    lufs = excluded.lufs,
+                    track_peak = excluded.track_peak,
+                    start_secs = excluded.start_secs,
+                    end_secs = excluded.end_secs,
+                    unavailable = 0,
+                    metadata_from_filename = excluded.metadata_from_filename,
+                    track_total = excluded.track_total,
+                    computed_lufs = CASE
+                        WHEN songs.file_modified = excluded.file_modified
+                            AND songs.file_size = excluded.file_size
+                        THEN songs.computed_lufs
+                    END,
+                    computed_peak = CASE
+                        WHEN songs.file_modified = excluded.file_modified
+                            AND songs.file_size = excluded.file_size
+                        THEN songs.computed_peak
+                    END,
+                    suspected_transcode = CASE
+                        WHEN songs.file_modified = excluded.file_modified
+                            AND songs.file_size = excluded.file_size
+                        THEN songs.suspected_transcode
+                    END
                  RETURNING id",
             )?
             .query_row(
-                params![id, title, album_id, file_path, file_size, file_modified, year_str, duration, image_id, track_number, lufs],
+                params![id, title, album_id, file_path, file_size, file_modified, year_str, duration, image_id, track_number, disc_number, lufs, track_peak, segment.cue_track, segment.start_secs, segment.end_secs, track_total, metadata_from_filename],
                 |row| row.get(0),
             )?;
 
@@ -184,14 +340,7 @@ impl Database {
             params![song_id],
         )?;
         for (position, &artist_name) in artists.iter().enumerate() {
-            let artist_id = Cuid::new();
-            let actual_artist_id: Cuid = tx
-                .prepare_cached(
-                    "INSERT INTO artists (id, name) VALUES (?1, ?2)
-                     ON CONFLICT(name) DO UPDATE SET name = excluded.name
-                     RETURNING id",
-                )?
-                .query_row(params![artist_id, artist_name], |row| row.get(0))?;
+            let actual_artist_id = resolve_artist(&tx, artist_name)?;
             tx.execute(
                 "INSERT INTO songs_artists (song_id, artist_id, position) VALUES (?1, ?2, ?3)
                  ON CONFLICT(song_id, artist_id) DO UPDATE SET position = excluded.position",
@@ -224,20 +373,201 @@ impl Database {
     }
 
     pub fn delete_song(&self, id: &Cuid) -> Result<()> {
-        let conn = self.conn.lock();
-        conn.execute("DELETE FROM songs WHERE id = ?1", params![id])?;
+        self.delete_songs(std::slice::from_ref(id))?;
         Ok(())
     }
 
+    pub fn delete_songs(&self, ids: &[Cuid]) -> Result<usize> {
+        if self.skip_write("removing songs") {
+            return Ok(0);
+        }
+        self.remove_songs(ids)
+    }
+
+    /// Deletes songs for the scanner, which keeps writing in read-only mode.
+    pub(crate) fn remove_songs(&self, ids: &[Cuid]) -> Result<usize> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let mut removed = 0;
+        // Links are cleared by hand so the orphan triggers drop unused artists and albums.
+        for id in ids {
+            tx.execute("DELETE FROM songs_artists WHERE song_id = ?1", params![id])?;
+            tx.execute("DELETE FROM songs_genres WHERE song_id = ?1", params![id])?;
+            tx.execute("DELETE FROM playlist_songs WHERE song_id = ?1", params![id])?;
+            removed += tx.execute("DELETE FROM songs WHERE id = ?1", params![id])?;
+        }
+        tx.execute(
+            "DELETE FROM albums_artists WHERE album_id NOT IN (SELECT id FROM albums)",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
     pub fn get_song_paths(&self) -> Result<Vec<String>> {
         let conn = self.conn.lock();
-        let mut stmt = conn.prepare_cached("SELECT file_path FROM songs")?;
+        let mut stmt = conn.prepare_cached("SELECT DISTINCT file_path FROM songs")?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// The songs stored for a file as `(id, cue_track)`, several when a cue sheet splits it.
+    pub fn get_song_segments(&self, file_path: &str) -> Result<Vec<(Cuid, i32)>> {
+        let conn = self.conn.lock();
+        let mut stmt =
+            conn.prepare_cached("SELECT id, cue_track FROM songs WHERE file_path = ?1")?;
+        let rows = stmt
+            .query_map(params![file_path], |row| {
+                Ok((row.get::<_, Cuid>(0)?, row.get::<_, i32>(1)?))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn delete_songs_by_path(&self, paths: &[String]) -> Result<usize> {
+        let mut ids = Vec::new();
+        for path in paths {
+            ids.extend(self.get_song_segments(path)?.into_iter().map(|(id, _)| id));
+        }
+        self.remove_songs(&ids)
+    }
+
+    /// Marks exactly `paths` as unavailable and clears the flag on every other song inside
+    /// `scope`, or the whole library without one.
+    pub fn set_unavailable_songs(&self, paths: &[String], scope: Option<&Path>) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        {
+            let flagged = tx
+                .prepare_cached("SELECT DISTINCT file_path FROM songs WHERE unavailable = 1")?
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            let mut clear =
+                tx.prepare_cached("UPDATE songs SET unavailable = 0 WHERE file_path = ?1")?;
+            for path in flagged
+                .iter()
+                .filter(|p| scope.is_none_or(|scope| Path::new(p).starts_with(scope)))
+            {
+                clear.execute(params![path])?;
+            }
+            let mut stmt =
+                tx.prepare_cached("UPDATE songs SET unavailable = 1 WHERE file_path = ?1")?;
+            for path in paths {
+                stmt.execute(params![path])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn rename_song_paths(&self, renames: &[(String, String)]) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt =
+                tx.prepare_cached("UPDATE songs SET file_path = ?2 WHERE file_path = ?1")?;
+            for (old_path, new_path) in renames {
+                stmt.execute(params![old_path, new_path])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Files the transcode check has not looked at yet. The check covers a whole file, so the
+    /// tracks of a cue sheet share one entry.
+    pub fn get_files_pending_transcode_check(&self) -> Result<Vec<String>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached(
+            "SELECT DISTINCT file_path FROM songs WHERE suspected_transcode IS NULL",
+        )?;
         let rows = stmt
             .query_map([], |row| row.get::<_, String>(0))?
             .collect::<rusqlite::Result<Vec<_>>>()?;
         Ok(rows)
     }
 
+    /// Stores the transcode check's result on every song of the file.
+    pub fn set_suspected_transcode(&self, file_path: &str, suspected: bool) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE songs SET suspected_transcode = ?1 WHERE file_path = ?2",
+            params![suspected, file_path],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_computed_loudness(&self, id: &Cuid, lufs: Option<f32>, peak: f32) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE songs SET computed_lufs = ?1, computed_peak = ?2 WHERE id = ?3",
+            params![lufs, peak, id],
+        )?;
+        Ok(())
+    }
+
+    /// Songs normalization knows too little about, missing either a loudness or a peak from both
+    /// tags and measurement, as `(id, file_path, start_secs, end_secs)`.
+    pub fn get_songs_pending_measurement(&self) -> Result<Vec<(Cuid, String, f32, Option<f32>)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, file_path, start_secs, end_secs FROM songs
+             WHERE computed_peak IS NULL
+               AND (track_peak IS NULL OR (lufs IS NULL AND computed_lufs IS NULL))",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, Cuid>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f32>(2)?,
+                    row.get::<_, Option<f32>>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    pub fn set_gain_offset(&self, id: &Cuid, gain_offset_db: f32) -> Result<()> {
+        if self.skip_write("a gain offset") {
+            return Ok(());
+        }
+        let conn = self.conn.lock();
+        conn.execute(
+            "UPDATE songs SET gain_offset_db = ?1 WHERE id = ?2",
+            params![gain_offset_db, id],
+        )?;
+        Ok(())
+    }
+
+    pub fn get_suspected_transcode(&self, id: &Cuid) -> Result<Option<bool>> {
+        let conn = self.conn.lock();
+        let suspected = conn
+            .query_row(
+                "SELECT suspected_transcode FROM songs WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, Option<bool>>(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(suspected)
+    }
+
+    pub fn get_metadata_from_filename(&self, id: &Cuid) -> Result<bool> {
+        let conn = self.conn.lock();
+        let from_filename = conn
+            .query_row(
+                "SELECT metadata_from_filename FROM songs WHERE id = ?1",
+                params![id],
+                |row| row.get::<_, bool>(0),
+            )
+            .optional()?
+            .unwrap_or(false);
+        Ok(from_filename)
+    }
+
     pub fn get_song_file_states(&self) -> Result<Vec<(String, i64, i64)>> {
         let conn = self.conn.lock();
         let mut stmt =
@@ -291,9 +621,34 @@ impl Database {
         ascending: bool,
         offset: i64,
         limit: i64,
+    ) -> Result<Vec<SongListItem>> {
+        self.query_songs(query, sort, ascending, offset, limit, false)
+    }
+
+    /// Like `get_songs`, but always fills in `last_played` from the play history.
+    pub fn get_songs_with_last_played(
+        &self,
+        query: Option<&str>,
+        sort: SongSort,
+        ascending: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<SongListItem>> {
+        self.query_songs(query, sort, ascending, offset, limit, true)
+    }
+
+    fn query_songs(
+        &self,
+        query: Option<&str>,
+        sort: SongSort,
+        ascending: bool,
+        offset: i64,
+        limit: i64,
+        with_last_played: bool,
     ) -> Result<Vec<SongListItem>> {
         let has_query = query.map(|q| !q.trim().is_empty()).unwrap_or(false);
         let order_clause = song_order(sort, ascending, has_query);
+        let last_played = last_played_column(with_last_played || sort == SongSort::LastPlayed);
         let conn = self.conn.lock();
 
         if !has_query {
@@ -301,8 +656,9 @@ impl Database {
                 "SELECT s.id, s.title,
                         (SELECT GROUP_CONCAT(name, ', ') FROM (SELECT ar.name FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id WHERE sa.song_id = s.id ORDER BY sa.position)) AS artist_name,
                         al.title AS album_title,
-                        s.album_id, s.duration, s.image_id,
-                        (SELECT GROUP_CONCAT(g.name, ', ') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres
+                        s.album_id, s.duration, s.image_id, s.unavailable,
+                        (SELECT GROUP_CONCAT(g.name, ', ') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres,
+                        {last_played}
                  FROM songs s
                  LEFT JOIN albums al ON s.album_id = al.id
                  ORDER BY {order_clause}
@@ -327,13 +683,14 @@ impl Database {
             "SELECT s.id, s.title,
                     (SELECT GROUP_CONCAT(name, ', ') FROM (SELECT ar.name FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id WHERE sa.song_id = s.id ORDER BY sa.position)) AS artist_name,
                     al.title AS album_title,
-                    s.album_id, s.duration, s.image_id,
-                    (SELECT GROUP_CONCAT(g.name, ', ') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres
+                    s.album_id, s.duration, s.image_id, s.unavailable,
+                    (SELECT GROUP_CONCAT(g.name, ', ') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres,
+                    {last_played}
              FROM songs_fts
              JOIN songs s ON s.id = songs_fts.song_id
              LEFT JOIN albums al ON s.album_id = al.id
              WHERE songs_fts MATCH ?2
-             GROUP BY s.id, s.title, al.title, s.album_id, s.duration, s.image_id, genres
+             GROUP BY s.id, s.title, al.title, s.album_id, s.duration, s.image_id, s.unavailable, genres, last_played
              ORDER BY {order_clause}
              LIMIT ?3 OFFSET ?4"
         );
@@ -356,11 +713,14 @@ impl Database {
         let query = query.trim();
         let has_query = !query.is_empty();
         let order_clause = song_order(sort, ascending, has_query);
+        let last_played = last_played_column(sort == SongSort::LastPlayed);
         let conn = self.conn.lock();
 
         if !has_query {
             let sql = format!(
-                "SELECT s.id
+                "SELECT s.id,
+                        (SELECT GROUP_CONCAT(g.name, ', ') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres,
+                        {last_played}
                  FROM songs s
                  LEFT JOIN albums al ON s.album_id = al.id
                  ORDER BY {order_clause}
@@ -378,12 +738,14 @@ impl Database {
         };
 
         let sql = format!(
-            "SELECT s.id
+            "SELECT s.id,
+                    (SELECT GROUP_CONCAT(g.name, ', ') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres,
+                    {last_played}
              FROM songs_fts
              JOIN songs s ON s.id = songs_fts.song_id
              LEFT JOIN albums al ON s.album_id = al.id
              WHERE songs_fts MATCH ?2
-             GROUP BY s.id, s.title, al.title, s.duration
+             GROUP BY s.id, s.title, al.title, s.duration, genres, last_played
              ORDER BY {order_clause}
              LIMIT -1 OFFSET ?3"
         );
@@ -397,6 +759,26 @@ impl Database {
         Ok(rows)
     }
 
+    /// Songs with no play recorded at or after `cutoff`, including ones never played.
+    pub fn get_song_ids_not_played_since(&self, cutoff: &str) -> Result<Vec<Cuid>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached(
+            "SELECT s.id
+             FROM songs s
+             WHERE NOT EXISTS (
+                 SELECT 1
+                 FROM events e
+                 JOIN event_contexts ec ON ec.id = e.context_id
+                 WHERE ec.song_id = s.id AND e.event_type = 'PLAY' AND e.timestamp >= ?1
+             )
+             ORDER BY s.title COLLATE NOCASE ASC, s.id ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![cutoff], |row| row.get::<_, Cuid>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
     pub fn get_album_songs(&self, album_id: &Cuid) -> Result<Vec<Song>> {
         let conn = self.conn.lock();
         collect_mapped::<SongRow, Song, _>(
@@ -406,7 +788,11 @@ impl Database {
                     (SELECT GROUP_CONCAT(g.name, ',') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres
              FROM songs s
              WHERE s.album_id = ?1
-             ORDER BY s.track_number ASC",
+             ORDER BY s.track_number IS NULL,
+                      COALESCE(s.disc_number, 1) ASC,
+                      s.track_number ASC,
+                      CASE WHEN s.track_number IS NULL THEN s.file_path END ASC,
+                      s.title COLLATE NOCASE ASC",
             params![album_id],
             SongRow::from_row,
         )
@@ -443,54 +829,382 @@ impl Database {
         Ok(count.max(0) as usize)
     }
 
+    /// The column a title or album sort orders by, for every song in list order. Other sorts
+    /// have no alphabetical key and return nothing.
+    pub fn get_song_index_keys(
+        &self,
+        query: &str,
+        sort: SongSort,
+        ascending: bool,
+    ) -> Result<Vec<String>> {
+        let key = match sort {
+            SongSort::Title => "s.title",
+            SongSort::Album => "COALESCE(al.title, '')",
+            _ => return Ok(Vec::new()),
+        };
+        let query = query.trim();
+        let has_query = !query.is_empty();
+        let order_clause = song_order(sort, ascending, has_query);
+        let conn = self.conn.lock();
+
+        if !has_query {
+            let sql = format!(
+                "SELECT {key}
+                 FROM songs s
+                 LEFT JOIN albums al ON s.album_id = al.id
+                 ORDER BY {order_clause}"
+            );
+            let mut stmt = conn.prepare_cached(&sql)?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            return Ok(rows);
+        }
+
+        let Some(fts_query) = to_fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let sql = format!(
+            "SELECT {key}
+             FROM songs_fts
+             JOIN songs s ON s.id = songs_fts.song_id
+             LEFT JOIN albums al ON s.album_id = al.id
+             WHERE songs_fts MATCH ?1
+             GROUP BY s.id
+             ORDER BY {order_clause}"
+        );
+        let mut stmt = conn.prepare_cached(&sql)?;
+        let rows = stmt
+            .query_map(params![fts_query], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Artist names as they sort, in list order, for the index rail. With articles ignored the
+    /// leading "The " is already cut off.
+    pub fn get_artist_names(&self, query: &str) -> Result<Vec<String>> {
+        let conn = self.conn.lock();
+        let sort_key = sort_name("ar.name", self.ignore_articles());
+        let sql = format!(
+            "SELECT {sort_key} AS sort_key
+             FROM artists ar
+             WHERE ?1 = '' OR ar.name LIKE '%' || ?1 || '%' COLLATE NOCASE
+             ORDER BY sort_key COLLATE NOCASE ASC"
+        );
+        let mut stmt = conn.prepare_cached(&sql)?;
+        let rows = stmt
+            .query_map(params![query.trim()], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
     pub fn get_artists(&self, query: &str, offset: i64, limit: i64) -> Result<Vec<ArtistListItem>> {
         let conn = self.conn.lock();
         let query = query.trim();
+        let sort_key = sort_name("ar.name", self.ignore_articles());
         if query.is_empty() {
+            let sql = format!(
+                "SELECT ar.id, ar.name, ar.image_id,
+                        (SELECT COUNT(*) FROM albums_artists aa WHERE aa.artist_id = ar.id) AS album_count,
+                        (SELECT COUNT(*) FROM songs_artists sa WHERE sa.artist_id = ar.id) AS song_count
+                 FROM artists ar
+                 ORDER BY {sort_key} COLLATE NOCASE ASC
+                 LIMIT ?1 OFFSET ?2"
+            );
             return collect_mapped::<ArtistListRow, ArtistListItem, _>(
                 &conn,
-                "SELECT ar.id, ar.name, ar.image_id
-                 FROM artists ar
-                 ORDER BY ar.name COLLATE NOCASE ASC
-                 LIMIT ?1 OFFSET ?2",
+                &sql,
                 params![limit, offset],
                 ArtistListRow::from_row,
             );
         }
 
-        collect_mapped::<ArtistListRow, ArtistListItem, _>(
-            &conn,
-            "SELECT ar.id, ar.name, ar.image_id
+        let sql = format!(
+            "SELECT ar.id, ar.name, ar.image_id,
+                    (SELECT COUNT(*) FROM albums_artists aa WHERE aa.artist_id = ar.id) AS album_count,
+                    (SELECT COUNT(*) FROM songs_artists sa WHERE sa.artist_id = ar.id) AS song_count
              FROM artists ar
              WHERE ar.name LIKE '%' || ?1 || '%' COLLATE NOCASE
-             ORDER BY ar.name COLLATE NOCASE ASC
-             LIMIT ?2 OFFSET ?3",
+             ORDER BY {sort_key} COLLATE NOCASE ASC
+             LIMIT ?2 OFFSET ?3"
+        );
+        collect_mapped::<ArtistListRow, ArtistListItem, _>(
+            &conn,
+            &sql,
             params![query, limit, offset],
             ArtistListRow::from_row,
         )
     }
 
-    pub fn get_album(&self, id: &Cuid) -> Result<Option<Album>> {
+    /// Other artists whose names match `artist_id`'s once case, punctuation and a leading
+    /// "The " are ignored, most songs first.
+    pub fn get_duplicate_artists(&self, artist_id: &Cuid) -> Result<Vec<ArtistListItem>> {
+        let artists = {
+            let conn = self.conn.lock();
+            collect_mapped::<ArtistListRow, ArtistListItem, _>(
+                &conn,
+                "SELECT ar.id, ar.name, ar.image_id,
+                        (SELECT COUNT(*) FROM albums_artists aa WHERE aa.artist_id = ar.id) AS album_count,
+                        (SELECT COUNT(*) FROM songs_artists sa WHERE sa.artist_id = ar.id) AS song_count
+                 FROM artists ar",
+                [],
+                ArtistListRow::from_row,
+            )?
+        };
+        let Some(key) = artists
+            .iter()
+            .find(|artist| &artist.id == artist_id)
+            .map(|artist| artist_match_key(&artist.name))
+        else {
+            return Ok(Vec::new());
+        };
+        if key.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut duplicates: Vec<ArtistListItem> = artists
+            .into_iter()
+            .filter(|artist| &artist.id != artist_id && artist_match_key(&artist.name) == key)
+            .collect();
+        duplicates.sort_by(|a, b| b.song_count.cmp(&a.song_count).then(a.name.cmp(&b.name)));
+        Ok(duplicates)
+    }
+
+    /// Moves every song and album credit of `from_id` to `into_id` and deletes `from_id`. Its
+    /// name is kept as an alias so a rescan doesn't split the artist again.
+    pub fn merge_artists(&self, from_id: &Cuid, into_id: &Cuid) -> Result<()> {
+        if from_id == into_id || self.skip_write("merging artists") {
+            return Ok(());
+        }
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT OR IGNORE INTO songs_artists (song_id, artist_id, position)
+             SELECT song_id, ?2, position FROM songs_artists WHERE artist_id = ?1",
+            params![from_id, into_id],
+        )?;
+        tx.execute(
+            "INSERT OR IGNORE INTO albums_artists (album_id, artist_id, position)
+             SELECT album_id, ?2, position FROM albums_artists WHERE artist_id = ?1",
+            params![from_id, into_id],
+        )?;
+        tx.execute(
+            "UPDATE artists SET
+                favorite = favorite OR (SELECT favorite FROM artists WHERE id = ?1),
+                pinned = pinned OR (SELECT pinned FROM artists WHERE id = ?1),
+                image_id = COALESCE(image_id, (SELECT image_id FROM artists WHERE id = ?1))
+             WHERE id = ?2",
+            params![from_id, into_id],
+        )?;
+        tx.execute(
+            "UPDATE artist_aliases SET artist_id = ?2 WHERE artist_id = ?1",
+            params![from_id, into_id],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO artist_aliases (name, artist_id)
+             SELECT name, ?2 FROM artists WHERE id = ?1",
+            params![from_id, into_id],
+        )?;
+        // Deleting the artist while its links still exist lets the search index drop its name
+        // from those songs; the links go afterwards.
+        tx.execute("DELETE FROM artists WHERE id = ?1", params![from_id])?;
+        tx.execute(
+            "DELETE FROM songs_artists WHERE artist_id = ?1",
+            params![from_id],
+        )?;
+        tx.execute(
+            "DELETE FROM albums_artists WHERE artist_id = ?1",
+            params![from_id],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn get_album(&self, id: &Cuid) -> Result<Option<Album>> {
+        let conn = self.conn.lock();
+        let row = conn
+            .prepare_cached(
+                "SELECT al.id, al.title, al.image_id, al.favorite, al.pinned,
+                        (SELECT GROUP_CONCAT(name, ',')
+                         FROM (SELECT ar.name FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id ORDER BY aa.position)) AS artists
+                 FROM albums al WHERE al.id = ?1",
+            )?
+            .query_row(params![id], AlbumRow::from_row)
+            .optional()?;
+        Ok(row.map(Into::into))
+    }
+
+    /// Tracks the album's tags say exist but the library lacks, as `(disc, track)`. Each disc is
+    /// checked against the highest track total declared by its songs.
+    pub fn get_album_missing_tracks(&self, album_id: &Cuid) -> Result<Vec<(i32, i32)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached(
+            "SELECT COALESCE(disc_number, 1), track_number, track_total
+             FROM songs WHERE album_id = ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![album_id], |row| {
+                Ok((
+                    row.get::<_, i32>(0)?,
+                    row.get::<_, Option<i32>>(1)?,
+                    row.get::<_, Option<i32>>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut discs: BTreeMap<i32, (i32, HashSet<i32>)> = BTreeMap::new();
+        for (disc, track_number, track_total) in rows {
+            let (total, present) = discs.entry(disc).or_default();
+            *total = (*total).max(track_total.unwrap_or(0));
+            present.extend(track_number);
+        }
+        Ok(discs
+            .into_iter()
+            .flat_map(|(disc, (total, present))| {
+                (1..=total)
+                    .filter(move |track| !present.contains(track))
+                    .map(move |track| (disc, track))
+            })
+            .collect())
+    }
+
+    /// Albums where some disc has fewer of its numbered tracks than its tags declare.
+    pub fn get_incomplete_albums(&self) -> Result<Vec<Cuid>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached(
+            "WITH discs AS (
+                SELECT album_id, COALESCE(disc_number, 1) AS disc, MAX(track_total) AS total
+                FROM songs
+                WHERE album_id IS NOT NULL AND track_total IS NOT NULL
+                GROUP BY album_id, disc
+             )
+             SELECT DISTINCT d.album_id FROM discs d
+             WHERE (SELECT COUNT(DISTINCT s.track_number) FROM songs s
+                    WHERE s.album_id = d.album_id
+                      AND COALESCE(s.disc_number, 1) = d.disc
+                      AND s.track_number BETWEEN 1 AND d.total) < d.total",
+        )?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, Cuid>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(ids)
+    }
+
+    pub fn get_artist_by_name(&self, name: &str) -> Result<Option<Artist>> {
+        let conn = self.conn.lock();
+        let row = conn
+            .prepare_cached("SELECT * FROM artists WHERE name = ?1")?
+            .query_row(params![name], ArtistRow::from_row)
+            .optional()?;
+        Ok(row.map(Into::into))
+    }
+
+    pub fn get_artist_albums(&self, artist_id: &Cuid) -> Result<Vec<AlbumListItem>> {
+        let conn = self.conn.lock();
+        collect_mapped::<AlbumListRow, AlbumListItem, _>(
+            &conn,
+            "SELECT al.id, al.title,
+                    (SELECT GROUP_CONCAT(name, ', ')
+                     FROM (SELECT ar.name FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id ORDER BY aa.position)) AS artist_name,
+                    al.image_id, MIN(s.date) AS year
+             FROM albums al
+             JOIN albums_artists aa ON aa.album_id = al.id AND aa.artist_id = ?1
+             LEFT JOIN songs s ON s.album_id = al.id
+             GROUP BY al.id
+             ORDER BY year ASC, al.title COLLATE NOCASE ASC",
+            params![artist_id],
+            AlbumListRow::from_row,
+        )
+    }
+
+    pub fn get_tracks_featuring_artist(&self, artist_id: &Cuid) -> Result<Vec<Song>> {
+        let conn = self.conn.lock();
+        collect_mapped::<SongRow, Song, _>(
+            &conn,
+            "SELECT s.*,
+                    (SELECT GROUP_CONCAT(name, ',') FROM (SELECT ar.name FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id WHERE sa.song_id = s.id ORDER BY sa.position)) AS artists,
+                    (SELECT GROUP_CONCAT(g.name, ',') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres
+             FROM songs s
+             JOIN songs_artists sa ON sa.song_id = s.id AND sa.artist_id = ?1
+             LEFT JOIN albums al ON al.id = s.album_id
+             WHERE s.album_id IS NOT NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM albums_artists aa
+                   WHERE aa.album_id = s.album_id AND aa.artist_id = ?1
+               )
+             ORDER BY al.title COLLATE NOCASE ASC, COALESCE(s.disc_number, 1) ASC, s.track_number ASC",
+            params![artist_id],
+            SongRow::from_row,
+        )
+    }
+
+    pub fn get_artist_song_ids(&self, artist_id: &Cuid) -> Result<Vec<Cuid>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached(
+            "SELECT s.id
+             FROM songs s
+             LEFT JOIN albums al ON al.id = s.album_id
+             WHERE EXISTS (SELECT 1 FROM songs_artists sa WHERE sa.song_id = s.id AND sa.artist_id = ?1)
+                OR EXISTS (SELECT 1 FROM albums_artists aa WHERE aa.album_id = s.album_id AND aa.artist_id = ?1)
+             ORDER BY COALESCE(al.title, '') COLLATE NOCASE ASC, COALESCE(s.disc_number, 1) ASC, s.track_number ASC, s.id ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![artist_id], |row| row.get::<_, Cuid>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Songs related to `seed` for radio: same artist, shared genres and songs played within
+    /// half an hour of it. Higher scores come first, with a little shuffle among close ones.
+    pub fn get_radio_song_ids(&self, seed: &Cuid, limit: i64) -> Result<Vec<Cuid>> {
         let conn = self.conn.lock();
-        let row = conn
-            .prepare_cached(
-                "SELECT al.id, al.title, al.image_id, al.favorite, al.pinned,
-                        (SELECT GROUP_CONCAT(name, ',')
-                         FROM (SELECT ar.name FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id ORDER BY aa.position)) AS artists
-                 FROM albums al WHERE al.id = ?1",
-            )?
-            .query_row(params![id], AlbumRow::from_row)
-            .optional()?;
-        Ok(row.map(Into::into))
+        let mut stmt = conn.prepare_cached(
+            "WITH seed_plays AS (
+                SELECT e.timestamp FROM events e
+                JOIN event_contexts ec ON e.context_id = ec.id
+                WHERE ec.song_id = ?1 AND e.event_type = 'PLAY'
+             ),
+             co_played AS (
+                SELECT ec.song_id, COUNT(*) AS plays FROM events e
+                JOIN event_contexts ec ON e.context_id = ec.id
+                JOIN seed_plays sp
+                  ON ABS(julianday(e.timestamp) - julianday(sp.timestamp)) < 1.0 / 48
+                WHERE e.event_type = 'PLAY' AND ec.song_id IS NOT NULL AND ec.song_id != ?1
+                GROUP BY ec.song_id
+             ),
+             scored AS (
+                SELECT s.id,
+                       3 * EXISTS (SELECT 1 FROM songs_artists sa
+                                   WHERE sa.song_id = s.id AND sa.artist_id IN
+                                       (SELECT artist_id FROM songs_artists WHERE song_id = ?1))
+                       + 2 * (SELECT COUNT(*) FROM songs_genres sg
+                              WHERE sg.song_id = s.id AND sg.genre_id IN
+                                  (SELECT genre_id FROM songs_genres WHERE song_id = ?1))
+                       + COALESCE((SELECT MIN(cp.plays, 5) FROM co_played cp
+                                   WHERE cp.song_id = s.id), 0) AS score
+                FROM songs s
+                WHERE s.id != ?1 AND s.unavailable = 0
+             )
+             SELECT id FROM scored
+             WHERE score > 0
+             ORDER BY score + ABS(RANDOM() % 3) DESC
+             LIMIT ?2",
+        )?;
+        let rows = stmt
+            .query_map(params![seed, limit], |row| row.get::<_, Cuid>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
     }
 
-    pub fn get_artist_by_name(&self, name: &str) -> Result<Option<Artist>> {
+    pub fn get_random_song_ids(&self, limit: i64) -> Result<Vec<Cuid>> {
         let conn = self.conn.lock();
-        let row = conn
-            .prepare_cached("SELECT * FROM artists WHERE name = ?1")?
-            .query_row(params![name], ArtistRow::from_row)
-            .optional()?;
-        Ok(row.map(Into::into))
+        let mut stmt = conn.prepare_cached(
+            "SELECT id FROM songs WHERE unavailable = 0 ORDER BY RANDOM() LIMIT ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![limit], |row| row.get::<_, Cuid>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
     }
 
     pub fn get_albums_count(&self, query: &str) -> Result<usize> {
@@ -521,12 +1235,19 @@ impl Database {
         Ok(count.max(0) as usize)
     }
 
-    pub fn get_albums(&self, query: &str, offset: i64, limit: i64) -> Result<Vec<AlbumListItem>> {
+    pub fn get_albums(
+        &self,
+        query: &str,
+        sort: AlbumSort,
+        ascending: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<AlbumListItem>> {
         let conn = self.conn.lock();
         let query = query.trim();
+        let order_clause = album_order(sort, ascending, self.ignore_articles());
         if query.is_empty() {
-            return collect_mapped::<AlbumListRow, AlbumListItem, _>(
-                &conn,
+            let sql = format!(
                 "SELECT al.id, al.title,
                         (SELECT GROUP_CONCAT(name, ', ')
                          FROM (SELECT ar.name FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id ORDER BY aa.position)) AS artist_name,
@@ -534,15 +1255,18 @@ impl Database {
                  FROM albums al
                  LEFT JOIN songs s ON s.album_id = al.id
                  GROUP BY al.id
-                 ORDER BY al.title COLLATE NOCASE ASC
-                 LIMIT ?1 OFFSET ?2",
+                 ORDER BY {order_clause}
+                 LIMIT ?1 OFFSET ?2"
+            );
+            return collect_mapped::<AlbumListRow, AlbumListItem, _>(
+                &conn,
+                &sql,
                 params![limit, offset],
                 AlbumListRow::from_row,
             );
         }
 
-        collect_mapped::<AlbumListRow, AlbumListItem, _>(
-            &conn,
+        let sql = format!(
             "SELECT al.id, al.title,
                     (SELECT GROUP_CONCAT(name, ', ')
                      FROM (SELECT ar.name FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id ORDER BY aa.position)) AS artist_name,
@@ -559,8 +1283,12 @@ impl Database {
                        AND ar.name LIKE '%' || ?1 || '%' COLLATE NOCASE
                  )
              GROUP BY al.id
-             ORDER BY al.title COLLATE NOCASE ASC
-             LIMIT ?2 OFFSET ?3",
+             ORDER BY {order_clause}
+             LIMIT ?2 OFFSET ?3"
+        );
+        collect_mapped::<AlbumListRow, AlbumListItem, _>(
+            &conn,
+            &sql,
             params![query, limit, offset],
             AlbumListRow::from_row,
         )
@@ -610,17 +1338,11 @@ impl Database {
             if existing_set.contains(artist_name) {
                 continue;
             }
-            let artist_id = Cuid::new();
-            let actual_artist_id: Cuid = tx
-                .prepare_cached(
-                    "INSERT INTO artists (id, name) VALUES (?1, ?2)
-                     ON CONFLICT(name) DO UPDATE SET name = excluded.name
-                     RETURNING id",
-                )?
-                .query_row(params![artist_id, artist_name], |row| row.get(0))?;
+            let actual_artist_id = resolve_artist(&tx, artist_name)?;
+            // A merged-away name resolves to an artist the album may already list.
             tx.prepare_cached(
                 "INSERT INTO albums_artists (album_id, artist_id, position) VALUES (?1, ?2, ?3)
-                 ON CONFLICT(album_id, artist_id) DO UPDATE SET position = excluded.position",
+                 ON CONFLICT(album_id, artist_id) DO NOTHING",
             )?
             .execute(params![album_id, actual_artist_id, next_position])?;
             next_position += 1;
@@ -630,6 +1352,9 @@ impl Database {
     }
 
     pub fn delete_album(&self, id: &Cuid) -> Result<()> {
+        if self.skip_write("removing an album") {
+            return Ok(());
+        }
         let conn = self.conn.lock();
         conn.execute("DELETE FROM albums WHERE id = ?1", params![id])?;
         Ok(())
@@ -655,6 +1380,9 @@ impl Database {
         image_id: Option<&str>,
         pinned: bool,
     ) -> Result<()> {
+        if self.skip_write("a playlist change") {
+            return Ok(());
+        }
         let conn = self.conn.lock();
         conn.execute(
             "INSERT INTO playlists (id, name, description, image_id, pinned)
@@ -671,12 +1399,18 @@ impl Database {
     }
 
     pub fn delete_playlist(&self, id: &Cuid) -> Result<()> {
+        if self.skip_write("deleting a playlist") {
+            return Ok(());
+        }
         let conn = self.conn.lock();
         conn.execute("DELETE FROM playlists WHERE id = ?1", params![id])?;
         Ok(())
     }
 
     pub fn upsert_playlist_song(&self, playlist_id: &Cuid, song_id: &Cuid) -> Result<()> {
+        if self.skip_write("adding to a playlist") {
+            return Ok(());
+        }
         let id = Cuid::new();
         let conn = self.conn.lock();
         conn.execute(
@@ -688,8 +1422,21 @@ impl Database {
         Ok(())
     }
 
+    pub fn playlist_contains(&self, playlist_id: &Cuid, song_id: &Cuid) -> Result<bool> {
+        let conn = self.conn.lock();
+        let contains = conn
+            .prepare_cached(
+                "SELECT EXISTS(SELECT 1 FROM playlist_songs WHERE playlist_id = ?1 AND song_id = ?2)",
+            )?
+            .query_row(params![playlist_id, song_id], |row| row.get(0))?;
+        Ok(contains)
+    }
+
     #[allow(dead_code)]
     pub fn delete_playlist_song(&self, playlist_id: &Cuid, song_id: &Cuid) -> Result<()> {
+        if self.skip_write("removing from a playlist") {
+            return Ok(());
+        }
         let conn = self.conn.lock();
         conn.execute(
             "DELETE FROM playlist_songs WHERE playlist_id = ?1 AND song_id = ?2",
@@ -699,6 +1446,9 @@ impl Database {
     }
 
     pub fn clear_playlist(&self, playlist_id: &Cuid) -> Result<()> {
+        if self.skip_write("clearing a playlist") {
+            return Ok(());
+        }
         let conn = self.conn.lock();
         conn.execute(
             "DELETE FROM playlist_songs WHERE playlist_id = ?1",
@@ -745,6 +1495,7 @@ impl Database {
             EventType::Stop => "STOP",
             EventType::Pause => "PAUSE",
             EventType::Resume => "RESUME",
+            EventType::Skip => "SKIP",
         };
 
         let conn = self.conn.lock();
@@ -763,6 +1514,7 @@ impl Database {
             EventType::Stop => "STOP",
             EventType::Pause => "PAUSE",
             EventType::Resume => "RESUME",
+            EventType::Skip => "SKIP",
         };
         let conn = self.conn.lock();
         collect_mapped::<EventRow, Event, _>(
@@ -822,7 +1574,125 @@ impl Database {
         )
     }
 
+    /// Deletes listening history older than `days`, returning how many events went.
+    pub fn purge_history_older_than(&self, days: u32) -> Result<usize> {
+        if self.skip_write("a history purge") {
+            return Ok(0);
+        }
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        let purged = tx.execute(
+            "DELETE FROM events WHERE timestamp < DATETIME('now', ?1)",
+            params![format!("-{days} days")],
+        )?;
+        tx.execute(
+            "DELETE FROM event_contexts
+             WHERE id NOT IN (SELECT context_id FROM events WHERE context_id IS NOT NULL)",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(purged)
+    }
+
+    /// Seconds spent listening since `since` (a `YYYY-MM-DD HH:MM:SS` UTC timestamp), or over
+    /// all kept history. Each play runs from PLAY or RESUME to the next event and is capped at
+    /// the song's length, so seeking back or quitting mid-song doesn't inflate it.
+    pub fn get_total_listen_time(&self, since: Option<&str>) -> Result<i64> {
+        let conn = self.conn.lock();
+        let total = conn
+            .prepare_cached(
+                "WITH ordered AS (
+                     SELECT e.event_type, ec.song_id,
+                            CAST(strftime('%s', e.timestamp) AS INTEGER) AS at,
+                            LEAD(CAST(strftime('%s', e.timestamp) AS INTEGER))
+                                OVER (ORDER BY e.timestamp, e.rowid) AS next_at,
+                            SUM(e.event_type = 'PLAY')
+                                OVER (ORDER BY e.timestamp, e.rowid) AS play_no
+                     FROM events e
+                     JOIN event_contexts ec ON ec.id = e.context_id
+                     WHERE ?1 IS NULL OR e.timestamp >= ?1
+                 ),
+                 plays AS (
+                     SELECT play_no, song_id, SUM(next_at - at) AS listened
+                     FROM ordered
+                     WHERE event_type IN ('PLAY', 'RESUME') AND next_at IS NOT NULL
+                     GROUP BY play_no, song_id
+                 )
+                 SELECT COALESCE(SUM(MIN(p.listened, s.duration)), 0)
+                 FROM plays p
+                 JOIN songs s ON s.id = p.song_id",
+            )?
+            .query_row(params![since], |row| row.get(0))?;
+        Ok(total)
+    }
+
+    /// How many times a song was played and how many of those plays were skipped.
+    pub fn get_play_and_skip_counts(&self, song_id: &Cuid) -> Result<(i64, i64)> {
+        let conn = self.conn.lock();
+        let counts = conn
+            .prepare_cached(
+                "SELECT COALESCE(SUM(e.event_type = 'PLAY'), 0),
+                        COALESCE(SUM(e.event_type = 'SKIP'), 0)
+                 FROM events e
+                 JOIN event_contexts ec ON ec.id = e.context_id
+                 WHERE ec.song_id = ?1",
+            )?
+            .query_row(params![song_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(counts)
+    }
+
+    /// Songs skipped at least `min_skips` times and on more than half of their plays, most
+    /// skipped first.
+    pub fn get_frequently_skipped(&self, min_skips: i64, limit: i64) -> Result<Vec<SkippedSong>> {
+        let conn = self.conn.lock();
+        let songs = conn
+            .prepare_cached(
+                "WITH counts AS (
+                     SELECT ec.song_id,
+                            SUM(e.event_type = 'SKIP') AS skips,
+                            SUM(e.event_type = 'PLAY') AS plays
+                     FROM events e
+                     JOIN event_contexts ec ON ec.id = e.context_id
+                     WHERE ec.song_id IS NOT NULL AND e.event_type IN ('PLAY', 'SKIP')
+                     GROUP BY ec.song_id
+                 )
+                 SELECT s.*,
+                        (SELECT GROUP_CONCAT(name, ',') FROM (SELECT ar.name FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id WHERE sa.song_id = s.id ORDER BY sa.position)) AS artists,
+                        (SELECT GROUP_CONCAT(g.name, ',') FROM songs_genres sg JOIN genres g ON sg.genre_id = g.id WHERE sg.song_id = s.id) AS genres,
+                        c.skips, c.plays
+                 FROM counts c
+                 JOIN songs s ON s.id = c.song_id
+                 WHERE c.skips >= ?1 AND c.skips * 2 > c.plays
+                 ORDER BY c.skips DESC, c.plays ASC, s.title COLLATE NOCASE ASC
+                 LIMIT ?2",
+            )?
+            .query_map(params![min_skips, limit], |row| {
+                Ok(SkippedSong {
+                    song: SongRow::from_row(row)?.into(),
+                    skips: row.get("skips")?,
+                    plays: row.get("plays")?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(songs)
+    }
+
+    pub fn clear_history(&self) -> Result<()> {
+        if self.skip_write("clearing listening history") {
+            return Ok(());
+        }
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM events", [])?;
+        tx.execute("DELETE FROM event_contexts", [])?;
+        tx.commit()?;
+        Ok(())
+    }
+
     pub fn set_favorite<T: Toggleable>(&self, id: &Cuid, favorite: bool) -> Result<()> {
+        if self.skip_write("a favorite change") {
+            return Ok(());
+        }
         let sql = format!(
             "UPDATE {} SET favorite = ?1 WHERE {} = ?2",
             T::TABLE,
@@ -834,6 +1704,9 @@ impl Database {
     }
 
     pub fn set_pinned<T: Toggleable>(&self, id: &Cuid, pinned: bool) -> Result<()> {
+        if self.skip_write("a pin change") {
+            return Ok(());
+        }
         let sql = format!(
             "UPDATE {} SET pinned = ?1 WHERE {} = ?2",
             T::TABLE,
@@ -844,6 +1717,146 @@ impl Database {
         Ok(())
     }
 
+    /// Sets `favorite` on every id in a single transaction.
+    pub fn set_favorite_many<T: Toggleable>(&self, ids: &[Cuid], favorite: bool) -> Result<()> {
+        self.set_flag_many::<T>("favorite", ids, favorite)
+    }
+
+    pub fn set_pinned_many<T: Toggleable>(&self, ids: &[Cuid], pinned: bool) -> Result<()> {
+        self.set_flag_many::<T>("pinned", ids, pinned)
+    }
+
+    fn set_flag_many<T: Toggleable>(&self, column: &str, ids: &[Cuid], value: bool) -> Result<()> {
+        if self.skip_write("a batch edit") {
+            return Ok(());
+        }
+        let sql = format!(
+            "UPDATE {} SET {} = ?1 WHERE {} = ?2",
+            T::TABLE,
+            column,
+            T::ID_COL
+        );
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(&sql)?;
+            for id in ids {
+                stmt.execute(params![value, id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Whether every song in `ids` is `(favorite, pinned)`.
+    pub fn songs_all_flagged(&self, ids: &[Cuid]) -> Result<(bool, bool)> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached("SELECT favorite, pinned FROM songs WHERE id = ?1")?;
+        let mut all_favorite = true;
+        let mut all_pinned = true;
+        for id in ids {
+            let (favorite, pinned): (Option<bool>, Option<bool>) = stmt
+                .query_row(params![id], |row| Ok((row.get(0)?, row.get(1)?)))
+                .optional()?
+                .unwrap_or((None, None));
+            all_favorite &= favorite.unwrap_or(false);
+            all_pinned &= pinned.unwrap_or(false);
+            if !all_favorite && !all_pinned {
+                break;
+            }
+        }
+        Ok((all_favorite, all_pinned))
+    }
+
+    pub fn get_view_sort(&self, view_key: &str) -> Result<Option<(String, bool)>> {
+        let conn = self.conn.lock();
+        let sort = conn
+            .prepare_cached("SELECT sort_column, ascending FROM view_sorts WHERE view_key = ?1")?
+            .query_row(params![view_key], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?;
+        Ok(sort)
+    }
+
+    /// Stores the sort for `view_key`, or forgets it when `sort` is `None`.
+    pub fn set_view_sort(&self, view_key: &str, sort: Option<(&str, bool)>) -> Result<()> {
+        if self.skip_write("a sort change") {
+            return Ok(());
+        }
+        let conn = self.conn.lock();
+        match sort {
+            Some((column, ascending)) => conn.execute(
+                "INSERT INTO view_sorts (view_key, sort_column, ascending) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(view_key) DO UPDATE SET
+                    sort_column = excluded.sort_column,
+                    ascending = excluded.ascending",
+                params![view_key, column, ascending],
+            )?,
+            None => conn.execute(
+                "DELETE FROM view_sorts WHERE view_key = ?1",
+                params![view_key],
+            )?,
+        };
+        Ok(())
+    }
+
+    /// Replaces the saved queue with `queue`.
+    pub fn save_queue(&self, queue: &SavedQueue) -> Result<()> {
+        if self.skip_write("the queue") {
+            return Ok(());
+        }
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute("DELETE FROM saved_queue", [])?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO saved_queue (queue_index, song_id, file_path) VALUES (?1, ?2, ?3)",
+            )?;
+            for (index, item) in queue.items.iter().enumerate() {
+                stmt.execute(params![index as i64, item.song_id, item.file_path])?;
+            }
+        }
+        tx.execute(
+            "INSERT INTO saved_queue_state (id, current_index, position_secs) VALUES (1, ?1, ?2)
+             ON CONFLICT(id) DO UPDATE SET
+                current_index = excluded.current_index,
+                position_secs = excluded.position_secs",
+            params![queue.current_index.map(|i| i as i64), queue.position_secs],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn load_queue(&self) -> Result<Option<SavedQueue>> {
+        let conn = self.conn.lock();
+        let items = conn
+            .prepare_cached("SELECT song_id, file_path FROM saved_queue ORDER BY queue_index")?
+            .query_map([], |row| {
+                Ok(SavedQueueItem {
+                    song_id: row.get(0)?,
+                    file_path: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if items.is_empty() {
+            return Ok(None);
+        }
+        let (current_index, position_secs) = conn
+            .prepare_cached("SELECT current_index, position_secs FROM saved_queue_state")?
+            .query_row([], |row| {
+                Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, f32>(1)?))
+            })
+            .optional()?
+            .unwrap_or((None, 0.0));
+        let current_index = current_index
+            .and_then(|i| usize::try_from(i).ok())
+            .filter(|&i| i < items.len());
+        Ok(Some(SavedQueue {
+            items,
+            current_index,
+            position_secs,
+        }))
+    }
+
     pub fn search_library(&self, query: &str, limit: i64) -> Result<Vec<SearchResultRow>> {
         let query = query.trim();
         if query.is_empty() {
@@ -856,85 +1869,57 @@ impl Database {
 
         let per_type_limit = (limit.saturating_mul(2)).max(20);
         let conn = self.conn.lock();
+        let sql = format!(
+            "{SEARCH_MATCHES_SQL}
+            SELECT id, name, image, item_type
+            FROM all_matches
+            ORDER BY score DESC, name COLLATE NOCASE ASC
+            LIMIT ?4"
+        );
 
         collect_mapped::<SearchResultRow, SearchResultRow, _>(
             &conn,
-            r#"
-            WITH
-            search_params AS (SELECT ?1 AS query_text),
-            song_matches AS (
-                SELECT DISTINCT
-                    s.id, s.title AS name, s.image_id AS image, 'Song' AS item_type,
-                    CASE
-                        WHEN s.title = sp.query_text COLLATE NOCASE THEN 400
-                        WHEN s.title LIKE sp.query_text || '%' COLLATE NOCASE THEN 300
-                        WHEN EXISTS (SELECT 1 FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id WHERE sa.song_id = s.id AND ar.name LIKE sp.query_text || '%' COLLATE NOCASE) THEN 220
-                        WHEN EXISTS (SELECT 1 FROM albums al WHERE al.id = s.album_id AND al.title LIKE sp.query_text || '%' COLLATE NOCASE) THEN 200
-                        ELSE 100
-                    END AS score
-                FROM songs_fts
-                JOIN songs s ON s.id = songs_fts.song_id
-                CROSS JOIN search_params sp
-                WHERE songs_fts MATCH ?2
-                ORDER BY score DESC, s.title COLLATE NOCASE ASC
-                LIMIT ?3
-            ),
-            album_matches AS (
-                SELECT
-                    al.id, al.title AS name, al.image_id AS image, 'Album' AS item_type,
-                    CASE
-                        WHEN al.title = sp.query_text COLLATE NOCASE THEN 350
-                        WHEN al.title LIKE sp.query_text || '%' COLLATE NOCASE THEN 260
-                        WHEN EXISTS (SELECT 1 FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id AND ar.name LIKE sp.query_text || '%' COLLATE NOCASE) THEN 180
-                        ELSE 90
-                    END AS score
-                FROM albums al
-                CROSS JOIN search_params sp
-                WHERE al.title LIKE '%' || sp.query_text || '%' COLLATE NOCASE
-                   OR EXISTS (SELECT 1 FROM albums_artists aa JOIN artists ar ON aa.artist_id = ar.id WHERE aa.album_id = al.id AND ar.name LIKE '%' || sp.query_text || '%' COLLATE NOCASE)
-                ORDER BY score DESC, al.title COLLATE NOCASE ASC
-                LIMIT ?3
-            ),
-            artist_matches AS (
-                SELECT
-                    ar.id, ar.name AS name, ar.image_id AS image, 'Artist' AS item_type,
-                    CASE
-                        WHEN ar.name = sp.query_text COLLATE NOCASE THEN 320
-                        WHEN ar.name LIKE sp.query_text || '%' COLLATE NOCASE THEN 250
-                        ELSE 80
-                    END AS score
-                FROM artists ar
-                CROSS JOIN search_params sp
-                WHERE ar.name LIKE '%' || sp.query_text || '%' COLLATE NOCASE
-                ORDER BY score DESC, ar.name COLLATE NOCASE ASC
-                LIMIT ?3
-            ),
-            playlist_matches AS (
-                SELECT
-                    p.id, p.name AS name, p.image_id AS image, 'Playlist' AS item_type,
-                    CASE
-                        WHEN p.name = sp.query_text COLLATE NOCASE THEN 300
-                        WHEN p.name LIKE sp.query_text || '%' COLLATE NOCASE THEN 240
-                        ELSE 70
-                    END AS score
-                FROM playlists p
-                CROSS JOIN search_params sp
-                WHERE p.name LIKE '%' || sp.query_text || '%' COLLATE NOCASE
-                ORDER BY score DESC, p.name COLLATE NOCASE ASC
-                LIMIT ?3
-            ),
-            all_matches AS (
-                SELECT * FROM song_matches
-                UNION ALL SELECT * FROM album_matches
-                UNION ALL SELECT * FROM artist_matches
-                UNION ALL SELECT * FROM playlist_matches
+            &sql,
+            params![query, fts_query, per_type_limit, limit],
+            SearchResultRow::from_row,
+        )
+    }
+
+    /// Like `search_library`, but keeps the best `per_type` matches of each item type so
+    /// one kind of result can't crowd out the others. Rows stay in overall rank order.
+    pub fn search_library_grouped(
+        &self,
+        query: &str,
+        per_type: i64,
+    ) -> Result<Vec<SearchResultRow>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let Some(fts_query) = to_fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let conn = self.conn.lock();
+        let sql = format!(
+            "{SEARCH_MATCHES_SQL},
+            ranked AS (
+                SELECT *, ROW_NUMBER() OVER (
+                    PARTITION BY item_type ORDER BY score DESC, name COLLATE NOCASE ASC
+                ) AS type_rank
+                FROM all_matches
             )
             SELECT id, name, image, item_type
-            FROM all_matches
-            ORDER BY score DESC, name COLLATE NOCASE ASC
-            LIMIT ?4
-            "#,
-            params![query, fts_query, per_type_limit, limit],
+            FROM ranked
+            WHERE type_rank <= ?4
+            ORDER BY score DESC, name COLLATE NOCASE ASC"
+        );
+
+        collect_mapped::<SearchResultRow, SearchResultRow, _>(
+            &conn,
+            &sql,
+            params![query, fts_query, per_type, per_type],
             SearchResultRow::from_row,
         )
     }
@@ -952,6 +1937,85 @@ impl Database {
         ))
     }
 
+    pub fn get_library_totals(&self) -> Result<LibraryTotals> {
+        let conn = self.conn.lock();
+        let totals = conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM songs),
+                    (SELECT COUNT(*) FROM albums),
+                    (SELECT COUNT(*) FROM artists),
+                    (SELECT COUNT(*) FROM playlists),
+                    (SELECT COALESCE(SUM(duration), 0) FROM songs),
+                    (SELECT COALESCE(SUM(file_size), 0) FROM songs),
+                    (SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size())",
+            [],
+            |row| {
+                Ok(LibraryTotals {
+                    songs: row.get(0)?,
+                    albums: row.get(1)?,
+                    artists: row.get(2)?,
+                    playlists: row.get(3)?,
+                    duration_secs: row.get(4)?,
+                    file_size: row.get(5)?,
+                    db_size: row.get(6)?,
+                })
+            },
+        )?;
+        Ok(totals)
+    }
+
+    /// Logs a completed scan, dropping the oldest runs past the last `SCAN_RUNS_KEPT`.
+    pub fn record_scan_run(
+        &self,
+        scanned: usize,
+        added: usize,
+        updated: usize,
+        removed: usize,
+        missing: usize,
+    ) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO scan_runs (scanned, added, updated, removed, missing)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                scanned as i64,
+                added as i64,
+                updated as i64,
+                removed as i64,
+                missing as i64
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM scan_runs
+             WHERE id NOT IN (SELECT id FROM scan_runs ORDER BY id DESC LIMIT ?1)",
+            params![SCAN_RUNS_KEPT],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Most recent scans first.
+    pub fn get_scan_runs(&self, limit: i64) -> Result<Vec<ScanRun>> {
+        let conn = self.conn.lock();
+        let runs = conn
+            .prepare_cached(
+                "SELECT finished_at, scanned, added, updated, removed, missing
+                 FROM scan_runs ORDER BY id DESC LIMIT ?1",
+            )?
+            .query_map(params![limit], |row| {
+                Ok(ScanRun {
+                    finished_at: row.get(0)?,
+                    scanned: row.get(1)?,
+                    added: row.get(2)?,
+                    updated: row.get(3)?,
+                    removed: row.get(4)?,
+                    missing: row.get(5)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(runs)
+    }
+
     pub fn get_playlists_count(&self, query: &str) -> Result<i64> {
         let conn = self.conn.lock();
         let query = query.trim();
@@ -982,9 +2046,11 @@ impl Database {
         if query.is_empty() {
             return collect_mapped::<PlaylistListRow, PlaylistListItem, _>(
                 &conn,
-                "SELECT p.id, p.name, p.image_id, COUNT(pt.id) AS song_count
+                "SELECT p.id, p.name, p.image_id, COUNT(pt.id) AS song_count,
+                        COALESCE(SUM(s.duration), 0) AS duration_secs
                  FROM playlists p
                  LEFT JOIN playlist_songs pt ON pt.playlist_id = p.id
+                 LEFT JOIN songs s ON s.id = pt.song_id
                  GROUP BY p.id, p.name, p.image_id
                  ORDER BY p.name COLLATE NOCASE ASC
                  LIMIT ?1 OFFSET ?2",
@@ -994,9 +2060,11 @@ impl Database {
         }
         collect_mapped::<PlaylistListRow, PlaylistListItem, _>(
             &conn,
-            "SELECT p.id, p.name, p.image_id, COUNT(pt.id) AS song_count
+            "SELECT p.id, p.name, p.image_id, COUNT(pt.id) AS song_count,
+                    COALESCE(SUM(s.duration), 0) AS duration_secs
              FROM playlists p
              LEFT JOIN playlist_songs pt ON pt.playlist_id = p.id
+             LEFT JOIN songs s ON s.id = pt.song_id
              WHERE p.name LIKE '%' || ?1 || '%' COLLATE NOCASE
              GROUP BY p.id, p.name, p.image_id
              ORDER BY p.name COLLATE NOCASE ASC
@@ -1037,7 +2105,10 @@ impl Database {
         Ok(())
     }
 
-    pub fn get_recently_added_items(&self, limit: i64) -> Result<Vec<RecentItem>> {
+    pub fn get_recently_added_items(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<(RecentItem, Option<String>)>> {
         let conn = self.conn.lock();
         let mut stmt = conn.prepare_cached(
             r#"
@@ -1049,7 +2120,11 @@ impl Database {
             ),
             image_groups AS (
                 SELECT
-                    COALESCE(rs.image_id, 'no_image_' || rs.id) AS group_key,
+                    -- Songs outside any album never merge, even when they share a cover.
+                    CASE
+                        WHEN rs.album_id IS NULL THEN 'single_' || rs.id
+                        ELSE COALESCE(rs.image_id, 'no_image_' || rs.id)
+                    END AS group_key,
                     rs.image_id,
                     rs.album_id,
                     MAX(rs.date_added) AS most_recent_date,
@@ -1067,7 +2142,8 @@ impl Database {
                 ig.album_id,
                 al.title AS album_title,
                 (SELECT ar.name FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id
-                 WHERE sa.song_id = s.id ORDER BY sa.position LIMIT 1) AS artist_name
+                 WHERE sa.song_id = s.id ORDER BY sa.position LIMIT 1) AS artist_name,
+                ig.most_recent_date
             FROM image_groups ig
             JOIN songs s ON ig.first_song_id = s.id
             LEFT JOIN albums al ON ig.album_id = al.id
@@ -1078,7 +2154,13 @@ impl Database {
         let rows = stmt
             .query_map(params![limit], RecentItemRow::from_row)?
             .collect::<rusqlite::Result<Vec<_>>>()?;
-        Ok(rows.into_iter().map(|r| r.into_recent_item()).collect())
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                let date = r.most_recent_date.clone();
+                (r.into_recent_item(), date)
+            })
+            .collect())
     }
 
     pub fn get_recently_played_items(&self, limit: i64) -> Result<Vec<RecentItem>> {
@@ -1104,7 +2186,8 @@ impl Database {
                 s.album_id,
                 al.title AS album_title,
                 (SELECT ar.name FROM songs_artists sa JOIN artists ar ON sa.artist_id = ar.id
-                 WHERE sa.song_id = s.id ORDER BY sa.position LIMIT 1) AS artist_name
+                 WHERE sa.song_id = s.id ORDER BY sa.position LIMIT 1) AS artist_name,
+                rsp.most_recent_date
             FROM recent_song_plays rsp
             JOIN songs s ON rsp.song_id = s.id
             LEFT JOIN albums al ON s.album_id = al.id
@@ -1144,6 +2227,28 @@ impl Database {
     }
 }
 
+/// The artist a tag name belongs to. A name merged into another artist resolves to that artist,
+/// any other name is found or created by exact match.
+fn resolve_artist(conn: &Connection, name: &str) -> rusqlite::Result<Cuid> {
+    let merged: Option<Cuid> = conn
+        .prepare_cached(
+            "SELECT aa.artist_id FROM artist_aliases aa
+             JOIN artists ar ON ar.id = aa.artist_id
+             WHERE aa.name = ?1",
+        )?
+        .query_row(params![name], |row| row.get(0))
+        .optional()?;
+    if let Some(artist_id) = merged {
+        return Ok(artist_id);
+    }
+    conn.prepare_cached(
+        "INSERT INTO artists (id, name) VALUES (?1, ?2)
+         ON CONFLICT(name) DO UPDATE SET name = excluded.name
+         RETURNING id",
+    )?
+    .query_row(params![Cuid::new(), name], |row| row.get(0))
+}
+
 fn to_fts_query(query: &str) -> Option<String> {
     let terms: Vec<String> = query
         .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '_')
@@ -1158,6 +2263,15 @@ fn to_fts_query(query: &str) -> Option<String> {
     }
 }
 
+/// The play-history lookup is a correlated subquery per row, so listings skip it unless asked.
+fn last_played_column(include: bool) -> &'static str {
+    if include {
+        "(SELECT MAX(e.timestamp) FROM events e JOIN event_contexts ec ON ec.id = e.context_id WHERE ec.song_id = s.id AND e.event_type = 'PLAY') AS last_played"
+    } else {
+        "NULL AS last_played"
+    }
+}
+
 fn song_order(sort: SongSort, ascending: bool, has_query: bool) -> &'static str {
     match sort {
         SongSort::Title => {
@@ -1188,6 +2302,13 @@ fn song_order(sort: SongSort, ascending: bool, has_query: bool) -> &'static str
                 "genres COLLATE NOCASE DESC, s.id ASC"
             }
         }
+        SongSort::LastPlayed => {
+            if ascending {
+                "last_played ASC, s.id ASC"
+            } else {
+                "last_played IS NULL, last_played DESC, s.id ASC"
+            }
+        }
         SongSort::Default => {
             if has_query {
                 r#"
@@ -1207,3 +2328,35 @@ fn song_order(sort: SongSort, ascending: bool, has_query: bool) -> &'static str
         }
     }
 }
+
+fn album_order(sort: AlbumSort, ascending: bool, ignore_articles: bool) -> String {
+    let direction = if ascending { "ASC" } else { "DESC" };
+    let title = sort_name("al.title", ignore_articles);
+    match sort {
+        AlbumSort::Title => format!("{title} COLLATE NOCASE {direction}, al.id ASC"),
+        AlbumSort::Year => format!(
+            "CAST(substr(MIN(s.date), 1, 4) AS INTEGER) IS NULL, CAST(substr(MIN(s.date), 1, 4) AS INTEGER) {direction}, {title} COLLATE NOCASE ASC, al.id ASC"
+        ),
+        AlbumSort::Artist => format!(
+            "{} COLLATE NOCASE {direction}, {title} COLLATE NOCASE ASC, al.id ASC",
+            sort_name("COALESCE(artist_name, '')", ignore_articles)
+        ),
+        AlbumSort::DateAdded => format!("MAX(s.date_added) {direction}, al.id ASC"),
+    }
+}
+
+/// `column` as it sorts: with a leading "The ", "A " or "An " cut off when articles are
+/// ignored, so "The Beatles" files under B.
+fn sort_name(column: &str, ignore_articles: bool) -> String {
+    if !ignore_articles {
+        return column.to_string();
+    }
+    format!(
+        "CASE
+            WHEN {column} LIKE 'the %' THEN substr({column}, 5)
+            WHEN {column} LIKE 'an %' THEN substr({column}, 4)
+            WHEN {column} LIKE 'a %' THEN substr({column}, 3)
+            ELSE {column}
+         END"
+    )
+}
@@ -0,0 +1,57 @@
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+/// Moves `path` to the OS trash (recycle bin on Windows) instead of deleting it for good.
+///
+/// Every place that removes a user's file goes through here so a mistake can be undone.
+pub fn trash(path: &Path) -> Result<()> {
+    trash::delete(path).with_context(|| format!("Failed to move {:?} to the trash", path))
+}
+
+/// Opens the folder holding `path` in the OS file manager, with the file selected where the
+/// platform supports it.
+pub fn reveal_in_file_manager(path: &Path) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let spawned = Command::new("open").arg("-R").arg(path).spawn();
+
+    #[cfg(target_os = "windows")]
+    let spawned = {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        Command::new("explorer").arg(select).spawn()
+    };
+
+    // Most Linux file managers implement the FileManager1 D-Bus interface, which selects the
+    // file; otherwise fall back to just opening the folder.
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let spawned = {
+        let uri = url::Url::from_file_path(path)
+            .map(|url| url.to_string())
+            .unwrap_or_default();
+        let shown = Command::new("dbus-send")
+            .args([
+                "--session",
+                "--print-reply",
+                "--dest=org.freedesktop.FileManager1",
+                "/org/freedesktop/FileManager1",
+                "org.freedesktop.FileManager1.ShowItems",
+                &format!("array:string:{uri}"),
+                "string:",
+            ])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        if shown {
+            return Ok(());
+        }
+        Command::new("xdg-open")
+            .arg(path.parent().unwrap_or(path))
+            .spawn()
+    };
+
+    spawned
+        .map(|_| ())
+        .with_context(|| format!("Failed to reveal {:?} in the file manager", path))
+}
@@ -1,16 +1,21 @@
 use anyhow::{Context, Result};
 use image::{DynamicImage, GenericImageView, imageops::FilterType, load_from_memory};
 use lofty::config::ParseOptions;
-use lofty::file::{AudioFile, TaggedFileExt};
+use lofty::file::{AudioFile, FileType, TaggedFile, TaggedFileExt};
+use lofty::mp4::{Mp4Codec, Mp4File};
 use lofty::picture::{Picture, PictureType};
 use lofty::probe::Probe;
-use lofty::tag::{Accessor, ItemKey, Tag};
+use lofty::tag::{Accessor, ItemKey};
 use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io::{BufReader, Cursor};
 use std::path::Path;
 use std::time::Duration;
 
+use crate::data::models::{Cuid, Song};
+
+pub mod online;
+
 const PROBE_BUFFER_CAPACITY: usize = 64 * 1024;
 
 const COVER_SIZE: u32 = 1024;
@@ -22,10 +27,27 @@ pub struct AudioMetadata {
     pub artists: Vec<String>,
     pub album: Option<String>,
     pub track_number: Option<u32>,
+    pub track_total: Option<u32>,
+    pub disc_number: Option<u32>,
     pub year: Option<i32>,
     pub duration: Duration,
     pub genres: Vec<String>,
     pub lufs: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub properties: AudioProperties,
+    /// Set when the title and friends were guessed from the path rather than read from tags.
+    pub from_filename: bool,
+}
+
+/// Technical stream details, mostly for telling lossless files from lossy ones.
+#[derive(Debug, Clone, Default)]
+pub struct AudioProperties {
+    pub format: Option<String>,
+    pub lossless: bool,
+    pub bitrate_kbps: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u8>,
+    pub channels: Option<u8>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,8 +56,23 @@ pub struct ImageData {
     pub data: Vec<u8>,
 }
 
-fn extract_metadata_from_tag(tag: Option<&Tag>, duration: Duration) -> AudioMetadata {
-    let (title, artists, album, genres, year, track_number, lufs) = if let Some(tag) = tag {
+fn extract_metadata(tagged_file: &TaggedFile, mp4_codec: Option<&Mp4Codec>) -> AudioMetadata {
+    let duration = tagged_file.properties().duration();
+    let tag = tagged_file
+        .primary_tag()
+        .or_else(|| tagged_file.first_tag());
+    let (
+        title,
+        artists,
+        album,
+        genres,
+        year,
+        track_number,
+        track_total,
+        disc_number,
+        lufs,
+        track_peak,
+    ) = if let Some(tag) = tag {
         let title = tag.title().map(|s| s.to_string());
         let artists = tag
             .artist()
@@ -58,17 +95,41 @@ fn extract_metadata_from_tag(tag: Option<&Tag>, duration: Duration) -> AudioMeta
             .unwrap_or_default();
         let year = tag.date().map(|d| d.year as i32);
         let track_number = tag.track();
+        let track_total = tag.track_total();
+        let disc_number = tag.disk();
 
-        let lufs = tag.get_string(ItemKey::ReplayGainTrackGain).and_then(|s| {
-            s.trim_end_matches(" dB")
-                .parse::<f32>()
-                .ok()
-                .map(|gain| -18.0 - (gain))
-        });
+        let lufs = replaygain_lufs(tag.get_string(ItemKey::ReplayGainTrackGain))
+            .or_else(|| replaygain_lufs(tag.get_string(ItemKey::ReplayGainAlbumGain)));
+        let track_peak = tag
+            .get_string(ItemKey::ReplayGainTrackPeak)
+            .and_then(|s| s.trim().parse::<f32>().ok())
+            .filter(|peak| *peak > 0.0);
 
-        (title, artists, album, genres, year, track_number, lufs)
+        (
+            title,
+            artists,
+            album,
+            genres,
+            year,
+            track_number,
+            track_total,
+            disc_number,
+            lufs,
+            track_peak,
+        )
     } else {
-        (None, vec![], None, vec![], None, None, None)
+        (
+            None,
+            vec![],
+            None,
+            vec![],
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
     };
 
     AudioMetadata {
@@ -78,8 +139,53 @@ fn extract_metadata_from_tag(tag: Option<&Tag>, duration: Duration) -> AudioMeta
         genres,
         year,
         track_number,
+        track_total,
+        disc_number,
         duration,
         lufs,
+        track_peak,
+        properties: audio_properties(tagged_file, mp4_codec),
+        from_filename: false,
+    }
+}
+
+// ReplayGain targets -18 LUFS, so a tagged gain maps straight back to the track's loudness.
+fn replaygain_lufs(gain: Option<&str>) -> Option<f32> {
+    gain.and_then(|s| s.trim().trim_end_matches("dB").trim().parse::<f32>().ok())
+        .map(|gain| -18.0 - gain)
+}
+
+fn audio_properties(tagged_file: &TaggedFile, mp4_codec: Option<&Mp4Codec>) -> AudioProperties {
+    let properties = tagged_file.properties();
+    let (format, lossless) = match tagged_file.file_type() {
+        FileType::Aac => ("AAC", false),
+        FileType::Aiff => ("AIFF", true),
+        FileType::Ape => ("APE", true),
+        FileType::Flac => ("FLAC", true),
+        FileType::Mpeg => ("MP3", false),
+        FileType::Mp4 => match mp4_codec {
+            Some(Mp4Codec::AAC) => ("AAC", false),
+            Some(Mp4Codec::ALAC) => ("ALAC", true),
+            Some(Mp4Codec::FLAC) => ("FLAC", true),
+            Some(Mp4Codec::MP3) => ("MP3", false),
+            _ => ("MP4", false),
+        },
+        FileType::Mpc => ("Musepack", false),
+        FileType::Opus => ("Opus", false),
+        FileType::Vorbis => ("Vorbis", false),
+        FileType::Speex => ("Speex", false),
+        FileType::Wav => ("WAV", true),
+        FileType::WavPack => ("WavPack", true),
+        _ => ("Unknown", false),
+    };
+
+    AudioProperties {
+        format: Some(format.to_string()),
+        lossless,
+        bitrate_kbps: properties.audio_bitrate().or(properties.overall_bitrate()),
+        sample_rate: properties.sample_rate(),
+        bit_depth: properties.bit_depth(),
+        channels: properties.channels(),
     }
 }
 
@@ -112,6 +218,18 @@ fn open_probe(path: &Path) -> Result<Probe<BufReader<File>>> {
     Ok(Probe::new(reader))
 }
 
+/// Reads `path` with `options`. MP4 is only a container, so those files go through the MP4
+/// reader to also learn the codec, which is what tells ALAC from AAC.
+fn read_tagged_file(path: &Path, options: ParseOptions) -> Result<(TaggedFile, Option<Mp4Codec>)> {
+    let probe = open_probe(path)?.guess_file_type()?.options(options);
+    if probe.file_type() != Some(FileType::Mp4) {
+        return Ok((probe.read()?, None));
+    }
+    let mp4 = Mp4File::read_from(&mut probe.into_inner(), options)?;
+    let codec = mp4.properties().codec().clone();
+    Ok((mp4.into(), Some(codec)))
+}
+
 impl AudioMetadata {
     pub fn from_path_with_options(path: &Path, read_pictures: bool) -> Result<Self> {
         let parse_options = if read_pictures {
@@ -120,30 +238,178 @@ impl AudioMetadata {
             ParseOptions::new().read_cover_art(false)
         };
 
-        let tagged_file = open_probe(path)?
-            .guess_file_type()?
-            .options(parse_options)
-            .read()?;
+        let (tagged_file, mp4_codec) = read_tagged_file(path, parse_options)?;
+
+        Ok(extract_metadata(&tagged_file, mp4_codec.as_ref()))
+    }
 
-        let duration = tagged_file.properties().duration();
-        let tag = tagged_file
-            .primary_tag()
-            .or_else(|| tagged_file.first_tag());
+    /// Fills what an untagged file is missing from its name and the folders above it, using the
+    /// first of `patterns` that matches. The file stem becomes the title when none do.
+    pub fn fill_from_path(&mut self, path: &Path, patterns: &[String]) {
+        if self.title.as_deref().is_some_and(|t| !t.trim().is_empty()) {
+            return;
+        }
+        let fields = patterns
+            .iter()
+            .find_map(|pattern| match_path_pattern(path, pattern))
+            .unwrap_or_default();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(field, _)| *field == name)
+                .map(|(_, value)| value.clone())
+        };
 
-        Ok(extract_metadata_from_tag(tag, duration))
+        self.title =
+            field("title").or_else(|| path.file_stem().map(|s| s.to_string_lossy().to_string()));
+        if self.artists.is_empty() {
+            self.artists = field("artist").into_iter().collect();
+        }
+        if self.album.is_none() {
+            self.album = field("album");
+        }
+        if self.track_number.is_none() {
+            self.track_number = field("track").and_then(|n| n.parse().ok());
+        }
+        self.from_filename = true;
     }
+
+    /// Builds a `Song` for a file that is not in the library so it can sit in the queue.
+    pub fn into_transient_song(self, path: &Path) -> Song {
+        let title = self.title.unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        let file_size = std::fs::metadata(path).map(|m| m.len() as i64).unwrap_or(0);
+
+        Song {
+            id: Cuid::new(),
+            title,
+            artists: self.artists,
+            album_id: None,
+            file_path: path.to_string_lossy().to_string(),
+            file_size,
+            file_modified: 0,
+            genres: self.genres,
+            date: self.year.map(|y| y.to_string()),
+            duration: self.duration.as_secs() as i32,
+            image_id: None,
+            track_number: self.track_number.map(|n| n as i32),
+            disc_number: self.disc_number.map(|n| n as i32),
+            favorite: false,
+            lufs: self.lufs,
+            track_peak: self.track_peak,
+            computed_lufs: None,
+            computed_peak: None,
+            gain_offset_db: 0.0,
+            pinned: false,
+            date_added: String::new(),
+            date_updated: String::new(),
+            start_secs: 0.0,
+            end_secs: None,
+            unavailable: false,
+        }
+    }
+}
+
+enum PatternToken<'a> {
+    Literal(&'a str),
+    Field(&'a str),
+}
+
+fn tokenize_pattern(segment: &str) -> Vec<PatternToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = segment;
+    while !rest.is_empty() {
+        let field = rest
+            .strip_prefix('{')
+            .and_then(|inner| inner.split_once('}'));
+        match field {
+            Some((name, after)) => {
+                tokens.push(PatternToken::Field(name));
+                rest = after;
+            }
+            None => {
+                let end = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|(_, c)| *c == '{')
+                    .map_or(rest.len(), |(i, _)| i);
+                tokens.push(PatternToken::Literal(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+    }
+    tokens
+}
+
+// Placeholders match as little as they can, so `{artist} - {title}` splits on the first
+// separator. `{track}` only matches digits.
+fn match_tokens<'a>(
+    tokens: &[PatternToken<'a>],
+    text: &str,
+    fields: &mut Vec<(&'a str, String)>,
+) -> bool {
+    match tokens.split_first() {
+        None => text.is_empty(),
+        Some((PatternToken::Literal(literal), rest)) => text
+            .strip_prefix(*literal)
+            .is_some_and(|text| match_tokens(rest, text, fields)),
+        Some((PatternToken::Field(name), rest)) => {
+            let ends = text
+                .char_indices()
+                .skip(1)
+                .map(|(i, _)| i)
+                .chain([text.len()]);
+            for end in ends {
+                let value = text[..end].trim();
+                if *name == "track" && !value.chars().all(|c| c.is_ascii_digit()) {
+                    break;
+                }
+                if value.is_empty() {
+                    continue;
+                }
+                fields.push((*name, value.to_string()));
+                if match_tokens(rest, &text[end..], fields) {
+                    return true;
+                }
+                fields.pop();
+            }
+            false
+        }
+    }
+}
+
+/// Matches a `/`-separated pattern against the end of `path`, the last segment taking the file
+/// stem and each earlier one a parent folder.
+fn match_path_pattern<'a>(path: &Path, pattern: &'a str) -> Option<Vec<(&'a str, String)>> {
+    let segments: Vec<&str> = pattern.split('/').collect();
+    let mut names = vec![path.file_stem()?.to_str()?];
+    let mut dir = path.parent();
+    for _ in 1..segments.len() {
+        let parent = dir?;
+        names.push(parent.file_name()?.to_str()?);
+        dir = parent.parent();
+    }
+    names.reverse();
+
+    let mut fields = Vec::new();
+    segments
+        .into_iter()
+        .zip(names)
+        .all(|(segment, name)| match_tokens(&tokenize_pattern(segment), name, &mut fields))
+        .then_some(fields)
 }
 
 pub fn read_metadata_and_image(path: &Path) -> Result<(AudioMetadata, Option<ImageData>)> {
-    let tagged_file = open_probe(path)?.guess_file_type()?.read()?;
+    let (tagged_file, mp4_codec) = read_tagged_file(path, ParseOptions::new())?;
 
-    let duration = tagged_file.properties().duration();
+    let metadata = extract_metadata(&tagged_file, mp4_codec.as_ref());
     let tag = tagged_file
         .primary_tag()
         .or_else(|| tagged_file.first_tag());
 
-    let metadata = extract_metadata_from_tag(tag, duration);
-
     let image_data = tag.and_then(|tag| {
         tag.pictures()
             .iter()
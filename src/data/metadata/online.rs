@@ -0,0 +1,299 @@
+use anyhow::{Context, Result};
+use lofty::config::WriteOptions;
+use lofty::file::{AudioFile, TaggedFileExt};
+use lofty::tag::{Accessor, ItemKey, Tag};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use ureq::Agent;
+
+use crate::data::models::Song;
+
+const API_URL: &str = "https://musicbrainz.org/ws/2";
+const SEARCH_LIMIT: &str = "5";
+const MIN_SEARCH_SCORE: u32 = 70;
+// MusicBrainz asks clients to stay under one request per second.
+const REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+const LENGTH_TOLERANCE_SECS: i32 = 5;
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    releases: Vec<SearchRelease>,
+}
+
+#[derive(Deserialize)]
+struct SearchRelease {
+    id: String,
+    #[serde(default)]
+    score: u32,
+    #[serde(rename = "track-count", default)]
+    track_count: usize,
+}
+
+#[derive(Deserialize)]
+struct ReleaseResponse {
+    id: String,
+    title: String,
+    date: Option<String>,
+    #[serde(rename = "artist-credit", default)]
+    artist_credit: Vec<ArtistCredit>,
+    #[serde(default)]
+    media: Vec<Medium>,
+}
+
+#[derive(Deserialize)]
+struct ArtistCredit {
+    name: String,
+    #[serde(default)]
+    joinphrase: String,
+}
+
+#[derive(Deserialize)]
+struct Medium {
+    position: u32,
+    #[serde(default)]
+    tracks: Vec<MediumTrack>,
+}
+
+#[derive(Deserialize)]
+struct MediumTrack {
+    position: u32,
+    title: String,
+    length: Option<u64>,
+    #[serde(rename = "artist-credit", default)]
+    artist_credit: Vec<ArtistCredit>,
+}
+
+/// A release as MusicBrainz lists it.
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub id: String,
+    pub title: String,
+    pub artist: String,
+    pub date: Option<String>,
+    pub tracks: Vec<ReleaseTrack>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReleaseTrack {
+    pub title: String,
+    pub artist: String,
+    pub disc: u32,
+    pub number: u32,
+    pub length_secs: Option<i32>,
+}
+
+/// Tags a library file gets once the matched release is confirmed.
+#[derive(Debug, Clone)]
+pub struct PlannedRetag {
+    pub path: PathBuf,
+    pub old_title: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub album_artist: String,
+    pub track_number: u32,
+    pub disc_number: u32,
+    pub date: Option<String>,
+    pub release_id: String,
+}
+
+fn agent() -> Agent {
+    Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(15)))
+        .user_agent(concat!(
+            "Vleer/",
+            env!("CARGO_PKG_VERSION"),
+            " ( https://vleer.app )"
+        ))
+        .build()
+        .into()
+}
+
+// Inside a quoted Lucene phrase only quotes and backslashes need escaping.
+fn escape_phrase(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn credit_name(credits: &[ArtistCredit]) -> String {
+    credits
+        .iter()
+        .map(|credit| format!("{}{}", credit.name, credit.joinphrase))
+        .collect()
+}
+
+/// Looks up the release that best fits an album's current tags and track count.
+///
+/// Returns `None` when MusicBrainz has nothing that matches closely enough.
+pub fn find_release(
+    album: &str,
+    artist: Option<&str>,
+    track_count: usize,
+) -> Result<Option<Release>> {
+    let agent = agent();
+    let mut query = format!("release:\"{}\"", escape_phrase(album));
+    if let Some(artist) = artist {
+        query.push_str(&format!(" AND artist:\"{}\"", escape_phrase(artist)));
+    }
+
+    let search: SearchResponse = agent
+        .get(format!("{API_URL}/release"))
+        .query("query", &query)
+        .query("fmt", "json")
+        .query("limit", SEARCH_LIMIT)
+        .call()
+        .context("searching MusicBrainz")?
+        .body_mut()
+        .read_json()
+        .context("parsing MusicBrainz search results")?;
+
+    let Some(best) = search
+        .releases
+        .into_iter()
+        .filter(|release| release.score >= MIN_SEARCH_SCORE)
+        .max_by_key(|release| (release.track_count == track_count, release.score))
+    else {
+        return Ok(None);
+    };
+
+    std::thread::sleep(REQUEST_INTERVAL);
+
+    let release: ReleaseResponse = agent
+        .get(format!("{API_URL}/release/{}", best.id))
+        .query("inc", "recordings artist-credits")
+        .query("fmt", "json")
+        .call()
+        .context("fetching MusicBrainz release")?
+        .body_mut()
+        .read_json()
+        .context("parsing MusicBrainz release")?;
+
+    let artist = credit_name(&release.artist_credit);
+    let tracks = release
+        .media
+        .iter()
+        .flat_map(|medium| {
+            medium.tracks.iter().map(|track| ReleaseTrack {
+                title: track.title.clone(),
+                artist: if track.artist_credit.is_empty() {
+                    artist.clone()
+                } else {
+                    credit_name(&track.artist_credit)
+                },
+                disc: medium.position,
+                number: track.position,
+                length_secs: track.length.map(|ms| (ms / 1000) as i32),
+            })
+        })
+        .collect();
+
+    Ok(Some(Release {
+        id: release.id,
+        title: release.title,
+        artist,
+        date: release.date.filter(|date| !date.is_empty()),
+        tracks,
+    }))
+}
+
+fn lengths_agree(song: &Song, track: &ReleaseTrack) -> bool {
+    track
+        .length_secs
+        .is_none_or(|length| (length - song.duration).abs() <= LENGTH_TOLERANCE_SECS)
+}
+
+/// Pairs each song with a track of `release` and works out its new tags.
+///
+/// Songs are matched on disc and track number first, then on length for whatever is left.
+/// Songs that fit no track are left out, so they keep their current tags. So are cue sheet
+/// tracks: they share one file and take their tags from the sheet.
+pub fn plan_retag(songs: &[Song], release: &Release) -> Vec<PlannedRetag> {
+    let mut taken: HashSet<usize> = HashSet::new();
+    let mut matches: Vec<(usize, usize)> = Vec::new();
+    let songs: Vec<&Song> = songs.iter().filter(|song| !song.is_cue_track()).collect();
+
+    for (song_index, song) in songs.iter().enumerate() {
+        let Some(number) = song.track_number else {
+            continue;
+        };
+        let disc = song.disc_number.unwrap_or(1);
+        let found = release.tracks.iter().position(|track| {
+            track.disc as i32 == disc && track.number as i32 == number && lengths_agree(song, track)
+        });
+        if let Some(track_index) = found
+            && taken.insert(track_index)
+        {
+            matches.push((song_index, track_index));
+        }
+    }
+
+    for (song_index, song) in songs.iter().enumerate() {
+        if matches.iter().any(|(matched, _)| *matched == song_index) {
+            continue;
+        }
+        let found = release
+            .tracks
+            .iter()
+            .enumerate()
+            .filter(|(index, track)| !taken.contains(index) && track.length_secs.is_some())
+            .filter(|(_, track)| lengths_agree(song, track))
+            .min_by_key(|(_, track)| (track.length_secs.unwrap_or_default() - song.duration).abs());
+        if let Some((track_index, _)) = found {
+            taken.insert(track_index);
+            matches.push((song_index, track_index));
+        }
+    }
+
+    matches.sort_by_key(|(_, track_index)| *track_index);
+    matches
+        .into_iter()
+        .map(|(song_index, track_index)| {
+            let song = songs[song_index];
+            let track = &release.tracks[track_index];
+            PlannedRetag {
+                path: PathBuf::from(&song.file_path),
+                old_title: song.title.clone(),
+                title: track.title.clone(),
+                artist: track.artist.clone(),
+                album: release.title.clone(),
+                album_artist: release.artist.clone(),
+                track_number: track.number,
+                disc_number: track.disc,
+                date: release.date.clone(),
+                release_id: release.id.clone(),
+            }
+        })
+        .collect()
+}
+
+/// Writes `planned` into the file's primary tag, adding one if the file has none.
+pub fn write_tags(planned: &PlannedRetag) -> Result<()> {
+    let mut tagged_file = super::open_probe(&planned.path)?
+        .guess_file_type()?
+        .read()?;
+
+    if tagged_file.primary_tag().is_none() {
+        let tag_type = tagged_file.primary_tag_type();
+        tagged_file.insert_tag(Tag::new(tag_type));
+    }
+    let tag = tagged_file
+        .primary_tag_mut()
+        .context("File type does not support tags")?;
+
+    tag.set_title(planned.title.clone());
+    tag.set_artist(planned.artist.clone());
+    tag.set_album(planned.album.clone());
+    tag.insert_text(ItemKey::AlbumArtist, planned.album_artist.clone());
+    tag.set_track(planned.track_number);
+    tag.set_disk(planned.disc_number);
+    if let Some(date) = &planned.date {
+        tag.insert_text(ItemKey::RecordingDate, date.clone());
+    }
+    tag.insert_text(ItemKey::MusicBrainzReleaseId, planned.release_id.clone());
+
+    tagged_file
+        .save_to_path(&planned.path, WriteOptions::default())
+        .with_context(|| format!("Failed to write tags to {:?}", planned.path))
+}
@@ -1,6 +1,9 @@
 pub mod config;
+pub mod cue;
 pub mod db;
+pub mod fs;
 pub mod metadata;
 pub mod models;
+pub mod organize;
 pub mod scanner;
 pub mod telemetry;
@@ -3,6 +3,7 @@ use crate::data::db::models::{
     PinnedItemRow, PlaylistListRow, PlaylistRow, PlaylistTrackRow, SearchResultRow, SongListRow,
     SongRow,
 };
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -60,11 +61,64 @@ pub struct Song {
     pub duration: i32,
     pub image_id: Option<String>,
     pub track_number: Option<i32>,
+    pub disc_number: Option<i32>,
     pub favorite: bool,
     pub lufs: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub computed_lufs: Option<f32>,
+    pub computed_peak: Option<f32>,
+    pub gain_offset_db: f32,
     pub pinned: bool,
     pub date_added: String,
     pub date_updated: String,
+    /// Where the song starts in its file, past 0 for cue sheet tracks.
+    pub start_secs: f32,
+    /// Where the song stops in its file, `None` for the end of the file.
+    pub end_secs: Option<f32>,
+    /// The file was gone at the last scan but the song was kept.
+    pub unavailable: bool,
+}
+
+impl Song {
+    /// Whether the song is one of the tracks a cue sheet splits its file into, so it shares the
+    /// file with the others.
+    pub fn is_cue_track(&self) -> bool {
+        self.start_secs > 0.0 || self.end_secs.is_some()
+    }
+}
+
+/// The stretch of a file a song covers. Whole files use the default; the tracks of a cue sheet
+/// share one file and are told apart by their number.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SongSegment {
+    pub cue_track: i32,
+    pub start_secs: f32,
+    pub end_secs: Option<f32>,
+}
+
+/// What a scan stores for one song. Songs are matched by file and segment, so storing the same
+/// stretch of a file again updates it.
+#[derive(Debug, Clone, Default)]
+pub struct SongUpsert<'a> {
+    pub title: &'a str,
+    pub artists: &'a [&'a str],
+    pub album_id: Option<&'a Cuid>,
+    pub file_path: &'a str,
+    pub duration: i32,
+    pub track_number: Option<i32>,
+    pub track_total: Option<i32>,
+    pub disc_number: Option<i32>,
+    pub year: Option<i32>,
+    pub genres: &'a [&'a str],
+    pub image_id: Option<&'a str>,
+    pub file_size: i64,
+    pub file_modified: i64,
+    pub lufs: Option<f32>,
+    pub track_peak: Option<f32>,
+    /// The title and artist were guessed from the file's path, so it can be found and tagged
+    /// later.
+    pub metadata_from_filename: bool,
+    pub segment: SongSegment,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -77,6 +131,9 @@ pub struct SongListItem {
     pub duration: i32,
     pub image_id: Option<String>,
     pub genres: Option<String>,
+    /// When the song last started playing, as a UTC timestamp; `None` if it never has.
+    pub last_played: Option<String>,
+    pub unavailable: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -86,6 +143,17 @@ pub enum SongSort {
     Album,
     Duration,
     Genre,
+    /// Newest plays first when descending; never played songs come first when ascending.
+    LastPlayed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlbumSort {
+    #[default]
+    Title,
+    Year,
+    Artist,
+    DateAdded,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,6 +180,22 @@ pub struct ArtistListItem {
     pub id: Cuid,
     pub name: String,
     pub image_id: Option<String>,
+    pub album_count: i64,
+    pub song_count: i64,
+}
+
+/// Reduces an artist name to what duplicate detection compares: lowercase letters and digits
+/// with a leading "The " dropped, so "The Beatles" and "beatles" match.
+pub fn artist_match_key(name: &str) -> String {
+    let name = name.trim();
+    let name = match name.get(..4) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("the ") => &name[4..],
+        _ => name,
+    };
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -129,6 +213,7 @@ pub struct PlaylistListItem {
     pub name: String,
     pub image_id: Option<String>,
     pub song_count: i64,
+    pub duration_secs: i64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,6 +260,8 @@ pub enum EventType {
     Stop,
     Pause,
     Resume,
+    /// Moved on by hand before the song got going, see `Playback::next`.
+    Skip,
 }
 
 #[derive(Debug, Clone)]
@@ -194,6 +281,95 @@ pub enum RecentItem {
     },
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedQueueItem {
+    pub song_id: Cuid,
+    pub file_path: Option<String>,
+}
+
+/// Queue and playback position as left at the end of a session.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SavedQueue {
+    pub items: Vec<SavedQueueItem>,
+    pub current_index: Option<usize>,
+    pub position_secs: f32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LibraryTotals {
+    pub songs: i64,
+    pub albums: i64,
+    pub artists: i64,
+    pub playlists: i64,
+    pub duration_secs: i64,
+    pub file_size: i64,
+    pub db_size: i64,
+}
+
+/// One completed library scan, as shown in the scan history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanRun {
+    pub finished_at: String,
+    pub scanned: i64,
+    pub added: i64,
+    pub updated: i64,
+    pub removed: i64,
+    pub missing: i64,
+}
+
+/// A song skipped on more than half of its plays, with its counts from the kept history.
+#[derive(Debug, Clone)]
+pub struct SkippedSong {
+    pub song: Song,
+    pub skips: i64,
+    pub plays: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecentGroup {
+    Today,
+    ThisWeek,
+    ThisMonth,
+    Earlier,
+}
+
+impl RecentGroup {
+    pub const ALL: [RecentGroup; 4] = [
+        RecentGroup::Today,
+        RecentGroup::ThisWeek,
+        RecentGroup::ThisMonth,
+        RecentGroup::Earlier,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RecentGroup::Today => "Today",
+            RecentGroup::ThisWeek => "This Week",
+            RecentGroup::ThisMonth => "This Month",
+            RecentGroup::Earlier => "Earlier",
+        }
+    }
+
+    pub fn from_date_added(date_added: Option<&str>, today: NaiveDate) -> Self {
+        let Some(date) = date_added
+            .and_then(|d| NaiveDateTime::parse_from_str(d, "%Y-%m-%d %H:%M:%S").ok())
+            .map(|dt| dt.and_utc().with_timezone(&Local).date_naive())
+        else {
+            return RecentGroup::Earlier;
+        };
+
+        if date == today {
+            RecentGroup::Today
+        } else if date.iso_week() == today.iso_week() {
+            RecentGroup::ThisWeek
+        } else if date.year() == today.year() && date.month() == today.month() {
+            RecentGroup::ThisMonth
+        } else {
+            RecentGroup::Earlier
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct PinnedItem {
     pub id: Cuid,
@@ -217,11 +393,19 @@ impl From<SongRow> for Song {
             duration: row.duration,
             image_id: row.image_id,
             track_number: row.track_number,
+            disc_number: row.disc_number,
             favorite: row.favorite,
             lufs: row.lufs,
+            track_peak: row.track_peak,
+            computed_lufs: row.computed_lufs,
+            computed_peak: row.computed_peak,
+            gain_offset_db: row.gain_offset_db,
             pinned: row.pinned,
             date_added: row.date_added,
             date_updated: row.date_updated,
+            start_secs: row.start_secs,
+            end_secs: row.end_secs,
+            unavailable: row.unavailable,
         }
     }
 }
@@ -299,6 +483,8 @@ impl From<SongListRow> for SongListItem {
             duration: row.duration,
             image_id: row.image_id,
             genres: row.genres,
+            last_played: row.last_played,
+            unavailable: row.unavailable,
         }
     }
 }
@@ -309,6 +495,8 @@ impl From<ArtistListRow> for ArtistListItem {
             id: row.id,
             name: row.name,
             image_id: row.image_id,
+            album_count: row.album_count,
+            song_count: row.song_count,
         }
     }
 }
@@ -320,6 +508,7 @@ impl From<PlaylistListRow> for PlaylistListItem {
             name: row.name,
             image_id: row.image_id,
             song_count: row.song_count,
+            duration_secs: row.duration_secs,
         }
     }
 }
@@ -345,6 +534,7 @@ impl From<EventRow> for Event {
                 "STOP" => EventType::Stop,
                 "PAUSE" => EventType::Pause,
                 "RESUME" => EventType::Resume,
+                "SKIP" => EventType::Skip,
                 other => {
                     tracing::error!("Unknown event type in DB: {}; defaulting to PLAY", other);
                     EventType::Play
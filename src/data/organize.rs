@@ -0,0 +1,242 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::data::config::ImportMode;
+use crate::data::cue;
+use crate::data::fs::trash;
+use crate::data::metadata::AudioMetadata;
+
+const MAX_COMPONENT_LEN: usize = 180;
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Expands `pattern` for a track into a path relative to the library root.
+///
+/// Supported placeholders: `{artist}`, `{album}`, `{title}`, `{track}`, `{year}` and
+/// `{genre}`. `/` separates folders; each folder and the file name are sanitized separately.
+pub fn render_path(pattern: &str, metadata: &AudioMetadata, source: &Path) -> PathBuf {
+    let artist = metadata
+        .artists
+        .first()
+        .cloned()
+        .unwrap_or_else(|| "Unknown Artist".to_string());
+    let album = metadata
+        .album
+        .clone()
+        .unwrap_or_else(|| "Unknown Album".to_string());
+    let title = metadata.title.clone().unwrap_or_else(|| {
+        source
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Unknown Title".to_string())
+    });
+    let track = metadata
+        .track_number
+        .map(|n| format!("{:02}", n))
+        .unwrap_or_default();
+    let year = metadata.year.map(|y| y.to_string()).unwrap_or_default();
+    let genre = metadata.genres.first().cloned().unwrap_or_default();
+
+    let mut path = PathBuf::new();
+    let components: Vec<String> = pattern
+        .split(['/', '\\'])
+        .map(|component| {
+            let expanded = component
+                .replace("{artist}", &artist)
+                .replace("{album}", &album)
+                .replace("{title}", &title)
+                .replace("{track}", &track)
+                .replace("{year}", &year)
+                .replace("{genre}", &genre);
+            sanitize_component(&expanded)
+        })
+        .filter(|component| !component.is_empty())
+        .collect();
+
+    let last = components.len().saturating_sub(1);
+    for (i, component) in components.into_iter().enumerate() {
+        if i == last {
+            let file_name = match source.extension() {
+                Some(ext) => format!("{}.{}", component, ext.to_string_lossy().to_lowercase()),
+                None => component,
+            };
+            path.push(file_name);
+        } else {
+            path.push(component);
+        }
+    }
+    path
+}
+
+pub fn sanitize_component(component: &str) -> String {
+    let replaced: String = component
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let mut cleaned = replaced.trim().trim_end_matches('.').trim_end().to_string();
+    if cleaned.chars().all(|c| c == '.') {
+        cleaned = cleaned.replace('.', "_");
+    }
+
+    if cleaned.len() > MAX_COMPONENT_LEN {
+        let mut end = MAX_COMPONENT_LEN;
+        while !cleaned.is_char_boundary(end) {
+            end -= 1;
+        }
+        cleaned.truncate(end);
+        cleaned = cleaned.trim_end().to_string();
+    }
+
+    let stem = cleaned.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(stem)) {
+        cleaned.push('_');
+    }
+    cleaned
+}
+
+/// Returns `path`, or `path` with ` (2)`, ` (3)`, ... appended to the stem if it is taken.
+pub fn unique_destination(path: PathBuf) -> PathBuf {
+    unique_destination_with(path, |candidate| candidate.exists())
+}
+
+fn unique_destination_with(path: PathBuf, is_taken: impl Fn(&Path) -> bool) -> PathBuf {
+    if !is_taken(&path) {
+        return path;
+    }
+
+    let parent = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path.extension().map(|e| e.to_string_lossy().to_string());
+
+    (2..)
+        .map(|n| {
+            let name = match &ext {
+                Some(ext) => format!("{} ({}).{}", stem, n, ext),
+                None => format!("{} ({})", stem, n),
+            };
+            parent.join(name)
+        })
+        .find(|candidate| !is_taken(candidate))
+        .expect("unbounded range always yields a free name")
+}
+
+/// Moves `source` to `destination`, creating missing folders on the way.
+pub fn relocate(source: &Path, destination: &Path) -> Result<()> {
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create folder {:?}", parent))?;
+    }
+    move_file(source, destination)
+}
+
+fn move_file(source: &Path, destination: &Path) -> Result<()> {
+    if fs::rename(source, destination).is_ok() {
+        return Ok(());
+    }
+    fs::copy(source, destination)
+        .with_context(|| format!("Failed to copy {:?} to {:?}", source, destination))?;
+    trash(source)
+}
+
+/// Copies or moves `source` into `root` following `pattern` and returns the new location.
+///
+/// `ImportMode::InPlace` leaves the file where it is.
+pub fn place_file(
+    source: &Path,
+    root: &Path,
+    pattern: &str,
+    metadata: &AudioMetadata,
+    mode: ImportMode,
+) -> Result<PathBuf> {
+    if mode == ImportMode::InPlace {
+        return Ok(source.to_path_buf());
+    }
+
+    let target = root.join(render_path(pattern, metadata, source));
+    if target == source {
+        return Ok(target);
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create folder {:?}", parent))?;
+    }
+    let destination = unique_destination(target);
+
+    match mode {
+        ImportMode::Copy => {
+            fs::copy(source, &destination)
+                .with_context(|| format!("Failed to copy {:?} to {:?}", source, destination))?;
+        }
+        ImportMode::Move => move_file(source, &destination)?,
+        ImportMode::InPlace => {}
+    }
+
+    Ok(destination)
+}
+
+#[derive(Debug, Clone)]
+pub struct PlannedMove {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Works out where every file in `paths` belongs under `root` without touching the disk.
+///
+/// Files that are already in place are left out; files whose tags cannot be read are skipped.
+/// Files split by a cue sheet stay put too, since moving them would break the sheet's `FILE`
+/// line and collapse the tracks back into one song on the next scan.
+pub fn plan_library(paths: &[String], root: &Path, pattern: &str) -> Vec<PlannedMove> {
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+    let mut moves = Vec::new();
+
+    for path in paths {
+        let from = PathBuf::from(path);
+        if !from.is_file() {
+            continue;
+        }
+        if cue::find_for(&from).is_some() {
+            claimed.insert(from);
+            continue;
+        }
+
+        let metadata = match AudioMetadata::from_path_with_options(&from, false) {
+            Ok(m) => m,
+            Err(e) => {
+                warn!("Failed to read metadata from {:?}: {}", from, e);
+                continue;
+            }
+        };
+
+        let target = root.join(render_path(pattern, &metadata, &from));
+        if target == from {
+            claimed.insert(target);
+            continue;
+        }
+
+        let to = unique_destination_with(target, |candidate| {
+            claimed.contains(candidate) || (candidate != from && candidate.exists())
+        });
+        if to == from {
+            claimed.insert(to);
+            continue;
+        }
+        claimed.insert(to.clone());
+        moves.push(PlannedMove { from, to });
+    }
+
+    moves
+}
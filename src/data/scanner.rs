@@ -3,34 +3,41 @@ use futures::channel::mpsc;
 use futures::lock::Mutex as AsyncMutex;
 use futures::stream::{self, StreamExt};
 use gpui::{App, BackgroundExecutor, Global};
-use notify::{EventKind, RecursiveMode};
-use notify_debouncer_full::{DebounceEventResult, Debouncer, RecommendedCache, new_debouncer};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecursiveMode, Watcher};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::time::Duration;
 use std::time::UNIX_EPOCH;
 use tracing::{debug, error, info, warn};
 use walkdir::WalkDir;
 
-use crate::data::config::Config;
+use crate::data::config::{Config, ImportMode, MissingSongsPolicy, OrganizeSettings};
+use crate::data::cue;
 use crate::data::db::repo::Database;
+use crate::data::metadata::online::{self, PlannedRetag};
 use crate::data::metadata::{
     AudioMetadata, ImageData, extract_image_data, read_metadata_and_image,
 };
-use crate::data::models::Cuid;
+use crate::data::models::{Cuid, SongSegment, SongUpsert};
+use crate::data::organize::{self, PlannedMove};
 use crate::data::telemetry::Telemetry;
+use crate::media::{loudness, transcode};
 use crate::ui::components::context_menu::{BackgroundUiEvent, BackgroundUiNotifier};
 use crate::ui::layout::navbar;
 
-type FsWatcher = Debouncer<notify::RecommendedWatcher, RecommendedCache>;
+type FsWatcher = notify::RecommendedWatcher;
 
 const SUPPORTED_EXTENSIONS: &[&str] = &[
     "aac", "aiff", "aif", "flac", "mp3", "mp4", "m4a", "mp4a", "ogg", "oga", "opus", "wav", "wv",
 ];
-const MAX_CONCURRENT_SCANS: usize = 4;
-const IO_POOL_THREADS: usize = 4;
+const MAX_SCAN_CONCURRENCY: usize = 32;
+const MIN_WATCH_DEBOUNCE_MS: u64 = 100;
+const PARTIAL_FILE_SETTLE: Duration = Duration::from_millis(750);
+// Sized for the highest setting; the scan's `buffer_unordered` decides how many are busy.
+const IO_POOL_THREADS: usize = MAX_SCAN_CONCURRENCY;
 
 type IoJob = Box<dyn FnOnce() + Send + 'static>;
 
@@ -65,6 +72,13 @@ fn io_pool() -> &'static std::sync::mpsc::Sender<IoJob> {
     })
 }
 
+/// How many files a scan reads at once: the configured count, or one per CPU core.
+fn scan_concurrency(configured: Option<usize>) -> usize {
+    configured
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(4, |n| n.get()))
+        .clamp(1, MAX_SCAN_CONCURRENCY)
+}
+
 fn io_spawn<F, T>(f: F) -> futures::channel::oneshot::Receiver<T>
 where
     F: FnOnce() -> T + Send + 'static,
@@ -108,11 +122,15 @@ pub struct ScannedTrack {
     pub file_modified: i64,
     pub metadata: AudioMetadata,
     pub image_data: Option<ImageData>,
+    /// One entry per track when a cue sheet splits the file, empty otherwise.
+    pub cue_tracks: Vec<(SongSegment, AudioMetadata)>,
 }
 
 #[derive(Default)]
 struct ScanOptions {
     force: bool,
+    /// Limits the scan to this folder. Songs outside it are left alone.
+    root: Option<PathBuf>,
 }
 
 #[derive(Clone)]
@@ -124,12 +142,42 @@ pub struct Scanner {
     scan_generation: Arc<AtomicU64>,
     pending_changed_paths: Arc<AsyncMutex<HashSet<PathBuf>>>,
     incremental_worker_running: Arc<AtomicBool>,
+    watch_timing: Arc<WatchTiming>,
+    detect_transcodes: Arc<AtomicBool>,
+    include_hidden: Arc<AtomicBool>,
+    transcode_check_running: Arc<AtomicBool>,
+    measurement_running: Arc<AtomicBool>,
+    missing_policy: Arc<std::sync::RwLock<MissingSongsPolicy>>,
+    filename_patterns: Arc<std::sync::RwLock<Vec<String>>>,
+    concurrency: Arc<AtomicUsize>,
     executor: BackgroundExecutor,
     background_ui: Option<BackgroundUiNotifier>,
 }
 
 impl Global for Scanner {}
 
+struct WatchTiming {
+    debounce_ms: AtomicU64,
+    max_wait_ms: AtomicU64,
+}
+
+impl WatchTiming {
+    fn set(&self, debounce_ms: u64, max_wait_ms: u64) {
+        let debounce_ms = debounce_ms.max(MIN_WATCH_DEBOUNCE_MS);
+        self.debounce_ms.store(debounce_ms, Ordering::Relaxed);
+        self.max_wait_ms
+            .store(max_wait_ms.max(debounce_ms), Ordering::Relaxed);
+    }
+
+    fn debounce(&self) -> Duration {
+        Duration::from_millis(self.debounce_ms.load(Ordering::Relaxed))
+    }
+
+    fn max_wait(&self) -> Duration {
+        Duration::from_millis(self.max_wait_ms.load(Ordering::Relaxed))
+    }
+}
+
 impl Scanner {
     pub fn new(
         scan_paths: Vec<PathBuf>,
@@ -144,6 +192,17 @@ impl Scanner {
             scan_generation: Arc::new(AtomicU64::new(0)),
             pending_changed_paths: Arc::new(AsyncMutex::new(HashSet::new())),
             incremental_worker_running: Arc::new(AtomicBool::new(false)),
+            watch_timing: Arc::new(WatchTiming {
+                debounce_ms: AtomicU64::new(2000),
+                max_wait_ms: AtomicU64::new(30_000),
+            }),
+            detect_transcodes: Arc::new(AtomicBool::new(false)),
+            include_hidden: Arc::new(AtomicBool::new(false)),
+            transcode_check_running: Arc::new(AtomicBool::new(false)),
+            measurement_running: Arc::new(AtomicBool::new(false)),
+            missing_policy: Arc::new(std::sync::RwLock::new(MissingSongsPolicy::default())),
+            filename_patterns: Arc::new(std::sync::RwLock::new(Vec::new())),
+            concurrency: Arc::new(AtomicUsize::new(scan_concurrency(None))),
             executor,
             background_ui,
         }
@@ -156,6 +215,29 @@ impl Scanner {
             .unwrap_or_default()
     }
 
+    fn set_missing_policy(&self, policy: MissingSongsPolicy) {
+        if let Ok(mut current) = self.missing_policy.write() {
+            *current = policy;
+        }
+    }
+
+    fn missing_policy(&self) -> MissingSongsPolicy {
+        self.missing_policy.read().map(|p| *p).unwrap_or_default()
+    }
+
+    fn set_filename_patterns(&self, patterns: &[String]) {
+        if let Ok(mut current) = self.filename_patterns.write() {
+            *current = patterns.to_vec();
+        }
+    }
+
+    fn filename_patterns(&self) -> Vec<String> {
+        self.filename_patterns
+            .read()
+            .map(|p| p.clone())
+            .unwrap_or_default()
+    }
+
     fn install_watcher(&self, watcher: FsWatcher) {
         if let Ok(mut slot) = self.watcher.lock() {
             *slot = Some(watcher);
@@ -252,6 +334,24 @@ impl Scanner {
         let scan_paths = expand_scan_paths(&config.get().scan.paths);
         let scanner = Scanner::new(scan_paths, executor.clone(), background_ui.clone());
 
+        let scan_settings = &config.get().scan;
+        scanner.watch_timing.set(
+            scan_settings.watch_debounce_ms,
+            scan_settings.watch_max_wait_ms,
+        );
+        scanner
+            .detect_transcodes
+            .store(scan_settings.detect_transcodes, Ordering::Relaxed);
+        scanner
+            .include_hidden
+            .store(scan_settings.include_hidden, Ordering::Relaxed);
+        scanner.set_missing_policy(scan_settings.remove_missing);
+        scanner.set_filename_patterns(&scan_settings.filename_patterns);
+        scanner.concurrency.store(
+            scan_concurrency(scan_settings.concurrency),
+            Ordering::Relaxed,
+        );
+
         cx.set_global(scanner.clone());
 
         let scanner = Arc::new(scanner);
@@ -263,7 +363,25 @@ impl Scanner {
         let last_paths: Arc<std::sync::Mutex<Vec<PathBuf>>> =
             Arc::new(std::sync::Mutex::new(scanner.get_scan_paths()));
         cx.observe_global::<Config>(move |cx| {
-            let new_paths = expand_scan_paths(&cx.global::<Config>().get().scan.paths);
+            let scan_settings = &cx.global::<Config>().get().scan;
+            scanner_for_observe
+                .watch_timing
+                .set(scan_settings.watch_debounce_ms, scan_settings.watch_max_wait_ms);
+            let was_detecting = scanner_for_observe
+                .detect_transcodes
+                .swap(scan_settings.detect_transcodes, Ordering::Relaxed);
+            if scan_settings.detect_transcodes && !was_detecting {
+                scanner_for_observe.spawn_transcode_check(db_for_observe.clone());
+            }
+            scanner_for_observe
+                .include_hidden
+                .store(scan_settings.include_hidden, Ordering::Relaxed);
+            scanner_for_observe.set_missing_policy(scan_settings.remove_missing);
+            scanner_for_observe.set_filename_patterns(&scan_settings.filename_patterns);
+            scanner_for_observe
+                .concurrency
+                .store(scan_concurrency(scan_settings.concurrency), Ordering::Relaxed);
+            let new_paths = expand_scan_paths(&scan_settings.paths);
             let (changed, removed_paths) = {
                 let mut last = match last_paths.lock() {
                     Ok(l) => l,
@@ -417,7 +535,7 @@ impl Scanner {
             .detach();
     }
 
-    fn is_audio_file(path: &Path) -> bool {
+    pub fn is_audio_file(path: &Path) -> bool {
         path.extension()
             .and_then(|ext| ext.to_str())
             .map(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
@@ -448,19 +566,20 @@ impl Scanner {
         force || existing_size != current_size || existing_modified != current_modified
     }
 
-    async fn collect_audio_files(&self) -> Result<Vec<PathBuf>> {
+    async fn collect_audio_files(&self, roots: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
         let mut all_files = Vec::new();
-        let scan_paths = self.get_scan_paths();
 
-        for root in scan_paths {
+        for root in roots {
             if self.is_cancelled() {
                 break;
             }
-            if !root.exists() || !root.is_dir() {
+            if let Some(reason) = scan_path_error(&root) {
+                warn!("Skipping scan path {:?}: {}", root, reason);
                 continue;
             }
 
             let cancel_flag = self.cancel_flag.clone();
+            let include_hidden = self.include_hidden.load(Ordering::Relaxed);
             let files = self
                 .executor
                 .spawn(async move {
@@ -468,6 +587,9 @@ impl Scanner {
                     for entry in WalkDir::new(&root)
                         .follow_links(false)
                         .into_iter()
+                        .filter_entry(|e| {
+                            include_hidden || e.depth() == 0 || !is_hidden_name(e.file_name())
+                        })
                         .filter_map(|e| e.ok())
                     {
                         if cancel_flag.load(Ordering::Acquire) {
@@ -503,38 +625,70 @@ impl Scanner {
         &self,
         db: &Database,
         scanned_files: &HashSet<String>,
+        scope: Option<&Path>,
     ) -> Result<Vec<String>> {
         let paths = self.collect_song_paths(db)?;
         Ok(paths
             .into_iter()
+            .filter(|p| scope.is_none_or(|scope| Path::new(p).starts_with(scope)))
             .filter(|p| !scanned_files.contains(p))
+            .filter(|p| !Path::new(p).exists())
             .collect())
     }
 
+    /// Applies the missing-songs policy and returns `(removed, kept)`. Kept songs are marked
+    /// unavailable. Under `OnlyInScanPaths`, a song is only removed when its scan path is
+    /// readable and the walk found audio in it, since an unmounted drive usually leaves an empty
+    /// mountpoint behind. A `scope` limits this to songs inside that folder.
     fn remove_missing_songs(
         &self,
         db: &Database,
         scanned_files: &HashSet<String>,
-    ) -> Result<usize> {
-        let stale_paths = self.find_missing_songs(db, scanned_files)?;
+        scope: Option<&Path>,
+    ) -> Result<(usize, usize)> {
+        let stale_paths = self.find_missing_songs(db, scanned_files, scope)?;
+
+        let (to_remove, to_keep): (Vec<String>, Vec<String>) = match self.missing_policy() {
+            MissingSongsPolicy::Never => (Vec::new(), stale_paths),
+            MissingSongsPolicy::Always => (stale_paths, Vec::new()),
+            MissingSongsPolicy::OnlyInScanPaths => {
+                let roots: Vec<PathBuf> = self
+                    .get_scan_paths()
+                    .into_iter()
+                    .filter(|root| scan_path_error(root).is_none())
+                    .filter(|root| scanned_files.iter().any(|f| Path::new(f).starts_with(root)))
+                    .collect();
+                stale_paths
+                    .into_iter()
+                    .partition(|p| roots.iter().any(|root| Path::new(p).starts_with(root)))
+            }
+        };
 
-        if stale_paths.is_empty() {
-            return Ok(0);
-        }
+        let removed = if to_remove.is_empty() {
+            0
+        } else {
+            db.delete_songs_by_path(&to_remove)?
+        };
+        db.set_unavailable_songs(&to_keep, scope)?;
 
-        warn!(
-            "{} songs are missing from disk but were kept in the library. Remove them manually in Settings if no longer needed.",
-            stale_paths.len()
-        );
-        for p in &stale_paths {
-            warn!("Missing: {}", p);
+        if removed > 0 {
+            info!("Removed {} songs missing from disk", removed);
+        }
+        if !to_keep.is_empty() {
+            warn!(
+                "{} songs are missing from disk and were marked unavailable",
+                to_keep.len()
+            );
+            for p in &to_keep {
+                warn!("Missing: {}", p);
+            }
         }
 
-        Ok(stale_paths.len())
+        Ok((removed, to_keep.len()))
     }
 
     pub async fn count_missing_songs(&self, db: &Database) -> usize {
-        let audio_files = match self.collect_audio_files().await {
+        let audio_files = match self.collect_audio_files(self.get_scan_paths()).await {
             Ok(f) => f,
             Err(_) => return 0,
         };
@@ -542,7 +696,7 @@ impl Scanner {
             .iter()
             .map(|p| p.to_string_lossy().to_string())
             .collect();
-        match self.find_missing_songs(db, &scanned_files) {
+        match self.find_missing_songs(db, &scanned_files, None) {
             Ok(paths) => paths.len(),
             Err(_) => 0,
         }
@@ -555,7 +709,11 @@ impl Scanner {
         let mut skipped = 0;
         let mut failed = 0;
 
-        let audio_files = self.collect_audio_files().await?;
+        let roots = match &options.root {
+            Some(root) => vec![root.clone()],
+            None => self.get_scan_paths(),
+        };
+        let audio_files = self.collect_audio_files(roots).await?;
         if self.is_cancelled() {
             info!("Scan cancelled before processing files");
             self.clear_scan_progress();
@@ -581,20 +739,24 @@ impl Scanner {
         });
 
         let force = options.force;
+        let filename_patterns = Arc::new(self.filename_patterns());
+        let concurrency = self.concurrency.load(Ordering::Relaxed);
 
         let songs_stream = stream::iter(audio_files)
             .map({
                 let existing_track_state = existing_track_state.clone();
                 move |path: PathBuf| {
                     let existing_track_state = existing_track_state.clone();
+                    let filename_patterns = filename_patterns.clone();
                     async move {
-                        let rx =
-                            io_spawn(move || process_one_file(path, &existing_track_state, force));
+                        let rx = io_spawn(move || {
+                            process_one_file(path, &existing_track_state, &filename_patterns, force)
+                        });
                         rx.await.ok().flatten()
                     }
                 }
             })
-            .buffer_unordered(MAX_CONCURRENT_SCANS)
+            .buffer_unordered(concurrency)
             .filter_map(|item| async move { item });
 
         futures::pin_mut!(songs_stream);
@@ -674,12 +836,18 @@ impl Scanner {
             });
         }
 
-        let missing = self.remove_missing_songs(db, &scanned_files)?;
+        let (removed, missing) =
+            self.remove_missing_songs(db, &scanned_files, options.root.as_deref())?;
 
         info!(
-            "Scan complete: {} scanned, {} added, {} updated, {} skipped, {} failed, {} missing",
-            scanned, added, updated, skipped, failed, missing
+            "Scan complete: {} scanned, {} added, {} updated, {} skipped, {} failed, {} removed, {} missing",
+            scanned, added, updated, skipped, failed, removed, missing
         );
+        if options.root.is_none()
+            && let Err(e) = db.record_scan_run(scanned, added, updated, removed, missing)
+        {
+            warn!("Failed to record scan run: {}", e);
+        }
 
         self.update_scan_progress(ScanProgress {
             current: total_files.max(1),
@@ -692,7 +860,7 @@ impl Scanner {
             scanned,
             added,
             updated,
-            removed: 0,
+            removed,
             missing,
         })
     }
@@ -721,16 +889,165 @@ impl Scanner {
         let result = self.scan_with_options(db, options).await;
         if result.is_err() {
             self.clear_scan_progress();
+        } else {
+            self.spawn_transcode_check(db.clone());
         }
         result
     }
 
+    /// Runs the opt-in transcode check over lossless songs that have not been looked at yet.
+    ///
+    /// Decoding is slow, so this runs on its own task after a scan instead of inside it.
+    fn spawn_transcode_check(&self, db: Database) {
+        if !self.detect_transcodes.load(Ordering::Relaxed)
+            || self.transcode_check_running.swap(true, Ordering::AcqRel)
+        {
+            return;
+        }
+
+        let scanner = self.clone();
+        self.executor
+            .spawn(async move {
+                let pending = db.get_files_pending_transcode_check().unwrap_or_else(|e| {
+                    error!("Failed to load files for transcode check: {}", e);
+                    Vec::new()
+                });
+
+                let mut flagged = 0;
+                for file_path in pending {
+                    if !scanner.detect_transcodes.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let path = PathBuf::from(&file_path);
+                    let lossless = match Self::read_metadata(&path) {
+                        Ok(metadata) => metadata.properties.lossless,
+                        Err(e) => {
+                            debug!("Skipping transcode check for {:?}: {}", path, e);
+                            continue;
+                        }
+                    };
+
+                    // Lossy files are stored as checked too, so later scans skip them.
+                    let suspected = lossless
+                        && transcode::looks_transcoded(&path).unwrap_or_else(|e| {
+                            debug!("Transcode check failed for {:?}: {}", path, e);
+                            false
+                        });
+                    if suspected {
+                        flagged += 1;
+                        info!("Possible transcode: {:?}", path);
+                    }
+                    if let Err(e) = db.set_suspected_transcode(&file_path, suspected) {
+                        error!("Failed to store transcode check for {:?}: {}", path, e);
+                    }
+                }
+
+                if flagged > 0 {
+                    info!("Transcode check flagged {} file(s)", flagged);
+                }
+                scanner
+                    .transcode_check_running
+                    .store(false, Ordering::Release);
+            })
+            .detach();
+    }
+
+    /// Decodes the songs normalization lacks a loudness or peak for and stores what they
+    /// measure, for files that were never tagged or played through.
+    pub fn spawn_loudness_measurement(&self, db: Database) {
+        if self.measurement_running.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let scanner = self.clone();
+        self.executor
+            .spawn(async move {
+                let pending = db.get_songs_pending_measurement().unwrap_or_else(|e| {
+                    error!("Failed to load songs to measure: {}", e);
+                    Vec::new()
+                });
+                info!("Measuring loudness of {} songs", pending.len());
+
+                let mut measured = 0;
+                for (id, path, start_secs, end_secs) in pending {
+                    let path = PathBuf::from(path);
+                    let measurement = match loudness::measure_file(&path, start_secs, end_secs) {
+                        Ok(measurement) => measurement,
+                        Err(e) => {
+                            debug!("Failed to measure {:?}: {}", path, e);
+                            continue;
+                        }
+                    };
+                    match db.set_computed_loudness(&id, measurement.lufs, measurement.peak) {
+                        Ok(()) => measured += 1,
+                        Err(e) => error!("Failed to store measurement for {:?}: {}", path, e),
+                    }
+                }
+
+                info!("Measured loudness of {} songs", measured);
+                scanner.measurement_running.store(false, Ordering::Release);
+            })
+            .detach();
+    }
+
     pub async fn scan(&self, db: &Database) -> Result<ScanStats> {
         self.run_scan(db, ScanOptions::default()).await
     }
 
+    /// Scans only `path`, adding, updating and removing songs inside it without touching the
+    /// rest of the library.
+    pub async fn scan_path(&self, db: &Database, path: PathBuf) -> Result<ScanStats> {
+        self.run_scan(
+            db,
+            ScanOptions {
+                root: Some(path),
+                ..ScanOptions::default()
+            },
+        )
+        .await
+    }
+
+    /// Starts a scan in the background and refreshes the library views when it finds anything.
+    pub fn rescan_now(&self, db: Database) {
+        self.spawn_rescan(db, None);
+    }
+
+    /// Like [`Scanner::rescan_now`], limited to one folder.
+    pub fn rescan_path_now(&self, db: Database, path: PathBuf) {
+        self.spawn_rescan(db, Some(path));
+    }
+
+    fn spawn_rescan(&self, db: Database, path: Option<PathBuf>) {
+        let scanner = self.clone();
+        self.executor
+            .spawn(async move {
+                let result = match path {
+                    Some(path) => scanner.scan_path(&db, path).await,
+                    None => scanner.scan(&db).await,
+                };
+                match result {
+                    Ok(stats) => {
+                        if (stats.added > 0 || stats.updated > 0 || stats.removed > 0)
+                            && let Some(background_ui) = &scanner.background_ui
+                        {
+                            background_ui.notify(BackgroundUiEvent::LibraryDataChanged);
+                        }
+                    }
+                    Err(e) => error!("Rescan failed: {}", e),
+                }
+            })
+            .detach();
+    }
+
     pub async fn force_scan(&self, db: &Database) -> Result<ScanStats> {
-        self.run_scan(db, ScanOptions { force: true }).await
+        self.run_scan(
+            db,
+            ScanOptions {
+                force: true,
+                ..ScanOptions::default()
+            },
+        )
+        .await
     }
 
     fn process_changed_files_inner(
@@ -744,6 +1061,7 @@ impl Scanner {
         let mut seen_image_ids = HashSet::new();
         let mut artist_cache: HashMap<String, Cuid> = HashMap::new();
         let mut album_cache: HashMap<String, (Cuid, bool)> = HashMap::new();
+        let filename_patterns = self.filename_patterns();
 
         for path in changed_paths {
             let path_clone = path.clone();
@@ -760,13 +1078,7 @@ impl Scanner {
                 }
             };
 
-            let file_size = file_meta.len() as i64;
-            let file_modified = file_meta
-                .modified()
-                .ok()
-                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
-                .map(|d| d.as_secs() as i64)
-                .unwrap_or(0);
+            let (file_size, file_modified) = stored_file_state(&path, &file_meta);
 
             let existing = db
                 .get_song_by_path(path_clone.to_string_lossy().as_ref())
@@ -775,22 +1087,34 @@ impl Scanner {
 
             let is_new = existing.is_none();
 
+            // A deleted sheet leaves the audio's own time behind, which may be what was stored.
             if let Some(existing) = existing
                 && existing.file_size == file_size
                 && existing.file_modified == file_modified
+                && existing.is_cue_track() == cue::sheet_modified(&path).is_some()
             {
                 continue;
             }
 
-            let metadata = match Self::read_metadata(&path) {
+            let mut metadata = match Self::read_metadata(&path) {
                 Ok(m) => m,
                 Err(e) => {
                     warn!("Failed to read metadata from {:?}: {}", path_clone, e);
                     continue;
                 }
             };
+            metadata.fill_from_path(&path, &filename_patterns);
+
+            if metadata.duration.is_zero() {
+                debug!(
+                    "Deferring {:?}: no duration, file may be incomplete",
+                    path_clone
+                );
+                continue;
+            }
 
             let image_data = Self::extract_image(&path);
+            let cue_tracks = split_by_cue(&path, &metadata);
 
             let track = ScannedTrack {
                 path: path.clone(),
@@ -798,6 +1122,7 @@ impl Scanner {
                 file_modified,
                 metadata,
                 image_data,
+                cue_tracks,
             };
 
             match self.save_track(
@@ -829,6 +1154,30 @@ impl Scanner {
         })
     }
 
+    async fn settled_files(&self, paths: Vec<PathBuf>) -> Vec<PathBuf> {
+        let before: Vec<(PathBuf, _)> = paths
+            .into_iter()
+            .filter_map(|path| file_size_and_modified(&path).map(|state| (path, state)))
+            .collect();
+        if before.is_empty() {
+            return Vec::new();
+        }
+
+        self.executor.timer(PARTIAL_FILE_SETTLE).await;
+
+        before
+            .into_iter()
+            .filter(|(path, state)| {
+                let settled = file_size_and_modified(path).as_ref() == Some(state);
+                if !settled {
+                    debug!("Deferring {:?}: file is still being written", path);
+                }
+                settled
+            })
+            .map(|(path, _)| path)
+            .collect()
+    }
+
     pub async fn queue_changed_files(
         &self,
         db: Arc<Database>,
@@ -882,6 +1231,11 @@ impl Scanner {
                         break;
                     }
 
+                    let batch = scanner.settled_files(batch).await;
+                    if batch.is_empty() {
+                        continue;
+                    }
+
                     info!(
                         "Processing coalesced incremental batch with {} files",
                         batch.len()
@@ -895,6 +1249,7 @@ impl Scanner {
                                 stats.scanned, stats.added, stats.updated, stats.missing
                             );
                             let _ = tx.unbounded_send(stats);
+                            scanner.spawn_transcode_check(db.as_ref().clone());
                         }
                         Err(e) => {
                             error!("Incremental scan failed: {}", e);
@@ -905,6 +1260,149 @@ impl Scanner {
             .detach();
     }
 
+    pub async fn import_files(
+        &self,
+        db: &Database,
+        paths: Vec<PathBuf>,
+        organize: &OrganizeSettings,
+    ) -> Result<Vec<PathBuf>> {
+        if organize.import_mode != ImportMode::InPlace && db.read_only() {
+            anyhow::bail!("Read-only mode: files are not copied or moved into the library");
+        }
+        let root = expand_tilde(&organize.library_root);
+        if organize.import_mode != ImportMode::InPlace && organize.library_root.trim().is_empty() {
+            anyhow::bail!("No library folder configured for organized imports");
+        }
+
+        let mut placed = Vec::with_capacity(paths.len());
+        for path in paths {
+            if !path.is_file() || !Self::is_audio_file(&path) {
+                continue;
+            }
+
+            let metadata = match Self::read_metadata(&path) {
+                Ok(m) => m,
+                Err(e) => {
+                    warn!("Failed to read metadata from {:?}: {}", path, e);
+                    continue;
+                }
+            };
+
+            match organize::place_file(
+                &path,
+                &root,
+                &organize.pattern,
+                &metadata,
+                organize.import_mode,
+            ) {
+                Ok(destination) => placed.push(destination),
+                Err(e) => warn!("Failed to import {:?}: {}", path, e),
+            }
+        }
+
+        let _scan_guard = self.scan_lock.lock().await;
+        let stats = self.process_changed_files_inner(db, placed.clone())?;
+        info!(
+            "Import complete - Scanned: {}, Added: {}, Updated: {}",
+            stats.scanned, stats.added, stats.updated
+        );
+        if (stats.added > 0 || stats.updated > 0)
+            && let Some(background_ui) = &self.background_ui
+        {
+            background_ui.notify(BackgroundUiEvent::LibraryDataChanged);
+        }
+        Ok(placed)
+    }
+
+    pub async fn preview_organize(
+        &self,
+        db: &Database,
+        organize: &OrganizeSettings,
+    ) -> Result<Vec<PlannedMove>> {
+        if organize.library_root.trim().is_empty() {
+            anyhow::bail!("No library folder configured");
+        }
+        let root = expand_tilde(&organize.library_root);
+        let paths = db.get_song_paths()?;
+        Ok(organize::plan_library(&paths, &root, &organize.pattern))
+    }
+
+    pub async fn apply_organize(&self, db: &Database, moves: Vec<PlannedMove>) -> Result<usize> {
+        if db.read_only() {
+            anyhow::bail!("Read-only mode: files are not organized");
+        }
+        let _scan_guard = self.scan_lock.lock().await;
+
+        let mut moved = Vec::with_capacity(moves.len());
+        for planned in moves {
+            if planned.to.exists() {
+                warn!(
+                    "Not moving {:?}: {:?} already exists",
+                    planned.from, planned.to
+                );
+                continue;
+            }
+            match organize::relocate(&planned.from, &planned.to) {
+                Ok(()) => moved.push(planned),
+                Err(e) => warn!("Failed to move {:?}: {}", planned.from, e),
+            }
+        }
+
+        let renames: Vec<(String, String)> = moved
+            .iter()
+            .map(|m| {
+                (
+                    m.from.to_string_lossy().to_string(),
+                    m.to.to_string_lossy().to_string(),
+                )
+            })
+            .collect();
+
+        if let Err(e) = db.rename_song_paths(&renames) {
+            error!("Failed to update library paths, moving files back: {}", e);
+            for m in moved.iter().rev() {
+                if let Err(e) = organize::relocate(&m.to, &m.from) {
+                    error!("Failed to restore {:?} to {:?}: {}", m.to, m.from, e);
+                }
+            }
+            return Err(e);
+        }
+
+        info!("Organized {} files", moved.len());
+        if !moved.is_empty()
+            && let Some(background_ui) = &self.background_ui
+        {
+            background_ui.notify(BackgroundUiEvent::LibraryDataChanged);
+        }
+        Ok(moved.len())
+    }
+
+    /// Writes confirmed tags from an online lookup into the files and reads them back into the
+    /// library. Returns how many songs were updated.
+    pub async fn apply_retag(&self, db: &Database, planned: Vec<PlannedRetag>) -> Result<usize> {
+        if db.read_only() {
+            anyhow::bail!("Read-only mode: files are not re-tagged");
+        }
+        let _scan_guard = self.scan_lock.lock().await;
+
+        let mut written = Vec::with_capacity(planned.len());
+        for retag in planned {
+            match online::write_tags(&retag) {
+                Ok(()) => written.push(retag.path),
+                Err(e) => warn!("Failed to re-tag {:?}: {}", retag.path, e),
+            }
+        }
+
+        let stats = self.process_changed_files_inner(db, written)?;
+        info!("Re-tagged {} files", stats.updated);
+        if stats.updated > 0
+            && let Some(background_ui) = &self.background_ui
+        {
+            background_ui.notify(BackgroundUiEvent::LibraryDataChanged);
+        }
+        Ok(stats.updated)
+    }
+
     fn save_track(
         &self,
         db: &Database,
@@ -914,7 +1412,6 @@ impl Scanner {
         album_cache: &mut HashMap<String, (Cuid, bool)>,
     ) -> Result<()> {
         let path_str = track.path.to_string_lossy().to_string();
-        let meta = &track.metadata;
 
         let image_id = if let Some(image) = &track.image_data {
             if seen_image_ids.insert(image.id.clone()) {
@@ -925,18 +1422,57 @@ impl Scanner {
             None
         };
 
+        let songs: Vec<(SongSegment, &AudioMetadata)> = if track.cue_tracks.is_empty() {
+            vec![(SongSegment::default(), &track.metadata)]
+        } else {
+            track
+                .cue_tracks
+                .iter()
+                .map(|(segment, meta)| (*segment, meta))
+                .collect()
+        };
+        for &(segment, meta) in &songs {
+            self.save_song(db, track, segment, meta, image_id.as_deref(), album_cache)?;
+        }
+
+        // Drops the whole-file song once a cue sheet shows up, and cue tracks once it's gone.
+        let kept: HashSet<i32> = songs.iter().map(|(segment, _)| segment.cue_track).collect();
+        let stale: Vec<Cuid> = db
+            .get_song_segments(&path_str)?
+            .into_iter()
+            .filter(|(_, cue_track)| !kept.contains(cue_track))
+            .map(|(id, _)| id)
+            .collect();
+        if !stale.is_empty() {
+            db.remove_songs(&stale)?;
+        }
+
+        debug!("Saved track: {:?}", track.path);
+        Ok(())
+    }
+
+    fn save_song(
+        &self,
+        db: &Database,
+        track: &ScannedTrack,
+        segment: SongSegment,
+        meta: &AudioMetadata,
+        image_id: Option<&str>,
+        album_cache: &mut HashMap<String, (Cuid, bool)>,
+    ) -> Result<()> {
+        let path_str = track.path.to_string_lossy();
         let artist_names: Vec<&str> = meta.artists.iter().map(|s| s.as_str()).collect();
 
         let album_id = if let Some(album_name) = &meta.album {
             let key = album_name.clone();
             if let Some((cached_album_id, has_image)) = album_cache.get_mut(&key) {
                 if !*has_image && image_id.is_some() {
-                    db.upsert_album(album_name, &artist_names, image_id.as_deref())?;
+                    db.upsert_album(album_name, &artist_names, image_id)?;
                     *has_image = true;
                 }
                 Some(cached_album_id.clone())
             } else {
-                let album_id = db.upsert_album(album_name, &artist_names, image_id.as_deref())?;
+                let album_id = db.upsert_album(album_name, &artist_names, image_id)?;
                 album_cache.insert(key, (album_id.clone(), image_id.is_some()));
                 Some(album_id)
             }
@@ -948,29 +1484,98 @@ impl Scanner {
         let duration = meta.duration.as_secs() as i32;
         let track_number = meta.track_number.map(|n| n as i32);
 
-        db.upsert_song(
+        let genres: Vec<&str> = meta.genres.iter().map(|s| s.as_str()).collect();
+        db.upsert_song(&SongUpsert {
             title,
-            &artist_names,
-            album_id.as_ref(),
-            &path_str,
+            artists: &artist_names,
+            album_id: album_id.as_ref(),
+            file_path: &path_str,
             duration,
             track_number,
-            meta.year,
-            &meta.genres.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
-            image_id.as_deref(),
-            track.file_size,
-            track.file_modified,
-            meta.lufs,
-        )?;
-
-        debug!("Saved track: {:?}", track.path);
+            track_total: meta.track_total.map(|n| n as i32),
+            disc_number: meta.disc_number.map(|n| n as i32),
+            year: meta.year,
+            genres: &genres,
+            image_id,
+            file_size: track.file_size,
+            file_modified: track.file_modified,
+            lufs: meta.lufs,
+            track_peak: meta.track_peak,
+            metadata_from_filename: meta.from_filename,
+            segment,
+        })?;
         Ok(())
     }
 }
 
+/// Splits a file's metadata into one entry per track of a cue sheet next to it. The sheet's
+/// titles and performers win over the file's own tags.
+fn split_by_cue(path: &Path, metadata: &AudioMetadata) -> Vec<(SongSegment, AudioMetadata)> {
+    let Some(sheet) = cue::find_for(path) else {
+        return Vec::new();
+    };
+    let Some(tracks) = sheet.tracks_for(path) else {
+        return Vec::new();
+    };
+    let file_secs = metadata.duration.as_secs_f32();
+
+    tracks
+        .iter()
+        .filter(|track| track.start_secs < file_secs)
+        .map(|track| {
+            let end_secs = track.end_secs.map(|end| end.min(file_secs));
+            let mut meta = metadata.clone();
+            meta.title = Some(
+                track
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| format!("Track {}", track.number)),
+            );
+            if let Some(performer) = track.performer.as_ref().or(sheet.performer.as_ref()) {
+                meta.artists = vec![performer.clone()];
+            }
+            if sheet.title.is_some() {
+                meta.album = sheet.title.clone();
+            }
+            meta.track_number = Some(track.number);
+            meta.track_total = Some(tracks.len() as u32);
+            meta.duration =
+                Duration::from_secs_f32(end_secs.unwrap_or(file_secs) - track.start_secs);
+            meta.from_filename = false;
+            let segment = SongSegment {
+                cue_track: track.number as i32,
+                start_secs: track.start_secs,
+                end_secs,
+            };
+            (segment, meta)
+        })
+        .collect()
+}
+
+/// The size and modification time stored for an audio file. A cue sheet next to it counts
+/// towards the time, so adding or editing the sheet rescans the file into its tracks.
+fn stored_file_state(path: &Path, file_meta: &std::fs::Metadata) -> (i64, i64) {
+    let file_modified = file_meta
+        .modified()
+        .ok()
+        .into_iter()
+        .chain(cue::sheet_modified(path))
+        .max()
+        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    (file_meta.len() as i64, file_modified)
+}
+
+fn file_size_and_modified(path: &Path) -> Option<(u64, Option<std::time::SystemTime>)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()))
+}
+
 fn process_one_file(
     path: PathBuf,
     existing_track_state: &HashMap<String, (i64, i64)>,
+    filename_patterns: &[String],
     force: bool,
 ) -> Option<(Option<ScannedTrack>, bool, bool)> {
     let file_path = path.to_string_lossy().to_string();
@@ -983,13 +1588,7 @@ fn process_one_file(
         }
     };
 
-    let file_size = file_meta.len() as i64;
-    let file_modified = file_meta
-        .modified()
-        .ok()
-        .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
-        .map(|d| d.as_secs() as i64)
-        .unwrap_or(0);
+    let (file_size, file_modified) = stored_file_state(&path, &file_meta);
 
     let existing = existing_track_state.get(&file_path).copied();
     let is_new = existing.is_none();
@@ -1006,13 +1605,23 @@ fn process_one_file(
         return Some((None, false, false));
     }
 
-    let (metadata, image_data) = match read_metadata_and_image(&path) {
+    let (mut metadata, image_data) = match read_metadata_and_image(&path) {
         Ok(result) => result,
         Err(e) => {
             warn!("Failed to read metadata for {}: {}", file_path, e);
             return Some((None, false, true));
         }
     };
+    metadata.fill_from_path(&path, filename_patterns);
+
+    if metadata.duration.is_zero() {
+        debug!(
+            "Skipping {}: no duration, file may be incomplete",
+            file_path
+        );
+        return Some((None, false, false));
+    }
+    let cue_tracks = split_by_cue(&path, &metadata);
 
     Some((
         Some(ScannedTrack {
@@ -1021,6 +1630,7 @@ fn process_one_file(
             file_modified,
             metadata,
             image_data,
+            cue_tracks,
         }),
         is_new,
         false,
@@ -1036,10 +1646,128 @@ pub fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Explains why `path` can't be scanned, or `None` when it is a readable folder.
+pub fn scan_path_error(path: &Path) -> Option<&'static str> {
+    if !path.exists() {
+        Some("Folder does not exist")
+    } else if !path.is_dir() {
+        Some("Not a folder")
+    } else if std::fs::read_dir(path).is_err() {
+        Some("Folder can't be read")
+    } else {
+        None
+    }
+}
+
+/// Inverse of `expand_tilde`, so stored paths keep working when the home folder moves.
+pub fn collapse_tilde(path: &Path) -> String {
+    if let Some(home) = dirs::home_dir()
+        && let Ok(rest) = path.strip_prefix(&home)
+        && !rest.as_os_str().is_empty()
+    {
+        return format!("~/{}", rest.to_string_lossy().replace('\\', "/"));
+    }
+    path.to_string_lossy().to_string()
+}
+
+/// Adds `path` to `paths` in `~` form unless an entry already points at the same folder.
+pub fn add_scan_path(paths: &mut Vec<String>, path: &Path) {
+    if !paths.iter().any(|p| expand_tilde(p) == path) {
+        paths.push(collapse_tilde(path));
+    }
+}
+
 pub fn expand_scan_paths(paths: &[String]) -> Vec<PathBuf> {
     paths.iter().map(|p| expand_tilde(p)).collect()
 }
 
+fn is_hidden_name(name: &std::ffi::OsStr) -> bool {
+    name.to_str().is_some_and(|name| name.starts_with('.'))
+}
+
+/// Whether `path` is a dotfile or sits in a hidden folder below the scan path it belongs to.
+/// Hidden folders above the scan path itself don't count.
+pub fn is_hidden_in_scan_paths(path: &Path, scan_paths: &[PathBuf]) -> bool {
+    let relative = scan_paths
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    relative
+        .components()
+        .any(|component| is_hidden_name(component.as_os_str()))
+}
+
+fn batch_watch_events(
+    rx: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+    timing: Arc<WatchTiming>,
+    mut on_batch: impl FnMut(Vec<notify::Event>),
+) {
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Instant;
+
+    let mut pending: Vec<notify::Event> = Vec::new();
+    let mut first_at: Option<Instant> = None;
+    let mut last_at = Instant::now();
+
+    loop {
+        let received = match first_at {
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            Some(first) => {
+                let until_quiet = timing.debounce().saturating_sub(last_at.elapsed());
+                let until_max = timing.max_wait().saturating_sub(first.elapsed());
+                rx.recv_timeout(until_quiet.min(until_max))
+            }
+        };
+
+        match received {
+            Ok(Ok(event)) => {
+                let now = Instant::now();
+                first_at.get_or_insert(now);
+                last_at = now;
+                pending.push(event);
+            }
+            Ok(Err(e)) => error!("Filesystem watch error: {:?}", e),
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        let Some(first) = first_at else {
+            continue;
+        };
+        if last_at.elapsed() >= timing.debounce() || first.elapsed() >= timing.max_wait() {
+            if first.elapsed() >= timing.max_wait() {
+                debug!("Watch events still arriving after max wait, flushing batch");
+            }
+            first_at = None;
+            on_batch(std::mem::take(&mut pending));
+        }
+    }
+}
+
+/// The library paths to move for renames seen by the watcher. A renamed folder moves every song
+/// under it.
+fn library_renames(renames: &[(PathBuf, PathBuf)], db: &Database) -> Vec<(String, String)> {
+    let mut library_paths: Option<Vec<String>> = None;
+    let mut moved = Vec::new();
+    for (from, to) in renames {
+        if to.is_dir() {
+            let paths =
+                library_paths.get_or_insert_with(|| db.get_song_paths().unwrap_or_default());
+            for path in paths.iter() {
+                if let Ok(rest) = Path::new(path).strip_prefix(from) {
+                    moved.push((path.clone(), to.join(rest).to_string_lossy().to_string()));
+                }
+            }
+        } else if Scanner::is_audio_file(to) {
+            moved.push((
+                from.to_string_lossy().to_string(),
+                to.to_string_lossy().to_string(),
+            ));
+        }
+    }
+    moved
+}
+
 pub struct MusicWatcher;
 
 impl MusicWatcher {
@@ -1053,23 +1781,52 @@ impl MusicWatcher {
         let db_clone = db.clone();
         let exec_clone = executor.clone();
 
-        let mut debouncer = new_debouncer(
-            Duration::from_secs(2),
-            None,
-            move |result: DebounceEventResult| match result {
-                Ok(events) => {
+        let (event_tx, event_rx) = std::sync::mpsc::channel::<notify::Result<notify::Event>>();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = event_tx.send(event);
+        })
+        .context("Failed to create filesystem watcher")?;
+
+        let timing = scanner.watch_timing.clone();
+        std::thread::Builder::new()
+            .name("vleer-watch-batcher".to_string())
+            .spawn(move || {
+                batch_watch_events(event_rx, timing, move |events| {
+                    let include_hidden = scanner_clone.include_hidden.load(Ordering::Relaxed);
+                    let scan_paths = scanner_clone.get_scan_paths();
                     let mut changed_audio_files: Vec<PathBuf> = Vec::new();
                     let mut removed_files: Vec<PathBuf> = Vec::new();
                     let mut removed_dirs: Vec<String> = Vec::new();
+                    let mut renames: Vec<(PathBuf, PathBuf)> = Vec::new();
 
                     for event in events {
                         debug!("File event: {:?} - {:?}", event.kind, event.paths);
 
+                        if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind
+                            && let [from, to] = event.paths.as_slice()
+                            && (include_hidden || !is_hidden_in_scan_paths(to, &scan_paths))
+                        {
+                            renames.push((from.clone(), to.clone()));
+                        }
+
                         if matches!(
                             event.kind,
                             EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
                         ) {
                             for path in &event.paths {
+                                if !include_hidden && is_hidden_in_scan_paths(path, &scan_paths) {
+                                    continue;
+                                }
+                                // A sheet changes how its audio splits into songs, even once
+                                // it's deleted.
+                                if cue::is_sheet(path) {
+                                    changed_audio_files.extend(
+                                        cue::audio_for(path).into_iter().filter(|audio| {
+                                            audio.is_file() && Scanner::is_audio_file(audio)
+                                        }),
+                                    );
+                                    continue;
+                                }
                                 if path.exists() {
                                     if Scanner::is_audio_file(path) {
                                         changed_audio_files.push(path.clone());
@@ -1083,8 +1840,21 @@ impl MusicWatcher {
                         }
                     }
 
+                    // Moving the stored path keeps the song, and with it its favorite and
+                    // playlist entries, where a rescan would swap it for a new one.
+                    let renames = library_renames(&renames, &db_clone);
+                    if !renames.is_empty() {
+                        info!("Following {} renamed files", renames.len());
+                        if let Err(e) = db_clone.rename_song_paths(&renames) {
+                            error!("Failed to follow renamed files: {}", e);
+                        }
+                    }
+                    let renamed_from: HashSet<&str> =
+                        renames.iter().map(|(from, _)| from.as_str()).collect();
+
                     let removed_files: Vec<PathBuf> = removed_files
                         .into_iter()
+                        .filter(|path| !renamed_from.contains(path.to_string_lossy().as_ref()))
                         .collect::<HashSet<_>>()
                         .into_iter()
                         .collect();
@@ -1092,7 +1862,6 @@ impl MusicWatcher {
                     for path in removed_files {
                         let path_str = path.to_string_lossy().to_string();
                         warn!("File removed from disk, keeping in library: {}", path_str);
-                        let _ = tx;
                     }
 
                     let changed_audio_files: Vec<PathBuf> = changed_audio_files
@@ -1117,15 +1886,9 @@ impl MusicWatcher {
                             })
                             .detach();
                     }
-                }
-                Err(errors) => {
-                    for error in errors {
-                        error!("Filesystem watch error: {:?}", error);
-                    }
-                }
-            },
-        )
-        .context("Failed to create filesystem watcher")?;
+                });
+            })
+            .context("Failed to spawn watch batcher thread")?;
 
         for path in scanner.get_scan_paths() {
             if !path.exists() {
@@ -1133,12 +1896,12 @@ impl MusicWatcher {
                 continue;
             }
             debug!("Watching directory for changes: {:?}", path);
-            debouncer
+            watcher
                 .watch(&path, RecursiveMode::Recursive)
                 .with_context(|| format!("Failed to watch directory: {:?}", path))?;
         }
 
-        scanner.install_watcher(debouncer);
+        scanner.install_watcher(watcher);
 
         let sync_paths = scanner.get_scan_paths();
         if let Ok(mut watcher_slot) = scanner.watcher.lock()
@@ -1,7 +1,18 @@
 pub mod data {
+    pub mod config;
+    pub mod cue;
     pub mod db;
     pub mod models;
 }
 
+pub mod media {
+    pub mod loudness;
+    pub mod now_playing;
+    #[cfg(feature = "null-audio")]
+    pub mod null_output;
+    pub mod queue;
+    pub mod resampler;
+}
+
 pub mod status;
 pub mod updater;
@@ -0,0 +1,131 @@
+use crate::media::playback::PlaybackCommand;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::debug;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+pub fn watch(playback_tx: mpsc::UnboundedSender<PlaybackCommand>) {
+    thread::spawn(move || {
+        let Some(mut probe) = platform::probe() else {
+            return;
+        };
+
+        let mut lost = false;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            let other_playing = match probe() {
+                Ok(playing) => playing,
+                Err(err) => {
+                    debug!(?err, "audio focus probe failed");
+                    continue;
+                }
+            };
+            if other_playing == lost {
+                continue;
+            }
+
+            lost = other_playing;
+            let cmd = if lost {
+                PlaybackCommand::FocusLost
+            } else {
+                PlaybackCommand::FocusRegained
+            };
+            if playback_tx.send(cmd).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+// Other MPRIS players on the session bus reporting `Playing` count as another app taking focus.
+#[cfg(target_os = "linux")]
+mod platform {
+    use anyhow::Result;
+    use zbus::blocking::fdo::DBusProxy;
+    use zbus::blocking::{Connection, Proxy};
+
+    const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+    const OWN_NAME: &str = "org.mpris.MediaPlayer2.vleer";
+
+    pub fn probe() -> Option<Box<dyn FnMut() -> Result<bool> + Send>> {
+        let conn = Connection::session().ok()?;
+        Some(Box::new(move || other_player_active(&conn)))
+    }
+
+    fn other_player_active(conn: &Connection) -> Result<bool> {
+        let names = DBusProxy::new(conn)?.list_names()?;
+        for name in names {
+            let name = name.as_str();
+            if !name.starts_with(MPRIS_PREFIX) || name.starts_with(OWN_NAME) {
+                continue;
+            }
+            let player = Proxy::new(
+                conn,
+                name,
+                "/org/mpris/MediaPlayer2",
+                "org.mpris.MediaPlayer2.Player",
+            )?;
+            if player
+                .get_property::<String>("PlaybackStatus")
+                .is_ok_and(|status| status == "Playing")
+            {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use anyhow::{Result, anyhow};
+    use std::time::{Duration, Instant};
+    use windows::Media::Control::{
+        GlobalSystemMediaTransportControlsSessionManager as SessionManager,
+        GlobalSystemMediaTransportControlsSessionPlaybackStatus as SessionPlaybackStatus,
+    };
+    use windows_future::AsyncStatus;
+
+    pub fn probe() -> Option<Box<dyn FnMut() -> Result<bool> + Send>> {
+        let manager = request_manager().ok()?;
+        Some(Box::new(move || other_session_playing(&manager)))
+    }
+
+    fn request_manager() -> Result<SessionManager> {
+        let op = SessionManager::RequestAsync()?;
+        let start = Instant::now();
+        while op.Status()? == AsyncStatus::Started {
+            if start.elapsed() > Duration::from_secs(2) {
+                return Err(anyhow!("timeout waiting for media session manager"));
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        Ok(op.GetResults()?)
+    }
+
+    fn other_session_playing(manager: &SessionManager) -> Result<bool> {
+        for session in manager.GetSessions()? {
+            let app = session.SourceAppUserModelId()?.to_string_lossy();
+            if app.to_lowercase().contains("vleer") {
+                continue;
+            }
+            if session.GetPlaybackInfo()?.PlaybackStatus()? == SessionPlaybackStatus::Playing {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+// macOS has no public API for other apps' playback state.
+#[cfg(target_os = "macos")]
+mod platform {
+    use anyhow::Result;
+
+    pub fn probe() -> Option<Box<dyn FnMut() -> Result<bool> + Send>> {
+        None
+    }
+}
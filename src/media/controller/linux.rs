@@ -9,7 +9,7 @@ use std::cell::Cell;
 use std::rc::Rc;
 use std::{fs, thread};
 use tokio::sync::mpsc;
-use tracing::error;
+use tracing::{debug, error};
 use url::Url;
 
 pub struct LinuxController {
@@ -83,16 +83,14 @@ fn run_mpris(
     let spawner = pool.spawner();
 
     pool.run_until(async move {
-        let player = Player::builder("vleer")
-            .identity("Vleer")
-            .desktop_entry("vleer")
-            .can_play(true)
-            .can_pause(true)
-            .can_go_next(true)
-            .can_go_previous(true)
-            .can_seek(true)
-            .build()
-            .await?;
+        let player = match build_player("vleer").await {
+            Ok(player) => player,
+            // Another instance owns the name. MPRIS lets each further one add its own suffix.
+            Err(err) => {
+                debug!(?err, "mpris bus name taken, using a per-instance name");
+                build_player(&format!("vleer.instance{}", std::process::id())).await?
+            }
+        };
 
         let position_ms = Rc::new(Cell::new(0i64));
 
@@ -243,6 +241,20 @@ fn run_mpris(
     Ok(())
 }
 
+async fn build_player(bus_name_suffix: &str) -> Result<Player> {
+    let player = Player::builder(bus_name_suffix)
+        .identity("Vleer")
+        .desktop_entry("vleer")
+        .can_play(true)
+        .can_pause(true)
+        .can_go_next(true)
+        .can_go_previous(true)
+        .can_seek(true)
+        .build()
+        .await?;
+    Ok(player)
+}
+
 #[derive(Default)]
 struct ArtworkCache {
     id: Option<String>,
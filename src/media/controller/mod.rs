@@ -1,7 +1,10 @@
+use crate::data::config::FallbackArtwork;
 use crate::data::db::repo::Database;
 use crate::data::models::Song;
+use crate::data::scanner::expand_tilde;
+use crate::media::now_playing;
 use crate::media::playback::Playback;
-use crate::media::queue::Queue;
+use crate::ui::assets::{BUNDLED_FALLBACK_ARTWORK, bundled::BundledAssets};
 use anyhow::Result;
 #[cfg(target_os = "windows")]
 use gpui::Window;
@@ -10,10 +13,14 @@ use gpui::{App, Global};
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 use std::sync::Arc;
 
+pub use crate::media::now_playing::PlaybackState;
+
+mod focus;
 #[cfg(target_os = "linux")]
 mod linux;
 #[cfg(target_os = "macos")]
 mod macos;
+mod power;
 #[cfg(target_os = "windows")]
 mod windows;
 
@@ -24,11 +31,15 @@ use macos::MacosController as PlatformController;
 #[cfg(target_os = "windows")]
 use windows::WindowsController as PlatformController;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PlaybackState {
-    Playing,
-    Paused,
-    Stopped,
+const MAX_METADATA_CHARS: usize = 512;
+
+fn truncate_metadata(value: String) -> String {
+    if value.chars().count() <= MAX_METADATA_CHARS {
+        return value;
+    }
+    let mut truncated: String = value.chars().take(MAX_METADATA_CHARS - 1).collect();
+    truncated.push('\u{2026}');
+    truncated
 }
 
 #[derive(Debug, Clone, Default)]
@@ -45,6 +56,26 @@ pub(crate) struct ResolvedMetadata {
     pub track_id: Option<String>,
 }
 
+fn fallback_artwork_data(fallback: &FallbackArtwork) -> Option<Vec<u8>> {
+    match fallback {
+        FallbackArtwork::None => None,
+        FallbackArtwork::Bundled => {
+            let path = BUNDLED_FALLBACK_ARTWORK.trim_start_matches("!bundled:");
+            BundledAssets::get(path).map(|f| f.data.into_owned())
+        }
+        FallbackArtwork::Custom(path) => std::fs::read(expand_tilde(path)).ok(),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn fallback_artwork_id(fallback: &FallbackArtwork) -> Option<String> {
+    match fallback {
+        FallbackArtwork::None => None,
+        FallbackArtwork::Bundled => Some("fallback".to_string()),
+        FallbackArtwork::Custom(path) => Some(format!("fallback-{path}")),
+    }
+}
+
 #[derive(Clone)]
 pub struct MediaController {
     inner: Arc<MediaControllerInner>,
@@ -60,6 +91,8 @@ impl Global for MediaController {}
 impl MediaController {
     pub fn init(cx: &mut App) {
         let playback_tx = Playback::get_command_sender(cx);
+        power::watch(playback_tx.clone());
+        focus::watch(playback_tx.clone());
         let platform = PlatformController::new(playback_tx);
         let db = cx.global::<Database>().clone();
         let controller = MediaController {
@@ -74,30 +107,38 @@ impl MediaController {
         self.inner.platform.set_state(state)
     }
 
-    pub fn update_song(&self, song: Song) -> Result<()> {
+    pub fn update_song(&self, song: Song, fallback: &FallbackArtwork) -> Result<()> {
         let db = self.inner.db.clone();
 
-        let artist = Some(song.artists.join(", ")).filter(|s| !s.is_empty());
+        let artist = Some(song.artists.join(", "))
+            .filter(|s| !s.is_empty())
+            .map(truncate_metadata);
 
         let album = match song.album_id.clone() {
-            Some(id) => db.get_album(&id).ok().flatten().map(|a| a.title),
+            Some(id) => db
+                .get_album(&id)
+                .ok()
+                .flatten()
+                .map(|a| truncate_metadata(a.title)),
             None => None,
         };
 
-        let artwork_data = match song.image_id.as_deref() {
+        let own_artwork = match song.image_id.as_deref() {
             Some(id) => db.get_image(id).ok().flatten().map(|i| i.data),
             None => None,
         };
 
         #[cfg(any(target_os = "linux", target_os = "windows"))]
-        let artwork_id = if artwork_data.is_some() {
+        let artwork_id = if own_artwork.is_some() {
             song.image_id.clone().or_else(|| Some(song.id.to_string()))
         } else {
-            None
+            fallback_artwork_id(fallback)
         };
 
+        let artwork_data = own_artwork.or_else(|| fallback_artwork_data(fallback));
+
         let metadata = ResolvedMetadata {
-            title: Some(song.title),
+            title: Some(truncate_metadata(song.title)),
             artist,
             album,
             duration_ms: Some(song.duration.max(0) as u64 * 1000),
@@ -142,7 +183,8 @@ impl MediaController {
 
     fn start_monitor(cx: &mut App, controller: MediaController) {
         let executor = cx.background_executor().clone();
-        cx.spawn(async move |cx| {
+        let now_playing = now_playing::subscribe();
+        cx.spawn(async move |_cx| {
             let mut last_position_ms: Option<u64> = None;
             let mut last_can_next: Option<bool> = None;
             let mut last_can_prev: Option<bool> = None;
@@ -150,21 +192,14 @@ impl MediaController {
             loop {
                 executor.timer(std::time::Duration::from_millis(500)).await;
 
-                let (position_ms, can_next, can_prev) = cx.update(|app| {
-                    let position_ms = app
-                        .try_global::<Playback>()
-                        .map(|p| (p.get_position().max(0.0) * 1000.0) as u64)
-                        .unwrap_or(0);
-                    let can_next = app
-                        .try_global::<Queue>()
-                        .map(|q| q.has_next())
-                        .unwrap_or(false);
-                    let can_prev = app
-                        .try_global::<Queue>()
-                        .map(|q| q.has_previous())
-                        .unwrap_or(false);
-                    (position_ms, can_next, can_prev)
-                });
+                let (position_ms, can_next, can_prev) = {
+                    let snapshot = now_playing.borrow();
+                    (
+                        (snapshot.position * 1000.0) as u64,
+                        snapshot.has_next,
+                        snapshot.has_previous,
+                    )
+                };
 
                 if last_position_ms != Some(position_ms) {
                     controller.set_position_ms(position_ms).ok();
@@ -0,0 +1,169 @@
+use crate::media::playback::PlaybackCommand;
+use tokio::sync::mpsc;
+
+pub fn watch(playback_tx: mpsc::UnboundedSender<PlaybackCommand>) {
+    platform::watch(playback_tx);
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use crate::media::playback::PlaybackCommand;
+    use std::thread;
+    use tokio::sync::mpsc;
+    use tracing::warn;
+    use zbus::blocking::{Connection, Proxy};
+    use zbus::zvariant::OwnedObjectPath;
+
+    const LOGIN1: &str = "org.freedesktop.login1";
+    const LOGIN1_PATH: &str = "/org/freedesktop/login1";
+    const MANAGER: &str = "org.freedesktop.login1.Manager";
+    const SESSION: &str = "org.freedesktop.login1.Session";
+
+    pub fn watch(playback_tx: mpsc::UnboundedSender<PlaybackCommand>) {
+        let tx = playback_tx.clone();
+        thread::spawn(move || {
+            if let Err(err) = watch_sleep(tx) {
+                warn!(?err, "logind sleep watcher stopped");
+            }
+        });
+
+        thread::spawn(move || {
+            if let Err(err) = watch_lock(playback_tx) {
+                warn!(?err, "logind lock watcher stopped");
+            }
+        });
+    }
+
+    fn watch_sleep(tx: mpsc::UnboundedSender<PlaybackCommand>) -> zbus::Result<()> {
+        let conn = Connection::system()?;
+        let manager = Proxy::new(&conn, LOGIN1, LOGIN1_PATH, MANAGER)?;
+
+        for msg in manager.receive_signal("PrepareForSleep")? {
+            let sleeping: bool = msg.body().deserialize()?;
+            let cmd = if sleeping {
+                PlaybackCommand::SystemSleep
+            } else {
+                PlaybackCommand::SystemWake
+            };
+            if tx.send(cmd).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn watch_lock(tx: mpsc::UnboundedSender<PlaybackCommand>) -> zbus::Result<()> {
+        let conn = Connection::system()?;
+        let manager = Proxy::new(&conn, LOGIN1, LOGIN1_PATH, MANAGER)?;
+        let session_path: OwnedObjectPath =
+            manager.call("GetSessionByPID", &(std::process::id()))?;
+        let session = Proxy::new(&conn, LOGIN1, session_path, SESSION)?;
+
+        for msg in session.receive_all_signals()? {
+            let header = msg.header();
+            let cmd = match header.member().map(|m| m.as_str()) {
+                Some("Lock") => PlaybackCommand::SessionLocked,
+                Some("Unlock") => PlaybackCommand::SessionUnlocked,
+                _ => continue,
+            };
+            if tx.send(cmd).is_err() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use crate::media::playback::PlaybackCommand;
+    use block2::RcBlock;
+    use objc2_app_kit::{
+        NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification,
+    };
+    use objc2_foundation::{
+        NSDistributedNotificationCenter, NSNotification, NSNotificationCenter, NSNotificationName,
+        NSString,
+    };
+    use std::ptr::NonNull;
+    use tokio::sync::mpsc;
+
+    fn observe(
+        center: &NSNotificationCenter,
+        name: &NSNotificationName,
+        tx: &mpsc::UnboundedSender<PlaybackCommand>,
+        cmd: PlaybackCommand,
+    ) {
+        let tx = tx.clone();
+        let block = RcBlock::new(move |_notification: NonNull<NSNotification>| {
+            let _ = tx.send(cmd.clone());
+        });
+        let observer = unsafe {
+            center.addObserverForName_object_queue_usingBlock(Some(name), None, None, &block)
+        };
+        // Observers live for the whole process.
+        std::mem::forget(observer);
+        std::mem::forget(block);
+    }
+
+    pub fn watch(playback_tx: mpsc::UnboundedSender<PlaybackCommand>) {
+        let workspace_center = NSWorkspace::sharedWorkspace().notificationCenter();
+        unsafe {
+            observe(
+                &workspace_center,
+                NSWorkspaceWillSleepNotification,
+                &playback_tx,
+                PlaybackCommand::SystemSleep,
+            );
+            observe(
+                &workspace_center,
+                NSWorkspaceDidWakeNotification,
+                &playback_tx,
+                PlaybackCommand::SystemWake,
+            );
+        }
+
+        let distributed_center = NSDistributedNotificationCenter::defaultCenter();
+        observe(
+            &distributed_center,
+            &NSString::from_str("com.apple.screenIsLocked"),
+            &playback_tx,
+            PlaybackCommand::SessionLocked,
+        );
+        observe(
+            &distributed_center,
+            &NSString::from_str("com.apple.screenIsUnlocked"),
+            &playback_tx,
+            PlaybackCommand::SessionUnlocked,
+        );
+    }
+}
+
+// Windows has no sleep hook that works without owning the window procedure, so resume is
+// detected from a short poll sleeping far longer than it asked to.
+#[cfg(target_os = "windows")]
+mod platform {
+    use crate::media::playback::PlaybackCommand;
+    use std::thread;
+    use std::time::{Duration, SystemTime};
+    use tokio::sync::mpsc;
+
+    const POLL_INTERVAL: Duration = Duration::from_secs(5);
+    const WAKE_THRESHOLD: Duration = Duration::from_secs(30);
+
+    pub fn watch(playback_tx: mpsc::UnboundedSender<PlaybackCommand>) {
+        thread::spawn(move || {
+            loop {
+                let before = SystemTime::now();
+                thread::sleep(POLL_INTERVAL);
+
+                let elapsed = before.elapsed().unwrap_or_default();
+                if elapsed > POLL_INTERVAL + WAKE_THRESHOLD
+                    && playback_tx.send(PlaybackCommand::SystemWake).is_err()
+                {
+                    break;
+                }
+            }
+        });
+    }
+}
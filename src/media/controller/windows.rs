@@ -1,13 +1,14 @@
 use super::{PlaybackState, ResolvedMetadata};
 use crate::media::playback::PlaybackCommand;
 use anyhow::{Result, anyhow};
-use image::ImageFormat;
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
+use tracing::warn;
 use windows::Foundation::{TimeSpan, TypedEventHandler};
 use windows::Media::*;
-use windows::Storage::StorageFile;
-use windows::Storage::Streams::RandomAccessStreamReference;
+use windows::Storage::Streams::{
+    DataWriter, InMemoryRandomAccessStream, RandomAccessStreamReference,
+};
 use windows::Win32::Foundation::HWND;
 use windows::Win32::System::WinRT::ISystemMediaTransportControlsInterop;
 use windows::core::HSTRING;
@@ -36,12 +37,25 @@ struct SmtcState {
     display_updater: SystemMediaTransportControlsDisplayUpdater,
     timeline_properties: SystemMediaTransportControlsTimelineProperties,
     artwork_cache: ArtworkCache,
+    button_token: i64,
+    position_token: i64,
+}
+
+/// Detaches the event handlers, so nothing sends into the playback channel once it's gone.
+impl Drop for SmtcState {
+    fn drop(&mut self) {
+        self.controls.RemoveButtonPressed(self.button_token).ok();
+        self.controls
+            .RemovePlaybackPositionChangeRequested(self.position_token)
+            .ok();
+        self.controls.SetIsEnabled(false).ok();
+    }
 }
 
 #[derive(Default)]
 struct ArtworkCache {
     id: Option<String>,
-    path: Option<String>,
+    thumbnail: Option<RandomAccessStreamReference>,
 }
 
 impl WindowsController {
@@ -201,14 +215,16 @@ fn init_smtc(
 
     let timeline_properties = SystemMediaTransportControlsTimelineProperties::new()?;
 
-    attach_button_handler(&controls, playback_tx.clone())?;
-    attach_position_handler(&controls, playback_tx)?;
+    let button_token = attach_button_handler(&controls, playback_tx.clone())?;
+    let position_token = attach_position_handler(&controls, playback_tx)?;
 
     Ok(SmtcState {
         controls,
         display_updater,
         timeline_properties,
         artwork_cache: ArtworkCache::default(),
+        button_token,
+        position_token,
     })
 }
 
@@ -292,6 +308,9 @@ fn apply_position(smtc: &mut SmtcState, position_ms: u64) -> Result<()> {
 }
 
 fn apply_metadata(smtc: &mut SmtcState, metadata: ResolvedMetadata) -> Result<()> {
+    // Start from a clean slate so fields and artwork the new song lacks don't linger.
+    smtc.display_updater.ClearAll()?;
+    smtc.display_updater.SetType(MediaPlaybackType::Music)?;
     let properties = smtc.display_updater.MusicProperties()?;
 
     if let Some(title) = metadata.title {
@@ -322,14 +341,14 @@ fn apply_metadata(smtc: &mut SmtcState, metadata: ResolvedMetadata) -> Result<()
             )))?;
     }
 
-    if let Some(artwork) = metadata
+    if let Some((id, data)) = metadata
         .artwork_id
         .as_deref()
         .zip(metadata.artwork_data.as_deref())
     {
-        if let Some(path) = smtc.artwork_cache.resolve(artwork.0, artwork.1)? {
-            let stream = thumbnail_from_path(&path)?;
-            smtc.display_updater.SetThumbnail(&stream)?;
+        match smtc.artwork_cache.resolve(id, data) {
+            Ok(thumbnail) => smtc.display_updater.SetThumbnail(&thumbnail)?,
+            Err(e) => warn!("Failed to load artwork for media controls: {}", e),
         }
     }
 
@@ -340,61 +359,44 @@ fn apply_metadata(smtc: &mut SmtcState, metadata: ResolvedMetadata) -> Result<()
 }
 
 impl ArtworkCache {
-    fn resolve(&mut self, id: &str, data: &[u8]) -> Result<Option<String>> {
-        if self.id.as_deref() == Some(id) {
-            return Ok(self.path.clone());
+    fn resolve(&mut self, id: &str, data: &[u8]) -> Result<RandomAccessStreamReference> {
+        if self.id.as_deref() == Some(id)
+            && let Some(thumbnail) = &self.thumbnail
+        {
+            return Ok(thumbnail.clone());
         }
 
-        let path = write_artwork_to_cache(id, data)?;
+        let thumbnail = thumbnail_from_bytes(data)?;
         self.id = Some(id.to_string());
-        self.path = Some(path.clone());
-        Ok(Some(path))
+        self.thumbnail = Some(thumbnail.clone());
+        Ok(thumbnail)
     }
 }
 
-fn write_artwork_to_cache(id: &str, data: &[u8]) -> Result<String> {
-    let cache_dir = dirs::cache_dir()
-        .unwrap_or_else(std::env::temp_dir)
-        .join("vleer")
-        .join("smtc");
-
-    std::fs::create_dir_all(&cache_dir)?;
-
-    let ext = match image::guess_format(data) {
-        Ok(ImageFormat::Png) => "png",
-        Ok(ImageFormat::Jpeg) => "jpg",
-        Ok(ImageFormat::Gif) => "gif",
-        Ok(ImageFormat::Bmp) => "bmp",
-        Ok(ImageFormat::Tiff) => "tiff",
-        Ok(ImageFormat::WebP) => "webp",
-        _ => "bin",
-    };
-
-    let safe_id: String = id
-        .chars()
-        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
-        .collect();
-
-    let file_path = cache_dir.join(format!("artwork-{}.{}", safe_id, ext));
-    std::fs::write(&file_path, data)?;
-
-    Ok(file_path.to_string_lossy().to_string())
+fn thumbnail_from_bytes(data: &[u8]) -> Result<RandomAccessStreamReference> {
+    let stream = InMemoryRandomAccessStream::new()?;
+    let writer = DataWriter::CreateDataWriter(&stream)?;
+    writer.WriteBytes(data)?;
+    let store = writer.StoreAsync()?;
+    wait_for(|| store.Status())?;
+    store.GetResults()?;
+    writer.DetachStream()?;
+    stream.Seek(0)?;
+    Ok(RandomAccessStreamReference::CreateFromStream(&stream)?)
 }
 
-fn thumbnail_from_path(path: &str) -> Result<RandomAccessStreamReference> {
-    let op = StorageFile::GetFileFromPathAsync(&HSTRING::from(path))?;
+fn wait_for(status: impl Fn() -> windows::core::Result<AsyncStatus>) -> Result<()> {
     let start = std::time::Instant::now();
     loop {
-        match op.Status()? {
+        match status()? {
             AsyncStatus::Started => {
                 if start.elapsed() > std::time::Duration::from_secs(2) {
-                    return Err(anyhow!("timeout waiting for artwork file to load"));
+                    return Err(anyhow!("timeout waiting for artwork to load"));
                 }
                 std::thread::sleep(std::time::Duration::from_millis(10));
             }
-            _ => break,
+            AsyncStatus::Completed => return Ok(()),
+            _ => return Err(anyhow!("loading artwork failed")),
         }
     }
-    let file = op.GetResults()?;
-    Ok(RandomAccessStreamReference::CreateFromFile(&file)?)
 }
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
 
 use parking_lot::RwLock;
 
@@ -8,6 +9,8 @@ pub struct Equalizer {
     sample_rate: u32,
     bands: Vec<Band>,
     coeffs: Arc<RwLock<Vec<Coeffs>>>,
+    /// Linear preamp gain, stored as `f32` bits so the audio thread can read it without a lock.
+    preamp: Arc<AtomicU32>,
 }
 
 pub struct Band {
@@ -51,11 +54,18 @@ impl Equalizer {
             .collect();
 
         let coeffs = Arc::new(RwLock::new(coeffs_vec));
+        let preamp_db = if config.enabled {
+            config.preamp_db
+        } else {
+            0.0
+        };
+        let preamp = Arc::new(AtomicU32::new(db_to_linear(preamp_db).to_bits()));
 
         Self {
             sample_rate,
             bands,
             coeffs,
+            preamp,
         }
     }
 
@@ -79,6 +89,17 @@ impl Equalizer {
                     Coeffs::peaking(freq as f32, q, effective_gain, self.sample_rate as f32);
             }
         }
+
+        self.set_preamp(if config.enabled {
+            config.preamp_db
+        } else {
+            0.0
+        });
+    }
+
+    pub fn set_preamp(&mut self, preamp_db: f32) {
+        self.preamp
+            .store(db_to_linear(preamp_db).to_bits(), Ordering::Relaxed);
     }
 
     pub fn set_gain(&mut self, band: usize, gain_db: f32) {
@@ -112,6 +133,14 @@ impl Equalizer {
     pub(crate) fn get_coeffs(&self) -> Arc<RwLock<Vec<Coeffs>>> {
         self.coeffs.clone()
     }
+
+    pub(crate) fn get_preamp(&self) -> Arc<AtomicU32> {
+        self.preamp.clone()
+    }
+}
+
+fn db_to_linear(db: f32) -> f32 {
+    10.0f32.powf(db / 20.0)
 }
 
 impl Coeffs {
@@ -151,6 +180,7 @@ impl Coeffs {
 pub struct EqualizerSource<S> {
     inner: S,
     coeffs: Arc<RwLock<Vec<Coeffs>>>,
+    preamp: Arc<AtomicU32>,
     states: Vec<Vec<(f32, f32)>>,
     current_channel: usize,
 }
@@ -166,6 +196,7 @@ impl<S: rodio::Source<Item = f32>> EqualizerSource<S> {
         Self {
             inner,
             coeffs: eq.get_coeffs(),
+            preamp: eq.get_preamp(),
             states,
             current_channel: 0,
         }
@@ -179,7 +210,7 @@ impl<S: rodio::Source<Item = f32>> Iterator for EqualizerSource<S> {
         let input = self.inner.next()?;
         let ch = self.current_channel;
         let coeffs_guard = self.coeffs.read();
-        let mut s = input;
+        let mut s = input * f32::from_bits(self.preamp.load(Ordering::Relaxed));
         let state = &mut self.states[ch];
         let bands = state.len().min(coeffs_guard.len());
         for (i, (z1, z2)) in state.iter_mut().enumerate().take(bands) {
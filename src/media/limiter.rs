@@ -0,0 +1,119 @@
+use rodio::Source;
+use std::collections::VecDeque;
+use std::num::NonZero;
+use std::time::Duration;
+
+const THRESHOLD: f32 = 0.891; // -1 dBFS
+const LOOKAHEAD_SECS: f32 = 0.0015;
+const RELEASE_SECS: f32 = 0.08;
+
+pub struct Limiter<S> {
+    inner: S,
+    enabled: bool,
+    delay: VecDeque<f32>,
+    lookahead: usize,
+    gain: f32,
+    target: f32,
+    attack_step: f32,
+    release_coeff: f32,
+    hold: usize,
+}
+
+impl<S: Source<Item = f32>> Limiter<S> {
+    pub fn new(inner: S, enabled: bool) -> Self {
+        let channels = inner.channels().get() as usize;
+        let sample_rate = inner.sample_rate().get() as f32;
+        let lookahead = ((sample_rate * LOOKAHEAD_SECS) as usize).max(1) * channels;
+        let release_coeff = 1.0 - (-1.0 / (sample_rate * channels as f32 * RELEASE_SECS)).exp();
+        Self {
+            inner,
+            enabled,
+            delay: VecDeque::with_capacity(lookahead + 1),
+            lookahead,
+            gain: 1.0,
+            target: 1.0,
+            attack_step: 0.0,
+            release_coeff,
+            hold: 0,
+        }
+    }
+
+    fn analyse(&mut self, sample: f32) {
+        let magnitude = sample.abs();
+        let required = if magnitude > THRESHOLD {
+            THRESHOLD / magnitude
+        } else {
+            1.0
+        };
+
+        if required < self.target {
+            self.target = required;
+            self.attack_step = (self.gain - required).max(0.0) / self.lookahead as f32;
+            self.hold = self.lookahead;
+        } else if self.hold > 0 {
+            self.hold -= 1;
+        } else {
+            self.target = 1.0;
+        }
+    }
+
+    fn pull(&mut self) -> bool {
+        match self.inner.next() {
+            Some(sample) => {
+                self.analyse(sample);
+                self.delay.push_back(sample);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn soft_clip(sample: f32) -> f32 {
+    let magnitude = sample.abs();
+    if magnitude <= THRESHOLD {
+        return sample;
+    }
+    let headroom = 1.0 - THRESHOLD;
+    let over = (magnitude - THRESHOLD) / headroom;
+    (THRESHOLD + headroom * over.tanh()).copysign(sample)
+}
+
+impl<S: Source<Item = f32>> Iterator for Limiter<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.enabled {
+            return self.inner.next();
+        }
+
+        while self.delay.len() <= self.lookahead && self.pull() {}
+        let sample = self.delay.pop_front()?;
+
+        if self.gain > self.target {
+            self.gain = (self.gain - self.attack_step).max(self.target);
+        } else {
+            self.gain += (self.target - self.gain) * self.release_coeff;
+        }
+
+        Some(soft_clip(sample * self.gain))
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Limiter<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> NonZero<u16> {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> NonZero<u32> {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
@@ -0,0 +1,245 @@
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rodio::Source;
+use rodio::decoder::DecoderBuilder;
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::BufReader;
+use std::num::NonZero;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use symphonia_adapter_libopus::OpusDecoder;
+
+use super::visualizer::F32Converter;
+
+const STEP_SECS: f64 = 0.1;
+const STEPS_PER_BLOCK: usize = 4;
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = 10.0;
+
+#[derive(Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+// The two BS.1770 K-weighting stages, recomputed for any sample rate.
+fn k_weighting(sample_rate: f64) -> [Biquad; 2] {
+    let (f0, gain_db, q) = (1681.97445095553, 3.99984385397335, 0.70717523695542);
+    let k = (PI * f0 / sample_rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.499666774154542);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    let (f0, q) = (38.1354708760244, 0.500327037323877);
+    let k = (PI * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        z: [0.0; 2],
+    };
+
+    [shelf, high_pass]
+}
+
+fn block_loudness(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+/// Gated integrated loudness (ITU-R BS.1770) and sample peak over interleaved samples.
+pub struct LoudnessMeter {
+    filters: Vec<[Biquad; 2]>,
+    peak: f32,
+    channel: usize,
+    step_len: usize,
+    step_frames: usize,
+    step_energy: f64,
+    steps: VecDeque<f64>,
+    blocks: Vec<f64>,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        let channels = channels.max(1) as usize;
+        Self {
+            filters: vec![k_weighting(sample_rate as f64); channels],
+            peak: 0.0,
+            channel: 0,
+            step_len: ((sample_rate as f64 * STEP_SECS) as usize).max(1),
+            step_frames: 0,
+            step_energy: 0.0,
+            steps: VecDeque::with_capacity(STEPS_PER_BLOCK),
+            blocks: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, sample: f32) {
+        self.peak = self.peak.max(sample.abs());
+        let [shelf, high_pass] = &mut self.filters[self.channel];
+        let weighted = high_pass.process(shelf.process(sample as f64));
+        self.step_energy += weighted * weighted;
+
+        self.channel += 1;
+        if self.channel < self.filters.len() {
+            return;
+        }
+        self.channel = 0;
+        self.step_frames += 1;
+        if self.step_frames < self.step_len {
+            return;
+        }
+
+        self.steps.push_back(self.step_energy);
+        self.step_frames = 0;
+        self.step_energy = 0.0;
+        if self.steps.len() == STEPS_PER_BLOCK {
+            let energy: f64 = self.steps.iter().sum();
+            self.blocks
+                .push(energy / (self.step_len * STEPS_PER_BLOCK) as f64);
+            self.steps.pop_front();
+        }
+    }
+
+    /// `None` until at least one 400 ms block rises above the absolute gate.
+    pub fn integrated(&self) -> Option<f32> {
+        let gated_mean = |threshold: f64| {
+            let above: Vec<f64> = self
+                .blocks
+                .iter()
+                .copied()
+                .filter(|&z| z > 0.0 && block_loudness(z) > threshold)
+                .collect();
+            (!above.is_empty()).then(|| above.iter().sum::<f64>() / above.len() as f64)
+        };
+
+        let absolute = gated_mean(ABSOLUTE_GATE_LUFS)?;
+        let relative = gated_mean(block_loudness(absolute) - RELATIVE_GATE_LU)?;
+        Some(block_loudness(relative) as f32)
+    }
+
+    pub fn measurement(&self) -> Measurement {
+        Measurement {
+            lufs: self.integrated(),
+            peak: self.peak,
+        }
+    }
+}
+
+/// What a song measured when decoded from start to end.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    /// `None` for silence or clips too short to gate.
+    pub lufs: Option<f32>,
+    pub peak: f32,
+}
+
+/// Decodes the stretch of `path` a song covers and measures it.
+pub fn measure_file(path: &Path, start_secs: f32, end_secs: Option<f32>) -> Result<Measurement> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let file_len = file.metadata()?.len();
+    let mut decoder = DecoderBuilder::new()
+        .with_decoder::<OpusDecoder>()
+        .with_data(BufReader::new(file))
+        .with_byte_len(file_len)
+        .build()
+        .context("Failed to decode audio file")?;
+    if start_secs > 0.0
+        && decoder
+            .try_seek(Duration::from_secs_f32(start_secs))
+            .is_err()
+    {
+        anyhow::bail!("Failed to seek to the track's start at {}s", start_secs);
+    }
+    let length = end_secs.map_or(Duration::MAX, |end| {
+        Duration::from_secs_f32((end - start_secs).max(0.0))
+    });
+
+    let samples = F32Converter {
+        input: decoder.take_duration(length),
+    };
+    let mut meter = LoudnessMeter::new(samples.sample_rate().get(), samples.channels().get());
+    for sample in samples {
+        meter.push(sample);
+    }
+    Ok(meter.measurement())
+}
+
+/// Passes samples through untouched and publishes the measurement once the inner source has
+/// played to its end.
+pub struct MeteredSource<S> {
+    inner: S,
+    meter: Option<LoudnessMeter>,
+    result: Arc<Mutex<Option<Measurement>>>,
+}
+
+impl<S: Source<Item = f32>> MeteredSource<S> {
+    pub fn new(inner: S, result: Arc<Mutex<Option<Measurement>>>) -> Self {
+        let meter = LoudnessMeter::new(inner.sample_rate().get(), inner.channels().get());
+        Self {
+            inner,
+            meter: Some(meter),
+            result,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for MeteredSource<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        match self.inner.next() {
+            Some(sample) => {
+                if let Some(meter) = &mut self.meter {
+                    meter.push(sample);
+                }
+                Some(sample)
+            }
+            None => {
+                if let Some(meter) = self.meter.take() {
+                    *self.result.lock() = Some(meter.measurement());
+                }
+                None
+            }
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Source for MeteredSource<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> NonZero<u16> {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> NonZero<u32> {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
@@ -1,5 +1,13 @@
 pub mod controller;
 pub mod equalizer;
+pub mod limiter;
+pub mod loudness;
+pub mod now_playing;
+#[cfg(feature = "null-audio")]
+pub mod null_output;
 pub mod playback;
 pub mod queue;
+pub mod resampler;
+pub mod seek_fade;
+pub mod transcode;
 pub mod visualizer;
@@ -0,0 +1,126 @@
+use crate::data::models::Song;
+use crate::media::queue::{Queue, RepeatMode};
+use std::sync::OnceLock;
+use tokio::sync::watch;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackState {
+    Playing,
+    Paused,
+    Stopped,
+}
+
+/// Everything integrations need to know about the current track, captured in one place so
+/// scrobbling, presence and media controls never disagree about what's playing.
+#[derive(Debug, Clone)]
+pub struct NowPlaying {
+    pub song: Option<Song>,
+    pub position: f32,
+    pub duration: f32,
+    pub state: PlaybackState,
+    pub shuffle: bool,
+    pub repeat: RepeatMode,
+    pub queue_index: Option<usize>,
+    pub queue_len: usize,
+    pub has_next: bool,
+    pub has_previous: bool,
+}
+
+impl NowPlaying {
+    pub fn capture(queue: &Queue, song: Option<Song>, position: f32, paused: bool) -> Self {
+        let state = match &song {
+            None => PlaybackState::Stopped,
+            Some(_) if paused => PlaybackState::Paused,
+            Some(_) => PlaybackState::Playing,
+        };
+        Self {
+            duration: song.as_ref().map_or(0.0, |s| s.duration.max(0) as f32),
+            position: song.as_ref().map_or(0.0, |_| position.max(0.0)),
+            song,
+            state,
+            shuffle: queue.get_shuffle(),
+            repeat: queue.get_repeat_mode(),
+            queue_index: queue.get_current_display_index(),
+            queue_len: queue.get_items().len(),
+            has_next: queue.has_next(),
+            has_previous: queue.has_previous(),
+        }
+    }
+}
+
+impl Default for NowPlaying {
+    fn default() -> Self {
+        Self {
+            song: None,
+            position: 0.0,
+            duration: 0.0,
+            state: PlaybackState::Stopped,
+            shuffle: false,
+            repeat: RepeatMode::Off,
+            queue_index: None,
+            queue_len: 0,
+            has_next: false,
+            has_previous: false,
+        }
+    }
+}
+
+// Songs are compared by id only, the rest of their fields can't change while they play.
+impl PartialEq for NowPlaying {
+    fn eq(&self, other: &Self) -> bool {
+        self.song.as_ref().map(|s| &s.id) == other.song.as_ref().map(|s| &s.id)
+            && self.position == other.position
+            && self.duration == other.duration
+            && self.state == other.state
+            && self.shuffle == other.shuffle
+            && self.repeat == other.repeat
+            && self.queue_index == other.queue_index
+            && self.queue_len == other.queue_len
+            && self.has_next == other.has_next
+            && self.has_previous == other.has_previous
+    }
+}
+
+/// A track that failed to open or seek, so the UI can say why nothing is playing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlaybackError {
+    pub title: String,
+    pub path: String,
+    pub reason: String,
+}
+
+static NOW_PLAYING_TX: OnceLock<watch::Sender<NowPlaying>> = OnceLock::new();
+
+fn sender() -> &'static watch::Sender<NowPlaying> {
+    NOW_PLAYING_TX.get_or_init(|| watch::Sender::new(NowPlaying::default()))
+}
+
+/// Receives every snapshot that differs from the previous one, starting with the latest.
+pub fn subscribe() -> watch::Receiver<NowPlaying> {
+    sender().subscribe()
+}
+
+pub fn publish(snapshot: NowPlaying) {
+    sender().send_if_modified(|current| {
+        if *current == snapshot {
+            return false;
+        }
+        *current = snapshot;
+        true
+    });
+}
+
+static PLAYBACK_ERROR_TX: OnceLock<watch::Sender<Option<PlaybackError>>> = OnceLock::new();
+
+fn error_sender() -> &'static watch::Sender<Option<PlaybackError>> {
+    PLAYBACK_ERROR_TX.get_or_init(|| watch::Sender::new(None))
+}
+
+/// Receives every reported playback error, including repeats of the same one.
+pub fn subscribe_errors() -> watch::Receiver<Option<PlaybackError>> {
+    error_sender().subscribe()
+}
+
+pub fn report_error(error: PlaybackError) {
+    error_sender().send_replace(Some(error));
+}
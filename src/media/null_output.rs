@@ -0,0 +1,51 @@
+use parking_lot::Mutex;
+use rodio::Source;
+use rodio::mixer::{self, Mixer, MixerSource};
+use std::num::NonZero;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+const SAMPLE_RATE: NonZero<u32> = NonZero::new(44100).unwrap();
+const CHANNELS: NonZero<u16> = NonZero::new(2).unwrap();
+
+/// Output that consumes samples without a device. Time only moves when `advance` is called, so
+/// playback can be stepped deterministically.
+#[derive(Clone)]
+pub struct NullOutput {
+    mixer: Mixer,
+    sample_rate: NonZero<u32>,
+    source: Arc<Mutex<MixerSource>>,
+}
+
+impl NullOutput {
+    pub fn new(sample_rate: NonZero<u32>, channels: NonZero<u16>) -> Self {
+        let (mixer, source) = mixer::mixer(channels, sample_rate);
+        Self {
+            mixer,
+            sample_rate,
+            source: Arc::new(Mutex::new(source)),
+        }
+    }
+
+    pub fn shared() -> &'static NullOutput {
+        static OUTPUT: OnceLock<NullOutput> = OnceLock::new();
+        OUTPUT.get_or_init(|| NullOutput::new(SAMPLE_RATE, CHANNELS))
+    }
+
+    pub fn sample_rate(&self) -> NonZero<u32> {
+        self.sample_rate
+    }
+
+    pub fn mixer(&self) -> &Mixer {
+        &self.mixer
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut source = self.source.lock();
+        let frames = (by.as_secs_f64() * source.sample_rate().get() as f64).round() as usize;
+        let samples = frames * source.channels().get() as usize;
+        for _ in 0..samples {
+            source.next();
+        }
+    }
+}
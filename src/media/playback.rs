@@ -1,28 +1,57 @@
 use super::equalizer::{Equalizer, EqualizerSource};
+use super::limiter::Limiter;
+use super::loudness::{Measurement, MeteredSource};
+use super::now_playing::{self, NowPlaying, PlaybackError};
+#[cfg(feature = "null-audio")]
+use super::null_output::NullOutput;
 use super::queue::Queue;
-use crate::data::config::{Config, EqualizerSettings};
+use super::resampler::Resampler;
+use super::seek_fade::SeekFade;
+use crate::data::config::{
+    AudioFocusBehavior, Config, EqualizerSettings, LoudnessSource, ResamplerQuality, ResumeOnLaunch,
+};
 use crate::data::db::repo::Database;
-use crate::data::models::{Cuid, EventType};
+use crate::data::metadata::AudioMetadata;
+use crate::data::models::{Cuid, EventType, SavedQueue, SavedQueueItem, Song};
+use crate::data::scanner::Scanner;
 use crate::media::controller::{MediaController, PlaybackState};
-use crate::media::visualizer::{F32Converter, VisualizerSource, VisualizerState};
+use crate::media::visualizer::{F32Converter, SPECTRUM_BARS, VisualizerSource, VisualizerState};
 use crate::ui::components::context_menu::{BackgroundUiEvent, BackgroundUiNotifier, QueueChanged};
 use anyhow::{Context, Result};
 use gpui::{App, AsyncWindowContext, BorrowAppContext, Global, Window};
 use parking_lot::Mutex;
+use rand::seq::SliceRandom;
+#[cfg(not(feature = "null-audio"))]
+use rodio::DeviceSinkBuilder;
 use rodio::decoder::{Decoder, DecoderBuilder};
 use rodio::mixer::Mixer;
 use rodio::source::Source;
-use rodio::{DeviceSinkBuilder, MixerDeviceSink, Player as Sink};
+use rodio::{MixerDeviceSink, Player as Sink};
 use std::fs::File;
 use std::io::BufReader;
+use std::num::NonZero;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, OnceLock};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use symphonia_adapter_libopus::OpusDecoder;
 
 use tokio::sync::mpsc;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 const DEFAULT_TARGET_LUFS: f32 = -14.0;
+const MAX_NORMALIZATION_GAIN_DB: f32 = 12.0;
+pub const MAX_PREAMP_DB: f32 = 12.0;
+pub const MAX_GAIN_OFFSET_DB: f32 = 12.0;
+const DUCK_GAIN: f32 = 0.25;
+const MONITOR_INTERVAL: Duration = Duration::from_millis(100);
+const RADIO_CANDIDATES: i64 = 60;
+const RADIO_BATCH: usize = 10;
+/// Moving on by hand before this far into a song counts as skipping it.
+const SKIP_BEFORE_SECS: f32 = 30.0;
+/// With gapless playback on, the next song is queued up once this little of the current one
+/// is left.
+const PRELOAD_BEFORE_END_SECS: f32 = 5.0;
 
 #[derive(Debug, Clone)]
 pub enum PlaybackCommand {
@@ -34,30 +63,105 @@ pub enum PlaybackCommand {
     Next,
     Previous,
     Seek(f32),
+    SystemSleep,
+    SystemWake,
+    SessionLocked,
+    SessionUnlocked,
+    FocusLost,
+    FocusRegained,
+}
+
+// Where a track should play: the open mixer and its rate if there is one, otherwise what to open.
+struct OutputRequest {
+    existing: Option<(Mixer, Option<NonZero<u32>>)>,
+    forced_rate: Option<NonZero<u32>>,
+    quality: ResamplerQuality,
+}
+
+// What a track's playback gain is worked out from.
+#[derive(Clone, Copy)]
+struct TrackLevels {
+    lufs: Option<f32>,
+    track_peak: Option<f32>,
+    gain_offset_db: f32,
 }
 
 struct PreparedPlayback {
     _device: Option<MixerDeviceSink>,
     mixer: Mixer,
+    output_rate: Option<NonZero<u32>>,
     sink: Sink,
     current_file: String,
     lufs: Option<f32>,
+    track_peak: Option<f32>,
+    gain_offset_db: f32,
+    measurement: Arc<Mutex<Option<Measurement>>>,
+    fading_out: Arc<AtomicBool>,
+}
+
+// The song after the current one, queued on the sink behind it.
+struct PreloadedTrack {
+    song: Song,
+    levels: TrackLevels,
+    measurement: Arc<Mutex<Option<Measurement>>>,
+    fading_out: Arc<AtomicBool>,
+    // Raised to drop the track from the sink if the queue moves elsewhere before it starts.
+    cancelled: Arc<AtomicBool>,
+    // Set once the track is on the sink; until then it is still being opened.
+    appended: bool,
+}
+
+// What the gapless check needs from the queue, looked up once per track and again when the
+// queue changes, since finding the next song can query the database for every entry.
+#[derive(Clone)]
+struct GaplessPeek {
+    current_duration: i32,
+    next: Option<Cuid>,
 }
 
 pub struct Playback {
     _device: Option<MixerDeviceSink>,
     mixer: Option<Mixer>,
+    output_rate: Option<NonZero<u32>>,
+    output_sample_rate: Option<u32>,
+    resampler_quality: ResamplerQuality,
     sink: Option<Sink>,
     equalizer: Arc<Mutex<Equalizer>>,
     volume: f32,
     paused: bool,
     current_file: Option<String>,
     current_lufs: Option<f32>,
+    current_track_peak: Option<f32>,
+    current_gain_offset_db: f32,
+    current_measurement: Option<(Cuid, Arc<Mutex<Option<Measurement>>>)>,
+    normalization_preamp_db: f32,
+    limiter_enabled: bool,
+    eq_enabled: bool,
+    seek_fade: Duration,
+    // Raised to fade the sink's current source out before it is replaced.
+    fading_out: Arc<AtomicBool>,
+    // The stretch of `current_file` the song covers, which is part of it for cue sheet tracks.
+    current_start_secs: f32,
+    current_end_secs: Option<f32>,
+    gapless: bool,
+    preloaded: Option<PreloadedTrack>,
+    preload_token: u64,
+    gapless_peek: Option<GaplessPeek>,
     position: f32,
     visualizer_state: VisualizerState,
     command_rx: Option<mpsc::UnboundedReceiver<PlaybackCommand>>,
     load_token: u64,
     loading: bool,
+    pause_on_sleep: bool,
+    paused_by_system: bool,
+    /// Set between a session lock and its unlock, so waking into a lock screen stays paused.
+    session_locked: bool,
+    focus_behavior: AudioFocusBehavior,
+    paused_for_focus: bool,
+    ducked: bool,
+    release_output_after: Option<Duration>,
+    // Set when the device was closed for being idle; `play` opens it again.
+    output_released: bool,
 }
 
 impl Global for Playback {}
@@ -65,57 +169,80 @@ impl Global for Playback {}
 static PLAYBACK_CMD_TX: OnceLock<mpsc::UnboundedSender<PlaybackCommand>> = OnceLock::new();
 
 impl Playback {
-    fn compute_normalization_gain_for(current_lufs: Option<f32>) -> f32 {
+    fn compute_normalization_gain_for(
+        current_lufs: Option<f32>,
+        track_peak: Option<f32>,
+        preamp_db: f32,
+    ) -> f32 {
         if let Some(lufs) = current_lufs {
-            let gain_db = (DEFAULT_TARGET_LUFS - lufs).clamp(-12.0, 12.0);
+            let mut gain_db = requested_gain_db(lufs, preamp_db);
+            // Positive gain must not push the known peak past full scale.
+            if gain_db > 0.0
+                && let Some(peak) = track_peak.filter(|p| *p > 0.0)
+            {
+                gain_db = gain_db.min((-20.0 * peak.log10()).max(0.0));
+            }
             let linear_gain = 10.0f32.powf(gain_db / 20.0);
-            debug!("Normalization: LUFS {:.2}, gain {:.2} dB", lufs, gain_db);
+            debug!(
+                "Normalization: LUFS {:.2}, peak {:?}, preamp {:.2} dB, gain {:.2} dB",
+                lufs, track_peak, preamp_db, gain_db
+            );
             return linear_gain;
         }
         1.0
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn prepare_playback(
         path: String,
-        lufs: Option<f32>,
+        start_secs: f32,
+        end_secs: Option<f32>,
+        levels: TrackLevels,
+        preamp_db: f32,
+        limiter_enabled: bool,
         volume: f32,
         eq_settings: EqualizerSettings,
         equalizer: Arc<Mutex<Equalizer>>,
         visualizer_state: VisualizerState,
-        existing_mixer: Option<Mixer>,
+        output: OutputRequest,
+        seek_fade: Duration,
     ) -> Result<PreparedPlayback> {
         let file =
             File::open(&path).with_context(|| format!("Failed to open audio file: {:?}", path))?;
         let file_len = file.metadata()?.len();
 
-        let decoder = DecoderBuilder::new()
+        let mut decoder = DecoderBuilder::new()
             .with_decoder::<OpusDecoder>()
             .with_data(BufReader::new(file))
             .with_byte_len(file_len)
             .build()
             .context("Failed to decode audio file")?;
+        if start_secs > 0.0
+            && decoder
+                .try_seek(Duration::from_secs_f32(start_secs))
+                .is_err()
+        {
+            anyhow::bail!("Failed to seek to the track's start at {}s", start_secs);
+        }
+        let decoder = decoder.take_duration(play_length(start_secs, end_secs, 0.0));
 
-        let source = F32Converter { input: decoder };
-        let sample_rate = source.sample_rate();
+        let measurement = Arc::new(Mutex::new(None));
+        let source = MeteredSource::new(F32Converter { input: decoder }, measurement.clone());
+        let track_rate = source.sample_rate();
         let channels = source.channels();
 
-        debug!("Audio file: {:?}Hz, {:?} channels", sample_rate, channels);
+        debug!("Audio file: {:?}Hz, {:?} channels", track_rate, channels);
 
-        let (new_device, mixer) = if let Some(m) = existing_mixer {
-            (None, m)
-        } else {
-            let mut device = DeviceSinkBuilder::from_default_device()
-                .and_then(|b| {
-                    b.with_sample_rate(sample_rate)
-                        .with_channels(channels)
-                        .open_stream()
-                })
-                .or_else(|_| DeviceSinkBuilder::open_default_sink())
-                .context("Failed to open audio device")?;
-            device.log_on_drop(false);
-            let m = device.mixer().clone();
-            (Some(device), m)
+        let (new_device, mixer, output_rate) = match output.existing {
+            Some((mixer, rate)) => (None, mixer, rate),
+            None => {
+                let rate = output.forced_rate.unwrap_or(track_rate);
+                let (device, mixer, rate) = Self::open_output(Some(rate), Some(channels))?;
+                (device, mixer, Some(rate))
+            }
         };
+        let source = Resampler::new(source, output_rate.unwrap_or(track_rate), output.quality);
+        let sample_rate = source.sample_rate();
 
         let sink = Sink::connect_new(&mixer);
 
@@ -124,40 +251,122 @@ impl Playback {
 
         let eq_source = EqualizerSource::new(source, equalizer.clone());
         let vis_source = VisualizerSource::new(eq_source, visualizer_state);
-        let gain = Self::compute_normalization_gain_for(lufs);
-        let normalized = vis_source.amplify(gain);
-
-        sink.append(normalized);
+        let gain = Self::compute_track_gain(levels, preamp_db);
+        let limiter_active = limiter_enabled
+            && (levels.lufs.is_some() || eq_settings.enabled || levels.gain_offset_db > 0.0);
+        let normalized = Limiter::new(vis_source.amplify(gain), limiter_active);
+        let fading_out = Arc::new(AtomicBool::new(false));
+
+        sink.append(SeekFade::new(
+            normalized,
+            seek_fade,
+            false,
+            fading_out.clone(),
+        ));
         sink.set_volume(Self::compute_log_volume(volume));
         sink.pause();
 
         Ok(PreparedPlayback {
             _device: new_device,
             mixer,
+            output_rate,
             sink,
             current_file: path,
-            lufs,
+            lufs: levels.lufs,
+            track_peak: levels.track_peak,
+            gain_offset_db: levels.gain_offset_db,
+            measurement,
+            fading_out,
         })
     }
 
+    // Asks the device for `sample_rate` and falls back to its default format, returning the rate
+    // the stream actually opened at either way.
+    #[cfg(not(feature = "null-audio"))]
+    fn open_output(
+        sample_rate: Option<NonZero<u32>>,
+        channels: Option<NonZero<u16>>,
+    ) -> Result<(Option<MixerDeviceSink>, Mixer, NonZero<u32>)> {
+        let requested = sample_rate.and_then(|rate| {
+            DeviceSinkBuilder::from_default_device()
+                .and_then(|b| {
+                    let b = b.with_sample_rate(rate);
+                    match channels {
+                        Some(channels) => b.with_channels(channels),
+                        None => b,
+                    }
+                    .open_stream()
+                })
+                .ok()
+                .map(|device| (device, rate))
+        });
+        let (mut device, sample_rate) = match requested {
+            Some((device, rate)) => (device, rate),
+            None => {
+                let device = DeviceSinkBuilder::open_default_sink()
+                    .context("Failed to open audio device")?;
+                let rate = device.config().sample_rate();
+                (device, rate)
+            }
+        };
+        device.log_on_drop(false);
+        let mixer = device.mixer().clone();
+        Ok((Some(device), mixer, sample_rate))
+    }
+
+    #[cfg(feature = "null-audio")]
+    fn open_output(
+        _sample_rate: Option<NonZero<u32>>,
+        _channels: Option<NonZero<u16>>,
+    ) -> Result<(Option<MixerDeviceSink>, Mixer, NonZero<u32>)> {
+        let output = NullOutput::shared();
+        Ok((None, output.mixer().clone(), output.sample_rate()))
+    }
+
+    /// Moves the null output forward by `by`, as if that much audio had been played.
+    #[cfg(feature = "null-audio")]
+    pub fn advance_clock(&self, by: Duration) {
+        NullOutput::shared().advance(by);
+    }
+
     fn load_song_by_id(&mut self, cx: &mut App, song_id: Cuid) {
+        self.load_song_at(cx, song_id, 0.0, true);
+    }
+
+    /// Loads `song_id` at `start_secs`, left paused there unless `autoplay` is set.
+    fn load_song_at(&mut self, cx: &mut App, song_id: Cuid, start_secs: f32, autoplay: bool) {
+        refill_radio(cx);
         let db = cx.global::<Database>().clone();
         let config = cx.global::<Config>().clone();
         let eq_settings = config.get().equalizer.clone();
+        let loudness_source = config.get().audio.loudness_source;
         let equalizer = self.equalizer.clone();
         let visualizer_state = self.visualizer_state.clone();
         let volume = self.volume;
+        let preamp_db = self.normalization_preamp_db;
+        let limiter_enabled = self.limiter_enabled;
+        let seek_fade = self.seek_fade;
         let executor = cx.background_executor().clone();
 
+        self.cancel_preload();
+        self.gapless_peek = None;
         self.load_token = self.load_token.wrapping_add(1);
         let token = self.load_token;
         self.loading = true;
         self.position = 0.0;
         self.sink = None;
-        let existing_mixer = self.mixer.clone();
+        let output = OutputRequest {
+            existing: self.mixer.clone().map(|mixer| (mixer, self.output_rate)),
+            forced_rate: self.output_sample_rate.and_then(NonZero::new),
+            quality: self.resampler_quality,
+        };
+        let transient = cx.global::<Queue>().transient_song(&song_id);
 
         cx.spawn(async move |cx| {
-            let song = db.get_song(&song_id);
+            let song = match transient {
+                Some(song) => Ok(Some(song)),
+                None => db.get_song(&song_id),
+            };
 
             let Ok(Some(song)) = song else {
                 cx.update(|cx| {
@@ -171,18 +380,35 @@ impl Playback {
             };
 
             let path = song.file_path.clone();
-            let lufs = song.lufs;
+            let lufs = song_loudness(&song, loudness_source).map(|(lufs, _)| lufs);
+            if let Some(overshoot) = clipping_overshoot_db(&song, loudness_source, preamp_db) {
+                warn!(
+                    "{:?} peaks {:.1} dB over full scale with a {:+.1} dB preamp",
+                    path, overshoot, preamp_db
+                );
+            }
+            let levels = TrackLevels {
+                lufs,
+                track_peak: song_peak(&song),
+                gain_offset_db: song.gain_offset_db,
+            };
+            let (start_secs_in_file, end_secs_in_file) = (song.start_secs, song.end_secs);
 
             let prepared = executor
                 .spawn(async move {
                     Playback::prepare_playback(
                         path,
-                        lufs,
+                        start_secs_in_file,
+                        end_secs_in_file,
+                        levels,
+                        preamp_db,
+                        limiter_enabled,
                         volume,
                         eq_settings,
                         equalizer,
                         visualizer_state,
-                        existing_mixer,
+                        output,
+                        seek_fade,
                     )
                 })
                 .await;
@@ -190,7 +416,8 @@ impl Playback {
             let prepared = match prepared {
                 Ok(prepared) => prepared,
                 Err(e) => {
-                    error!("Failed to open track: {}", e);
+                    error!("Failed to open track {:?}: {}", song.file_path, e);
+                    report_playback_error(&song.title, &song.file_path, &e);
                     cx.update(|cx| {
                         cx.update_global::<Playback, _>(|playback, _cx| {
                             if playback.load_token == token {
@@ -217,18 +444,37 @@ impl Playback {
                     if let Some(device) = prepared._device {
                         playback._device = Some(device);
                         playback.mixer = Some(prepared.mixer);
+                        playback.output_released = false;
                     }
+                    playback.output_rate = prepared.output_rate;
                     playback.sink = Some(prepared.sink);
+                    playback.apply_sink_volume();
                     playback.position = 0.0;
                     playback.current_file = Some(prepared.current_file);
                     playback.current_lufs = prepared.lufs;
+                    playback.current_track_peak = prepared.track_peak;
+                    playback.current_gain_offset_db = prepared.gain_offset_db;
+                    playback.current_measurement = Some((song.id.clone(), prepared.measurement));
+                    playback.fading_out = prepared.fading_out;
+                    playback.current_start_secs = start_secs_in_file;
+                    playback.current_end_secs = end_secs_in_file;
                     playback.paused = true;
                     playback.loading = false;
-                    debug!("Calling play()");
-                    playback.play(cx);
+                    if start_secs > 0.0
+                        && let Err(e) = playback.seek(start_secs)
+                    {
+                        error!("Failed to seek to {}s: {}", start_secs, e);
+                        report_playback_error(&song.title, &song.file_path, &e);
+                    }
                     applied = true;
-                    debug!("Song applied to playback");
-                    Self::log_event(cx, EventType::Play, Some(song.id.clone()));
+                    if autoplay {
+                        debug!("Calling play()");
+                        playback.play(cx);
+                        debug!("Song applied to playback");
+                        Self::log_event(cx, EventType::Play, Some(song.id.clone()));
+                    } else if let Some(mc) = cx.try_global::<MediaController>() {
+                        mc.set_state(PlaybackState::Paused).ok();
+                    }
                 });
 
                 if !applied {
@@ -242,13 +488,7 @@ impl Playback {
 
                 debug!("Song cached in queue");
 
-                if let Some(mc) = cx.try_global::<MediaController>().cloned() {
-                    cx.background_executor()
-                        .spawn(async move {
-                            mc.update_song(song).ok();
-                        })
-                        .detach();
-                }
+                update_media_controller(song, cx);
             });
 
             debug!("cx.update completed");
@@ -262,18 +502,43 @@ impl Playback {
         Ok(Self {
             _device: None,
             mixer: None,
+            output_rate: None,
+            output_sample_rate: None,
+            resampler_quality: ResamplerQuality::default(),
             sink: None,
             equalizer,
             volume: 0.5,
             paused: true,
             current_file: None,
             current_lufs: None,
+            current_track_peak: None,
+            current_gain_offset_db: 0.0,
+            current_measurement: None,
+            normalization_preamp_db: 0.0,
+            limiter_enabled: true,
+            eq_enabled: false,
+            seek_fade: Duration::ZERO,
+            fading_out: Arc::new(AtomicBool::new(false)),
+            current_start_secs: 0.0,
+            current_end_secs: None,
+            gapless: true,
+            preloaded: None,
+            preload_token: 0,
+            gapless_peek: None,
 
             position: 0.0,
             visualizer_state: VisualizerState::default(),
             command_rx: None,
             load_token: 0,
             loading: false,
+            pause_on_sleep: true,
+            paused_by_system: false,
+            session_locked: false,
+            focus_behavior: AudioFocusBehavior::default(),
+            paused_for_focus: false,
+            ducked: false,
+            release_output_after: None,
+            output_released: false,
         })
     }
 
@@ -288,6 +553,10 @@ impl Playback {
         cx.set_global(playback);
 
         Self::start_command_processor(cx);
+        cx.observe_global::<QueueChanged>(|cx| {
+            cx.update_global::<Playback, _>(|playback, _cx| playback.gapless_peek = None);
+        })
+        .detach();
 
         Ok(())
     }
@@ -341,8 +610,45 @@ impl Playback {
                         });
                     }
                     PlaybackCommand::Seek(position) => {
-                        cx.update_global::<Playback, _>(|playback, _cx| {
-                            playback.seek(position).ok();
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            playback.seek_reporting_errors(position, cx);
+                        });
+                    }
+                    PlaybackCommand::SystemSleep => {
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            playback.pause_for_system(cx);
+                        });
+                    }
+                    PlaybackCommand::SessionLocked => {
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            playback.session_locked = true;
+                            playback.pause_for_system(cx);
+                        });
+                    }
+                    PlaybackCommand::SystemWake => {
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            if let Err(e) = playback.reopen_output() {
+                                error!("Failed to reopen audio output after wake: {}", e);
+                            }
+                            if !playback.session_locked {
+                                playback.resume_after_system(cx);
+                            }
+                        });
+                    }
+                    PlaybackCommand::SessionUnlocked => {
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            playback.session_locked = false;
+                            playback.resume_after_system(cx);
+                        });
+                    }
+                    PlaybackCommand::FocusLost => {
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            playback.focus_lost(cx);
+                        });
+                    }
+                    PlaybackCommand::FocusRegained => {
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            playback.focus_regained(cx);
                         });
                     }
                 });
@@ -352,6 +658,12 @@ impl Playback {
     }
 
     pub fn play(&mut self, cx: &mut App) {
+        if self.paused && self.output_released {
+            if let Err(e) = self.reopen_output() {
+                error!("Failed to reopen audio output: {}", e);
+                return;
+            }
+        }
         if self.paused
             && let Some(sink) = &self.sink
         {
@@ -387,6 +699,7 @@ impl Playback {
 
         self.load_token = self.load_token.wrapping_add(1);
         self.loading = false;
+        self.cancel_preload();
 
         if let Some(sink) = &self.sink {
             sink.stop();
@@ -394,9 +707,13 @@ impl Playback {
         self.sink = None;
         self.current_file = None;
         self.current_lufs = None;
+        self.current_track_peak = None;
+        self.current_gain_offset_db = 0.0;
+        self.current_measurement = None;
         self.position = 0.0;
         self.paused = true;
         *self.visualizer_state.bands.lock() = [0.0; 4];
+        self.visualizer_state.spectrum.lock().clear();
 
         cx.update_global::<Queue, _>(|queue, _cx| {
             queue.clear();
@@ -413,7 +730,10 @@ impl Playback {
     }
 
     pub fn play_pause(&mut self, cx: &mut App) {
-        if self.paused {
+        if self.paused && self.sink.is_none() && !self.loading {
+            // A queue restored without loading its song starts on the first press.
+            self.play_queue(cx);
+        } else if self.paused {
             let song_id = cx.global::<Queue>().get_current_song_id();
             self.play(cx);
             if !self.paused {
@@ -442,19 +762,39 @@ impl Playback {
                 .with_data(BufReader::new(file))
                 .with_byte_len(file_len)
                 .build()?;
-            if source.try_seek(Duration::from_secs_f32(position)).is_err() {
+            let seek_to = self.current_start_secs + position;
+            if source.try_seek(Duration::from_secs_f32(seek_to)).is_err() {
                 return Ok(());
             }
-
+            let source = source.take_duration(play_length(
+                self.current_start_secs,
+                self.current_end_secs,
+                position,
+            ));
+
+            let output_rate = self.output_rate.unwrap_or(source.sample_rate());
+            let source = Resampler::new(source, output_rate, self.resampler_quality);
             let eq_source = EqualizerSource::new(source, self.equalizer.clone());
             let vis_source = VisualizerSource::new(eq_source, self.visualizer_state.clone());
             let gain = self.compute_normalization_gain();
-            let normalized = vis_source.amplify(gain);
-
+            let normalized = Limiter::new(vis_source.amplify(gain), self.limiter_active());
+            let fading_out = Arc::new(AtomicBool::new(false));
+            let faded = SeekFade::new(normalized, self.seek_fade, true, fading_out.clone());
+
+            // A preloaded track already on the sink may be the one playing, so that case stops
+            // the sink outright rather than fading out a source that isn't heard.
+            let preload_appended = self.preloaded.as_ref().is_some_and(|track| track.appended);
+            self.cancel_preload();
             if let Some(sink) = &self.sink {
-                sink.stop();
-                sink.append(normalized);
-                sink.set_volume(Self::compute_log_volume(self.volume));
+                if was_playing && !self.seek_fade.is_zero() && !preload_appended {
+                    // The current source fades out and ends on the audio thread, and the sink
+                    // moves straight on to the seek target queued behind it.
+                    self.fading_out.store(true, Ordering::Relaxed);
+                } else {
+                    sink.stop();
+                }
+                sink.append(faded);
+                sink.set_volume(self.effective_volume());
 
                 if was_playing {
                     sink.play();
@@ -464,18 +804,131 @@ impl Playback {
                     self.paused = true;
                 }
             }
+            self.fading_out = fading_out;
             self.position = position;
         }
         Ok(())
     }
 
-    pub fn set_volume(&mut self, volume: f32, _cx: &mut App) {
-        self.volume = volume.clamp(0.0, 1.0);
-        let log_volume = Self::compute_log_volume(self.volume);
+    /// Seeks like [`Playback::seek`], but logs a failure and reports it to the UI.
+    pub fn seek_reporting_errors(&mut self, position: f32, cx: &App) {
+        let Err(e) = self.seek(position) else {
+            return;
+        };
+        error!("Failed to seek to {}s: {}", position, e);
+        let Some(path) = self.current_file.clone() else {
+            return;
+        };
+        let title = cx
+            .global::<Queue>()
+            .get_current_song(cx)
+            .map(|song| song.title)
+            .unwrap_or_else(|| path.clone());
+        report_playback_error(&title, &path, &e);
+    }
+
+    fn pause_for_system(&mut self, cx: &mut App) {
+        if !self.pause_on_sleep || self.paused {
+            return;
+        }
+        self.pause(cx);
+        self.paused_by_system = self.paused;
+    }
 
+    fn resume_after_system(&mut self, cx: &mut App) {
+        if std::mem::take(&mut self.paused_by_system) {
+            self.play(cx);
+        }
+    }
+
+    fn focus_lost(&mut self, cx: &mut App) {
+        if self.paused {
+            return;
+        }
+        match self.focus_behavior {
+            AudioFocusBehavior::Ignore => {}
+            AudioFocusBehavior::Pause => {
+                self.pause(cx);
+                self.paused_for_focus = self.paused;
+            }
+            AudioFocusBehavior::Duck => {
+                self.ducked = true;
+                self.apply_sink_volume();
+            }
+        }
+    }
+
+    fn focus_regained(&mut self, cx: &mut App) {
+        if std::mem::take(&mut self.ducked) {
+            self.apply_sink_volume();
+        }
+        if std::mem::take(&mut self.paused_for_focus) {
+            self.play(cx);
+        }
+    }
+
+    fn effective_volume(&self) -> f32 {
+        let volume = Self::compute_log_volume(self.volume);
+        if self.ducked {
+            volume * DUCK_GAIN
+        } else {
+            volume
+        }
+    }
+
+    fn apply_sink_volume(&self) {
         if let Some(sink) = &self.sink {
-            sink.set_volume(log_volume);
+            sink.set_volume(self.effective_volume());
         }
+    }
+
+    /// Whether the device is open with nothing playing, which the monitor counts towards
+    /// releasing it.
+    fn holds_idle_output(&self) -> bool {
+        self.paused && !self.loading && self._device.is_some()
+    }
+
+    // The sink stays so the position survives; the mixer goes with the device so the next
+    // track opens a fresh one.
+    fn release_output(&mut self) {
+        if !self.holds_idle_output() {
+            return;
+        }
+        self._device = None;
+        self.mixer = None;
+        self.output_released = true;
+        debug!("Released idle audio output");
+    }
+
+    // The output device may have vanished while the machine was asleep, so rebuild it and
+    // pick the current track back up where it was.
+    fn reopen_output(&mut self) -> Result<()> {
+        if self.loading || self.current_file.is_none() {
+            self._device = None;
+            self.mixer = None;
+            return Ok(());
+        }
+
+        let position = self.get_position();
+        let (device, mixer, output_rate) =
+            Self::open_output(self.output_sample_rate.and_then(NonZero::new), None)?;
+
+        if let Some(sink) = &self.sink {
+            sink.stop();
+        }
+        self.sink = Some(Sink::connect_new(&mixer));
+        self._device = device;
+        self.mixer = Some(mixer);
+        self.output_rate = Some(output_rate);
+        self.output_released = false;
+
+        self.seek(position)
+    }
+
+    pub fn set_volume(&mut self, volume: f32, _cx: &mut App) {
+        self.volume = volume.clamp(0.0, 1.0);
+        let log_volume = Self::compute_log_volume(self.volume);
+        self.apply_sink_volume();
 
         debug!("Volume: {:.2} (log: {:.2})", self.volume, log_volume);
     }
@@ -498,6 +951,12 @@ impl Playback {
 
     pub fn get_position(&self) -> f32 {
         if let Some(sink) = &self.sink {
+            // Until a seek's fade out finishes, the sink still reports the old source's position.
+            let seek_pending =
+                sink.len() > 1 && self.preloaded.as_ref().is_none_or(|track| !track.appended);
+            if seek_pending {
+                return self.position;
+            }
             self.position + sink.get_pos().as_secs_f32()
         } else {
             0.0
@@ -512,6 +971,10 @@ impl Playback {
         *self.visualizer_state.bands.lock()
     }
 
+    pub fn get_spectrum_bars(&self) -> [f32; SPECTRUM_BARS] {
+        self.visualizer_state.spectrum.lock().smoothed()
+    }
+
     pub fn apply_eq_settings(&mut self, gains: &[f32], q_values: &[f32]) {
         let mut eq = self.equalizer.lock();
         for i in 0..10.min(gains.len()).min(q_values.len()) {
@@ -522,18 +985,38 @@ impl Playback {
     }
 
     pub fn set_eq_enabled(&mut self, enabled: bool) {
+        self.eq_enabled = enabled;
         if !enabled {
             let mut eq = self.equalizer.lock();
             for i in 0..10 {
                 eq.set_gain(i, 0.0);
             }
+            eq.set_preamp(0.0);
         }
         debug!("EQ {}", if enabled { "enabled" } else { "disabled" });
     }
 
+    pub fn set_eq_preamp(&mut self, preamp_db: f32) {
+        if self.eq_enabled {
+            self.equalizer
+                .lock()
+                .set_preamp(preamp_db.clamp(-MAX_PREAMP_DB, MAX_PREAMP_DB));
+        }
+    }
+
     pub fn apply_config(&mut self, config: &Config) {
         let settings = config.get();
         self.volume = settings.audio.volume;
+        self.normalization_preamp_db = settings.audio.normalization_preamp_db;
+        self.limiter_enabled = settings.audio.limiter;
+        self.pause_on_sleep = settings.audio.pause_on_sleep;
+        self.focus_behavior = settings.audio.focus_behavior;
+        self.resampler_quality = settings.audio.resampler_quality;
+        self.output_sample_rate = settings.audio.output_sample_rate;
+        self.eq_enabled = settings.equalizer.enabled;
+        self.seek_fade = Duration::from_millis(settings.audio.seek_fade_ms.into());
+        self.gapless = settings.audio.gapless;
+        self.set_release_output_after(settings.audio.release_output_after_secs);
 
         let mut eq = self.equalizer.lock();
         eq.apply_settings(&settings.equalizer);
@@ -543,6 +1026,79 @@ impl Playback {
         debug!("Applied config to playback");
     }
 
+    pub fn set_normalization_preamp(&mut self, preamp_db: f32) {
+        self.normalization_preamp_db = preamp_db.clamp(-MAX_PREAMP_DB, MAX_PREAMP_DB);
+    }
+
+    /// Changes the offset of the song playing now, if it is `song_id`, without a restart.
+    pub fn set_gain_offset(&mut self, song_id: &Cuid, gain_offset_db: f32, cx: &App) {
+        if cx.global::<Queue>().get_current_song_id().as_ref() != Some(song_id)
+            || self.current_file.is_none()
+        {
+            return;
+        }
+        self.current_gain_offset_db = gain_offset_db;
+        if let Err(e) = self.seek(self.get_position()) {
+            error!("Failed to apply gain offset: {}", e);
+        }
+    }
+
+    pub fn set_limiter_enabled(&mut self, enabled: bool) {
+        self.limiter_enabled = enabled;
+    }
+
+    pub fn set_release_output_after(&mut self, secs: Option<u32>) {
+        self.release_output_after = secs.map(|secs| Duration::from_secs(secs.into()));
+    }
+
+    pub fn set_seek_fade_ms(&mut self, fade_ms: u32) {
+        self.seek_fade = Duration::from_millis(fade_ms.into());
+    }
+
+    pub fn set_gapless(&mut self, enabled: bool) {
+        self.gapless = enabled;
+        if !enabled {
+            self.cancel_preload();
+        }
+    }
+
+    pub fn set_pause_on_sleep(&mut self, enabled: bool) {
+        self.pause_on_sleep = enabled;
+        if !enabled {
+            self.paused_by_system = false;
+        }
+    }
+
+    /// Takes effect from the next track or seek.
+    pub fn set_resampler_quality(&mut self, quality: ResamplerQuality) {
+        self.resampler_quality = quality;
+    }
+
+    pub fn set_output_sample_rate(&mut self, sample_rate: Option<u32>) {
+        if self.output_sample_rate == sample_rate {
+            return;
+        }
+        self.output_sample_rate = sample_rate;
+        if let Err(e) = self.reopen_output() {
+            error!("Failed to reopen audio output: {}", e);
+        }
+    }
+
+    pub fn set_focus_behavior(&mut self, behavior: AudioFocusBehavior) {
+        self.focus_behavior = behavior;
+        if behavior != AudioFocusBehavior::Duck && std::mem::take(&mut self.ducked) {
+            self.apply_sink_volume();
+        }
+        if behavior != AudioFocusBehavior::Pause {
+            self.paused_for_focus = false;
+        }
+    }
+
+    fn limiter_active(&self) -> bool {
+        self.limiter_enabled
+            && (self.current_lufs.is_some() || self.eq_enabled || self.current_gain_offset_db > 0.0)
+    }
+
     pub fn set_visualizer_enabled(&mut self, enabled: bool) {
         self.visualizer_state.set_enabled(enabled);
     }
@@ -556,11 +1112,21 @@ impl Playback {
     }
 
     pub fn next(&mut self, cx: &mut App) {
+        self.cancel_preload();
         let current = cx.global::<Queue>().get_current_song_id();
         if current.is_some() {
-            Self::log_event(cx, EventType::Stop, current);
+            let event_type = if self.get_position() < SKIP_BEFORE_SECS {
+                EventType::Skip
+            } else {
+                EventType::Stop
+            };
+            Self::log_event(cx, event_type, current);
         }
-        let song_id = cx.update_global::<Queue, _>(|queue, _| queue.next_manual());
+        let db = cx.global::<Database>().clone();
+        let min_secs = cx.global::<Config>().get().audio.shuffle_min_duration_secs;
+        let song_id = cx.update_global::<Queue, _>(|queue, _| {
+            queue.next_manual_skipping(|id| too_short_for_shuffle(&db, min_secs, id))
+        });
         if let Some(song_id) = song_id {
             self.load_song_by_id(cx, song_id);
         } else {
@@ -579,21 +1145,37 @@ impl Playback {
         if current.is_some() {
             Self::log_event(cx, EventType::Stop, current);
         }
-        let song_id = cx.update_global::<Queue, _>(|queue, _| queue.next());
+        self.store_measured_loudness(cx);
+        let db = cx.global::<Database>().clone();
+        let min_secs = cx.global::<Config>().get().audio.shuffle_min_duration_secs;
+        let song_id = cx.update_global::<Queue, _>(|queue, _| {
+            queue.next_skipping(|id| too_short_for_shuffle(&db, min_secs, id))
+        });
         if let Some(song_id) = song_id {
             self.load_song_by_id(cx, song_id);
+        } else if let Some(last) = current.filter(|_| cx.global::<Config>().get().audio.autoplay) {
+            // Hold the monitor off while similar songs are looked up.
+            self.loading = true;
+            continue_autoplay(last, cx);
         } else {
-            if let Some(sink) = &self.sink {
-                sink.stop();
-            }
-            self.paused = true;
-            if let Some(mc) = cx.try_global::<MediaController>() {
-                mc.set_state(PlaybackState::Paused).ok();
-            }
+            self.stop_at_end(cx);
+        }
+    }
+
+    fn stop_at_end(&mut self, cx: &mut App) {
+        self.loading = false;
+        self.cancel_preload();
+        if let Some(sink) = &self.sink {
+            sink.stop();
+        }
+        self.paused = true;
+        if let Some(mc) = cx.try_global::<MediaController>() {
+            mc.set_state(PlaybackState::Paused).ok();
         }
     }
 
     pub fn previous(&mut self, cx: &mut App) {
+        self.cancel_preload();
         let current = cx.global::<Queue>().get_current_song_id();
         if current.is_some() {
             Self::log_event(cx, EventType::Stop, current);
@@ -618,8 +1200,17 @@ impl Playback {
             let mut cx = cx.clone();
             let executor = executor;
             async move {
+                let mut idle_since: Option<Instant> = None;
                 loop {
-                    executor.timer(std::time::Duration::from_millis(100)).await;
+                    executor.timer(MONITOR_INTERVAL).await;
+
+                    #[cfg(feature = "null-audio")]
+                    cx.update(|_window, cx| {
+                        if let Some(playback) = cx.try_global::<Playback>() {
+                            playback.advance_clock(MONITOR_INTERVAL);
+                        }
+                    })
+                    .ok();
 
                     let should_advance = cx
                         .update(|_window, cx| {
@@ -636,6 +1227,52 @@ impl Playback {
                             });
                         })
                         .ok();
+                    } else {
+                        cx.update(|_window, cx| {
+                            if cx.has_global::<Playback>() {
+                                cx.update_global::<Playback, _>(|playback, cx| {
+                                    playback.update_gapless(cx);
+                                });
+                            }
+                        })
+                        .ok();
+                    }
+
+                    cx.update(|_window, cx| {
+                        if let Some(playback) = cx.try_global::<Playback>() {
+                            let queue = cx.global::<Queue>();
+                            now_playing::publish(NowPlaying::capture(
+                                queue,
+                                queue.get_current_song(cx),
+                                playback.get_position(),
+                                playback.get_paused(),
+                            ));
+                        }
+                    })
+                    .ok();
+
+                    let release_after = cx
+                        .update(|_window, cx| {
+                            cx.try_global::<Playback>()
+                                .filter(|p| p.holds_idle_output())
+                                .and_then(|p| p.release_output_after)
+                        })
+                        .ok()
+                        .flatten();
+                    match release_after {
+                        Some(after) => {
+                            let since = *idle_since.get_or_insert_with(Instant::now);
+                            if since.elapsed() >= after {
+                                idle_since = None;
+                                cx.update(|_window, cx| {
+                                    cx.update_global::<Playback, _>(|playback, _cx| {
+                                        playback.release_output();
+                                    });
+                                })
+                                .ok();
+                            }
+                        }
+                        None => idle_since = None,
                     }
                 }
             }
@@ -643,11 +1280,256 @@ impl Playback {
         .detach();
     }
 
+    // Catches up once a preloaded track has taken over the sink, drops a preload the queue no
+    // longer leads to, and starts one near the end of the current song.
+    fn update_gapless(&mut self, cx: &mut App) {
+        if self.loading {
+            return;
+        }
+        let Some(sink) = &self.sink else {
+            return;
+        };
+        if self.preloaded.as_ref().is_some_and(|track| track.appended) && sink.len() == 1 {
+            self.finish_gapless_advance(cx);
+            return;
+        }
+
+        if self.preloaded.is_none() && (!self.gapless || self.paused) {
+            return;
+        }
+
+        let db = cx.global::<Database>().clone();
+        let peek = match &self.gapless_peek {
+            Some(peek) => peek.clone(),
+            None => {
+                let min_secs = cx.global::<Config>().get().audio.shuffle_min_duration_secs;
+                let queue = cx.global::<Queue>();
+                let peek = GaplessPeek {
+                    current_duration: queue.get_current_song(cx).map_or(0, |song| song.duration),
+                    next: queue.peek_next_skipping(|id| too_short_for_shuffle(&db, min_secs, id)),
+                };
+                self.gapless_peek = Some(peek.clone());
+                peek
+            }
+        };
+        let near_end = peek.current_duration > 0
+            && peek.current_duration as f32 - self.get_position() < PRELOAD_BEFORE_END_SECS;
+        if self.preloaded.is_none() && !near_end {
+            return;
+        }
+
+        let next = peek.next;
+        if let Some(track) = &self.preloaded {
+            if next.as_ref() != Some(&track.song.id) {
+                debug!(
+                    "Queue moved on, dropping preloaded {:?}",
+                    track.song.file_path
+                );
+                self.cancel_preload();
+            }
+            return;
+        }
+
+        let Some(next) = next else {
+            return;
+        };
+        let song = match cx.global::<Queue>().transient_song(&next) {
+            Some(song) => Some(song),
+            None => db.get_song(&next).ok().flatten(),
+        };
+        if let Some(song) = song {
+            let loudness_source = cx.global::<Config>().get().audio.loudness_source;
+            let lufs = song_loudness(&song, loudness_source).map(|(lufs, _)| lufs);
+            self.preload_next(song, lufs, cx);
+        }
+    }
+
+    /// Opens `song` in the background and queues it on the sink behind the current track, so it
+    /// starts the moment that one ends instead of after a fresh load. Replaces an earlier
+    /// preload. Does nothing until an output is open, since the two tracks share its rate.
+    pub fn preload_next(&mut self, song: Song, lufs: Option<f32>, cx: &mut App) {
+        self.cancel_preload();
+        let Some(output_rate) = self.output_rate else {
+            return;
+        };
+
+        let levels = TrackLevels {
+            lufs,
+            track_peak: song_peak(&song),
+            gain_offset_db: song.gain_offset_db,
+        };
+        let track = PreloadedTrack {
+            song,
+            levels,
+            measurement: Arc::new(Mutex::new(None)),
+            fading_out: Arc::new(AtomicBool::new(false)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+            appended: false,
+        };
+        let path = track.song.file_path.clone();
+        let (start_secs, end_secs) = (track.song.start_secs, track.song.end_secs);
+        let measurement = track.measurement.clone();
+        let fading_out = track.fading_out.clone();
+        let cancelled = track.cancelled.clone();
+        let quality = self.resampler_quality;
+        let equalizer = self.equalizer.clone();
+        let visualizer_state = self.visualizer_state.clone();
+        let gain = Self::compute_track_gain(levels, self.normalization_preamp_db);
+        let limiter_active = self.limiter_enabled
+            && (lufs.is_some() || self.eq_enabled || levels.gain_offset_db > 0.0);
+        let seek_fade = self.seek_fade;
+        let executor = cx.background_executor().clone();
+
+        debug!("Preloading {:?}", path);
+        self.preloaded = Some(track);
+        let token = self.preload_token;
+
+        cx.spawn(async move |cx| {
+            let opened_path = path.clone();
+            let source = executor
+                .spawn(async move {
+                    let file = File::open(&opened_path)?;
+                    let file_len = file.metadata()?.len();
+                    let mut decoder = DecoderBuilder::new()
+                        .with_decoder::<OpusDecoder>()
+                        .with_data(BufReader::new(file))
+                        .with_byte_len(file_len)
+                        .build()
+                        .context("Failed to decode audio file")?;
+                    if start_secs > 0.0
+                        && decoder
+                            .try_seek(Duration::from_secs_f32(start_secs))
+                            .is_err()
+                    {
+                        anyhow::bail!("Failed to seek to the track's start at {}s", start_secs);
+                    }
+                    let decoder = decoder.take_duration(play_length(start_secs, end_secs, 0.0));
+
+                    let source = MeteredSource::new(F32Converter { input: decoder }, measurement);
+                    let source = Resampler::new(source, output_rate, quality);
+                    let eq_source = EqualizerSource::new(source, equalizer);
+                    let vis_source = VisualizerSource::new(eq_source, visualizer_state);
+                    let normalized = Limiter::new(vis_source.amplify(gain), limiter_active);
+                    let faded = SeekFade::new(normalized, seek_fade, false, fading_out);
+                    let cancellable = faded.stoppable().periodic_access(
+                        Duration::from_millis(5),
+                        move |source| {
+                            if cancelled.load(Ordering::Relaxed) {
+                                source.stop();
+                            }
+                        },
+                    );
+                    Ok::<_, anyhow::Error>(cancellable)
+                })
+                .await;
+
+            cx.update(|cx| {
+                cx.update_global::<Playback, _>(|playback, _cx| {
+                    if playback.preload_token != token {
+                        return;
+                    }
+                    match source {
+                        Ok(source) => {
+                            if let (Some(sink), Some(track)) =
+                                (&playback.sink, playback.preloaded.as_mut())
+                            {
+                                sink.append(source);
+                                track.appended = true;
+                            }
+                        }
+                        // Left in place so the track isn't retried; the regular load at the end
+                        // of the current one reports the error.
+                        Err(e) => warn!("Failed to preload {:?}: {}", path, e),
+                    }
+                });
+            });
+        })
+        .detach();
+    }
+
+    // Drops the preloaded track, taking it off the sink if it already went on.
+    fn cancel_preload(&mut self) {
+        self.preload_token = self.preload_token.wrapping_add(1);
+        if let Some(track) = self.preloaded.take() {
+            track.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    // The preloaded track is playing now, so move the queue and the current track state on to
+    // it the way `advance_auto` would have.
+    fn finish_gapless_advance(&mut self, cx: &mut App) {
+        let Some(track) = self.preloaded.take() else {
+            return;
+        };
+        self.gapless_peek = None;
+        let current = cx.global::<Queue>().get_current_song_id();
+        if current.is_some() {
+            Self::log_event(cx, EventType::Stop, current);
+        }
+        self.store_measured_loudness(cx);
+        let db = cx.global::<Database>().clone();
+        let min_secs = cx.global::<Config>().get().audio.shuffle_min_duration_secs;
+        let song_id = cx.update_global::<Queue, _>(|queue, _| {
+            queue.next_skipping(|id| too_short_for_shuffle(&db, min_secs, id))
+        });
+        if song_id.as_ref() != Some(&track.song.id) {
+            // The queue changed since the last check, so the wrong song started.
+            match song_id {
+                Some(song_id) => self.load_song_by_id(cx, song_id),
+                None => self.stop_at_end(cx),
+            }
+            return;
+        }
+
+        let song = track.song;
+        debug!("Moved on to preloaded {:?} without a gap", song.file_path);
+        refill_radio(cx);
+        self.position = 0.0;
+        self.current_file = Some(song.file_path.clone());
+        self.current_lufs = track.levels.lufs;
+        self.current_track_peak = track.levels.track_peak;
+        self.current_gain_offset_db = track.levels.gain_offset_db;
+        self.current_measurement = Some((song.id.clone(), track.measurement));
+        self.fading_out = track.fading_out;
+        self.current_start_secs = song.start_secs;
+        self.current_end_secs = song.end_secs;
+        Self::log_event(cx, EventType::Play, Some(song.id.clone()));
+
+        cx.update_global::<Queue, _>(|queue, _cx| {
+            queue.set_current_song_cache(song.id.clone(), song.clone());
+        });
+        update_media_controller(song, cx);
+    }
+
+    // Only a song that played through from the start has a measurement, seeking swaps the source.
+    fn store_measured_loudness(&mut self, cx: &App) {
+        let Some((song_id, measurement)) = self.current_measurement.take() else {
+            return;
+        };
+        let Some(measurement) = *measurement.lock() else {
+            return;
+        };
+        if cx.global::<Queue>().is_transient(&song_id) {
+            return;
+        }
+        let db = cx.global::<Database>().clone();
+        cx.background_executor()
+            .spawn(async move {
+                if let Err(e) =
+                    db.set_computed_loudness(&song_id, measurement.lufs, measurement.peak)
+                {
+                    error!("Failed to store measured loudness: {}", e);
+                }
+            })
+            .detach();
+    }
+
     fn log_event(cx: &App, event_type: EventType, song_id: Option<Cuid>) {
         let db = cx.global::<Database>().clone();
         let background_ui = cx.try_global::<BackgroundUiNotifier>().cloned();
         let should_notify_home = matches!(event_type, EventType::Play);
         let playlist_id = cx.global::<Queue>().current_playlist_id.clone();
+        let song_id = song_id.filter(|id| !cx.global::<Queue>().is_transient(id));
         cx.background_executor()
             .spawn(async move {
                 let context_id = if let Some(song_id) = &song_id {
@@ -681,14 +1563,101 @@ impl Playback {
         amplitude
     }
 
+    // The per-song offset goes on top of normalization, unclamped, since it is set by hand.
+    fn compute_track_gain(levels: TrackLevels, preamp_db: f32) -> f32 {
+        Self::compute_normalization_gain_for(levels.lufs, levels.track_peak, preamp_db)
+            * 10.0f32.powf(levels.gain_offset_db / 20.0)
+    }
+
     fn compute_normalization_gain(&self) -> f32 {
-        if let Some(lufs) = self.current_lufs {
-            let gain_db = (DEFAULT_TARGET_LUFS - lufs).clamp(-12.0, 12.0);
-            let linear_gain = 10.0f32.powf(gain_db / 20.0);
-            debug!("Normalization: LUFS {:.2}, gain {:.2} dB", lufs, gain_db);
-            return linear_gain;
-        }
-        1.0
+        Self::compute_track_gain(
+            TrackLevels {
+                lufs: self.current_lufs,
+                track_peak: self.current_track_peak,
+                gain_offset_db: self.current_gain_offset_db,
+            },
+            self.normalization_preamp_db,
+        )
+    }
+}
+
+fn update_media_controller(song: Song, cx: &App) {
+    let Some(mc) = cx.try_global::<MediaController>().cloned() else {
+        return;
+    };
+    let fallback = cx
+        .global::<Config>()
+        .get()
+        .interface
+        .fallback_artwork
+        .clone();
+    cx.background_executor()
+        .spawn(async move {
+            mc.update_song(song, &fallback).ok();
+        })
+        .detach();
+}
+
+// Songs outside the library (opened files) are never skipped.
+fn too_short_for_shuffle(db: &Database, min_secs: Option<u32>, song_id: &Cuid) -> bool {
+    min_secs.is_some_and(|min_secs| {
+        db.get_song(song_id)
+            .ok()
+            .flatten()
+            .is_some_and(|song| (song.duration.max(0) as u32) < min_secs)
+    })
+}
+
+fn report_playback_error(title: &str, path: &str, e: &anyhow::Error) {
+    now_playing::report_error(PlaybackError {
+        title: title.to_string(),
+        path: path.to_string(),
+        reason: format!("{:#}", e),
+    });
+}
+
+// How long to play from `offset` seconds into a song that stops at `end_secs` of its file, or
+// runs to the end of the file.
+fn play_length(start_secs: f32, end_secs: Option<f32>, offset: f32) -> Duration {
+    end_secs.map_or(Duration::MAX, |end| {
+        Duration::from_secs_f32((end - start_secs - offset).max(0.0))
+    })
+}
+
+fn requested_gain_db(lufs: f32, preamp_db: f32) -> f32 {
+    let preamp_db = preamp_db.clamp(-MAX_PREAMP_DB, MAX_PREAMP_DB);
+    ((DEFAULT_TARGET_LUFS - lufs).clamp(-12.0, 12.0) + preamp_db)
+        .clamp(-MAX_NORMALIZATION_GAIN_DB, MAX_NORMALIZATION_GAIN_DB)
+}
+
+/// How far normalization at `preamp_db` would push `song`'s stored peak past full scale, in dB.
+/// `None` when it stays below, or when the song's loudness or peak is unknown.
+pub fn clipping_overshoot_db(song: &Song, source: LoudnessSource, preamp_db: f32) -> Option<f32> {
+    let (lufs, _) = song_loudness(song, source)?;
+    let peak = song_peak(song)?;
+    let overshoot = requested_gain_db(lufs, preamp_db) + song.gain_offset_db + 20.0 * peak.log10();
+    (overshoot > 0.0).then_some(overshoot)
+}
+
+/// The preamp, in the settings slider's half-dB steps, that takes `overshoot_db` back off.
+pub fn preamp_without_clipping(preamp_db: f32, overshoot_db: f32) -> f32 {
+    (((preamp_db - overshoot_db) * 2.0).floor() / 2.0).clamp(-MAX_PREAMP_DB, MAX_PREAMP_DB)
+}
+
+/// The sample peak normalization caps gain at, from the tags or else as measured.
+pub fn song_peak(song: &Song) -> Option<f32> {
+    song.track_peak
+        .or(song.computed_peak)
+        .filter(|peak| *peak > 0.0)
+}
+
+/// The loudness normalization uses for `song`, and where it came from.
+pub fn song_loudness(song: &Song, source: LoudnessSource) -> Option<(f32, &'static str)> {
+    let tagged = song.lufs.map(|lufs| (lufs, "ReplayGain tags"));
+    let measured = song.computed_lufs.map(|lufs| (lufs, "measured"));
+    match source {
+        LoudnessSource::TagsFirst => tagged.or(measured),
+        LoudnessSource::MeasuredFirst => measured.or(tagged),
     }
 }
 
@@ -707,6 +1676,282 @@ pub fn play_song_ids_now(song_ids: Vec<Cuid>, cx: &mut App) {
     cx.set_global(QueueChanged);
 }
 
+pub fn play_song_ids_shuffled(song_ids: Vec<Cuid>, cx: &mut App) {
+    if song_ids.is_empty() {
+        return;
+    }
+    cx.update_global::<Queue, _>(|queue, _| {
+        queue.clear();
+        queue.current_playlist_id = None;
+        queue.set_shuffle(true);
+        queue.add_songs(song_ids);
+    });
+    cx.update_global::<Playback, _>(|playback, cx| {
+        playback.play_queue(cx);
+    });
+    cx.set_global(QueueChanged);
+}
+
+/// Replaces the queue with `paths`, using library songs where they exist and transient
+/// entries for everything else.
+pub fn play_files(paths: Vec<PathBuf>, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let songs = bg
+            .spawn(async move {
+                paths
+                    .into_iter()
+                    .filter(|path| path.is_file() && Scanner::is_audio_file(path))
+                    .flat_map(|path| songs_for_file(&db, &path))
+                    .collect::<Vec<_>>()
+            })
+            .await;
+        if songs.is_empty() {
+            return;
+        }
+        cx.update(|cx| {
+            cx.update_global::<Queue, _>(|queue, _| {
+                queue.clear();
+                queue.current_playlist_id = None;
+                let ids = songs.iter().map(|(song, _)| song.id.clone()).collect();
+                for (song, transient) in songs {
+                    if transient {
+                        queue.register_transient(song);
+                    }
+                }
+                queue.add_songs(ids);
+            });
+            cx.update_global::<Playback, _>(|playback, cx| {
+                playback.play_queue(cx);
+            });
+            cx.set_global(QueueChanged);
+        });
+    })
+    .detach();
+}
+
+fn saved_queue(cx: &App) -> SavedQueue {
+    let queue = cx.global::<Queue>();
+    let items = queue
+        .get_items()
+        .into_iter()
+        .map(|song_id| SavedQueueItem {
+            file_path: queue.transient_song(&song_id).map(|song| song.file_path),
+            song_id,
+        })
+        .collect();
+    SavedQueue {
+        items,
+        current_index: queue.get_current_display_index(),
+        position_secs: cx.global::<Playback>().get_position(),
+    }
+}
+
+/// Keeps the saved queue in step with the live one, and stores the final position on quit.
+pub fn save_queue_on_change(cx: &mut App) {
+    cx.observe_global::<QueueChanged>(|cx| {
+        let db = cx.global::<Database>().clone();
+        let saved = saved_queue(cx);
+        cx.background_executor()
+            .spawn(async move {
+                if let Err(e) = db.save_queue(&saved) {
+                    error!("Failed to save queue: {}", e);
+                }
+            })
+            .detach();
+    })
+    .detach();
+
+    cx.on_app_quit(|cx| {
+        if let Err(e) = cx.global::<Database>().save_queue(&saved_queue(cx)) {
+            error!("Failed to save queue: {}", e);
+        }
+        async {}
+    })
+    .detach();
+}
+
+/// Brings back the queue from the last session. The audio `resume_on_launch` setting decides
+/// whether its current song is loaded, and whether it starts playing.
+pub fn restore_queue(cx: &mut App) {
+    let resume = cx.global::<Config>().get().audio.resume_on_launch;
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let restored = bg
+            .spawn(async move {
+                let saved = db
+                    .load_queue()
+                    .inspect_err(|e| error!("Failed to load saved queue: {}", e))
+                    .ok()
+                    .flatten()?;
+                let mut songs = Vec::new();
+                let mut current = None;
+                for (index, item) in saved.items.into_iter().enumerate() {
+                    let found = match item.file_path {
+                        Some(path) => Some(PathBuf::from(path))
+                            .filter(|path| path.is_file())
+                            .map(|path| songs_for_file(&db, &path))
+                            .unwrap_or_default(),
+                        None => db
+                            .get_song(&item.song_id)
+                            .ok()
+                            .flatten()
+                            .map(|s| (s, false))
+                            .into_iter()
+                            .collect(),
+                    };
+                    if found.is_empty() {
+                        continue;
+                    }
+                    if saved.current_index == Some(index) {
+                        current = Some(songs.len());
+                    }
+                    songs.extend(found);
+                }
+                // Missing songs are dropped, so the position only holds if the current one is left.
+                let position = current.map_or(0.0, |_| saved.position_secs);
+                (!songs.is_empty()).then_some((songs, current, position))
+            })
+            .await;
+
+        cx.update(|cx| {
+            if let Some((songs, current, position)) = restored
+                && cx.global::<Queue>().get_items().is_empty()
+            {
+                let ids = songs.iter().map(|(song, _)| song.id.clone()).collect();
+                let song_id = cx.update_global::<Queue, _>(|queue, _| {
+                    queue.clear_and_queue_songs(ids, current.unwrap_or(0));
+                    for (song, transient) in songs {
+                        if transient {
+                            queue.register_transient(song);
+                        }
+                    }
+                    queue.get_current_song_id()
+                });
+                if let Some(song_id) = song_id.filter(|_| resume != ResumeOnLaunch::Stopped) {
+                    let autoplay = resume == ResumeOnLaunch::Playing;
+                    cx.update_global::<Playback, _>(|playback, cx| {
+                        playback.load_song_at(cx, song_id, position, autoplay);
+                    });
+                }
+                cx.set_global(QueueChanged);
+            }
+            save_queue_on_change(cx);
+        });
+    })
+    .detach();
+}
+
+/// The library songs for a file, every track when a cue sheet splits it, or a transient song
+/// read from the file when it isn't in the library. The flag marks transient songs.
+fn songs_for_file(db: &Database, path: &Path) -> Vec<(Song, bool)> {
+    let key = path.to_string_lossy();
+    if let Ok(songs) = db.get_songs_by_path(&key)
+        && !songs.is_empty()
+    {
+        return songs.into_iter().map(|song| (song, false)).collect();
+    }
+    match AudioMetadata::from_path_with_options(path, false) {
+        Ok(metadata) => vec![(metadata.into_transient_song(path), true)],
+        Err(e) => {
+            error!("Failed to read {:?}: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+fn refill_radio(cx: &mut App) {
+    let Some(seed) = cx.update_global::<Queue, _>(|queue, _| queue.begin_radio_refill()) else {
+        return;
+    };
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let candidates = bg
+            .spawn(async move {
+                db.get_radio_song_ids(&seed, RADIO_CANDIDATES)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to find radio songs: {}", e);
+                        Vec::new()
+                    })
+            })
+            .await;
+        cx.update(|cx| {
+            let added = cx.update_global::<Queue, _>(|queue, _| {
+                queue.finish_radio_refill(candidates, RADIO_BATCH)
+            });
+            if added > 0 {
+                cx.set_global(QueueChanged);
+            }
+        });
+    })
+    .detach();
+}
+
+/// Queues songs similar to `last` once the queue has run out, falling back to random ones.
+fn continue_autoplay(last: Cuid, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let candidates = bg
+            .spawn(async move {
+                let similar = db
+                    .get_radio_song_ids(&last, RADIO_CANDIDATES)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to find autoplay songs: {}", e);
+                        Vec::new()
+                    });
+                if !similar.is_empty() {
+                    return similar;
+                }
+                db.get_random_song_ids(RADIO_CANDIDATES)
+                    .unwrap_or_else(|e| {
+                        error!("Failed to find autoplay songs: {}", e);
+                        Vec::new()
+                    })
+            })
+            .await;
+        cx.update(|cx| {
+            let next =
+                cx.update_global::<Queue, _>(|queue, _| queue.autoplay(candidates, RADIO_BATCH));
+            if next.is_some() {
+                cx.set_global(QueueChanged);
+            }
+            cx.update_global::<Playback, _>(|playback, cx| match next {
+                Some(song_id) => playback.load_song_by_id(cx, song_id),
+                None => playback.stop_at_end(cx),
+            });
+        });
+    })
+    .detach();
+}
+
+pub fn start_radio(seed: Cuid, cx: &mut App) {
+    cx.update_global::<Queue, _>(|queue, _| queue.start_radio(seed));
+    cx.update_global::<Playback, _>(|playback, cx| {
+        playback.play_queue(cx);
+    });
+    cx.set_global(QueueChanged);
+}
+
+/// Starts radio from a random song by the artist.
+pub fn start_artist_radio(artist_id: Cuid, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let mut song_ids = bg
+            .spawn(async move { db.get_artist_song_ids(&artist_id).unwrap_or_default() })
+            .await;
+        song_ids.shuffle(&mut rand::rng());
+        if let Some(seed) = song_ids.into_iter().next() {
+            cx.update(|cx| start_radio(seed, cx));
+        }
+    })
+    .detach();
+}
+
 pub fn play_song_now(song_id: Cuid, cx: &mut App) {
     play_song_ids_now(vec![song_id], cx);
 }
@@ -732,6 +1977,30 @@ pub fn play_album_now(album_id: Cuid, cx: &mut App) {
     .detach();
 }
 
+pub fn play_artist_now(artist_id: Cuid, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let song_ids = bg
+            .spawn(async move { db.get_artist_song_ids(&artist_id).unwrap_or_default() })
+            .await;
+        cx.update(|cx| play_song_ids_now(song_ids, cx));
+    })
+    .detach();
+}
+
+pub fn shuffle_artist_now(artist_id: Cuid, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let song_ids = bg
+            .spawn(async move { db.get_artist_song_ids(&artist_id).unwrap_or_default() })
+            .await;
+        cx.update(|cx| play_song_ids_shuffled(song_ids, cx));
+    })
+    .detach();
+}
+
 pub fn play_album_next(album_id: Cuid, cx: &mut App) {
     let db = cx.global::<Database>().clone();
     let bg = cx.background_executor().clone();
@@ -811,6 +2080,33 @@ pub fn play_playlist_now(playlist_id: Cuid, cx: &mut App) {
     .detach();
 }
 
+pub fn play_playlist_shuffled(playlist_id: Cuid, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    let bg = cx.background_executor().clone();
+    cx.spawn(async move |cx| {
+        let pl_id = playlist_id.clone();
+        let song_ids = bg
+            .spawn(async move {
+                db.get_playlist_songs(&playlist_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|pt| pt.song.id)
+                    .collect::<Vec<_>>()
+            })
+            .await;
+        if song_ids.is_empty() {
+            return;
+        }
+        cx.update(|cx| {
+            play_song_ids_shuffled(song_ids, cx);
+            cx.update_global::<Queue, _>(|queue, _| {
+                queue.current_playlist_id = Some(pl_id);
+            });
+        });
+    })
+    .detach();
+}
+
 pub fn play_playlist_next(playlist_id: Cuid, cx: &mut App) {
     let db = cx.global::<Database>().clone();
     let bg = cx.background_executor().clone();
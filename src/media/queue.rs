@@ -5,8 +5,14 @@ use crate::data::{
 use gpui::{App, Global};
 use rand::seq::SliceRandom;
 use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use tracing::debug;
 
+// Radio tops the queue up once fewer than this many songs are left after the current one.
+const RADIO_REFILL_BELOW: usize = 3;
+// Songs this far back in the queue may come round again on a long radio session.
+const RADIO_RECENT_WINDOW: usize = 50;
+
 pub struct Queue {
     items: Vec<Cuid>,
     current_index: Option<usize>,
@@ -15,6 +21,10 @@ pub struct Queue {
     shuffle_position: Option<usize>,
     repeat_mode: RepeatMode,
     current_song: RefCell<Option<(Cuid, Song)>>,
+    // Files that are not in the library, keyed by the id they were given when queued.
+    transient: HashMap<Cuid, Song>,
+    radio_seed: Option<Cuid>,
+    radio_refilling: bool,
     pub current_playlist_id: Option<Cuid>,
 }
 
@@ -37,6 +47,9 @@ impl Queue {
             shuffle_position: None,
             repeat_mode: RepeatMode::Off,
             current_song: RefCell::new(None),
+            transient: HashMap::new(),
+            radio_seed: None,
+            radio_refilling: false,
             current_playlist_id: None,
         }
     }
@@ -60,12 +73,97 @@ impl Queue {
                 self.shuffle_order.extend(tail);
             } else {
                 self.shuffle_order.push(new_idx);
+                if self.shuffle_order.len() == 1 {
+                    self.shuffle_position = Some(0);
+                }
             }
         }
 
         debug!("Added song to queue. Queue size: {}", self.items.len());
     }
 
+    /// Replaces the queue with `song_ids` and makes `start` current. With shuffle on, `start`
+    /// stays first in the shuffled order and only the rest is shuffled.
+    pub fn clear_and_queue_songs(&mut self, song_ids: Vec<Cuid>, start: usize) {
+        self.clear();
+        if song_ids.is_empty() {
+            return;
+        }
+
+        let start = start.min(song_ids.len() - 1);
+        self.items = song_ids;
+        self.current_index = Some(start);
+
+        if self.shuffle {
+            let mut rest: Vec<usize> = (0..self.items.len()).filter(|&i| i != start).collect();
+            rest.shuffle(&mut rand::rng());
+            self.shuffle_order = std::iter::once(start).chain(rest).collect();
+            self.shuffle_position = Some(0);
+        }
+
+        debug!("Queued {} songs starting at {}", self.items.len(), start);
+    }
+
+    /// Replaces the queue with `seed` and keeps appending related songs as it runs low.
+    /// Anything else that replaces the queue ends the radio.
+    pub fn start_radio(&mut self, seed: Cuid) {
+        self.clear_and_queue_songs(vec![seed.clone()], 0);
+        self.current_playlist_id = None;
+        self.radio_seed = Some(seed);
+    }
+
+    /// The seed to look up more songs for, if the radio is running low and no lookup is
+    /// already in flight. Pair with `finish_radio_refill`.
+    pub fn begin_radio_refill(&mut self) -> Option<Cuid> {
+        if self.radio_refilling {
+            return None;
+        }
+        let seed = self.radio_seed.clone()?;
+        let remaining = match self.get_current_display_index() {
+            Some(idx) => self.items.len().saturating_sub(idx + 1),
+            None => 0,
+        };
+        if remaining >= RADIO_REFILL_BELOW {
+            return None;
+        }
+        self.radio_refilling = true;
+        Some(seed)
+    }
+
+    /// Appends the candidates that haven't played recently. Returns how many were added.
+    pub fn finish_radio_refill(&mut self, candidates: Vec<Cuid>, batch: usize) -> usize {
+        if !std::mem::take(&mut self.radio_refilling) || self.radio_seed.is_none() {
+            return 0;
+        }
+        let recent_start = self.items.len().saturating_sub(RADIO_RECENT_WINDOW);
+        let recent = &self.items[recent_start..];
+        let fresh: Vec<Cuid> = candidates
+            .into_iter()
+            .filter(|id| !recent.contains(id))
+            .take(batch)
+            .collect();
+        let added = fresh.len();
+        self.add_songs(fresh);
+        added
+    }
+
+    /// Appends up to `batch` candidates that haven't played recently once the queue has run
+    /// out, and moves onto the first of them.
+    pub fn autoplay(&mut self, candidates: Vec<Cuid>, batch: usize) -> Option<Cuid> {
+        let recent_start = self.items.len().saturating_sub(RADIO_RECENT_WINDOW);
+        let recent = &self.items[recent_start..];
+        let fresh: Vec<Cuid> = candidates
+            .into_iter()
+            .filter(|id| !recent.contains(id))
+            .take(batch)
+            .collect();
+        if fresh.is_empty() {
+            return None;
+        }
+        self.add_songs(fresh);
+        self.next()
+    }
+
     pub fn add_song_at(&mut self, song_id: Cuid, position: usize) {
         self.add_song_at_offset(song_id, position, 0);
     }
@@ -151,11 +249,62 @@ impl Queue {
         );
     }
 
+    /// Keeps `song` around so its id can be queued like a library song.
+    pub fn register_transient(&mut self, song: Song) {
+        self.transient.insert(song.id.clone(), song);
+    }
+
+    pub fn transient_song(&self, song_id: &Cuid) -> Option<Song> {
+        self.transient.get(song_id).cloned()
+    }
+
+    pub fn is_transient(&self, song_id: &Cuid) -> bool {
+        self.transient.contains_key(song_id)
+    }
+
+    pub fn transient_songs(&self) -> Vec<Song> {
+        self.transient.values().cloned().collect()
+    }
+
+    /// Points every queue entry for a transient song at its library copy after an import.
+    /// Swaps a transient song for the library songs its file was imported as. When a cue sheet
+    /// splits the file, the first track takes its place and the others follow it.
+    pub fn replace_transient(&mut self, old_id: &Cuid, songs: Vec<Song>) {
+        let Some(first) = songs.first().cloned() else {
+            return;
+        };
+        if self.transient.remove(old_id).is_none() {
+            return;
+        }
+        let positions: Vec<usize> = (0..self.items.len())
+            .filter(|&index| self.items[index] == *old_id)
+            .collect();
+        // Later positions first, so inserting after one doesn't move the ones still to come.
+        for &position in positions.iter().rev() {
+            self.items[position] = first.id.clone();
+            for (offset, song) in songs.iter().skip(1).enumerate() {
+                self.add_song_at_offset(song.id.clone(), position + 1 + offset, offset);
+            }
+        }
+
+        let mut cache = self.current_song.borrow_mut();
+        if cache
+            .as_ref()
+            .is_some_and(|(cached_id, _)| cached_id == old_id)
+        {
+            *cache = Some((first.id.clone(), first));
+        }
+    }
+
     pub fn get_current_song(&self, cx: &App) -> Option<Song> {
         let song_id = self
             .current_index
             .and_then(|idx| self.items.get(idx).cloned())?;
 
+        if let Some(song) = self.transient.get(&song_id) {
+            return Some(song.clone());
+        }
+
         {
             let cache: std::cell::Ref<'_, Option<(Cuid, Song)>> = self.current_song.borrow();
             if let Some((cached_id, cached_song)) = cache.as_ref()
@@ -183,68 +332,76 @@ impl Queue {
     }
 
     pub fn next(&mut self) -> Option<Cuid> {
-        if self.items.is_empty() {
-            return None;
-        }
+        self.next_skipping(|_| false)
+    }
+
+    /// Like [`Queue::next`], but with shuffle on it passes over songs `skip_on_shuffle` picks
+    /// out. They only come up when nothing else is left, and still play when chosen directly.
+    pub fn next_skipping(&mut self, skip_on_shuffle: impl Fn(&Cuid) -> bool) -> Option<Cuid> {
         if self.repeat_mode == RepeatMode::One {
             return self.get_current_song_id();
         }
-
+        let (shuffle_position, index) = self.next_position(skip_on_shuffle)?;
         if self.shuffle {
-            if self.shuffle_order.is_empty() {
-                return None;
-            }
-            let next_pos = self.shuffle_position.map(|p| p + 1).unwrap_or(0);
+            self.shuffle_position = shuffle_position;
+        }
+        self.current_index = Some(index);
 
-            match self.repeat_mode {
-                RepeatMode::One => unreachable!(),
-                RepeatMode::All => {
-                    let wrapped = next_pos % self.shuffle_order.len();
-                    self.shuffle_position = Some(wrapped);
-                    self.current_index = self.shuffle_order.get(wrapped).copied();
-                }
-                RepeatMode::Off => {
-                    if next_pos < self.shuffle_order.len() {
-                        self.shuffle_position = Some(next_pos);
-                        self.current_index = self.shuffle_order.get(next_pos).copied();
-                    } else {
-                        return None;
-                    }
-                }
-            }
+        *self.current_song.borrow_mut() = None;
+        debug!(
+            "Moved to next song. Index: {:?}, shuffle position: {:?}",
+            self.current_index, self.shuffle_position
+        );
+        self.get_current_song_id()
+    }
 
-            *self.current_song.borrow_mut() = None;
-            debug!(
-                "Shuffle next. Position: {:?}, Item index: {:?}",
-                self.shuffle_position, self.current_index
-            );
+    /// The song [`Queue::next_skipping`] would move to, without moving there.
+    pub fn peek_next_skipping(&self, skip_on_shuffle: impl Fn(&Cuid) -> bool) -> Option<Cuid> {
+        if self.repeat_mode == RepeatMode::One {
             return self.get_current_song_id();
         }
+        let (_, index) = self.next_position(skip_on_shuffle)?;
+        self.items.get(index).cloned()
+    }
 
-        match self.repeat_mode {
-            RepeatMode::One => unreachable!(),
-            RepeatMode::All => {
-                if let Some(idx) = self.current_index {
-                    self.current_index = Some((idx + 1) % self.items.len());
-                } else {
-                    self.current_index = Some(0);
-                }
-            }
-            RepeatMode::Off => {
-                if let Some(idx) = self.current_index {
-                    if idx + 1 < self.items.len() {
-                        self.current_index = Some(idx + 1);
-                    } else {
-                        return None;
-                    }
-                } else {
-                    self.current_index = Some(0);
+    // Where the next song is: its shuffle position when shuffling, and its index in `items`.
+    fn next_position(
+        &self,
+        skip_on_shuffle: impl Fn(&Cuid) -> bool,
+    ) -> Option<(Option<usize>, usize)> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        if self.shuffle {
+            let len = self.shuffle_order.len();
+            let start = self.shuffle_position.map(|p| p + 1).unwrap_or(0);
+            let candidates: Vec<usize> = match self.repeat_mode {
+                RepeatMode::All | RepeatMode::One => {
+                    (0..len).map(|step| (start + step) % len).collect()
                 }
-            }
+                RepeatMode::Off => (start..len).collect(),
+            };
+            let &first = candidates.first()?;
+            let next_pos = candidates
+                .into_iter()
+                .find(|&pos| {
+                    self.shuffle_order
+                        .get(pos)
+                        .and_then(|&idx| self.items.get(idx))
+                        .is_some_and(|id| !skip_on_shuffle(id))
+                })
+                .unwrap_or(first);
+            let &index = self.shuffle_order.get(next_pos)?;
+            return Some((Some(next_pos), index));
         }
-        debug!("Moved to next song. Index: {:?}", self.current_index);
-        *self.current_song.borrow_mut() = None;
-        self.get_current_song_id()
+
+        let index = match (self.current_index, self.repeat_mode) {
+            (None, _) => 0,
+            (Some(idx), RepeatMode::Off) if idx + 1 >= self.items.len() => return None,
+            (Some(idx), _) => (idx + 1) % self.items.len(),
+        };
+        Some((None, index))
     }
 
     pub fn previous(&mut self) -> Option<Cuid> {
@@ -403,9 +560,57 @@ impl Queue {
         self.shuffle_order.clear();
         self.shuffle_position = None;
         *self.current_song.borrow_mut() = None;
+        self.transient.clear();
+        self.radio_seed = None;
+        self.radio_refilling = false;
         debug!("Queue cleared");
     }
 
+    /// Drops everything after the current song in play order and ends radio. Songs already
+    /// played stay, so going back still works. With nothing current the whole queue goes.
+    pub fn clear_after_current(&mut self) {
+        let Some(current) = self.current_index else {
+            self.clear();
+            return;
+        };
+        self.radio_seed = None;
+        self.radio_refilling = false;
+
+        let upcoming: HashSet<usize> = if self.shuffle {
+            self.shuffle_position
+                .map(|pos| self.shuffle_order.iter().skip(pos + 1).copied().collect())
+                .unwrap_or_default()
+        } else {
+            (current + 1..self.items.len()).collect()
+        };
+        if upcoming.is_empty() {
+            return;
+        }
+
+        let mut remap = vec![None; self.items.len()];
+        let mut kept = Vec::with_capacity(self.items.len() - upcoming.len());
+        for (index, item) in self.items.drain(..).enumerate() {
+            if !upcoming.contains(&index) {
+                remap[index] = Some(kept.len());
+                kept.push(item);
+            }
+        }
+        self.items = kept;
+        self.current_index = remap[current];
+        if self.shuffle {
+            self.shuffle_order = self
+                .shuffle_order
+                .iter()
+                .filter_map(|&i| remap[i])
+                .collect();
+        }
+
+        let items = &self.items;
+        self.transient.retain(|id, _| items.contains(id));
+
+        debug!("Cleared {} upcoming songs", upcoming.len());
+    }
+
     pub fn remove_at(&mut self, index: usize) -> Option<Cuid> {
         if index < self.items.len() {
             let item = self.items.remove(index);
@@ -447,6 +652,10 @@ impl Queue {
                 }
             }
 
+            if !self.items.contains(&item) {
+                self.transient.remove(&item);
+            }
+
             debug!(
                 "Removed song at index {}. Queue size: {}",
                 index,
@@ -561,14 +770,21 @@ impl Queue {
     }
 
     pub fn next_manual(&mut self) -> Option<Cuid> {
+        self.next_manual_skipping(|_| false)
+    }
+
+    pub fn next_manual_skipping(
+        &mut self,
+        skip_on_shuffle: impl Fn(&Cuid) -> bool,
+    ) -> Option<Cuid> {
         if self.repeat_mode == RepeatMode::One {
             let saved = self.repeat_mode;
             self.repeat_mode = RepeatMode::Off;
-            let result = self.next();
+            let result = self.next_skipping(skip_on_shuffle);
             self.repeat_mode = saved;
             result
         } else {
-            self.next()
+            self.next_skipping(skip_on_shuffle)
         }
     }
 
@@ -592,6 +808,29 @@ impl Queue {
         }
     }
 
+    /// Up to `count` songs after the current one in play order, with their display index.
+    /// Wraps around like playback does when repeating the whole queue.
+    pub fn upcoming(&self, count: usize) -> Vec<(usize, Cuid)> {
+        let items = self.get_items();
+        let len = items.len();
+        let start = self
+            .get_current_display_index()
+            .map(|idx| idx + 1)
+            .unwrap_or(0);
+        let positions: Vec<usize> = if self.repeat_mode == RepeatMode::All {
+            (0..len.saturating_sub(1))
+                .map(|step| (start + step) % len)
+                .collect()
+        } else {
+            (start..len).collect()
+        };
+        positions
+            .into_iter()
+            .take(count)
+            .map(|idx| (idx, items[idx].clone()))
+            .collect()
+    }
+
     pub fn get_shuffle(&self) -> bool {
         self.shuffle
     }
@@ -0,0 +1,219 @@
+use crate::data::config::ResamplerQuality;
+use rodio::Source;
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+use std::num::NonZero;
+use std::time::Duration;
+
+const SINC_HALF_WIDTH: usize = 16;
+const SINC_PHASES: usize = 512;
+
+enum Kernel {
+    Linear,
+    Cubic,
+    // One row of 2 * SINC_HALF_WIDTH taps per phase, SINC_PHASES + 1 rows.
+    Sinc(Vec<f32>),
+}
+
+impl Kernel {
+    fn half_width(&self) -> usize {
+        match self {
+            Kernel::Linear => 1,
+            Kernel::Cubic => 2,
+            Kernel::Sinc(_) => SINC_HALF_WIDTH,
+        }
+    }
+
+    fn weights(&self, frac: f64, out: &mut [f32]) {
+        match self {
+            Kernel::Linear => {
+                out[0] = (1.0 - frac) as f32;
+                out[1] = frac as f32;
+            }
+            Kernel::Cubic => {
+                let (t, t2, t3) = (frac, frac * frac, frac * frac * frac);
+                out[0] = ((-t3 + 2.0 * t2 - t) / 2.0) as f32;
+                out[1] = ((3.0 * t3 - 5.0 * t2 + 2.0) / 2.0) as f32;
+                out[2] = ((-3.0 * t3 + 4.0 * t2 + t) / 2.0) as f32;
+                out[3] = ((t3 - t2) / 2.0) as f32;
+            }
+            Kernel::Sinc(table) => {
+                let taps = 2 * SINC_HALF_WIDTH;
+                let phase = (frac * SINC_PHASES as f64).round() as usize;
+                out.copy_from_slice(&table[phase * taps..(phase + 1) * taps]);
+            }
+        }
+    }
+}
+
+// Blackman-windowed sinc, band-limited below the lower of the two Nyquist frequencies so
+// downsampling doesn't alias.
+fn sinc_table(cutoff: f64) -> Vec<f32> {
+    let taps = 2 * SINC_HALF_WIDTH;
+    let mut table = Vec::with_capacity((SINC_PHASES + 1) * taps);
+    for phase in 0..=SINC_PHASES {
+        let frac = phase as f64 / SINC_PHASES as f64;
+        let row: Vec<f64> = (0..taps)
+            .map(|k| {
+                let d = k as f64 - (SINC_HALF_WIDTH - 1) as f64 - frac;
+                let x = d / SINC_HALF_WIDTH as f64;
+                let window = 0.42 + 0.5 * (PI * x).cos() + 0.08 * (2.0 * PI * x).cos();
+                let arg = PI * cutoff * d;
+                let sinc = if arg.abs() < 1e-9 {
+                    1.0
+                } else {
+                    arg.sin() / arg
+                };
+                cutoff * sinc * window
+            })
+            .collect();
+        let sum: f64 = row.iter().sum();
+        table.extend(row.iter().map(|w| (w / sum) as f32));
+    }
+    table
+}
+
+/// Converts a source to `to` Hz before it reaches the mixer, so the conversion quality is ours
+/// to pick instead of rodio's. Sources already at `to` pass straight through.
+pub struct Resampler<S> {
+    inner: S,
+    to: NonZero<u32>,
+    passthrough: bool,
+    channels: usize,
+    step: f64,
+    kernel: Kernel,
+    weights: Vec<f32>,
+    window: VecDeque<f32>,
+    base: i64,
+    read_frames: i64,
+    exhausted: bool,
+    position: f64,
+    frame: Vec<f32>,
+    frame_idx: usize,
+}
+
+impl<S: Source<Item = f32>> Resampler<S> {
+    pub fn new(inner: S, to: NonZero<u32>, quality: ResamplerQuality) -> Self {
+        let from = inner.sample_rate().get() as f64;
+        let channels = inner.channels().get() as usize;
+        let kernel = match quality {
+            ResamplerQuality::Fast => Kernel::Linear,
+            ResamplerQuality::Balanced => Kernel::Cubic,
+            ResamplerQuality::High => Kernel::Sinc(sinc_table((to.get() as f64 / from).min(1.0))),
+        };
+        let taps = 2 * kernel.half_width();
+        Self {
+            passthrough: inner.sample_rate() == to,
+            inner,
+            to,
+            channels,
+            step: from / to.get() as f64,
+            kernel,
+            weights: vec![0.0; taps],
+            window: VecDeque::with_capacity((taps + 1) * channels),
+            base: 0,
+            read_frames: 0,
+            exhausted: false,
+            position: 0.0,
+            frame: vec![0.0; channels],
+            frame_idx: channels,
+        }
+    }
+
+    fn read_frame(&mut self) -> bool {
+        for _ in 0..self.channels {
+            match self.inner.next() {
+                Some(sample) => self.window.push_back(sample),
+                None => {
+                    self.exhausted = true;
+                    let complete = self.window.len() - self.window.len() % self.channels;
+                    self.window.truncate(complete);
+                    return false;
+                }
+            }
+        }
+        self.read_frames += 1;
+        true
+    }
+
+    fn input(&self, frame: i64, channel: usize) -> f32 {
+        if frame < self.base {
+            return 0.0;
+        }
+        let idx = (frame - self.base) as usize * self.channels + channel;
+        self.window.get(idx).copied().unwrap_or(0.0)
+    }
+
+    fn compute_frame(&mut self) -> bool {
+        let half = self.kernel.half_width() as i64;
+        let center = self.position.floor() as i64;
+        let first = center - half + 1;
+        let last = center + half;
+
+        while self.base < first && self.window.len() >= self.channels {
+            self.window.drain(..self.channels);
+            self.base += 1;
+        }
+        while !self.exhausted && self.read_frames <= last {
+            self.read_frame();
+        }
+        if self.exhausted && center >= self.read_frames {
+            return false;
+        }
+
+        self.kernel
+            .weights(self.position - center as f64, &mut self.weights);
+        for channel in 0..self.channels {
+            let sample: f32 = self
+                .weights
+                .iter()
+                .enumerate()
+                .map(|(k, w)| w * self.input(first + k as i64, channel))
+                .sum();
+            self.frame[channel] = sample;
+        }
+        self.position += self.step;
+        true
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for Resampler<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.passthrough {
+            return self.inner.next();
+        }
+        if self.frame_idx == self.channels {
+            if !self.compute_frame() {
+                return None;
+            }
+            self.frame_idx = 0;
+        }
+        let sample = self.frame[self.frame_idx];
+        self.frame_idx += 1;
+        Some(sample)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for Resampler<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        if self.passthrough {
+            self.inner.current_span_len()
+        } else {
+            None
+        }
+    }
+
+    fn channels(&self) -> NonZero<u16> {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> NonZero<u32> {
+        self.to
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
@@ -0,0 +1,71 @@
+use rodio::Source;
+use std::num::NonZero;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Ramps a track in from silence and, once its flag is raised, back out to silence and ends, so
+/// a seek splices the old and new audio at zero instead of jumping between two waveforms. The
+/// fade runs on the audio thread; the seek target just waits behind it on the sink.
+pub struct SeekFade<S> {
+    inner: S,
+    fading_out: Arc<AtomicBool>,
+    gain: f32,
+    step: f32,
+    channels: u16,
+    /// Samples into the current frame, so a faded out track ends between frames and the next
+    /// one starts on its first channel.
+    frame_offset: u16,
+}
+
+impl<S: Source<Item = f32>> SeekFade<S> {
+    pub fn new(inner: S, fade: Duration, fade_in: bool, fading_out: Arc<AtomicBool>) -> Self {
+        let channels = inner.channels().get();
+        let samples = inner.sample_rate().get() as f32 * channels as f32 * fade.as_secs_f32();
+        Self {
+            inner,
+            fading_out,
+            gain: if fade_in { 0.0 } else { 1.0 },
+            step: 1.0 / samples.max(1.0),
+            channels,
+            frame_offset: 0,
+        }
+    }
+}
+
+impl<S: Source<Item = f32>> Iterator for SeekFade<S> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let fading_out = self.fading_out.load(Ordering::Relaxed);
+        if fading_out && self.gain == 0.0 && self.frame_offset == 0 {
+            return None;
+        }
+        let sample = self.inner.next()?;
+        self.frame_offset = (self.frame_offset + 1) % self.channels;
+        if fading_out {
+            self.gain = (self.gain - self.step).max(0.0);
+        } else if self.gain < 1.0 {
+            self.gain = (self.gain + self.step).min(1.0);
+        }
+        Some(sample * self.gain)
+    }
+}
+
+impl<S: Source<Item = f32>> Source for SeekFade<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.inner.current_span_len()
+    }
+
+    fn channels(&self) -> NonZero<u16> {
+        self.inner.channels()
+    }
+
+    fn sample_rate(&self) -> NonZero<u32> {
+        self.inner.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.inner.total_duration()
+    }
+}
@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use rodio::Source;
+use rodio::decoder::DecoderBuilder;
+use spectrum_analyzer::scaling::divide_by_N_sqrt;
+use spectrum_analyzer::windows::hann_window;
+use spectrum_analyzer::{FrequencyLimit, samples_fft_to_spectrum};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use symphonia_adapter_libopus::OpusDecoder;
+
+use super::visualizer::F32Converter;
+
+const FFT_SIZE: usize = 4096;
+const MAX_WINDOWS: usize = 48;
+const MAX_FRAMES: usize = FFT_SIZE * MAX_WINDOWS * 4;
+const MIN_WINDOWS: usize = 8;
+const SILENCE_RMS: f32 = 1e-3;
+// How far below the 1-4 kHz level a bin may sit before it counts as empty.
+const NOISE_FLOOR_DB: f32 = 60.0;
+// Drop across the cutoff needed to call it an encoder lowpass instead of a natural roll-off.
+const BRICKWALL_DB: f32 = 25.0;
+// Lossy encoders rarely keep anything above this, real lossless masters usually do.
+const CUTOFF_LIMIT_HZ: f32 = 20_000.0;
+
+/// Decodes a stretch from the middle of `path` and reports whether the spectrum ends in a hard
+/// lowpass well below Nyquist, the usual trace of a lossy file re-encoded as lossless.
+pub fn looks_transcoded(path: &Path) -> Result<bool> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let file_len = file.metadata()?.len();
+    let mut decoder = DecoderBuilder::new()
+        .with_decoder::<OpusDecoder>()
+        .with_data(BufReader::new(file))
+        .with_byte_len(file_len)
+        .build()
+        .context("Failed to decode audio file")?;
+
+    if let Some(total) = decoder.total_duration() {
+        let _ = decoder.try_seek(total / 3);
+    }
+
+    let mut samples = F32Converter { input: decoder };
+    let sample_rate = samples.sample_rate().get();
+    let channels = samples.channels().get() as usize;
+
+    let mut sums: Vec<(f32, f32)> = Vec::new();
+    let mut windows = 0;
+    let mut mono = Vec::with_capacity(FFT_SIZE);
+    for _ in 0..MAX_FRAMES {
+        let frame: Vec<f32> = samples.by_ref().take(channels).collect();
+        if frame.len() < channels {
+            break;
+        }
+        mono.push(frame.iter().sum::<f32>() / channels as f32);
+
+        if mono.len() == FFT_SIZE {
+            if accumulate_window(&mono, sample_rate, &mut sums) {
+                windows += 1;
+            }
+            mono.clear();
+            if windows >= MAX_WINDOWS {
+                break;
+            }
+        }
+    }
+
+    if windows < MIN_WINDOWS {
+        return Ok(false);
+    }
+
+    let bins: Vec<(f32, f32)> = sums
+        .into_iter()
+        .map(|(freq, db)| (freq, db / windows as f32))
+        .collect();
+    Ok(has_brickwall(&bins, sample_rate as f32 / 2.0))
+}
+
+fn accumulate_window(mono: &[f32], sample_rate: u32, sums: &mut Vec<(f32, f32)>) -> bool {
+    let rms = (mono.iter().map(|s| s * s).sum::<f32>() / mono.len() as f32).sqrt();
+    if rms < SILENCE_RMS {
+        return false;
+    }
+
+    let windowed = hann_window(mono);
+    let Ok(spectrum) = samples_fft_to_spectrum(
+        &windowed,
+        sample_rate,
+        FrequencyLimit::All,
+        Some(&divide_by_N_sqrt),
+    ) else {
+        return false;
+    };
+
+    if sums.is_empty() {
+        sums.extend(spectrum.data().iter().map(|(freq, _)| (freq.val(), 0.0)));
+    }
+    for (sum, (_, value)) in sums.iter_mut().zip(spectrum.data().iter()) {
+        sum.1 += 20.0 * value.val().max(1e-12).log10();
+    }
+    true
+}
+
+fn band_mean(bins: &[(f32, f32)], low: f32, high: f32) -> Option<f32> {
+    let band: Vec<f32> = bins
+        .iter()
+        .filter(|(freq, _)| *freq >= low && *freq < high)
+        .map(|(_, db)| *db)
+        .collect();
+    (!band.is_empty()).then(|| band.iter().sum::<f32>() / band.len() as f32)
+}
+
+fn has_brickwall(bins: &[(f32, f32)], nyquist: f32) -> bool {
+    let Some(reference) = band_mean(bins, 1_000.0, 4_000.0) else {
+        return false;
+    };
+    let Some(&(cutoff, _)) = bins
+        .iter()
+        .rev()
+        .find(|(_, db)| *db > reference - NOISE_FLOOR_DB)
+    else {
+        return false;
+    };
+
+    if cutoff >= CUTOFF_LIMIT_HZ.min(nyquist - 1_000.0) {
+        return false;
+    }
+
+    match (
+        band_mean(bins, cutoff - 1_000.0, cutoff - 250.0),
+        band_mean(bins, cutoff + 250.0, cutoff + 1_250.0),
+    ) {
+        (Some(below), Some(above)) => below - above >= BRICKWALL_DB,
+        _ => false,
+    }
+}
@@ -27,9 +27,53 @@ impl ToF32 for u16 {
     }
 }
 
+/// Number of log-spaced bars the spectrum analyzer shows, from 20 Hz to 20 kHz.
+pub const SPECTRUM_BARS: usize = 24;
+const SPECTRUM_FRAMES: usize = 4;
+const SPECTRUM_LOW_HZ: f32 = 20.0;
+const SPECTRUM_HIGH_HZ: f32 = 20000.0;
+
+/// The last few spectrum frames, written by the audio thread and read by the UI. Averaging
+/// them smooths out the gaps between UI refreshes without any work on the render thread.
+pub struct SpectrumRing {
+    frames: [[f32; SPECTRUM_BARS]; SPECTRUM_FRAMES],
+    next: usize,
+}
+
+impl Default for SpectrumRing {
+    fn default() -> Self {
+        Self {
+            frames: [[0.0; SPECTRUM_BARS]; SPECTRUM_FRAMES],
+            next: 0,
+        }
+    }
+}
+
+impl SpectrumRing {
+    pub fn push(&mut self, frame: [f32; SPECTRUM_BARS]) {
+        self.frames[self.next] = frame;
+        self.next = (self.next + 1) % SPECTRUM_FRAMES;
+    }
+
+    pub fn smoothed(&self) -> [f32; SPECTRUM_BARS] {
+        let mut bars = [0.0; SPECTRUM_BARS];
+        for frame in &self.frames {
+            for (bar, value) in bars.iter_mut().zip(frame) {
+                *bar += value / SPECTRUM_FRAMES as f32;
+            }
+        }
+        bars
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
 #[derive(Clone)]
 pub struct VisualizerState {
     pub bands: Arc<Mutex<[f32; 4]>>,
+    pub spectrum: Arc<Mutex<SpectrumRing>>,
     pub enabled: Arc<AtomicBool>,
 }
 
@@ -37,6 +81,7 @@ impl Default for VisualizerState {
     fn default() -> Self {
         Self {
             bands: Arc::new(Mutex::new([0.0; 4])),
+            spectrum: Arc::new(Mutex::new(SpectrumRing::default())),
             enabled: Arc::new(AtomicBool::new(true)),
         }
     }
@@ -47,10 +92,17 @@ impl VisualizerState {
         self.enabled.store(enabled, Ordering::Relaxed);
         if !enabled {
             *self.bands.lock() = [0.0; 4];
+            self.spectrum.lock().clear();
         }
     }
 }
 
+/// Upper frequency edge of each spectrum bar, spaced evenly on a log scale.
+fn spectrum_bar_edges() -> [f32; SPECTRUM_BARS] {
+    let ratio = SPECTRUM_HIGH_HZ / SPECTRUM_LOW_HZ;
+    std::array::from_fn(|i| SPECTRUM_LOW_HZ * ratio.powf((i + 1) as f32 / SPECTRUM_BARS as f32))
+}
+
 struct BandDetector {
     value: f32,
     attack: f32,
@@ -86,6 +138,8 @@ pub struct VisualizerSource<I> {
     sample_rate: u32,
     bands: [BandDetector; 4],
     peak_ref: [f32; 4],
+    bar_edges: [f32; SPECTRUM_BARS],
+    bar_peak_ref: f32,
 }
 
 impl<I> VisualizerSource<I>
@@ -108,6 +162,8 @@ where
                 BandDetector::new(0.40, 0.11),
             ],
             peak_ref: [10.0; 4],
+            bar_edges: spectrum_bar_edges(),
+            bar_peak_ref: 10.0,
         }
     }
 
@@ -157,6 +213,37 @@ where
             for i in 0..4 {
                 bands_guard[i] = self.bands[i].update(energies[i]);
             }
+            drop(bands_guard);
+
+            let mut bars = [0.0f32; SPECTRUM_BARS];
+            let mut bar = 0;
+            for (freq, val) in spec.data().iter() {
+                let f = freq.val();
+                if f < SPECTRUM_LOW_HZ {
+                    continue;
+                }
+                while bar < SPECTRUM_BARS && f > self.bar_edges[bar] {
+                    bar += 1;
+                }
+                if bar == SPECTRUM_BARS {
+                    break;
+                }
+                bars[bar] = bars[bar].max(val.val());
+            }
+
+            let loudest = bars.iter().copied().fold(0.0f32, f32::max);
+            if loudest > self.bar_peak_ref {
+                self.bar_peak_ref = loudest;
+            } else {
+                self.bar_peak_ref *= 0.9985;
+            }
+            if self.bar_peak_ref > 1e-4 {
+                for value in &mut bars {
+                    // The square root lifts the quieter high bars so they stay visible.
+                    *value = (*value / self.bar_peak_ref).clamp(0.0, 1.0).sqrt();
+                }
+            }
+            self.state.spectrum.lock().push(bars);
         }
     }
 }
@@ -3,11 +3,23 @@ use futures::StreamExt;
 use gpui::*;
 use gpui_platform::application;
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
 use tracing::{debug, error};
 
 use crate::{
-    data::{config::Config, db::repo::Database, scanner::Scanner, telemetry::Telemetry},
-    media::{controller::MediaController, playback::Playback, queue::Queue},
+    data::{
+        config::{Config, WindowRect, WindowSettings},
+        db::repo::Database,
+        models::Song,
+        scanner::Scanner,
+        telemetry::Telemetry,
+    },
+    media::{
+        controller::MediaController,
+        playback::{Playback, play_files, restore_queue, save_queue_on_change},
+        queue::Queue,
+    },
     ui::{
         assets::{VleerAssetSource, image_cache::app_image_cache},
         components::{
@@ -18,18 +30,21 @@ use crate::{
             div::{flex_col, flex_row},
             input::bind_input_keys,
             pane::pane,
+            song_info::SongInfoPanel,
             window_controls::WindowControls,
         },
         discord_presence::DiscordPresence,
         global_actions::register_actions,
         layout::{
-            library::{Library, Search},
+            library::{Library, Search, sidebar_width},
             navbar::{self, Navbar, NavbarProgressBar},
             player::Player,
             queue::{QueuePane, QueueVisible},
         },
         variables::Variables,
-        views::{ActiveView, AppView, SelectedAlbum, SelectedPlaylist, ViewRegistry},
+        views::{
+            ActiveView, AppView, SelectedAlbum, SelectedArtist, SelectedPlaylist, ViewRegistry,
+        },
     },
     updater::Updater,
 };
@@ -40,6 +55,7 @@ pub(crate) struct MainWindow {
     navbar_progress: Entity<NavbarProgressBar>,
     player: Entity<Player>,
     queue: Entity<QueuePane>,
+    song_info: Entity<SongInfoPanel>,
     views: HashMap<AppView, AnyView>,
     current_view: AppView,
     titlebar_should_move: bool,
@@ -64,6 +80,15 @@ impl MainWindow {
         window.refresh();
         cx.notify();
     }
+
+    pub fn focus_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.library
+            .update(cx, |library, cx| library.focus_search(window, cx));
+    }
+
+    pub fn show_song_info(&mut self, song: Song, cx: &mut Context<Self>) {
+        self.song_info.update(cx, |panel, cx| panel.show(song, cx));
+    }
 }
 
 impl Render for MainWindow {
@@ -86,12 +111,16 @@ impl Render for MainWindow {
         let show_titlebar = cfg!(target_os = "windows") || show_linux_controls;
         let is_macos = cfg!(target_os = "macos");
         let titlebar_height = px(32.0);
+        let library_width = sidebar_width(window.bounds().size.width.into());
 
         let mut element = flex_col()
             .size_full()
             .min_h_0()
             .bg(variables.background)
-            .image_cache(app_image_cache());
+            .image_cache(app_image_cache())
+            .on_drop(|paths: &ExternalPaths, _window, cx| {
+                play_files(paths.paths().to_vec(), cx);
+            });
 
         if show_titlebar {
             let mut titlebar = flex_row()
@@ -156,7 +185,7 @@ impl Render for MainWindow {
                         .child(
                             div()
                                 .id("library-container")
-                                .w(px(300.0))
+                                .w(px(library_width))
                                 .flex_shrink_0()
                                 .min_h_0()
                                 .h_full()
@@ -238,13 +267,16 @@ impl Render for MainWindow {
                         ),
                 ),
         );
+        element = element.child(self.song_info.clone());
 
+        let (font_family, font_size) = ui_font(cx);
+        let font_size = px(font_size);
         let text_styles = element.text_style();
         *text_styles = TextStyleRefinement {
             color: Some(Hsla::from(variables.text)),
-            font_family: Some(SharedString::new("Feature Mono")),
-            font_size: Some(AbsoluteLength::Pixels(px(14.0))),
-            line_height: Some(DefiniteLength::Absolute(AbsoluteLength::Pixels(px(14.0)))),
+            font_family: Some(SharedString::from(font_family)),
+            font_size: Some(AbsoluteLength::Pixels(font_size)),
+            line_height: Some(DefiniteLength::Absolute(AbsoluteLength::Pixels(font_size))),
             ..Default::default()
         };
 
@@ -252,6 +284,37 @@ impl Render for MainWindow {
     }
 }
 
+fn ui_font(cx: &App) -> (String, f32) {
+    let interface = &cx.global::<Config>().get().interface;
+    (interface.font_family.clone(), interface.ui_font_size())
+}
+
+const MIN_WINDOW_WIDTH: f32 = 754.0;
+const MIN_WINDOW_HEIGHT: f32 = 443.0;
+
+const HISTORY_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Drops play history older than the configured retention, if there is one.
+pub fn purge_expired_history(cx: &App) {
+    let Some(days) = cx.global::<Config>().get().history_retention.days() else {
+        return;
+    };
+    let db = cx.global::<Database>().clone();
+    let background_ui = cx.global::<BackgroundUiNotifier>().clone();
+    cx.background_executor()
+        .spawn(async move {
+            match db.purge_history_older_than(days) {
+                Ok(0) => {}
+                Ok(purged) => {
+                    debug!("Purged {} history events older than {} days", purged, days);
+                    background_ui.notify(BackgroundUiEvent::HomeDataChanged);
+                }
+                Err(e) => error!("Failed to purge listening history: {}", e),
+            }
+        })
+        .detach();
+}
+
 pub fn find_fonts(cx: &mut App) -> gpui::Result<()> {
     let paths = cx.asset_source().list("!bundled:fonts")?;
     let mut fonts = vec![];
@@ -268,6 +331,70 @@ pub fn find_fonts(cx: &mut App) -> gpui::Result<()> {
     results
 }
 
+// Saved bounds go back on the display they were last on, or whichever one now holds them, and
+// are pulled inside it so the window never opens off-screen.
+fn restored_window_bounds(cx: &App) -> Option<(WindowBounds, DisplayId)> {
+    let saved = cx.global::<Config>().get().window.clone();
+    let rect = saved.bounds?;
+    let center = point(
+        px(rect.x + rect.width / 2.0),
+        px(rect.y + rect.height / 2.0),
+    );
+    let displays = cx.displays();
+    let display = saved
+        .display
+        .as_deref()
+        .and_then(|uuid| {
+            displays
+                .iter()
+                .find(|d| d.uuid().is_ok_and(|id| id.to_string() == uuid))
+        })
+        .or_else(|| displays.iter().find(|d| d.bounds().contains(&center)))
+        .cloned()
+        .or_else(|| cx.primary_display())?;
+
+    let area = display.bounds();
+    let size = size(
+        px(rect.width).min(area.size.width),
+        px(rect.height).min(area.size.height),
+    );
+    let origin = point(
+        px(rect.x).clamp(area.left(), area.right() - size.width),
+        px(rect.y).clamp(area.top(), area.bottom() - size.height),
+    );
+    let bounds = Bounds::new(origin, size);
+    let bounds = if saved.maximized {
+        WindowBounds::Maximized(bounds)
+    } else {
+        WindowBounds::Windowed(bounds)
+    };
+    Some((bounds, display.id()))
+}
+
+fn save_window_bounds(window: &Window, cx: &mut App) {
+    let (bounds, maximized) = match window.window_bounds() {
+        WindowBounds::Windowed(bounds) => (bounds, false),
+        WindowBounds::Maximized(bounds) | WindowBounds::Fullscreen(bounds) => (bounds, true),
+    };
+    let saved = WindowSettings {
+        bounds: Some(WindowRect {
+            x: bounds.origin.x.into(),
+            y: bounds.origin.y.into(),
+            width: bounds.size.width.into(),
+            height: bounds.size.height.into(),
+        }),
+        maximized,
+        display: window
+            .display(cx)
+            .and_then(|display| display.uuid().ok())
+            .map(|uuid| uuid.to_string()),
+    };
+    if cx.global::<Config>().get().window == saved {
+        return;
+    }
+    cx.update_global::<Config, _>(|config, _| config.set(|s| s.window = saved));
+}
+
 pub async fn run() -> anyhow::Result<()> {
     let data_dir = dirs::data_dir()
         .expect("couldn't get data directory")
@@ -279,6 +406,8 @@ pub async fn run() -> anyhow::Result<()> {
 
     let db_path = data_dir.join("library.db");
     let database = Database::new(&db_path)?;
+    let read_only = std::env::args().any(|arg| arg == "--read-only");
+    database.set_read_only(read_only);
 
     application()
         .with_assets(VleerAssetSource::new())
@@ -289,6 +418,7 @@ pub async fn run() -> anyhow::Result<()> {
             cx.set_global(Search::default());
             cx.set_global(ActiveView::default());
             cx.set_global(SelectedAlbum::default());
+            cx.set_global(SelectedArtist::default());
             cx.set_global(SelectedPlaylist::default());
             cx.set_global(BackgroundUiNotifier::new(background_ui_tx));
             cx.set_global(PinnedItemsChanged);
@@ -300,6 +430,9 @@ pub async fn run() -> anyhow::Result<()> {
             let is_first_launch = Telemetry::is_first_launch(&data_dir);
 
             Config::init(cx, &config_dir).expect("failed to initialize settings");
+            cx.update_global::<Config, _>(|config, _| config.set_read_only(read_only));
+            let ignore_articles = cx.global::<Config>().get().interface.ignore_articles;
+            cx.global::<Database>().set_ignore_articles(ignore_articles);
             Playback::init(cx).expect("failed to initialize playback context");
             DiscordPresence::init(cx);
             Queue::init(cx);
@@ -316,6 +449,14 @@ pub async fn run() -> anyhow::Result<()> {
                     crate::status::StatusColor::Destructive,
                 );
             }
+            if read_only {
+                navbar::status().set(
+                    "read_only",
+                    "Read-only mode: library edits and settings aren't saved",
+                    None,
+                    crate::status::StatusColor::Warning,
+                );
+            }
             if is_first_launch {
                 navbar::status().set(
                     "telemetry.consent",
@@ -353,8 +494,11 @@ pub async fn run() -> anyhow::Result<()> {
             })
             .detach();
 
+            let restored = restored_window_bounds(cx);
             cx.open_window(
                 WindowOptions {
+                    window_bounds: restored.as_ref().map(|(bounds, _)| *bounds),
+                    display_id: restored.map(|(_, display)| display),
                     titlebar: Some(TitlebarOptions {
                         title: Some(SharedString::new("Vleer")),
                         appears_transparent: true,
@@ -362,7 +506,7 @@ pub async fn run() -> anyhow::Result<()> {
                     }),
                     app_id: Some("vleer".to_string()),
                     kind: gpui::WindowKind::Normal,
-                    window_min_size: Some(Size::new(px(754.0), px(443.0))),
+                    window_min_size: Some(Size::new(px(MIN_WINDOW_WIDTH), px(MIN_WINDOW_HEIGHT))),
                     ..Default::default()
                 },
                 |window, cx| {
@@ -375,12 +519,27 @@ pub async fn run() -> anyhow::Result<()> {
 
                     cx.new(|cx| {
                         Playback::start_monitor(window, cx);
+                        cx.observe_window_bounds(window, |_, window, cx| {
+                            save_window_bounds(window, cx);
+                        })
+                        .detach();
+
+                        let mut font = ui_font(cx);
+                        cx.observe_global::<Config>(move |_, cx| {
+                            let current = ui_font(cx);
+                            if current != font {
+                                font = current;
+                                cx.notify();
+                            }
+                        })
+                        .detach();
 
                         let library_entity = cx.new(Library::new);
                         let navbar_entity = cx.new(Navbar::new);
                         let navbar_progress_entity = cx.new(NavbarProgressBar::new);
                         let player_entity = cx.new(Player::new);
                         let queue_entity = cx.new(QueuePane::new);
+                        let song_info_entity = cx.new(SongInfoPanel::new);
 
                         let views = ViewRegistry::register_all(window, cx);
 
@@ -390,6 +549,7 @@ pub async fn run() -> anyhow::Result<()> {
                             navbar_progress: navbar_progress_entity,
                             player: player_entity,
                             queue: queue_entity,
+                            song_info: song_info_entity,
                             views,
                             current_view: AppView::Home,
                             titlebar_should_move: false,
@@ -400,6 +560,26 @@ pub async fn run() -> anyhow::Result<()> {
             .expect("failed to open main window");
 
             Scanner::init(cx);
+
+            cx.spawn(async move |cx: &mut AsyncApp| {
+                loop {
+                    cx.update(|cx| purge_expired_history(cx));
+                    cx.background_executor().timer(HISTORY_PURGE_INTERVAL).await;
+                }
+            })
+            .detach();
+
+            let opened: Vec<PathBuf> = std::env::args_os()
+                .skip(1)
+                .map(PathBuf::from)
+                .filter(|path| !path.to_string_lossy().starts_with("--"))
+                .collect();
+            if opened.is_empty() {
+                restore_queue(cx);
+            } else {
+                play_files(opened, cx);
+                save_queue_on_change(cx);
+            }
         });
 
     Ok(())
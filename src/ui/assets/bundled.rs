@@ -13,9 +13,30 @@ pub struct BundledAssets;
 impl BundledAssets {
     pub fn load(url: Url) -> gpui::Result<Option<std::borrow::Cow<'static, [u8]>>> {
         let path = url.path().trim_start_matches('/');
+        #[cfg(debug_assertions)]
+        if let Some(data) = Self::load_from_disk(path) {
+            return Ok(Some(std::borrow::Cow::Owned(data)));
+        }
         Ok(Self::get(path).map(|f| Some(f.data)).unwrap_or(None))
     }
 
+    /// Debug builds read assets straight from the source tree when it's around, so edited
+    /// icons show up without a rebuild.
+    #[cfg(debug_assertions)]
+    fn load_from_disk(path: &str) -> Option<Vec<u8>> {
+        use std::path::{Component, Path};
+
+        let relative = Path::new(path);
+        if !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            return None;
+        }
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        std::fs::read(root.join(relative)).ok()
+    }
+
     pub fn list(&self, path: &str) -> gpui::Result<Vec<SharedString>> {
         Ok(Self::iter()
             .map(|p| format!("!bundled:{p}"))
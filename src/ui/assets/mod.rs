@@ -1,9 +1,11 @@
 pub mod bundled;
 pub mod image_cache;
 
+use crate::data::config::{Config, FallbackArtwork};
 use crate::data::db::repo::Database;
+use crate::data::scanner::expand_tilde;
 use crate::ui::assets::bundled::BundledAssets;
-use gpui::{App, Asset, ImageCacheError, RenderImage, Resource};
+use gpui::{App, Asset, ImageCacheError, ImageSource, RenderImage, Resource};
 use gpui::{AssetSource, Result as GpuiResult};
 use image::{Frame, ImageError};
 use rusqlite::{OptionalExtension, params};
@@ -11,6 +13,17 @@ use std::borrow::Cow;
 use std::sync::Arc;
 use url::Url;
 
+pub const BUNDLED_FALLBACK_ARTWORK: &str = "!bundled:images/icon-512.png";
+
+/// The configured cover for items without artwork, if one is set.
+pub fn fallback_artwork(cx: &App) -> Option<ImageSource> {
+    match &cx.global::<Config>().get().interface.fallback_artwork {
+        FallbackArtwork::None => None,
+        FallbackArtwork::Bundled => Some(BUNDLED_FALLBACK_ARTWORK.into()),
+        FallbackArtwork::Custom(path) => Some(expand_tilde(path).into()),
+    }
+}
+
 pub enum VleerImageLoader {}
 
 impl Asset for VleerImageLoader {
@@ -0,0 +1,34 @@
+use gpui::SharedString;
+
+const RLI: char = '\u{2067}';
+const PDI: char = '\u{2069}';
+
+/// Hebrew, Arabic, Syriac, Thaana, N'Ko and the other right-to-left scripts.
+pub fn is_rtl(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0590}'..='\u{08FF}'
+            | '\u{FB1D}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFC}'
+            | '\u{10800}'..='\u{10FFF}'
+            | '\u{1E800}'..='\u{1EFFF}'
+    )
+}
+
+pub fn has_rtl(text: &str) -> bool {
+    text.chars().any(is_rtl)
+}
+
+fn starts_rtl(text: &str) -> bool {
+    text.chars().find(|c| c.is_alphabetic()).is_some_and(is_rtl)
+}
+
+/// Lines are shaped left-to-right, so text that starts in a right-to-left script is wrapped in
+/// an isolate to keep brackets and trailing punctuation on the side its reader expects.
+pub fn isolate(text: &str) -> SharedString {
+    if starts_rtl(text) {
+        format!("{RLI}{text}{PDI}").into()
+    } else {
+        SharedString::from(text.to_string())
+    }
+}
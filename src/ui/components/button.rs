@@ -2,7 +2,7 @@ use gpui::{prelude::FluentBuilder as _, *};
 use std::rc::Rc;
 
 use crate::ui::{
-    components::{div::flex_row, icons::icon},
+    components::{div::flex_row, focus::FocusRing, icons::icon},
     variables::Variables,
 };
 
@@ -93,6 +93,7 @@ impl RenderOnce for Button {
         let color = self.color.unwrap_or(variables.text_secondary);
         let hover_color = self.hover_color.unwrap_or(variables.text);
         let group_id = self.group_id.clone();
+        let clickable = self.on_click.is_some();
 
         let icon_element = self.icon.map(|icon_path| {
             icon(icon_path)
@@ -108,6 +109,7 @@ impl RenderOnce for Button {
             .text_color(color)
             .when_some(self.bg_color, |this, bg| this.bg(bg))
             .group_hover(self.group_id, |s| s.text_color(hover_color))
+            .when(clickable, |this| this.keyboard_focusable(variables))
             .when_some(self.on_click, |this, on_click| {
                 this.on_click(move |event, window, cx| {
                     (on_click)(event, window, cx);
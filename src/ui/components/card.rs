@@ -2,8 +2,10 @@ use gpui::{prelude::FluentBuilder as _, *};
 use std::ops::Range;
 use std::rc::Rc;
 
+use crate::data::config::{ClickBehavior, Config};
 use crate::ui::{
     components::{
+        cover::{self, Cover},
         div::flex_col,
         icons::{self, icon},
     },
@@ -50,6 +52,7 @@ pub struct Card {
     image_size: f32,
     image_shape: CardImageShape,
     on_play: Option<PlayHandler>,
+    on_shuffle: Option<PlayHandler>,
 }
 
 impl Card {
@@ -72,6 +75,7 @@ impl Card {
             image_size,
             image_shape: CardImageShape::Square,
             on_play: None,
+            on_shuffle: None,
         }
     }
 
@@ -106,6 +110,12 @@ impl Card {
         self.on_play = Some(Rc::new(handler));
         self
     }
+
+    /// Adds a shuffle button next to the play button on hover.
+    pub fn on_shuffle(mut self, handler: impl Fn(&mut Window, &mut App) + 'static) -> Self {
+        self.on_shuffle = Some(Rc::new(handler));
+        self
+    }
 }
 
 impl Styled for Card {
@@ -134,43 +144,14 @@ impl RenderOnce for Card {
             image_size,
             image_shape,
             on_play,
+            on_shuffle,
         } = self;
         let variables = cx.global::<Variables>();
         let tile_id = id.to_string();
         let image_hover_group: SharedString = format!("{tile_id}-image-hover").into();
 
-        let image = match image_uri {
-            Some(uri) => match image_shape {
-                CardImageShape::Square => img(format!("!image://{}", uri))
-                    .id(ElementId::Name(format!("{tile_id}-image").into()))
-                    .size(px(image_size))
-                    .object_fit(ObjectFit::Cover)
-                    .into_any_element(),
-                CardImageShape::Circle => img(format!("!image://{}", uri))
-                    .id(ElementId::Name(format!("{tile_id}-image").into()))
-                    .size(px(image_size))
-                    .object_fit(ObjectFit::Cover)
-                    .rounded_full()
-                    .into_any_element(),
-            },
-            None => match image_shape {
-                CardImageShape::Square => div()
-                    .id(ElementId::Name(
-                        format!("{tile_id}-image-placeholder").into(),
-                    ))
-                    .size(px(image_size))
-                    .bg(variables.border)
-                    .into_any_element(),
-                CardImageShape::Circle => div()
-                    .id(ElementId::Name(
-                        format!("{tile_id}-image-placeholder").into(),
-                    ))
-                    .size(px(image_size))
-                    .bg(variables.border)
-                    .rounded_full()
-                    .into_any_element(),
-            },
-        };
+        let image = Cover::new(image_uri.map(|uri| cover::image_uri(&uri)))
+            .rounded(image_shape == CardImageShape::Circle);
 
         let mut image_container = div()
             .id(ElementId::Name(format!("{tile_id}-image-container").into()))
@@ -179,7 +160,46 @@ impl RenderOnce for Card {
             .group(image_hover_group.clone())
             .child(image);
 
-        if let Some(on_play) = on_play {
+        // Outside cover mode the whole cover plays on the configured click count, like a song
+        // row does. Other clicks on it are swallowed so the card doesn't open between the two
+        // clicks of a double click; the rest of the tile still opens it.
+        let click_behavior = cx.global::<Config>().get().interface.click_behavior;
+        if let Some(on_play) = on_play.clone()
+            && click_behavior != ClickBehavior::Cover
+        {
+            image_container = image_container.cursor_pointer().on_mouse_down(
+                MouseButton::Left,
+                move |event, window, cx| {
+                    cx.stop_propagation();
+                    if click_behavior.plays_on(event.click_count) {
+                        (on_play)(window, cx);
+                    }
+                },
+            );
+        }
+
+        if on_play.is_some() || on_shuffle.is_some() {
+            let hover_button = |suffix: &str, icon_path: &'static str, handler: PlayHandler| {
+                div()
+                    .id(ElementId::Name(format!("{tile_id}-{suffix}-button").into()))
+                    .size(px(variables.padding_32))
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .bg(variables.accent)
+                    .hover(|s| s.bg(variables.accent_background))
+                    .cursor_pointer()
+                    .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                        cx.stop_propagation();
+                        (handler)(window, cx);
+                    })
+                    .child(
+                        icon(icon_path)
+                            .size(px(variables.padding_16))
+                            .text_color(variables.background),
+                    )
+            };
+
             image_container = image_container.child(
                 div()
                     .id(ElementId::Name(
@@ -190,29 +210,16 @@ impl RenderOnce for Card {
                     .flex()
                     .items_end()
                     .justify_end()
+                    .gap(px(variables.padding_8))
                     .p(px(variables.padding_16))
                     .invisible()
                     .group_hover(image_hover_group, |s| s.visible())
-                    .child(
-                        div()
-                            .id(ElementId::Name(format!("{tile_id}-play-button").into()))
-                            .size(px(variables.padding_32))
-                            .flex()
-                            .items_center()
-                            .justify_center()
-                            .bg(variables.accent)
-                            .hover(|s| s.bg(variables.accent_background))
-                            .cursor_pointer()
-                            .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
-                                cx.stop_propagation();
-                                (on_play)(window, cx);
-                            })
-                            .child(
-                                icon(icons::PLAY)
-                                    .size(px(variables.padding_16))
-                                    .text_color(variables.background),
-                            ),
-                    ),
+                    .when_some(on_shuffle, |this, on_shuffle| {
+                        this.child(hover_button("shuffle", icons::SHUFFLE, on_shuffle))
+                    })
+                    .when_some(on_play, |this, on_play| {
+                        this.child(hover_button("play", icons::PLAY, on_play))
+                    }),
             );
         }
 
@@ -1,18 +1,25 @@
+use crate::data::config::Config;
 use crate::data::db::repo::Database;
+use crate::data::fs::{reveal_in_file_manager, trash};
 use crate::data::models::{Album, Artist, Cuid, Playlist, PlaylistListItem, Song};
+use crate::data::scanner::Scanner;
 use crate::media::playback::{
-    play_album_last, play_album_next, play_playlist_last, play_playlist_next,
+    play_album_last, play_album_next, play_playlist_last, play_playlist_next, start_artist_radio,
+    start_radio,
 };
 use crate::media::queue::Queue;
 use crate::ui::app::MainWindow;
 use crate::ui::assets::image_cache::vleer_cache;
+use crate::ui::components::cover::{Cover, image_uri};
 use crate::ui::components::div::{flex_col, flex_row};
 use crate::ui::components::icons::{self, icon};
 use crate::ui::variables::Variables;
-use crate::ui::views::{AppView, SelectedAlbum, SelectedPlaylist};
+use crate::ui::views::{AppView, SelectedAlbum, SelectedArtist, SelectedPlaylist};
 use futures::channel::mpsc;
 use gpui::{prelude::*, *};
+use std::path::Path;
 use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 use tracing::error;
 
@@ -70,6 +77,7 @@ pub struct ContextMenuItem {
     pub disabled: bool,
     pub is_separator: bool,
     pub is_destructive: bool,
+    pub mutates: bool,
     pub submenu: Option<ContextMenuSubmenu>,
 }
 
@@ -86,6 +94,7 @@ impl ContextMenuItem {
             disabled: false,
             is_separator: false,
             is_destructive: false,
+            mutates: false,
             submenu: None,
         }
     }
@@ -102,6 +111,7 @@ impl ContextMenuItem {
             disabled: false,
             is_separator: false,
             is_destructive: true,
+            mutates: false,
             submenu: None,
         }
     }
@@ -114,6 +124,7 @@ impl ContextMenuItem {
             disabled: false,
             is_separator: true,
             is_destructive: false,
+            mutates: false,
             submenu: None,
         }
     }
@@ -131,12 +142,34 @@ impl ContextMenuItem {
             disabled: false,
             is_separator: false,
             is_destructive: false,
+            mutates: false,
             submenu: Some(ContextMenuSubmenu {
                 playlists,
                 action: Rc::new(action) as SubmenuAction,
             }),
         }
     }
+
+    /// Marks an item that edits the library, so it is left out in read-only mode.
+    pub fn mutating(mut self) -> Self {
+        self.mutates = true;
+        self
+    }
+}
+
+fn without_mutating(items: Vec<ContextMenuItem>) -> Vec<ContextMenuItem> {
+    let mut kept: Vec<ContextMenuItem> = Vec::with_capacity(items.len());
+    for item in items.into_iter().filter(|item| !item.mutates) {
+        let after_separator = kept.last().is_none_or(|last| last.is_separator);
+        if item.is_separator && after_separator {
+            continue;
+        }
+        kept.push(item);
+    }
+    if kept.last().is_some_and(|last| last.is_separator) {
+        kept.pop();
+    }
+    kept
 }
 
 pub struct ContextMenu {
@@ -164,6 +197,15 @@ impl ContextMenu {
         items: Vec<ContextMenuItem>,
         cx: &mut Context<Self>,
     ) {
+        let items = if cx.global::<Database>().read_only() {
+            without_mutating(items)
+        } else {
+            items
+        };
+        if items.is_empty() {
+            self.hide(cx);
+            return;
+        }
         self.position = Some(position);
         self.items = items;
         self.active_submenu_idx = None;
@@ -326,23 +368,10 @@ impl Render for ContextMenu {
                             let action = action.clone();
                             let entity_close = entity.clone();
 
-                            let cover: AnyElement = if let Some(image_id) = &pl.image_id {
-                                div()
-                                    .size(px(32.0))
-                                    .flex_shrink_0()
-                                    .child(
-                                        img(format!("!image://{}", image_id))
-                                            .size_full()
-                                            .object_fit(ObjectFit::Cover),
-                                    )
-                                    .into_any_element()
-                            } else {
-                                div()
-                                    .size(px(32.0))
-                                    .flex_shrink_0()
-                                    .bg(variables.border)
-                                    .into_any_element()
-                            };
+                            let cover = div()
+                                .size(px(32.0))
+                                .flex_shrink_0()
+                                .child(Cover::new(pl.image_id.as_deref().map(image_uri)));
 
                             flex_row()
                                 .id(ElementId::Name(format!("ctx-pl-item-{}", pi).into()))
@@ -378,22 +407,9 @@ impl Render for ContextMenu {
                             .text_color(variables.text)
                             .hover(|s| s.bg(variables.element_hover))
                             .on_mouse_down(MouseButton::Left, move |_, window, cx| {
-                                let new_id = Cuid::new();
-                                let db = cx.global::<Database>().clone();
-                                if let Err(e) = db.upsert_playlist(&new_id, "", None, None, false) {
-                                    tracing::error!("Failed to create playlist: {}", e);
+                                if !create_playlist(window, cx) {
                                     return;
                                 }
-                                cx.update_global::<SelectedPlaylist, _>(|sel, _| {
-                                    sel.id = Some(new_id);
-                                    sel.focus_title = true;
-                                });
-                                cx.set_global(LibraryDataChanged);
-                                if let Some(Some(root)) = window.root::<MainWindow>() {
-                                    root.update(cx, |view, cx| {
-                                        view.set_current_view(AppView::Playlist, window, cx);
-                                    });
-                                }
                                 entity_close
                                     .update(cx, |this, cx| {
                                         this.hide(cx);
@@ -484,6 +500,32 @@ impl Render for ContextMenu {
     }
 }
 
+/// Creates an untitled playlist, pinned if the user asked for that, and opens it with the
+/// title ready for editing.
+pub fn create_playlist(window: &mut Window, cx: &mut App) -> bool {
+    let new_id = Cuid::new();
+    let pinned = cx.global::<Config>().get().interface.pin_new_playlists;
+    let db = cx.global::<Database>().clone();
+    if let Err(e) = db.upsert_playlist(&new_id, "", None, None, pinned) {
+        tracing::error!("Failed to create playlist: {}", e);
+        return false;
+    }
+    cx.update_global::<SelectedPlaylist, _>(|sel, _| {
+        sel.id = Some(new_id);
+        sel.focus_title = true;
+    });
+    cx.set_global(LibraryDataChanged);
+    if pinned {
+        cx.set_global(PinnedItemsChanged);
+    }
+    if let Some(Some(root)) = window.root::<MainWindow>() {
+        root.update(cx, |view, cx| {
+            view.set_current_view(AppView::Playlist, window, cx);
+        });
+    }
+    true
+}
+
 fn write_and_notify(cx: &mut App, write: impl FnOnce(&Database)) {
     let db = cx.global::<Database>().clone();
     write(&db);
@@ -498,12 +540,254 @@ fn write_and_notify_pinned(cx: &mut App, write: impl FnOnce(&Database)) {
     cx.set_global(PinnedItemsChanged);
 }
 
+const PLAYLIST_NOTICE: &str = "playlist.add";
+const PLAYLIST_NOTICE_DURATION: Duration = Duration::from_secs(3);
+
+// Shows `text` in the status bar for a few seconds; a newer notice restarts the countdown.
+fn show_playlist_notice(text: String, cx: &mut App) {
+    static GENERATION: AtomicU64 = AtomicU64::new(0);
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+    crate::ui::layout::navbar::status().set(
+        PLAYLIST_NOTICE,
+        text,
+        None,
+        crate::status::StatusColor::Warning,
+    );
+    let bg = cx.background_executor().clone();
+    cx.background_executor()
+        .spawn(async move {
+            bg.timer(PLAYLIST_NOTICE_DURATION).await;
+            if GENERATION.load(Ordering::Relaxed) == generation {
+                crate::ui::layout::navbar::status().clear(PLAYLIST_NOTICE);
+            }
+        })
+        .detach();
+}
+
+fn transient_song_context_menu_items(song: Song) -> Vec<ContextMenuItem> {
+    vec![
+        ContextMenuItem::entry("Play next", icons::PLAY_NEXT, {
+            let id = song.id.clone();
+            move |_, cx| {
+                cx.update_global::<Queue, _>(|queue, _| {
+                    queue.add_song_next(id.clone());
+                });
+                cx.set_global(QueueChanged);
+            }
+        }),
+        ContextMenuItem::entry("Info", icons::PROPERTIES, {
+            let song = song.clone();
+            move |window, cx| show_song_info(song.clone(), window, cx)
+        }),
+        ContextMenuItem::entry("Show in folder", icons::FOLDER, {
+            let file_path = song.file_path.clone();
+            move |_, cx| {
+                let file_path = file_path.clone();
+                cx.background_executor()
+                    .spawn(async move {
+                        if let Err(e) = reveal_in_file_manager(Path::new(&file_path)) {
+                            error!("{e:#}");
+                        }
+                    })
+                    .detach();
+            }
+        }),
+        ContextMenuItem::separator(),
+        ContextMenuItem::entry("Add to library", icons::PLUS, move |_, cx| {
+            import_transient_song(song.clone(), cx);
+        })
+        .mutating(),
+    ]
+}
+
+fn show_song_info(song: Song, window: &mut Window, cx: &mut App) {
+    if let Some(Some(root)) = window.root::<MainWindow>() {
+        root.update(cx, |view, cx| view.show_song_info(song, cx));
+    }
+}
+
+fn import_transient_song(song: Song, cx: &mut App) {
+    let scanner = cx.global::<Scanner>().clone();
+    let db = cx.global::<Database>().clone();
+    let organize = cx.global::<Config>().get().organize.clone();
+    let bg = cx.background_executor().clone();
+
+    cx.spawn(async move |cx| {
+        let path = std::path::PathBuf::from(&song.file_path);
+        let imported = bg
+            .spawn(async move {
+                let placed = scanner.import_files(&db, vec![path], &organize).await?;
+                let Some(placed) = placed.first() else {
+                    return Ok(Vec::new());
+                };
+                db.get_songs_by_path(&placed.to_string_lossy())
+            })
+            .await;
+
+        match imported {
+            Ok(imported) if !imported.is_empty() => cx.update(|cx| {
+                cx.update_global::<Queue, _>(|queue, _| {
+                    queue.replace_transient(&song.id, imported);
+                });
+                cx.set_global(QueueChanged);
+            }),
+            Ok(_) => error!("Import of {} added no song", song.file_path),
+            Err(e) => error!("Import of {} failed: {e}", song.file_path),
+        }
+    })
+    .detach();
+}
+
+/// Menu for several selected songs. Each toggle unsets the flag when every song already has it.
+pub fn selected_songs_context_menu_items(ids: Vec<Cuid>, cx: &App) -> Vec<ContextMenuItem> {
+    let db = cx.global::<Database>().clone();
+    let (all_favorite, all_pinned) = db.songs_all_flagged(&ids).unwrap_or((false, false));
+
+    let fav_label = if all_favorite {
+        "Unfavorite all"
+    } else {
+        "Favorite all"
+    };
+    let fav_icon = if all_favorite {
+        icons::UNFAVORITE
+    } else {
+        icons::FAVORITE
+    };
+    let pin_label = if all_pinned { "Unpin all" } else { "Pin all" };
+    let pin_icon = if all_pinned { icons::UNPIN } else { icons::PIN };
+
+    vec![
+        ContextMenuItem::entry(fav_label, fav_icon, {
+            let ids = ids.clone();
+            move |_, cx| {
+                let ids = &ids;
+                write_and_notify(cx, move |db| {
+                    if let Err(e) = db.set_favorite_many::<Song>(ids, !all_favorite) {
+                        error!("set_favorite_many songs failed: {e}");
+                    }
+                });
+            }
+        })
+        .mutating(),
+        ContextMenuItem::entry(pin_label, pin_icon, {
+            let ids = ids.clone();
+            move |_, cx| {
+                let ids = &ids;
+                write_and_notify_pinned(cx, move |db| {
+                    if let Err(e) = db.set_pinned_many::<Song>(ids, !all_pinned) {
+                        error!("set_pinned_many songs failed: {e}");
+                    }
+                });
+            }
+        })
+        .mutating(),
+        ContextMenuItem::separator(),
+        ContextMenuItem::destructive("Remove from library", icons::TRASH, {
+            let ids = ids.clone();
+            move |_, cx| {
+                let ids = &ids;
+                write_and_notify(cx, move |db| {
+                    if let Err(e) = db.delete_songs(ids) {
+                        error!("delete_songs failed: {e}");
+                    }
+                });
+            }
+        })
+        .mutating(),
+        ContextMenuItem::destructive("Delete files", icons::TRASH, move |window, cx| {
+            trash_song_files(ids.clone(), window, cx);
+        })
+        .mutating(),
+    ]
+}
+
+/// Moves the songs' files to the trash after confirming, then drops them from the library.
+/// A file split by a cue sheet takes every track cut from it along.
+fn trash_song_files(ids: Vec<Cuid>, window: &mut Window, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    let songs: Vec<Song> = ids
+        .iter()
+        .filter_map(|id| db.get_song(id).ok().flatten())
+        .collect();
+    let mut files: Vec<(String, Vec<Cuid>)> = Vec::new();
+    for song in &songs {
+        if files.iter().any(|(path, _)| *path == song.file_path) {
+            continue;
+        }
+        let segments = match db.get_song_segments(&song.file_path) {
+            Ok(segments) => segments.into_iter().map(|(id, _)| id).collect(),
+            Err(_) => vec![song.id.clone()],
+        };
+        files.push((song.file_path.clone(), segments));
+    }
+    if files.is_empty() {
+        return;
+    }
+
+    let message = match files.len() {
+        1 => "Move this file to the trash?".to_string(),
+        n => format!("Move {n} files to the trash?"),
+    };
+    let siblings = files
+        .iter()
+        .map(|(_, ids)| ids.len())
+        .sum::<usize>()
+        .saturating_sub(songs.len());
+    let detail = match siblings {
+        0 => "They'll also be removed from the library.".to_string(),
+        1 => "They'll also be removed from the library, along with 1 other track cut from \
+              the same file by a cue sheet."
+            .to_string(),
+        n => format!(
+            "They'll also be removed from the library, along with {n} other tracks cut from \
+             the same files by cue sheets."
+        ),
+    };
+    let answer = window.prompt(
+        PromptLevel::Warning,
+        &message,
+        Some(&detail),
+        &["Move to Trash", "Cancel"],
+        cx,
+    );
+    let background_ui = cx.global::<BackgroundUiNotifier>().clone();
+    cx.background_executor()
+        .spawn(async move {
+            if answer.await != Ok(0) {
+                return;
+            }
+            let mut trashed = Vec::new();
+            for (path, segments) in files {
+                match trash(std::path::Path::new(&path)) {
+                    Ok(()) => trashed.extend(segments),
+                    Err(e) => error!("{e:#}"),
+                }
+            }
+            if let Err(e) = db.delete_songs(&trashed) {
+                error!("delete_songs failed: {e}");
+            }
+            background_ui.notify(BackgroundUiEvent::LibraryDataChanged);
+        })
+        .detach();
+}
+
 pub fn song_context_menu_items(song_id: Cuid, cx: &App) -> Vec<ContextMenuItem> {
+    if let Some(song) = cx.global::<Queue>().transient_song(&song_id) {
+        return transient_song_context_menu_items(song);
+    }
+
     let db = cx.global::<Database>().clone();
     let song = db.get_song(&song_id).ok().flatten();
+    let artist_id = song
+        .as_ref()
+        .and_then(|s| s.artists.first())
+        .and_then(|name| db.get_artist_by_name(name).ok().flatten())
+        .map(|artist| artist.id);
     let (favorite, pinned, album_id) = song
-        .map(|s| (s.favorite, s.pinned, s.album_id))
+        .as_ref()
+        .map(|s| (s.favorite, s.pinned, s.album_id.clone()))
         .unwrap_or((false, false, None));
+    let file_path = song.as_ref().map(|s| s.file_path.clone());
 
     let fav_label = if favorite { "Unfavorite" } else { "Favorite" };
     let fav_icon = if favorite {
@@ -535,17 +819,29 @@ pub fn song_context_menu_items(song_id: Cuid, cx: &App) -> Vec<ContextMenuItem>
                 cx.set_global(QueueChanged);
             }
         }),
+        ContextMenuItem::entry("Start radio", icons::QUEUE, {
+            let id = song_id.clone();
+            move |_, cx| start_radio(id.clone(), cx)
+        }),
         ContextMenuItem::separator(),
         ContextMenuItem::with_submenu("Add to Playlist", icons::PLAYLIST, playlists, {
             let song_id = song_id.clone();
             move |playlist_id, cx| {
                 let db = cx.global::<Database>().clone();
+                if db
+                    .playlist_contains(&playlist_id, &song_id)
+                    .unwrap_or(false)
+                {
+                    show_playlist_notice("Already in this playlist".to_string(), cx);
+                    return;
+                }
                 if let Err(e) = db.upsert_playlist_song(&playlist_id, &song_id) {
                     error!("append_song_to_playlist failed: {e}");
                 }
                 cx.set_global(LibraryDataChanged);
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::separator(),
         ContextMenuItem::entry(fav_label, fav_icon, {
             let id = song_id.clone();
@@ -557,7 +853,8 @@ pub fn song_context_menu_items(song_id: Cuid, cx: &App) -> Vec<ContextMenuItem>
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::entry(pin_label, pin_icon, {
             let id = song_id.clone();
             move |_, cx| {
@@ -568,9 +865,19 @@ pub fn song_context_menu_items(song_id: Cuid, cx: &App) -> Vec<ContextMenuItem>
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::separator(),
-        ContextMenuItem::entry("Go to artist", icons::ARTIST, move |_, _| {}),
+        ContextMenuItem::entry("Go to artist", icons::ARTIST, move |window, cx| {
+            if let Some(artist_id) = &artist_id {
+                cx.set_global(SelectedArtist(Some(artist_id.clone())));
+                if let Some(Some(root)) = window.root::<MainWindow>() {
+                    root.update(cx, |view, cx| {
+                        view.set_current_view(AppView::Artist, window, cx);
+                    });
+                }
+            }
+        }),
         ContextMenuItem::entry("Go to album", icons::ALBUM, {
             let id = album_id.clone();
             move |window, cx| {
@@ -584,8 +891,22 @@ pub fn song_context_menu_items(song_id: Cuid, cx: &App) -> Vec<ContextMenuItem>
                 }
             }
         }),
-        // to be implemented
-        // ContextMenuItem::entry("Properties", icons::PROPERTIES, move |_, _| {}),
+        ContextMenuItem::entry("Info", icons::PROPERTIES, move |window, cx| {
+            if let Some(song) = &song {
+                show_song_info(song.clone(), window, cx);
+            }
+        }),
+        ContextMenuItem::entry("Show in folder", icons::FOLDER, move |_, cx| {
+            if let Some(file_path) = file_path.clone() {
+                cx.background_executor()
+                    .spawn(async move {
+                        if let Err(e) = reveal_in_file_manager(Path::new(&file_path)) {
+                            error!("{e:#}");
+                        }
+                    })
+                    .detach();
+            }
+        }),
         ContextMenuItem::separator(),
         ContextMenuItem::destructive("Remove from library", icons::TRASH, {
             let id = song_id.clone();
@@ -597,7 +918,12 @@ pub fn song_context_menu_items(song_id: Cuid, cx: &App) -> Vec<ContextMenuItem>
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
+        ContextMenuItem::destructive("Delete file", icons::TRASH, move |window, cx| {
+            trash_song_files(vec![song_id.clone()], window, cx);
+        })
+        .mutating(),
     ]
 }
 
@@ -633,16 +959,39 @@ pub fn album_context_menu_items(album_id: Cuid, cx: &App) -> Vec<ContextMenuItem
             let album_id = album_id.clone();
             move |playlist_id, cx| {
                 let db = cx.global::<Database>().clone();
+                let mut already_present = 0;
                 if let Ok(songs) = db.get_album_songs(&album_id) {
                     for song in &songs {
+                        if db
+                            .playlist_contains(&playlist_id, &song.id)
+                            .unwrap_or(false)
+                        {
+                            already_present += 1;
+                            continue;
+                        }
                         if let Err(e) = db.upsert_playlist_song(&playlist_id, &song.id) {
                             error!("upsert_playlist_song failed: {e}");
                         }
                     }
                 }
+                if already_present > 0 {
+                    show_playlist_notice(
+                        format!(
+                            "{} song{} already in this playlist",
+                            already_present,
+                            if already_present == 1 {
+                                " was"
+                            } else {
+                                "s were"
+                            }
+                        ),
+                        cx,
+                    );
+                }
                 cx.set_global(LibraryDataChanged);
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::separator(),
         ContextMenuItem::entry(fav_label, fav_icon, {
             let id = album_id.clone();
@@ -654,7 +1003,8 @@ pub fn album_context_menu_items(album_id: Cuid, cx: &App) -> Vec<ContextMenuItem
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::entry(pin_label, pin_icon, {
             let id = album_id.clone();
             move |_, cx| {
@@ -665,7 +1015,8 @@ pub fn album_context_menu_items(album_id: Cuid, cx: &App) -> Vec<ContextMenuItem
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::separator(),
         ContextMenuItem::entry("Go to artist", icons::ARTIST, move |_, _| {}),
         // to be implemented
@@ -680,7 +1031,8 @@ pub fn album_context_menu_items(album_id: Cuid, cx: &App) -> Vec<ContextMenuItem
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
     ]
 }
 
@@ -701,6 +1053,10 @@ pub fn artist_context_menu_items(artist_id: Cuid, cx: &App) -> Vec<ContextMenuIt
 
     vec![
         ContextMenuItem::entry("Play all songs", icons::PLAY, move |_, _| {}),
+        ContextMenuItem::entry("Start radio", icons::QUEUE, {
+            let id = artist_id.clone();
+            move |_, cx| start_artist_radio(id.clone(), cx)
+        }),
         ContextMenuItem::separator(),
         ContextMenuItem::entry(fav_label, fav_icon, {
             let id = artist_id.clone();
@@ -712,7 +1068,8 @@ pub fn artist_context_menu_items(artist_id: Cuid, cx: &App) -> Vec<ContextMenuIt
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::entry(pin_label, pin_icon, {
             move |_, cx| {
                 let id = &artist_id;
@@ -722,7 +1079,8 @@ pub fn artist_context_menu_items(artist_id: Cuid, cx: &App) -> Vec<ContextMenuIt
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::separator(),
         ContextMenuItem::entry("Go to albums", icons::ALBUM, move |_, _| {}),
         // to be implemented
@@ -758,7 +1116,8 @@ pub fn playlist_context_menu_items(playlist_id: Cuid, cx: &App) -> Vec<ContextMe
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         ContextMenuItem::separator(),
         ContextMenuItem::entry("Clear playlist", icons::X, {
             let id = playlist_id.clone();
@@ -770,7 +1129,8 @@ pub fn playlist_context_menu_items(playlist_id: Cuid, cx: &App) -> Vec<ContextMe
                     }
                 });
             }
-        }),
+        })
+        .mutating(),
         // to be implemented
         // ContextMenuItem::entry("Properties", icons::PROPERTIES, move |_, _| {}),
         ContextMenuItem::separator(),
@@ -792,6 +1152,7 @@ pub fn playlist_context_menu_items(playlist_id: Cuid, cx: &App) -> Vec<ContextMe
                     }
                 }
             }
-        }),
+        })
+        .mutating(),
     ]
 }
@@ -0,0 +1,65 @@
+use gpui::{prelude::FluentBuilder as _, *};
+
+use crate::ui::{assets::fallback_artwork, variables::Variables};
+
+/// The asset URI for an image stored in the library database.
+pub fn image_uri(image_id: &str) -> String {
+    format!("!image://{}", image_id)
+}
+
+/// Artwork filling its parent. Falls back from the source to the configured fallback artwork
+/// to a plain placeholder, both when there is no source and when it fails to load, so a
+/// missing cover looks the same everywhere. Round covers (artists) skip the fallback artwork.
+#[derive(IntoElement)]
+pub struct Cover {
+    source: Option<String>,
+    rounded: bool,
+}
+
+impl Cover {
+    pub fn new(source: Option<String>) -> Self {
+        Self {
+            source,
+            rounded: false,
+        }
+    }
+
+    pub fn rounded(mut self, rounded: bool) -> Self {
+        self.rounded = rounded;
+        self
+    }
+}
+
+impl RenderOnce for Cover {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let Cover { source, rounded } = self;
+        let placeholder_color = cx.global::<Variables>().border;
+        let fallback_source = if rounded { None } else { fallback_artwork(cx) };
+
+        let placeholder = move || {
+            div()
+                .size_full()
+                .bg(placeholder_color)
+                .when(rounded, |this| this.rounded_full())
+                .into_any_element()
+        };
+        let fallback = move || match fallback_source.clone() {
+            Some(source) => img(source)
+                .size_full()
+                .object_fit(ObjectFit::Cover)
+                .with_fallback(placeholder)
+                .into_any_element(),
+            None => placeholder(),
+        };
+
+        match source {
+            Some(source) => img(source)
+                .size_full()
+                .object_fit(ObjectFit::Cover)
+                .when(rounded, |this| this.rounded_full())
+                .with_fallback(fallback)
+                .into_any_element(),
+            None => fallback(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use gpui::*;
+
+use crate::ui::variables::Variables;
+
+pub trait FocusRing: InteractiveElement + Sized {
+    /// Draws a ring around the element while it holds keyboard focus. The ring is a
+    /// spread shadow so it doesn't shift layout.
+    fn focus_ring(self, variables: &Variables) -> Self {
+        let color = variables.focus_ring;
+        self.focus_visible(move |s| {
+            s.shadow(vec![BoxShadow {
+                color: color.into(),
+                offset: point(px(0.0), px(0.0)),
+                blur_radius: px(0.0),
+                spread_radius: px(2.0),
+            }])
+        })
+    }
+
+    /// Puts the element in the Tab order, in document order, and rings it while focused.
+    fn keyboard_focusable(self, variables: &Variables) -> Self {
+        self.tab_index(0).focus_ring(variables)
+    }
+}
+
+impl<E: InteractiveElement> FocusRing for E {}
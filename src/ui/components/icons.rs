@@ -32,12 +32,13 @@ pub const X: &str = "!bundled:icons/x.svg";
 pub const MAXIMIZE: &str = "!bundled:icons/maximize.svg";
 pub const UNMAXIMIZE: &str = "!bundled:icons/unmaximize.svg";
 pub const MINIMIZE: &str = "!bundled:icons/minimize.svg";
-// pub const PROPERTIES: &str = "!bundled:icons/properties.svg";
+pub const PROPERTIES: &str = "!bundled:icons/properties.svg";
 pub const TRASH: &str = "!bundled:icons/trash.svg";
 pub const PLAY_NEXT: &str = "!bundled:icons/play-next.svg";
 pub const PLAY_LAST: &str = "!bundled:icons/play-last.svg";
 pub const DOTS: &str = "!bundled:icons/dots.svg";
 pub const LINK: &str = "!bundled:icons/link.svg";
+pub const FOLDER: &str = "!bundled:icons/folder.svg";
 
 use gpui::*;
 
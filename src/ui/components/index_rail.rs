@@ -0,0 +1,86 @@
+use gpui::{prelude::FluentBuilder as _, *};
+use std::rc::Rc;
+
+use crate::ui::{components::div::flex_col, variables::Variables};
+
+/// Lists shorter than this scroll fine without a rail.
+pub const MIN_INDEXED_ITEMS: usize = 200;
+
+const LETTERS: &str = "#ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+pub type OnJumpHandler = Rc<dyn Fn(usize, &mut Window, &mut App) + 'static>;
+
+fn index_letter(key: &str) -> char {
+    match key.trim_start().chars().next() {
+        Some(c) if c.is_ascii_alphabetic() => c.to_ascii_uppercase(),
+        _ => '#',
+    }
+}
+
+/// First position of every index letter in `keys`, which must already be in list order.
+pub fn letter_offsets<'a>(keys: impl IntoIterator<Item = &'a str>) -> Vec<(char, usize)> {
+    let mut offsets: Vec<(char, usize)> = Vec::new();
+    for (idx, key) in keys.into_iter().enumerate() {
+        let letter = index_letter(key);
+        if !offsets.iter().any(|(l, _)| *l == letter) {
+            offsets.push((letter, idx));
+        }
+    }
+    offsets
+}
+
+#[derive(IntoElement)]
+pub struct IndexRail {
+    id: SharedString,
+    offsets: Vec<(char, usize)>,
+    on_jump: OnJumpHandler,
+}
+
+impl IndexRail {
+    pub fn new(
+        id: impl Into<SharedString>,
+        offsets: Vec<(char, usize)>,
+        on_jump: impl Fn(usize, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            offsets,
+            on_jump: Rc::new(on_jump),
+        }
+    }
+}
+
+impl RenderOnce for IndexRail {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let id = self.id;
+
+        flex_col()
+            .id(ElementId::Name(id.clone()))
+            .h_full()
+            .flex_shrink_0()
+            .justify_center()
+            .items_center()
+            .text_xs()
+            .children(LETTERS.chars().map(|letter| {
+                let offset = self
+                    .offsets
+                    .iter()
+                    .find(|(l, _)| *l == letter)
+                    .map(|(_, offset)| *offset);
+                let on_jump = self.on_jump.clone();
+
+                div()
+                    .id(ElementId::Name(format!("{}-{}", id, letter).into()))
+                    .px(px(variables.padding_8))
+                    .text_color(variables.text_secondary)
+                    .child(letter.to_string())
+                    .when_some(offset, |div, offset| {
+                        div.cursor_pointer()
+                            .hover(|s| s.text_color(variables.text))
+                            .on_click(move |_, window, cx| on_jump(offset, window, cx))
+                    })
+                    .when(offset.is_none(), |div| div.opacity(0.4))
+            }))
+    }
+}
@@ -14,13 +14,134 @@ use std::time::Instant;
 use unicode_segmentation::*;
 
 use crate::ui::{
-    components::{div::flex_row, icons::icon},
+    bidi,
+    components::{div::flex_row, focus::FocusRing, icons::icon},
     global_actions::PlayPause,
     variables::Variables,
 };
 
 pub type Validator = Rc<RefCell<dyn Fn(&str) -> bool>>;
 
+/// What happens to line breaks in pasted text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PastePolicy {
+    /// Lines are joined with spaces.
+    #[default]
+    Join,
+    /// Line breaks are dropped.
+    Strip,
+    /// Only the first non-empty line is kept.
+    FirstLine,
+}
+
+impl PastePolicy {
+    fn apply(self, text: &str) -> String {
+        match self {
+            PastePolicy::Join => text.lines().collect::<Vec<_>>().join(" "),
+            PastePolicy::Strip => text.lines().collect(),
+            PastePolicy::FirstLine => text
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+}
+
+// Every shaped cluster in visual order as (byte index, left, right). Right-to-left runs come
+// back from the shaper laid out visually, so x doesn't grow with the index inside them.
+fn visual_clusters(line: &ShapedLine) -> Vec<(usize, Pixels, Pixels)> {
+    let mut glyphs: Vec<(usize, Pixels)> = line
+        .runs
+        .iter()
+        .flat_map(|run| run.glyphs.iter().map(|g| (g.index, g.position.x)))
+        .collect();
+    glyphs.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let mut clusters: Vec<(usize, Pixels, Pixels)> = Vec::with_capacity(glyphs.len());
+    for (i, &(index, left)) in glyphs.iter().enumerate() {
+        let right = glyphs.get(i + 1).map_or(line.width, |g| g.1);
+        match clusters.iter_mut().find(|c| c.0 == index) {
+            Some(cluster) => {
+                cluster.1 = cluster.1.min(left);
+                cluster.2 = cluster.2.max(right);
+            }
+            None => clusters.push((index, left, right)),
+        }
+    }
+    clusters
+}
+
+fn rtl_at(text: &str, index: usize) -> bool {
+    text.get(index..)
+        .and_then(|rest| rest.chars().next())
+        .is_some_and(bidi::is_rtl)
+}
+
+// Caret position before the character at `index`, which is its right edge in an RTL run.
+fn x_for_index(line: &ShapedLine, index: usize) -> Pixels {
+    let text = line.text.as_str();
+    if !bidi::has_rtl(text) {
+        return line.x_for_index(index);
+    }
+    let clusters = visual_clusters(line);
+    if let Some(&(start, left, right)) = clusters.iter().find(|c| c.0 == index) {
+        return if rtl_at(text, start) { right } else { left };
+    }
+    match clusters.iter().filter(|c| c.0 < index).max_by_key(|c| c.0) {
+        Some(&(start, left, right)) => {
+            if rtl_at(text, start) {
+                left
+            } else {
+                right
+            }
+        }
+        None => px(0.0),
+    }
+}
+
+fn index_for_x(line: &ShapedLine, x: Pixels) -> usize {
+    let text = line.text.as_str();
+    if !bidi::has_rtl(text) {
+        return line.closest_index_for_x(x);
+    }
+    let clusters = visual_clusters(line);
+    let Some(&(start, left, right)) = clusters.iter().find(|c| x < c.2).or(clusters.last()) else {
+        return 0;
+    };
+    let end = clusters
+        .iter()
+        .map(|c| c.0)
+        .filter(|&i| i > start)
+        .min()
+        .unwrap_or(text.len());
+    let before_middle = x < (left + right) / 2.0;
+    if before_middle != rtl_at(text, start) {
+        start
+    } else {
+        end
+    }
+}
+
+// A selection crossing a direction change is split into several visual spans.
+fn selection_spans(line: &ShapedLine, range: &Range<usize>) -> Vec<Range<Pixels>> {
+    let text = line.text.as_str();
+    if !bidi::has_rtl(text) {
+        return vec![line.x_for_index(range.start)..line.x_for_index(range.end)];
+    }
+    let mut spans: Vec<Range<Pixels>> = Vec::new();
+    for (_, left, right) in visual_clusters(line)
+        .into_iter()
+        .filter(|c| range.contains(&c.0))
+    {
+        match spans.last_mut() {
+            Some(span) if left <= span.end + px(0.5) => span.end = span.end.max(right),
+            _ => spans.push(left..right),
+        }
+    }
+    spans
+}
+
 actions!(
     input,
     [
@@ -84,6 +205,8 @@ pub fn bind_input_keys(cx: &mut App) {
 pub struct TextInput {
     focus_handle: FocusHandle,
     content: SharedString,
+    // Content as of the last Change event, or as last set from code.
+    reported_content: SharedString,
     placeholder: SharedString,
     selected_range: Range<usize>,
     selection_reversed: bool,
@@ -101,6 +224,7 @@ pub struct TextInput {
     custom_height: Option<Pixels>,
     custom_padding_x: Option<Pixels>,
     validator: Option<Validator>,
+    paste_policy: PastePolicy,
     last_click_time: Option<Instant>,
     last_click_position: Point<Pixels>,
     click_count: u8,
@@ -109,8 +233,9 @@ pub struct TextInput {
 impl TextInput {
     pub fn new(cx: &mut Context<Self>, placeholder: impl Into<SharedString>) -> Self {
         Self {
-            focus_handle: cx.focus_handle(),
+            focus_handle: cx.focus_handle().tab_stop(true),
             content: "".into(),
+            reported_content: "".into(),
             placeholder: placeholder.into(),
             selected_range: 0..0,
             selection_reversed: false,
@@ -128,6 +253,7 @@ impl TextInput {
             custom_height: None,
             custom_padding_x: None,
             validator: None,
+            paste_policy: PastePolicy::default(),
             last_click_time: None,
             last_click_position: Point::new(px(0.0), px(0.0)),
             click_count: 0,
@@ -169,9 +295,15 @@ impl TextInput {
         self
     }
 
+    pub fn with_paste_policy(mut self, policy: PastePolicy) -> Self {
+        self.paste_policy = policy;
+        self
+    }
+
     pub fn with_text(mut self, text: impl Into<SharedString>) -> Self {
         let text: SharedString = text.into();
         self.selected_range = 0..text.len();
+        self.reported_content = text.clone();
         self.content = text;
         self.selected_range = self.content.len()..self.content.len();
         self
@@ -179,12 +311,19 @@ impl TextInput {
 
     pub fn set_text(&mut self, text: impl Into<SharedString>, cx: &mut Context<Self>) {
         let text: SharedString = text.into();
+        self.reported_content = text.clone();
         self.content = text;
         self.selected_range = self.content.len()..self.content.len();
         self.marked_range = None;
         cx.notify();
     }
 
+    /// Focuses the input with its whole content selected, so typing replaces it.
+    pub fn focus_and_select_all(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.focus_handle.focus(window, cx);
+        self.select_all_internal(cx);
+    }
+
     fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
         if self.selected_range.is_empty() {
             self.move_to(self.previous_boundary(self.cursor_offset()), cx);
@@ -342,7 +481,8 @@ impl TextInput {
 
     fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
-            self.replace_text_in_range(None, &text.replace("\n", " "), window, cx);
+            let text = self.paste_policy.apply(&text);
+            self.replace_text_in_range(None, &text, window, cx);
         }
     }
 
@@ -404,7 +544,10 @@ impl TextInput {
             return self.content.len();
         }
         let x_offset = self.horizontal_text_offset(bounds.size.width, line.width);
-        line.closest_index_for_x(position.x - bounds.left() + self.scroll_offset - x_offset)
+        index_for_x(
+            line,
+            position.x - bounds.left() + self.scroll_offset - x_offset,
+        )
     }
 
     fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
@@ -455,6 +598,16 @@ impl TextInput {
         self.offset_from_utf16(range_utf16.start)..self.offset_from_utf16(range_utf16.end)
     }
 
+    // Composition updates are held back until the text is committed, so an IME doesn't trigger
+    // a search for every intermediate character.
+    fn report_change(&mut self, cx: &mut Context<Self>) {
+        if self.marked_range.is_some() || self.content == self.reported_content {
+            return;
+        }
+        self.reported_content = self.content.clone();
+        cx.emit(InputEvent::Change(self.content.to_string()));
+    }
+
     fn previous_boundary(&self, offset: usize) -> usize {
         self.content
             .grapheme_indices(true)
@@ -529,8 +682,9 @@ impl EntityInputHandler for TextInput {
             .map(|range| self.range_to_utf16(range))
     }
 
-    fn unmark_text(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
+    fn unmark_text(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
         self.marked_range = None;
+        self.report_change(cx);
     }
 
     fn replace_text_in_range(
@@ -564,7 +718,7 @@ impl EntityInputHandler for TextInput {
         self.selected_range = range.start + new_text.len()..range.start + new_text.len();
         self.marked_range.take();
         self.blink_start = Instant::now();
-        cx.emit(InputEvent::Change(self.content.to_string()));
+        self.report_change(cx);
         cx.notify();
     }
 
@@ -595,7 +749,7 @@ impl EntityInputHandler for TextInput {
             .map(|new_range| new_range.start + range.start..new_range.end + range.end)
             .unwrap_or_else(|| range.start + new_text.len()..range.start + new_text.len());
         self.blink_start = Instant::now();
-        cx.emit(InputEvent::Change(self.content.to_string()));
+        self.report_change(cx);
         cx.notify();
     }
 
@@ -610,15 +764,11 @@ impl EntityInputHandler for TextInput {
         let x_offset = self.horizontal_text_offset(bounds.size.width, last_layout.width);
         let origin_x = bounds.left() - self.scroll_offset + x_offset;
         let range = self.range_from_utf16(&range_utf16);
+        let start_x = x_for_index(last_layout, range.start);
+        let end_x = x_for_index(last_layout, range.end);
         Some(Bounds::from_corners(
-            point(
-                origin_x + last_layout.x_for_index(range.start),
-                bounds.top(),
-            ),
-            point(
-                origin_x + last_layout.x_for_index(range.end),
-                bounds.bottom(),
-            ),
+            point(origin_x + start_x.min(end_x), bounds.top()),
+            point(origin_x + start_x.max(end_x), bounds.bottom()),
         ))
     }
 
@@ -664,6 +814,7 @@ impl Render for TextInput {
             .flex_shrink_0()
             .key_context("TextInput")
             .track_focus(&focus)
+            .focus_ring(variables)
             .cursor(CursorStyle::IBeam)
             .on_action(cx.listener(Self::backspace))
             .on_action(cx.listener(Self::space))
@@ -716,7 +867,7 @@ struct TextElement {
 struct PrepaintState {
     line: Option<ShapedLine>,
     cursor: Option<PaintQuad>,
-    selection: Option<PaintQuad>,
+    selection: Vec<PaintQuad>,
     scroll_offset: Pixels,
     x_offset: Pixels,
     y_offset: Pixels,
@@ -807,7 +958,7 @@ impl Element for TextElement {
         let y_offset = (bounds.size.height - line_height) / 2.0;
 
         let mut scroll_offset = input.scroll_offset;
-        let cursor_x = line.x_for_index(cursor_index);
+        let cursor_x = x_for_index(&line, cursor_index);
         let width = bounds.size.width;
         let x_offset = if self.centered {
             ((width - line.width).max(px(0.0))) / 2.0
@@ -831,7 +982,7 @@ impl Element for TextElement {
 
         let (selection, cursor) = if selected_range.is_empty() {
             (
-                None,
+                Vec::new(),
                 Some(fill(
                     Bounds::new(
                         point(bounds.left() + cursor_pos, bounds.top() + y_offset),
@@ -841,18 +992,20 @@ impl Element for TextElement {
                 )),
             )
         } else {
-            let start_x = line.x_for_index(selected_range.start) - scroll_offset + x_offset;
-            let end_x = line.x_for_index(selected_range.end) - scroll_offset + x_offset;
-            (
-                Some(fill(
-                    Bounds::from_corners(
-                        point(bounds.left() + start_x, bounds.top() + y_offset),
-                        point(bounds.left() + end_x, bounds.bottom() - y_offset),
-                    ),
-                    rgba(0xA058FF4F),
-                )),
-                None,
-            )
+            let origin_x = bounds.left() - scroll_offset + x_offset;
+            let selection = selection_spans(&line, &selected_range)
+                .into_iter()
+                .map(|span| {
+                    fill(
+                        Bounds::from_corners(
+                            point(origin_x + span.start, bounds.top() + y_offset),
+                            point(origin_x + span.end, bounds.bottom() - y_offset),
+                        ),
+                        rgba(0xA058FF4F),
+                    )
+                })
+                .collect();
+            (selection, None)
         };
 
         PrepaintState {
@@ -886,10 +1039,10 @@ impl Element for TextElement {
         );
 
         window.with_content_mask(Some(ContentMask { bounds }), |window| {
-            if self.is_focused
-                && let Some(selection) = prepaint.selection.take()
-            {
-                window.paint_quad(selection);
+            if self.is_focused {
+                for selection in prepaint.selection.drain(..) {
+                    window.paint_quad(selection);
+                }
             }
 
             if let Some(line) = prepaint.line.as_ref() {
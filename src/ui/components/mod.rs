@@ -1,14 +1,18 @@
 pub mod button;
 pub mod card;
 pub mod context_menu;
+pub mod cover;
 pub mod div;
+pub mod focus;
 pub mod icons;
+pub mod index_rail;
 pub mod input;
 pub mod nav_button;
 pub mod pane;
 pub mod progress_bar;
 pub mod scrollbar;
 pub mod slider;
+pub mod song_info;
 pub mod song_table;
 pub mod switch;
 pub mod window_controls;
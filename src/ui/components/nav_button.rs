@@ -1,6 +1,6 @@
 use crate::ui::{
     app::MainWindow,
-    components::{div::flex_row, icons::icon},
+    components::{div::flex_row, focus::FocusRing, icons::icon},
     variables::Variables,
     views::AppView,
 };
@@ -52,7 +52,7 @@ impl RenderOnce for NavButton {
         let group_name = if let Some(ref txt) = label {
             format!("nav_btn_{}", txt)
         } else {
-            "nav_btn_icon_only".to_string()
+            format!("nav_btn_{:?}", target_view)
         };
 
         flex_row()
@@ -79,6 +79,7 @@ impl RenderOnce for NavButton {
                     this
                 }
             })
+            .keyboard_focusable(variables)
             .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
                 if let Some(Some(root)) = window.root::<MainWindow>() {
                     root.update(cx, |view, cx| {
@@ -86,5 +87,15 @@ impl RenderOnce for NavButton {
                     });
                 }
             })
+            .on_click(move |event, window, cx| {
+                if !matches!(event, ClickEvent::Keyboard(_)) {
+                    return;
+                }
+                if let Some(Some(root)) = window.root::<MainWindow>() {
+                    root.update(cx, |view, cx| {
+                        view.set_current_view(target_view, window, cx);
+                    });
+                }
+            })
     }
 }
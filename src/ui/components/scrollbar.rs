@@ -1,13 +1,70 @@
 use gpui::prelude::FluentBuilder;
 use gpui::*;
-use std::{cell::Cell, ops::Deref, panic::Location, rc::Rc, time::Instant};
+use std::{
+    cell::Cell,
+    ops::Deref,
+    panic::Location,
+    rc::Rc,
+    sync::OnceLock,
+    time::{Duration, Instant},
+};
 
+use crate::data::config::{Config, ScrollbarClick, ScrollbarVisibility};
 use crate::ui::variables::Variables;
 
 const DEFAULT_WIDTH: Pixels = px(16.);
 const THUMB_WIDTH: Pixels = px(4.);
 const MIN_THUMB_SIZE: f32 = 48.;
 const FADE_OUT_DURATION: f32 = 3.0;
+const PAGE_REPEAT_DELAY: Duration = Duration::from_millis(400);
+const PAGE_REPEAT_INTERVAL: Duration = Duration::from_millis(60);
+const MOMENTUM_FRAME: Duration = Duration::from_millis(16);
+// Share of a wheel step that keeps going afterwards, and how much of it is left each frame.
+const MOMENTUM_CARRY: f32 = 0.15;
+const MOMENTUM_DECAY: f32 = 0.9;
+const MOMENTUM_STOP: Pixels = px(0.5);
+
+fn always_visible(cx: &App) -> bool {
+    match cx.global::<Config>().get().interface.scrollbar_visibility {
+        ScrollbarVisibility::System => {
+            static SYSTEM: OnceLock<bool> = OnceLock::new();
+            *SYSTEM.get_or_init(platform::always_show_scrollbars)
+        }
+        ScrollbarVisibility::AutoHide => false,
+        ScrollbarVisibility::Always => true,
+    }
+}
+
+// "Show scroll bars: Always" in System Settings.
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2_foundation::{NSUserDefaults, ns_string};
+
+    pub fn always_show_scrollbars() -> bool {
+        NSUserDefaults::standardUserDefaults()
+            .stringForKey(ns_string!("AppleShowScrollBars"))
+            .is_some_and(|value| value.to_string() == "Always")
+    }
+}
+
+// "Always show scrollbars" in the accessibility settings.
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows::UI::ViewManagement::UISettings;
+
+    pub fn always_show_scrollbars() -> bool {
+        UISettings::new()
+            .and_then(|settings| settings.AutoHideScrollBars())
+            .is_ok_and(|auto_hide| !auto_hide)
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn always_show_scrollbars() -> bool {
+        false
+    }
+}
 
 pub trait AxisExt {
     fn is_vertical(&self) -> bool;
@@ -98,6 +155,10 @@ struct ScrollbarStateInner {
     hovered_on_thumb: Option<Axis>,
     dragged_axis: Option<Axis>,
     drag_pos: Point<Pixels>,
+    // Axis and pointer position along the bar while the track is held down.
+    paging: Option<(Axis, Pixels)>,
+    velocity: Point<Pixels>,
+    coasting: bool,
     last_scroll_offset: Point<Pixels>,
     last_scroll_time: Option<Instant>,
     last_update: Instant,
@@ -109,6 +170,9 @@ impl Default for ScrollbarState {
             hovered_on_thumb: None,
             dragged_axis: None,
             drag_pos: point(px(0.), px(0.)),
+            paging: None,
+            velocity: point(px(0.), px(0.)),
+            coasting: false,
             last_scroll_offset: point(px(0.), px(0.)),
             last_scroll_time: None,
             last_update: Instant::now(),
@@ -135,6 +199,18 @@ impl ScrollbarStateInner {
     fn with_unset_drag_pos(&self) -> Self {
         let mut state = *self;
         state.dragged_axis = None;
+        state.paging = None;
+        state
+    }
+    fn with_velocity(&self, velocity: Point<Pixels>, coasting: bool) -> Self {
+        let mut state = *self;
+        state.velocity = velocity;
+        state.coasting = coasting;
+        state
+    }
+    fn with_paging(&self, axis: Axis, target: Pixels) -> Self {
+        let mut state = *self;
+        state.paging = Some((axis, target));
         state
     }
     fn with_hovered(&self, axis: Option<Axis>) -> Self {
@@ -177,6 +253,103 @@ impl ScrollbarStateInner {
     }
 }
 
+#[derive(Clone, Copy)]
+struct Track {
+    axis: Axis,
+    scroll_size: Pixels,
+    container_size: Pixels,
+    thumb_size: Pixels,
+    margin_end: Pixels,
+}
+
+impl Track {
+    // Scrolls a viewport towards `target`, a position along the bar. Returns false once the
+    // thumb is under it or there is nothing left to scroll.
+    fn page_towards(&self, handle: &dyn ScrollbarHandle, target: Pixels) -> bool {
+        let offset = handle.offset();
+        let current = if self.axis.is_vertical() {
+            offset.y
+        } else {
+            offset.x
+        };
+        let travel = self.container_size - self.margin_end - self.thumb_size;
+        let thumb_start = -(current / (self.scroll_size - self.container_size) * travel);
+        let step = if target < thumb_start {
+            self.container_size
+        } else if target > thumb_start + self.thumb_size {
+            -self.container_size
+        } else {
+            return false;
+        };
+        let next = (current + step).clamp(self.container_size - self.scroll_size, px(0.));
+        if next == current {
+            return false;
+        }
+        handle.set_offset(if self.axis.is_vertical() {
+            point(offset.x, next)
+        } else {
+            point(next, offset.y)
+        });
+        true
+    }
+}
+
+// Keeps a wheel scroll going after the wheel stops, slowing down every frame. Grabbing the thumb
+// or the track stops it.
+fn coast(
+    state: &ScrollbarState,
+    handle: &Rc<dyn ScrollbarHandle>,
+    viewport: Size<Pixels>,
+    push: Point<Pixels>,
+    view_id: EntityId,
+    cx: &mut App,
+) {
+    let inner = state.get();
+    let velocity = point(
+        inner.velocity.x + push.x * MOMENTUM_CARRY,
+        inner.velocity.y + push.y * MOMENTUM_CARRY,
+    );
+    state.set(inner.with_velocity(velocity, true));
+    if inner.coasting {
+        return;
+    }
+
+    let state = state.clone();
+    let handle = handle.clone();
+    cx.spawn(async move |cx: &mut AsyncApp| {
+        loop {
+            cx.background_executor().timer(MOMENTUM_FRAME).await;
+            let inner = state.get();
+            let velocity = inner.velocity;
+            if inner.dragged_axis.is_some()
+                || inner.paging.is_some()
+                || (velocity.x.abs() < MOMENTUM_STOP && velocity.y.abs() < MOMENTUM_STOP)
+            {
+                break;
+            }
+            let content = handle.content_size();
+            let min_x = (viewport.width - content.width).min(px(0.));
+            let min_y = (viewport.height - content.height).min(px(0.));
+            let offset = handle.offset();
+            let next = point(
+                (offset.x + velocity.x).clamp(min_x, px(0.)),
+                (offset.y + velocity.y).clamp(min_y, px(0.)),
+            );
+            if next == offset {
+                break;
+            }
+            handle.set_offset(next);
+            state.set(inner.with_velocity(
+                point(velocity.x * MOMENTUM_DECAY, velocity.y * MOMENTUM_DECAY),
+                true,
+            ));
+            cx.update(|cx| cx.notify(view_id));
+        }
+        state.set(state.get().with_velocity(point(px(0.), px(0.)), false));
+    })
+    .detach();
+}
+
 pub struct Scrollbar {
     id: ElementId,
     axis: ScrollbarAxis,
@@ -226,7 +399,7 @@ impl Scrollbar {
         let is_hovered_bar = state.hovered_axis == Some(axis);
         if is_dragged || is_hovered_thumb {
             (hover_thumb.into(), default_track, THUMB_WIDTH)
-        } else if is_hovered_bar || state.is_scrollbar_visible() {
+        } else if is_hovered_bar || state.is_scrollbar_visible() || always_visible(cx) {
             (default_thumb.into(), default_track, THUMB_WIDTH)
         } else {
             (default_track, default_track, THUMB_WIDTH)
@@ -431,6 +604,29 @@ impl Element for Scrollbar {
                 cx.notify(view_id);
             }
         }
+        if cx.global::<Config>().get().interface.momentum_scroll {
+            window.on_mouse_event({
+                let state = state.clone();
+                let scroll_handle = self.scroll_handle.clone();
+                move |event: &ScrollWheelEvent, phase, window, cx| {
+                    // Trackpads already coast on their own.
+                    if phase.bubble()
+                        && hitbox_bounds.contains(&event.position)
+                        && matches!(event.delta, ScrollDelta::Lines(_))
+                    {
+                        let push = event.delta.pixel_delta(window.line_height());
+                        coast(
+                            &state,
+                            &scroll_handle,
+                            hitbox_bounds.size,
+                            push,
+                            view_id,
+                            cx,
+                        );
+                    }
+                }
+            });
+        }
         window.with_content_mask(
             Some(ContentMask {
                 bounds: hitbox_bounds,
@@ -466,6 +662,13 @@ impl Element for Scrollbar {
                         }
                     });
                     let safe_range = (-scroll_area_size + container_size)..px(0.);
+                    let track = Track {
+                        axis,
+                        scroll_size: scroll_area_size,
+                        container_size,
+                        thumb_size,
+                        margin_end,
+                    };
 
                     window.on_mouse_event({
                         let state = state.clone();
@@ -479,6 +682,40 @@ impl Element for Scrollbar {
                                     scroll_handle.start_drag();
                                     state.set(state.get().with_drag_pos(axis, pos));
                                     cx.notify(view_id);
+                                } else if cx.global::<Config>().get().interface.scrollbar_click
+                                    == ScrollbarClick::Page
+                                {
+                                    let target = if axis.is_vertical() {
+                                        event.position.y - bounds.origin.y
+                                    } else {
+                                        event.position.x - bounds.origin.x
+                                    };
+                                    state.set(state.get().with_paging(axis, target));
+                                    if !track.page_towards(&*scroll_handle, target) {
+                                        return;
+                                    }
+                                    cx.notify(view_id);
+
+                                    let state = state.clone();
+                                    let scroll_handle = scroll_handle.clone();
+                                    cx.spawn(async move |cx: &mut AsyncApp| {
+                                        let mut delay = PAGE_REPEAT_DELAY;
+                                        loop {
+                                            cx.background_executor().timer(delay).await;
+                                            delay = PAGE_REPEAT_INTERVAL;
+                                            let Some((paging_axis, target)) = state.get().paging
+                                            else {
+                                                break;
+                                            };
+                                            if paging_axis != axis
+                                                || !track.page_towards(&*scroll_handle, target)
+                                            {
+                                                break;
+                                            }
+                                            cx.update(|cx| cx.notify(view_id));
+                                        }
+                                    })
+                                    .detach();
                                 } else {
                                     let offset = scroll_handle.offset();
                                     let percentage = if axis.is_vertical() {
@@ -0,0 +1,403 @@
+use gpui::{prelude::FluentBuilder as _, *};
+use std::path::PathBuf;
+
+use crate::data::config::Config;
+use crate::data::db::repo::Database;
+use crate::data::metadata::{AudioMetadata, AudioProperties};
+use crate::data::models::Song;
+use crate::media::playback::{
+    MAX_GAIN_OFFSET_DB, Playback, clipping_overshoot_db, preamp_without_clipping, song_loudness,
+};
+use crate::ui::{
+    components::{
+        div::{flex_col, flex_row},
+        icons::{self, icon},
+    },
+    variables::Variables,
+};
+
+const GAIN_OFFSET_STEP_DB: f32 = 0.5;
+
+pub struct SongInfoPanel {
+    song: Option<Song>,
+    properties: Option<Result<AudioProperties, String>>,
+    suspected_transcode: bool,
+    metadata_from_filename: bool,
+    play_counts: Option<(i64, i64)>,
+    load_token: u64,
+}
+
+impl SongInfoPanel {
+    pub fn new(_cx: &mut Context<Self>) -> Self {
+        Self {
+            song: None,
+            properties: None,
+            suspected_transcode: false,
+            metadata_from_filename: false,
+            play_counts: None,
+            load_token: 0,
+        }
+    }
+
+    pub fn show(&mut self, song: Song, cx: &mut Context<Self>) {
+        self.load_token = self.load_token.wrapping_add(1);
+        let token = self.load_token;
+        let path = PathBuf::from(&song.file_path);
+        let song_id = song.id.clone();
+        self.song = Some(song);
+        self.properties = None;
+        self.suspected_transcode = false;
+        self.metadata_from_filename = false;
+        self.play_counts = None;
+        cx.notify();
+
+        let db = cx.global::<Database>().clone();
+        let bg = cx.background_executor().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let (properties, suspected_transcode, metadata_from_filename, play_counts) = bg
+                .spawn(async move {
+                    let properties = AudioMetadata::from_path_with_options(&path, false)
+                        .map(|metadata| metadata.properties)
+                        .map_err(|e| e.to_string());
+                    let suspected = db
+                        .get_suspected_transcode(&song_id)
+                        .ok()
+                        .flatten()
+                        .unwrap_or(false);
+                    let from_filename = db.get_metadata_from_filename(&song_id).unwrap_or(false);
+                    let play_counts = db.get_play_and_skip_counts(&song_id).ok();
+                    (properties, suspected, from_filename, play_counts)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                if this.load_token == token {
+                    this.properties = Some(properties);
+                    this.suspected_transcode = suspected_transcode;
+                    this.metadata_from_filename = metadata_from_filename;
+                    this.play_counts = play_counts;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn set_gain_offset(&mut self, gain_offset_db: f32, cx: &mut Context<Self>) {
+        let Some(song) = &mut self.song else {
+            return;
+        };
+        let gain_offset_db = gain_offset_db.clamp(-MAX_GAIN_OFFSET_DB, MAX_GAIN_OFFSET_DB);
+        song.gain_offset_db = gain_offset_db;
+        let song_id = song.id.clone();
+        cx.notify();
+
+        cx.update_global::<Playback, _>(|playback, cx| {
+            playback.set_gain_offset(&song_id, gain_offset_db, cx);
+        });
+        let db = cx.global::<Database>().clone();
+        cx.background_executor()
+            .spawn(async move {
+                if let Err(e) = db.set_gain_offset(&song_id, gain_offset_db) {
+                    tracing::error!("Failed to save gain offset: {}", e);
+                }
+            })
+            .detach();
+    }
+
+    pub fn hide(&mut self, cx: &mut Context<Self>) {
+        if self.song.take().is_some() {
+            self.properties = None;
+            cx.notify();
+        }
+    }
+}
+
+fn format_file_size(bytes: i64) -> String {
+    format!("{:.1} MB", bytes.max(0) as f64 / (1024.0 * 1024.0))
+}
+
+fn format_sample_rate(hz: u32) -> String {
+    if hz % 1000 == 0 {
+        format!("{} kHz", hz / 1000)
+    } else {
+        format!("{:.1} kHz", hz as f64 / 1000.0)
+    }
+}
+
+fn format_channels(channels: u8) -> String {
+    match channels {
+        1 => "Mono".to_string(),
+        2 => "Stereo".to_string(),
+        n => format!("{} channels", n),
+    }
+}
+
+fn detail_rows(
+    song: &Song,
+    properties: Option<&Result<AudioProperties, String>>,
+    loudness: Option<(f32, &'static str)>,
+    play_counts: Option<(i64, i64)>,
+) -> Vec<(&'static str, String)> {
+    let mut rows = vec![("Path", song.file_path.clone())];
+
+    match properties {
+        None => rows.push(("Format", "Reading file…".to_string())),
+        Some(Err(e)) => rows.push(("Format", format!("Could not read file: {}", e))),
+        Some(Ok(props)) => {
+            let format = props
+                .format
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string());
+            let kind = if props.lossless { "lossless" } else { "lossy" };
+            rows.push(("Format", format!("{} ({})", format, kind)));
+            let unknown = || "Unknown".to_string();
+            rows.push((
+                "Bitrate",
+                props
+                    .bitrate_kbps
+                    .map(|kbps| format!("{} kbps", kbps))
+                    .unwrap_or_else(unknown),
+            ));
+            rows.push((
+                "Sample rate",
+                props
+                    .sample_rate
+                    .map(format_sample_rate)
+                    .unwrap_or_else(unknown),
+            ));
+            if let Some(depth) = props.bit_depth {
+                rows.push(("Bit depth", format!("{}-bit", depth)));
+            }
+            rows.push((
+                "Channels",
+                props.channels.map(format_channels).unwrap_or_else(unknown),
+            ));
+        }
+    }
+
+    rows.push((
+        "Loudness",
+        loudness
+            .map(|(lufs, origin)| format!("{:.1} LUFS ({})", lufs, origin))
+            .unwrap_or_else(|| "Not measured yet".to_string()),
+    ));
+    rows.push(("File size", format_file_size(song.file_size)));
+    rows.push((
+        "Duration",
+        format!("{}:{:02}", song.duration / 60, song.duration % 60),
+    ));
+    if let Some((plays, skips)) = play_counts {
+        rows.push(("Plays", format!("{} ({} skipped)", plays, skips)));
+    }
+    rows
+}
+
+impl Render for SongInfoPanel {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(song) = &self.song else {
+            return div().into_any_element();
+        };
+
+        let variables = *cx.global::<Variables>();
+        let viewport = window.viewport_size();
+        let audio = &cx.global::<Config>().get().audio;
+        let loudness_source = audio.loudness_source;
+        let preamp_db = audio.normalization_preamp_db;
+        let gain_offset_db = song.gain_offset_db;
+        let clipping = audio
+            .clipping_warning
+            .then(|| clipping_overshoot_db(song, loudness_source, preamp_db))
+            .flatten();
+        let rows = detail_rows(
+            song,
+            self.properties.as_ref(),
+            song_loudness(song, loudness_source),
+            self.play_counts,
+        );
+
+        let card =
+            flex_col()
+                .id("song-info-card")
+                .occlude()
+                .w(px(480.0))
+                .p(px(variables.padding_16))
+                .gap(px(variables.padding_16))
+                .bg(variables.element)
+                .border_1()
+                .border_color(variables.border)
+                .on_mouse_down_out(cx.listener(|this, _, _, cx| this.hide(cx)))
+                .child(
+                    flex_row()
+                        .w_full()
+                        .justify_between()
+                        .items_center()
+                        .child(
+                            div()
+                                .flex_1()
+                                .min_w_0()
+                                .truncate()
+                                .child(song.title.clone()),
+                        )
+                        .child(
+                            div()
+                                .id("song-info-close")
+                                .cursor_pointer()
+                                .on_click(cx.listener(|this, _, _, cx| this.hide(cx)))
+                                .child(icon(icons::X).text_color(variables.text_secondary)),
+                        ),
+                )
+                .when(self.suspected_transcode, |card| {
+                    card.child(
+                        div()
+                            .w_full()
+                            .px(px(variables.padding_8))
+                            .py(px(variables.padding_8))
+                            .border_1()
+                            .border_color(variables.warning)
+                            .text_color(variables.warning)
+                            .child("Possible transcode: the spectrum cuts off like a lossy file"),
+                    )
+                })
+                .when(self.metadata_from_filename, |card| {
+                    card.child(
+                        div()
+                            .w_full()
+                            .px(px(variables.padding_8))
+                            .py(px(variables.padding_8))
+                            .border_1()
+                            .border_color(variables.border)
+                            .text_color(variables.text_secondary)
+                            .child("No tags: title and artist were taken from the file name"),
+                    )
+                })
+                .when_some(clipping, |card, overshoot| {
+                    let fixed_preamp = preamp_without_clipping(preamp_db, overshoot);
+                    card.child(
+                        flex_row()
+                            .w_full()
+                            .justify_between()
+                            .items_center()
+                            .gap(px(variables.padding_8))
+                            .px(px(variables.padding_8))
+                            .py(px(variables.padding_8))
+                            .border_1()
+                            .border_color(variables.warning)
+                            .text_color(variables.warning)
+                            .child(format!(
+                                "Clips by {:.1} dB with normalization at this preamp",
+                                overshoot
+                            ))
+                            .child(
+                                div()
+                                    .id("song-info-fix-clipping")
+                                    .flex_shrink_0()
+                                    .cursor_pointer()
+                                    .underline()
+                                    .child(format!("Set preamp to {:+.1} dB", fixed_preamp))
+                                    .on_click(move |_, _, cx| {
+                                        cx.update_global::<Config, _>(|config, _cx| {
+                                            config.set(|s| {
+                                                s.audio.normalization_preamp_db = fixed_preamp
+                                            });
+                                        });
+                                        cx.update_global::<Playback, _>(|playback, _cx| {
+                                            playback.set_normalization_preamp(fixed_preamp);
+                                        });
+                                    }),
+                            ),
+                    )
+                })
+                .child(flex_col().w_full().gap(px(variables.padding_8)).children(
+                    rows.into_iter().map(|(label, value)| {
+                        flex_row()
+                            .w_full()
+                            .gap(px(variables.padding_16))
+                            .child(
+                                div()
+                                    .w(px(100.0))
+                                    .flex_shrink_0()
+                                    .text_color(variables.text_secondary)
+                                    .child(label),
+                            )
+                            .child(div().flex_1().min_w_0().child(value))
+                    }),
+                ))
+                .child(
+                    flex_row()
+                        .w_full()
+                        .items_center()
+                        .gap(px(variables.padding_16))
+                        .child(
+                            div()
+                                .w(px(100.0))
+                                .flex_shrink_0()
+                                .text_color(variables.text_secondary)
+                                .child("Gain offset"),
+                        )
+                        .child(
+                            flex_row()
+                                .items_center()
+                                .gap(px(variables.padding_8))
+                                .child(
+                                    div()
+                                        .id("song-info-gain-down")
+                                        .cursor_pointer()
+                                        .px(px(variables.padding_8))
+                                        .border_1()
+                                        .border_color(variables.border)
+                                        .child("−")
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.set_gain_offset(
+                                                gain_offset_db - GAIN_OFFSET_STEP_DB,
+                                                cx,
+                                            )
+                                        })),
+                                )
+                                .child(format!("{:+.1} dB", gain_offset_db))
+                                .child(
+                                    div()
+                                        .id("song-info-gain-up")
+                                        .cursor_pointer()
+                                        .px(px(variables.padding_8))
+                                        .border_1()
+                                        .border_color(variables.border)
+                                        .child("+")
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.set_gain_offset(
+                                                gain_offset_db + GAIN_OFFSET_STEP_DB,
+                                                cx,
+                                            )
+                                        })),
+                                )
+                                .when(gain_offset_db != 0.0, |row| {
+                                    row.child(
+                                        div()
+                                            .id("song-info-gain-reset")
+                                            .cursor_pointer()
+                                            .underline()
+                                            .text_color(variables.text_secondary)
+                                            .child("Reset")
+                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                this.set_gain_offset(0.0, cx)
+                                            })),
+                                    )
+                                }),
+                        ),
+                );
+
+        deferred(
+            anchored().position(point(px(0.0), px(0.0))).child(
+                div()
+                    .w(viewport.width)
+                    .h(viewport.height)
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .child(card),
+            ),
+        )
+        .with_priority(3)
+        .into_any_element()
+    }
+}
@@ -1,23 +1,44 @@
-use crate::data::config::Config;
-use crate::data::models::Cuid;
+use crate::data::config::{ClickBehavior, Config, PlayingSongClick, RowDensity};
+use crate::data::db::repo::Database;
+use crate::data::models::{Cuid, Song};
 use crate::media::playback::Playback;
 use crate::media::queue::Queue;
 use crate::ui::app::MainWindow;
-use crate::ui::components::context_menu::{ContextMenu, QueueChanged, song_context_menu_items};
+use crate::ui::bidi;
+use crate::ui::components::context_menu::{
+    ContextMenu, QueueChanged, selected_songs_context_menu_items, song_context_menu_items,
+};
+use crate::ui::components::cover::{Cover, image_uri};
 use crate::ui::components::div::{flex_col, flex_row};
 use crate::ui::components::icons::{self, icon};
 use crate::ui::components::scrollbar::{Scrollbar, ScrollbarAxis, ScrollbarHandle};
 use crate::ui::variables::Variables;
 use crate::ui::views::{AppView, SelectedAlbum};
 use gpui::{prelude::*, *};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cell::RefCell;
 use std::ops::Range;
 use std::rc::Rc;
 use std::sync::Arc;
-use tracing::debug;
+use tracing::{debug, error};
 
 const ANIMATION_FPS: f32 = 15.0;
 const COVER_SIZE: f32 = 36.0;
+const COMPACT_COVER_SIZE: f32 = 24.0;
+
+fn cover_size(density: RowDensity) -> f32 {
+    match density {
+        RowDensity::Comfortable => COVER_SIZE,
+        RowDensity::Compact => COMPACT_COVER_SIZE,
+    }
+}
+
+fn row_padding(density: RowDensity, variables: &Variables) -> f32 {
+    match density {
+        RowDensity::Comfortable => variables.padding_16,
+        RowDensity::Compact => variables.padding_8,
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ColumnSize {
@@ -31,6 +52,7 @@ pub enum SongColumn {
     Title,
     Album,
     Genre,
+    LastPlayed,
     Duration,
 }
 
@@ -41,10 +63,36 @@ impl SongColumn {
             SongColumn::Title => "Title",
             SongColumn::Album => "Album",
             SongColumn::Genre => "Genre",
+            SongColumn::LastPlayed => "Last played",
             SongColumn::Duration => "Duration",
         }
     }
 
+    fn key(&self) -> &'static str {
+        match self {
+            SongColumn::Number => "number",
+            SongColumn::Title => "title",
+            SongColumn::Album => "album",
+            SongColumn::Genre => "genre",
+            SongColumn::LastPlayed => "last_played",
+            SongColumn::Duration => "duration",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|column| column.key() == key)
+    }
+
+    /// Whether the first click on this header sorts ascending, per `descending_sort_columns`.
+    fn sorts_ascending_first(&self, cx: &App) -> bool {
+        !cx.global::<Config>()
+            .get()
+            .interface
+            .descending_sort_columns
+            .iter()
+            .any(|key| key == self.key())
+    }
+
     fn size(&self, number_width: f32, duration_width: f32) -> ColumnSize {
         match self {
             SongColumn::Number => ColumnSize::Fixed(number_width),
@@ -52,19 +100,21 @@ impl SongColumn {
             SongColumn::Album => ColumnSize::Flex(),
             SongColumn::Duration => ColumnSize::Fixed(duration_width),
             SongColumn::Genre => ColumnSize::Flex(),
+            SongColumn::LastPlayed => ColumnSize::Flex(),
         }
     }
 
-    const ALL: [SongColumn; 5] = [
+    const ALL: [SongColumn; 6] = [
         SongColumn::Number,
         SongColumn::Title,
         SongColumn::Album,
         SongColumn::Genre,
+        SongColumn::LastPlayed,
         SongColumn::Duration,
     ];
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct TableSort {
     pub column: SongColumn,
     pub ascending: bool,
@@ -82,6 +132,9 @@ pub struct SongEntry {
     pub cover_uri: Option<String>,
     pub track_number: Option<i32>,
     pub genre: String,
+    pub last_played: String,
+    /// Dims the row, the file was missing at the last scan.
+    pub unavailable: bool,
 }
 
 impl SongEntry {
@@ -91,11 +144,44 @@ impl SongEntry {
             SongColumn::Title => self.title.clone().into(),
             SongColumn::Album => self.album.clone().into(),
             SongColumn::Genre => self.genre.clone().into(),
+            SongColumn::LastPlayed => self.last_played.clone().into(),
             SongColumn::Duration => self.duration.clone().into(),
         }
     }
 }
 
+fn duration_secs(duration: &str) -> u32 {
+    duration
+        .split(':')
+        .fold(0, |acc, part| acc * 60 + part.parse::<u32>().unwrap_or(0))
+}
+
+/// Display order of `entries` under `sort`. Ties keep their original order.
+fn sorted_order(entries: &[Arc<SongEntry>], sort: Option<TableSort>) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    let Some(sort) = sort else {
+        return order;
+    };
+
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&entries[a], &entries[b]);
+        let ordering = match sort.column {
+            SongColumn::Number => a.track_number.cmp(&b.track_number),
+            SongColumn::Duration => duration_secs(&a.duration).cmp(&duration_secs(&b.duration)),
+            column => a
+                .get_column_value(column)
+                .to_lowercase()
+                .cmp(&b.get_column_value(column).to_lowercase()),
+        };
+        if sort.ascending {
+            ordering
+        } else {
+            ordering.reverse()
+        }
+    });
+    order
+}
+
 pub fn join_artists(artists: &[String]) -> (String, Vec<Range<usize>>) {
     let separator = " \u{00B7} ";
     let mut result = String::new();
@@ -133,9 +219,112 @@ pub type GetRowCountHandler = Rc<dyn Fn(&mut App, Option<TableSort>) -> usize +
 pub type GetRowHandler =
     Rc<dyn Fn(&mut App, usize, Option<TableSort>) -> Option<Arc<SongEntry>> + 'static>;
 pub type QueueHandler = Rc<dyn Fn(&mut App, Cuid, usize, Option<TableSort>) + 'static>;
+pub type LocateHandler =
+    Rc<dyn Fn(&mut App, &Cuid, Option<TableSort>) -> Task<Option<usize>> + 'static>;
+pub type SongCache = Rc<RefCell<Vec<Arc<SongEntry>>>>;
+
+/// Which songs a table built by [`SongTable::for_album`], [`SongTable::for_playlist`] or
+/// [`SongTable::for_artist`] lists for the id given to [`SongTable::load`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SongScope {
+    Album,
+    Playlist,
+    Artist,
+}
+
+impl SongScope {
+    /// Albums and playlists remember their sort; featured tracks always start unsorted.
+    fn sort_key(self, id: &Cuid) -> Option<String> {
+        match self {
+            SongScope::Album => Some(format!("album:{}", id)),
+            SongScope::Playlist => Some(format!("playlist:{}", id)),
+            SongScope::Artist => None,
+        }
+    }
+
+    /// The songs in display order, each with the album title its row shows.
+    fn load(self, db: &Database, id: &Cuid) -> Vec<(Song, String)> {
+        match self {
+            SongScope::Album => db
+                .get_album_songs(id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|song| (song, String::new()))
+                .collect(),
+            SongScope::Playlist => db
+                .get_playlist_songs(id)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|track| (track.song, track.album_title.unwrap_or_default()))
+                .collect(),
+            SongScope::Artist => {
+                let songs = db.get_tracks_featuring_artist(id).unwrap_or_default();
+                let mut album_titles: FxHashMap<Cuid, String> = FxHashMap::default();
+                for album_id in songs.iter().filter_map(|s| s.album_id.as_ref()) {
+                    if album_titles.contains_key(album_id) {
+                        continue;
+                    }
+                    if let Some(album) = db.get_album(album_id).ok().flatten() {
+                        album_titles.insert(album_id.clone(), album.title);
+                    }
+                }
+                songs
+                    .into_iter()
+                    .map(|song| {
+                        let album = song
+                            .album_id
+                            .as_ref()
+                            .and_then(|id| album_titles.get(id).cloned())
+                            .unwrap_or_default();
+                        (song, album)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+fn song_entry_from_song(song: &Song, album: String) -> Arc<SongEntry> {
+    let artists = if song.artists.is_empty() {
+        vec!["Unknown".to_string()]
+    } else {
+        song.artists.clone()
+    };
+    let (artist, artist_ranges) = join_artists(&artists);
+    let minutes = song.duration / 60;
+    let seconds = song.duration % 60;
+    Arc::new(SongEntry {
+        id: song.id.clone(),
+        title: song.title.clone(),
+        artist,
+        artist_ranges,
+        album,
+        album_id: song.album_id.clone(),
+        duration: format!("{}:{:02}", minutes, seconds),
+        cover_uri: song.image_id.as_deref().map(image_uri),
+        track_number: song.track_number,
+        genre: String::new(),
+        last_played: String::new(),
+        unavailable: song.unavailable,
+    })
+}
+
+/// The rows of a scoped table and the songs behind them.
+#[derive(Clone)]
+struct ScopedRows {
+    scope: SongScope,
+    cache: SongCache,
+    id: Option<Cuid>,
+    songs: Vec<Song>,
+    generation: u64,
+}
 
 type RowMap = FxHashMap<usize, Entity<SongTableItem>>;
 
+/// Set by the jump-to-playing shortcut, the active view scrolls its table to the current song.
+pub struct JumpToPlayingRequested;
+impl Global for JumpToPlayingRequested {}
+
 #[derive(Clone, Copy)]
 pub struct SongTableLayout {
     pub number_width: f32,
@@ -144,6 +333,7 @@ pub struct SongTableLayout {
     pub show_album: bool,
     pub show_cover: bool,
     pub show_genre: bool,
+    pub show_last_played: bool,
     pub sort_method: Option<TableSort>,
 }
 
@@ -152,6 +342,59 @@ pub struct SongTableHandlers {
     pub get_row: GetRowHandler,
     pub on_select: Option<OnSelectHandler>,
     pub get_queue: Option<QueueHandler>,
+    pub selection: Entity<SongSelection>,
+}
+
+/// Songs picked with Ctrl/Cmd or Shift click, shared by every row of a table.
+#[derive(Default)]
+pub struct SongSelection {
+    ids: FxHashSet<Cuid>,
+    anchor: Option<usize>,
+}
+
+fn update_selection(
+    selection: &Entity<SongSelection>,
+    get_row: &GetRowHandler,
+    row_index: usize,
+    sort_method: Option<TableSort>,
+    extend: bool,
+    cx: &mut App,
+) {
+    let anchor = selection.read(cx).anchor.filter(|_| extend);
+    let ids: Vec<Cuid> = match anchor {
+        Some(anchor) => (anchor.min(row_index)..=anchor.max(row_index))
+            .filter_map(|idx| get_row(cx, idx, sort_method).map(|row| row.id.clone()))
+            .collect(),
+        None => get_row(cx, row_index, sort_method)
+            .map(|row| row.id.clone())
+            .into_iter()
+            .collect(),
+    };
+
+    selection.update(cx, |selection, cx| {
+        if anchor.is_some() {
+            selection.ids.extend(ids);
+        } else {
+            for id in ids {
+                if !selection.ids.remove(&id) {
+                    selection.ids.insert(id);
+                }
+            }
+            selection.anchor = Some(row_index);
+        }
+        cx.notify();
+    });
+}
+
+fn clear_selection(selection: &Entity<SongSelection>, cx: &mut App) {
+    if selection.read(cx).ids.is_empty() {
+        return;
+    }
+    selection.update(cx, |selection, cx| {
+        selection.ids.clear();
+        selection.anchor = None;
+        cx.notify();
+    });
 }
 
 const MAX_CACHED_ROWS: usize = 500;
@@ -187,6 +430,47 @@ where
     view
 }
 
+fn play_from_row(
+    cx: &mut App,
+    data: &SongEntry,
+    row_index: usize,
+    sort_method: Option<TableSort>,
+    get_queue: &Option<QueueHandler>,
+) {
+    debug!("Songs play click: song_id={}", data.id);
+
+    let is_current = cx.global::<Queue>().get_current_song_id().as_ref() == Some(&data.id)
+        && !cx.global::<Playback>().empty();
+    if is_current {
+        let behavior = cx.global::<Config>().get().interface.playing_song_click;
+        cx.update_global::<Playback, _>(|playback, cx| match behavior {
+            PlayingSongClick::Restart => {
+                playback.seek_reporting_errors(0.0, cx);
+                if !playback.get_playing() {
+                    playback.play_pause(cx);
+                }
+            }
+            PlayingSongClick::Nothing => {}
+            PlayingSongClick::TogglePause => playback.play_pause(cx),
+        });
+        return;
+    }
+
+    cx.update_global::<Queue, _>(|queue, _cx| {
+        queue.clear_and_queue_songs(vec![data.id.clone()], 0);
+    });
+
+    cx.update_global::<Playback, _>(|playback, cx| {
+        playback.play_queue(cx);
+    });
+
+    cx.set_global(QueueChanged);
+
+    if let Some(get_queue) = get_queue {
+        (get_queue)(cx, data.id.clone(), row_index, sort_method);
+    }
+}
+
 #[derive(Clone)]
 pub struct SongTableItem {
     on_select: Option<OnSelectHandler>,
@@ -199,10 +483,12 @@ pub struct SongTableItem {
     show_album: bool,
     show_cover: bool,
     show_genre: bool,
+    show_last_played: bool,
     row_index: usize,
     is_animating: bool,
     hovered_artist: Option<usize>,
     context_menu: Entity<ContextMenu>,
+    selection: Entity<SongSelection>,
 }
 
 impl SongTableItem {
@@ -223,10 +509,12 @@ impl SongTableItem {
             show_album: layout.show_album,
             show_cover: layout.show_cover,
             show_genre: layout.show_genre,
+            show_last_played: layout.show_last_played,
             row_index,
             is_animating: false,
             hovered_artist: None,
             context_menu: cx.new(|_| ContextMenu::new()),
+            selection: handlers.selection,
         })
     }
 }
@@ -246,7 +534,17 @@ impl Render for SongTableItem {
         let show_album = self.show_album;
         let show_cover = self.show_cover;
         let show_genre = self.show_genre;
+        let show_last_played = self.show_last_played;
         let context_menu_entity = self.context_menu.clone();
+        let click_behavior = cx.global::<Config>().get().interface.click_behavior;
+        let density = cx.global::<Config>().get().interface.row_density;
+        let cover_size = cover_size(density);
+        let selection = self.selection.clone();
+        let get_row = self.get_row.clone();
+        let is_selected = row_data
+            .as_ref()
+            .is_some_and(|data| selection.read(cx).ids.contains(&data.id));
+        let unavailable = row_data.as_ref().is_some_and(|data| data.unavailable);
 
         let mut row = flex_row()
             .w_full()
@@ -254,7 +552,7 @@ impl Render for SongTableItem {
             .group("song-row")
             .items_center()
             .gap(px(variables.padding_8))
-            .pb(px(variables.padding_16))
+            .pb(px(row_padding(density, &variables)))
             .when_some(on_select, move |div, handler| {
                 let row_data = row_data_for_select.clone();
                 div.on_click(move |_, _, cx| {
@@ -264,11 +562,35 @@ impl Render for SongTableItem {
                 })
                 .cursor_pointer()
             })
+            .when(click_behavior != ClickBehavior::Cover, |div| {
+                div.cursor_pointer()
+            })
+            .when(is_selected, |div| div.bg(variables.element))
+            .when(unavailable, |div| div.opacity(0.5))
             .when_some(row_data.clone(), |div, data| {
                 let song_id = data.id.clone();
                 let ctx_menu = context_menu_entity.clone();
-                div.on_mouse_down(MouseButton::Right, move |event, _window, cx| {
-                    let items = song_context_menu_items(song_id.clone(), cx);
+                let get_queue = get_queue.clone();
+                let selection_for_click = selection.clone();
+                div.on_mouse_down(MouseButton::Left, move |event, _window, cx| {
+                    let selection = &selection_for_click;
+                    if event.modifiers.secondary() || event.modifiers.shift {
+                        let extend = event.modifiers.shift;
+                        update_selection(selection, &get_row, row_index, sort_method, extend, cx);
+                        return;
+                    }
+                    clear_selection(selection, cx);
+                    if click_behavior.plays_on(event.click_count) {
+                        play_from_row(cx, &data, row_index, sort_method, &get_queue);
+                    }
+                })
+                .on_mouse_down(MouseButton::Right, move |event, _window, cx| {
+                    let selected: Vec<Cuid> = selection.read(cx).ids.iter().cloned().collect();
+                    let items = if selected.len() > 1 && selected.contains(&song_id) {
+                        selected_songs_context_menu_items(selected, cx)
+                    } else {
+                        song_context_menu_items(song_id.clone(), cx)
+                    };
                     ctx_menu.update(cx, |menu, cx| {
                         menu.show(event.position, items, cx);
                     });
@@ -348,8 +670,11 @@ impl Render for SongTableItem {
                 if matches!(column, SongColumn::Genre) && !show_genre {
                     continue;
                 }
+                if matches!(column, SongColumn::LastPlayed) && !show_last_played {
+                    continue;
+                }
                 let size = if matches!(column, SongColumn::Number) && !show_cover {
-                    ColumnSize::Fixed(COVER_SIZE)
+                    ColumnSize::Fixed(cover_size)
                 } else {
                     column.size(self.number_width, self.duration_width)
                 };
@@ -365,7 +690,7 @@ impl Render for SongTableItem {
                 }
 
                 if matches!(column, SongColumn::Title) {
-                    let image_size = COVER_SIZE;
+                    let image_size = cover_size;
                     let get_queue = get_queue.clone();
                     let row_data_play = row_data.clone();
                     let mut title_row = column_div
@@ -383,15 +708,11 @@ impl Render for SongTableItem {
                                 .bg(variables.element)
                                 .relative()
                                 .group("cover-container")
-                                .when_some(data.cover_uri.clone(), |div, image| {
-                                    let sized_image =
-                                        format!("{}?size={}", image, image_size as u32);
-                                    div.child(
-                                        img(sized_image)
-                                            .size(px(image_size))
-                                            .object_fit(ObjectFit::Cover),
-                                    )
-                                })
+                                .child(Cover::new(
+                                    data.cover_uri
+                                        .clone()
+                                        .map(|uri| format!("{}?size={}", uri, image_size as u32)),
+                                ))
                                 .child(
                                     flex_row()
                                         .absolute()
@@ -404,7 +725,7 @@ impl Render for SongTableItem {
                                         .children((0..4).map(|i| {
                                             let height_pct =
                                                 (spectrum[i] * 100.0).clamp(10.0, 80.0);
-                                            let height_px = 36.0 * (height_pct / 100.0);
+                                            let height_px = image_size * (height_pct / 100.0);
                                             div().w(px(4.0)).h(px(height_px)).bg(variables.text)
                                         })),
                                 )
@@ -427,30 +748,14 @@ impl Render for SongTableItem {
                                 .cursor_pointer()
                                 .on_mouse_down(MouseButton::Left, {
                                     move |_event, _window, cx| {
+                                        if click_behavior == ClickBehavior::SingleClick {
+                                            return;
+                                        }
                                         let Some(data) = &row_data_play else {
                                             return;
                                         };
-                                        debug!("Songs play click: song_id={}", data.id);
-
-                                        cx.update_global::<Queue, _>(|queue, _cx| {
-                                            queue.clear();
-                                            queue.add_song(data.id.clone());
-                                        });
-
-                                        cx.update_global::<Playback, _>(|playback, cx| {
-                                            playback.play_queue(cx);
-                                        });
-
-                                        cx.set_global(QueueChanged);
-
-                                        if let Some(get_queue) = &get_queue {
-                                            (get_queue)(
-                                                cx,
-                                                data.id.clone(),
-                                                row_index,
-                                                sort_method,
-                                            );
-                                        }
+                                        cx.stop_propagation();
+                                        play_from_row(cx, data, row_index, sort_method, &get_queue);
                                     }
                                 }),
                         );
@@ -469,7 +774,7 @@ impl Render for SongTableItem {
                                     .text_ellipsis()
                                     .font_weight(FontWeight(500.0))
                                     .hover(|this| this.underline())
-                                    .child(data.title.clone()),
+                                    .child(bidi::isolate(&data.title)),
                             )
                             .child(
                                 div()
@@ -563,12 +868,14 @@ impl Render for SongTableItem {
                             .relative()
                             .group("number-cell")
                             .cursor_pointer()
-                            .size(px(COVER_SIZE))
+                            .size(px(cover_size))
                             .flex_shrink_0()
                             .flex()
                             .items_center()
                             .justify_center()
-                            .p(px(variables.padding_8))
+                            .when(density == RowDensity::Comfortable, |s| {
+                                s.p(px(variables.padding_8))
+                            })
                             .child(
                                 div()
                                     .text_color(variables.text_secondary)
@@ -587,7 +894,7 @@ impl Render for SongTableItem {
                                     .when(!is_current || !visualizer_enabled, |s| s.invisible())
                                     .children((0..4).map(|i| {
                                         let height_pct = (spectrum[i] * 100.0).clamp(10.0, 80.0);
-                                        let height_px = COVER_SIZE * (height_pct / 100.0);
+                                        let height_px = cover_size * (height_pct / 100.0);
                                         div().w(px(4.0)).h(px(height_px)).bg(variables.text)
                                     })),
                             )
@@ -607,25 +914,14 @@ impl Render for SongTableItem {
                                     ),
                             )
                             .on_mouse_down(MouseButton::Left, move |_event, _window, cx| {
+                                if click_behavior == ClickBehavior::SingleClick {
+                                    return;
+                                }
                                 let Some(data) = &row_data_play else {
                                     return;
                                 };
-                                debug!("Songs play click: song_id={}", data.id);
-
-                                cx.update_global::<Queue, _>(|queue, _cx| {
-                                    queue.clear();
-                                    queue.add_song(data.id.clone());
-                                });
-
-                                cx.update_global::<Playback, _>(|playback, cx| {
-                                    playback.play_queue(cx);
-                                });
-
-                                cx.set_global(QueueChanged);
-
-                                if let Some(get_queue) = &get_queue {
-                                    (get_queue)(cx, data.id.clone(), row_index, sort_method);
-                                }
+                                cx.stop_propagation();
+                                play_from_row(cx, data, row_index, sort_method, &get_queue);
                             });
                         row = row.child(number_cell);
                         continue;
@@ -672,6 +968,7 @@ impl Render for SongTableItem {
 pub enum SongTableEvent {
     NewRows,
     InvalidateRange(std::ops::Range<usize>),
+    SortChanged,
 }
 
 #[derive(Clone)]
@@ -684,30 +981,178 @@ pub struct SongTable {
     get_row_count: GetRowCountHandler,
     get_row: GetRowHandler,
     get_queue: Option<QueueHandler>,
+    locate: Option<LocateHandler>,
+    has_current: bool,
     number_width: f32,
     duration_width: f32,
     show_numbers: bool,
     show_album: bool,
     show_cover: bool,
     show_genre: bool,
+    show_last_played: bool,
+    row_density: RowDensity,
+    font_size: f32,
     scroll_handle: UniformListScrollHandle,
+    selection: Entity<SongSelection>,
+    sort_key: Option<String>,
+    scoped: Option<ScopedRows>,
 }
 
 impl EventEmitter<SongTableEvent> for SongTable {}
 
-fn calculate_column_widths(item_count: usize) -> (f32, f32) {
-    const CHAR_WIDTH: f32 = 8.5;
+fn calculate_column_widths(item_count: usize, font_size: f32) -> (f32, f32) {
+    // Feature Mono advances 8.5px per glyph at 14px; other fonts land close enough.
+    const CHAR_WIDTH_PER_PX: f32 = 8.5 / 14.0;
+    let char_width = font_size * CHAR_WIDTH_PER_PX;
     let digit_count = if item_count == 0 {
         1
     } else {
         (item_count as f32).log10().floor() as usize + 1
     };
-    let number_width = digit_count as f32 * CHAR_WIDTH;
-    let duration_width = 6.0 * CHAR_WIDTH;
+    let number_width = digit_count as f32 * char_width;
+    let duration_width = 6.0 * char_width;
     (number_width, duration_width)
 }
 
 impl SongTable {
+    pub fn for_album(cx: &mut App) -> Entity<Self> {
+        Self::scoped(cx, SongScope::Album, true, false, false)
+    }
+
+    pub fn for_playlist(cx: &mut App) -> Entity<Self> {
+        Self::scoped(cx, SongScope::Playlist, false, true, true)
+    }
+
+    /// Lists the songs an artist is featured on.
+    pub fn for_artist(cx: &mut App) -> Entity<Self> {
+        Self::scoped(cx, SongScope::Artist, false, true, true)
+    }
+
+    fn scoped(
+        cx: &mut App,
+        scope: SongScope,
+        show_numbers: bool,
+        show_album: bool,
+        show_cover: bool,
+    ) -> Entity<Self> {
+        let cache: SongCache = Rc::new(RefCell::new(Vec::new()));
+        let table = Self::from_cache(
+            cx,
+            &cache,
+            show_numbers,
+            show_album,
+            show_cover,
+            false,
+            false,
+        );
+        table.update(cx, |table, _| {
+            table.scoped = Some(ScopedRows {
+                scope,
+                cache,
+                id: None,
+                songs: Vec::new(),
+                generation: 0,
+            });
+        });
+        table
+    }
+
+    pub fn from_cache(
+        cx: &mut App,
+        cache: &SongCache,
+        show_numbers: bool,
+        show_album: bool,
+        show_cover: bool,
+        show_genre: bool,
+        show_last_played: bool,
+    ) -> Entity<Self> {
+        // Row counts are requested whenever the cache or the sort changes, so the display order
+        // is rebuilt there and reused by every row lookup.
+        let order: Rc<RefCell<Vec<usize>>> = Rc::new(RefCell::new(Vec::new()));
+
+        let get_row_count: GetRowCountHandler = {
+            let cache = cache.clone();
+            let order = order.clone();
+            Rc::new(move |_cx, sort| {
+                let cache = cache.borrow();
+                *order.borrow_mut() = sorted_order(&cache, sort);
+                cache.len()
+            })
+        };
+
+        let get_row: GetRowHandler = {
+            let cache = cache.clone();
+            let order = order.clone();
+            Rc::new(move |_cx, idx, _sort| {
+                let cache = cache.borrow();
+                let order = order.borrow();
+                let idx = if order.len() == cache.len() {
+                    *order.get(idx)?
+                } else {
+                    idx
+                };
+                cache.get(idx).cloned()
+            })
+        };
+
+        let locate: LocateHandler = {
+            let cache = cache.clone();
+            let order = order.clone();
+            Rc::new(move |_cx, id, _sort| {
+                let cache = cache.borrow();
+                let order = order.borrow();
+                let row = if order.len() == cache.len() {
+                    order
+                        .iter()
+                        .position(|&i| cache.get(i).is_some_and(|e| &e.id == id))
+                } else {
+                    cache.iter().position(|e| &e.id == id)
+                };
+                Task::ready(row)
+            })
+        };
+
+        let queue_handler: QueueHandler = {
+            let cache = cache.clone();
+            Rc::new(move |cx, current_id, index, _sort| {
+                let rest: Vec<Cuid> = {
+                    let cache = cache.borrow();
+                    let order = order.borrow();
+                    let entry_at = |pos: usize| order.get(pos).and_then(|&i| cache.get(i));
+                    if entry_at(index).map(|e| &e.id) != Some(&current_id) {
+                        return;
+                    }
+                    (index + 1..order.len())
+                        .filter_map(entry_at)
+                        .map(|e| e.id.clone())
+                        .collect()
+                };
+                if rest.is_empty() {
+                    return;
+                }
+                cx.update_global::<Queue, _>(|q, _| {
+                    q.add_songs(rest);
+                });
+                cx.set_global(QueueChanged);
+            })
+        };
+
+        let table = Self::new(
+            cx,
+            get_row_count,
+            get_row,
+            Some(queue_handler),
+            None,
+            show_numbers,
+            show_album,
+            show_cover,
+            show_genre,
+            show_last_played,
+        );
+        table.update(cx, |table, _| table.set_locate(locate));
+        table
+    }
+
     pub fn new(
         cx: &mut App,
         get_row_count: GetRowCountHandler,
@@ -718,14 +1163,17 @@ impl SongTable {
         show_album: bool,
         show_cover: bool,
         show_genre: bool,
+        show_last_played: bool,
     ) -> Entity<Self> {
         cx.new(|cx| {
             let views = cx.new(|_| FxHashMap::default());
             let render_counter = cx.new(|_| 0);
             let sort_method = cx.new(|_| None);
+            let selection = cx.new(|_| SongSelection::default());
 
             let row_count = get_row_count(cx, None);
-            let (number_width, duration_width) = calculate_column_widths(row_count);
+            let font_size = cx.global::<Config>().get().interface.ui_font_size();
+            let (number_width, duration_width) = calculate_column_widths(row_count, font_size);
 
             let get_row_count_clone = get_row_count.clone();
             cx.observe(&sort_method, move |this: &mut SongTable, sort, cx| {
@@ -740,10 +1188,46 @@ impl SongTable {
                     this.scroll_handle.set_offset(point(px(0.0), px(0.0)));
                 }
 
+                cx.emit(SongTableEvent::SortChanged);
+                cx.notify();
+            })
+            .detach();
+
+            cx.observe(&selection, |this: &mut SongTable, _, cx| {
+                let items: Vec<Entity<SongTableItem>> =
+                    this.views.read(cx).values().cloned().collect();
+                for item in items {
+                    item.update(cx, |_, cx| cx.notify());
+                }
+            })
+            .detach();
+
+            cx.observe_global::<Config>(|this: &mut SongTable, cx| {
+                let interface = &cx.global::<Config>().get().interface;
+                let density = interface.row_density;
+                let font_size = interface.ui_font_size();
+                if density == this.row_density && font_size == this.font_size {
+                    return;
+                }
+                this.row_density = density;
+                this.font_size = font_size;
+                (this.number_width, this.duration_width) =
+                    calculate_column_widths(this.row_count, font_size);
+                this.views.update(cx, |v, _| v.clear());
+                this.render_counter.update(cx, |c, _| *c = 0);
                 cx.notify();
             })
             .detach();
 
+            cx.observe_global::<Queue>(|this: &mut SongTable, cx| {
+                let has_current = cx.global::<Queue>().get_current_song_id().is_some();
+                if has_current != this.has_current {
+                    this.has_current = has_current;
+                    cx.notify();
+                }
+            })
+            .detach();
+
             let get_row_count_for_event = get_row_count_clone;
             cx.subscribe_self(move |this, event: &SongTableEvent, cx| match event {
                 SongTableEvent::NewRows => {
@@ -777,6 +1261,7 @@ impl SongTable {
                         item.update(cx, |_, cx| cx.notify());
                     }
                 }
+                SongTableEvent::SortChanged => {}
             })
             .detach();
 
@@ -789,15 +1274,151 @@ impl SongTable {
                 get_row_count,
                 get_row,
                 get_queue,
+                locate: None,
+                has_current: cx.global::<Queue>().get_current_song_id().is_some(),
                 number_width,
                 duration_width,
                 show_numbers,
                 show_album,
                 show_cover,
                 show_genre,
+                show_last_played,
+                row_density: cx.global::<Config>().get().interface.row_density,
+                font_size,
                 scroll_handle: UniformListScrollHandle::default(),
+                selection,
+                sort_key: None,
+                scoped: None,
+            }
+        })
+    }
+
+    /// Restores the sort saved under `key` and saves later header clicks there.
+    pub fn set_sort_key(&mut self, key: impl Into<String>, cx: &mut Context<Self>) {
+        let key = key.into();
+        if self.sort_key.as_deref() == Some(key.as_str()) {
+            return;
+        }
+
+        let saved = cx
+            .global::<Database>()
+            .get_view_sort(&key)
+            .ok()
+            .flatten()
+            .and_then(|(column, ascending)| {
+                SongColumn::from_key(&column).map(|column| TableSort { column, ascending })
+            });
+        self.sort_key = Some(key);
+        self.sort_method.update(cx, |sort, cx| {
+            if *sort != saved {
+                *sort = saved;
+                cx.notify();
             }
+        });
+    }
+
+    /// Loads the songs of the album, playlist or artist `id` into a scoped table, replacing
+    /// what it listed before. Emits [`SongTableEvent::NewRows`] once they're in.
+    pub fn load(&mut self, id: Option<Cuid>, cx: &mut Context<Self>) {
+        let Some(scope) = self.scoped.as_ref().map(|scoped| scoped.scope) else {
+            return;
+        };
+        if let Some(key) = id.as_ref().and_then(|id| scope.sort_key(id)) {
+            self.set_sort_key(key, cx);
+        }
+        let Some(scoped) = self.scoped.as_mut() else {
+            return;
+        };
+        scoped.id = id.clone();
+        scoped.generation += 1;
+        let generation = scoped.generation;
+
+        let Some(id) = id else {
+            scoped.songs.clear();
+            scoped.cache.borrow_mut().clear();
+            cx.emit(SongTableEvent::NewRows);
+            return;
+        };
+
+        let db = cx.global::<Database>().clone();
+        let bg = cx.background_executor().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let songs = bg.spawn(async move { scope.load(&db, &id) }).await;
+            this.update(cx, |this, cx| {
+                let Some(scoped) = this.scoped.as_mut() else {
+                    return;
+                };
+                if scoped.generation != generation {
+                    return;
+                }
+                *scoped.cache.borrow_mut() = songs
+                    .iter()
+                    .map(|(song, album)| song_entry_from_song(song, album.clone()))
+                    .collect();
+                scoped.songs = songs.into_iter().map(|(song, _)| song).collect();
+                cx.emit(SongTableEvent::NewRows);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// The songs a scoped table lists, in their unsorted order.
+    pub fn songs(&self) -> &[Song] {
+        self.scoped
+            .as_ref()
+            .map(|scoped| scoped.songs.as_slice())
+            .unwrap_or_default()
+    }
+
+    fn save_sort(&self, cx: &App) {
+        let Some(key) = &self.sort_key else {
+            return;
+        };
+        let sort = self.sort_method(cx).map(|s| (s.column.key(), s.ascending));
+        if let Err(e) = cx.global::<Database>().set_view_sort(key, sort) {
+            error!("Failed to save sort for {}: {}", key, e);
+        }
+    }
+
+    pub fn sort_method(&self, cx: &App) -> Option<TableSort> {
+        *self.sort_method.read(cx)
+    }
+
+    pub fn scroll_to_row(&self, row: usize) {
+        self.scroll_handle.scroll_to_item(row, ScrollStrategy::Top);
+    }
+
+    pub fn set_locate(&mut self, locate: LocateHandler) {
+        self.locate = Some(locate);
+    }
+
+    /// Scrolls the current song into view and selects it, if it's in this table.
+    pub fn jump_to_playing(&mut self, cx: &mut Context<Self>) {
+        let Some(locate) = self.locate.clone() else {
+            return;
+        };
+        let Some(song_id) = cx.global::<Queue>().get_current_song_id() else {
+            return;
+        };
+        let row = locate(cx, &song_id, self.sort_method(cx));
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let Some(row) = row.await else {
+                return;
+            };
+            this.update(cx, |this, cx| {
+                this.scroll_handle
+                    .scroll_to_item(row, ScrollStrategy::Center);
+                this.selection.update(cx, |selection, cx| {
+                    selection.ids.clear();
+                    selection.ids.insert(song_id);
+                    selection.anchor = Some(row);
+                    cx.notify();
+                });
+            })
+            .ok();
         })
+        .detach();
     }
 }
 
@@ -816,7 +1437,10 @@ impl Render for SongTable {
         let show_album = self.show_album;
         let show_cover = self.show_cover;
         let show_genre = self.show_genre;
+        let show_last_played = self.show_last_played;
         let row_count = self.row_count;
+        let cover_size = cover_size(self.row_density);
+        let selection = self.selection.clone();
 
         let mut header = flex_row()
             .w_full()
@@ -837,8 +1461,11 @@ impl Render for SongTable {
             if matches!(column_id, SongColumn::Genre) && !show_genre {
                 continue;
             }
+            if matches!(column_id, SongColumn::LastPlayed) && !show_last_played {
+                continue;
+            }
             let size = if matches!(column_id, SongColumn::Number) && !show_cover {
-                ColumnSize::Fixed(COVER_SIZE)
+                ColumnSize::Fixed(cover_size)
             } else {
                 column.size(number_width, duration_width)
             };
@@ -885,34 +1512,33 @@ impl Render for SongTable {
                     })
                 })
                 .on_click(cx.listener(move |this, _, _, cx| {
+                    let first_ascending = column_id.sorts_ascending_first(cx);
                     this.sort_method.update(cx, move |this, cx| {
-                        if let Some(method) = this.as_mut() {
-                            if method.column == column_id {
-                                if method.ascending {
-                                    method.ascending = false;
+                        match this.as_mut() {
+                            Some(method) if method.column == column_id => {
+                                if method.ascending == first_ascending {
+                                    method.ascending = !first_ascending;
                                 } else {
                                     *this = None;
                                 }
-                            } else {
+                            }
+                            _ => {
                                 *this = Some(TableSort {
                                     column: column_id,
-                                    ascending: true,
+                                    ascending: first_ascending,
                                 });
                             }
-                        } else {
-                            *this = Some(TableSort {
-                                column: column_id,
-                                ascending: true,
-                            });
                         }
                         cx.notify();
-                    })
+                    });
+                    this.save_sort(cx);
                 }))
             });
 
             header = header.child(header_col);
         }
 
+        let show_jump = self.locate.is_some() && self.has_current;
         let scroll_handle = self.scroll_handle.clone();
         let list = div()
             .flex_1()
@@ -932,6 +1558,7 @@ impl Render for SongTable {
                                     .map(|idx| {
                                         prune_views(&views_model, &render_counter, idx, cx);
                                         let get_queue_clone = get_queue.clone();
+                                        let selection = selection.clone();
                                         create_or_retrieve_view(
                                             &views_model,
                                             idx,
@@ -946,12 +1573,14 @@ impl Render for SongTable {
                                                         show_album,
                                                         show_cover,
                                                         show_genre,
+                                                        show_last_played,
                                                         sort_method,
                                                     },
                                                     SongTableHandlers {
                                                         get_row: get_row.clone(),
                                                         on_select: handler.clone(),
                                                         get_queue: get_queue_clone,
+                                                        selection,
                                                     },
                                                 )
                                             },
@@ -976,6 +1605,21 @@ impl Render for SongTable {
                         .left_0()
                         .child(Scrollbar::new(&self.scroll_handle).axis(ScrollbarAxis::Vertical)),
                 )
+                .when(show_jump, |this| {
+                    this.child(
+                        div()
+                            .id("song-table-jump-to-playing")
+                            .absolute()
+                            .right(px(variables.padding_16))
+                            .bottom(px(variables.padding_16))
+                            .p(px(variables.padding_8))
+                            .bg(variables.element)
+                            .hover(|s| s.bg(variables.element_hover))
+                            .cursor_pointer()
+                            .child(icon(icons::PLAY).text_color(variables.text))
+                            .on_click(cx.listener(|this, _, _, cx| this.jump_to_playing(cx))),
+                    )
+                })
             });
 
         div()
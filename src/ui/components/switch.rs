@@ -1,7 +1,7 @@
 use gpui::{prelude::FluentBuilder as _, *};
 use std::rc::Rc;
 
-use crate::ui::variables::Variables;
+use crate::ui::{components::focus::FocusRing, variables::Variables};
 
 const TRACK_WIDTH: f32 = 50.0;
 const TRACK_HEIGHT: f32 = 18.0;
@@ -63,6 +63,7 @@ impl RenderOnce for Switch {
             .h(px(TRACK_HEIGHT))
             .bg(variables.element)
             .relative()
+            .keyboard_focusable(variables)
             .when_some(self.on_change, |this, on_change| {
                 this.on_click(move |_event, window, cx| {
                     (on_change)(!checked, window, cx);
@@ -1,8 +1,5 @@
 use crate::data::config::Config;
-use crate::data::db::repo::Database;
-use crate::data::models::Cuid;
-use crate::media::playback::Playback;
-use crate::media::queue::Queue;
+use crate::media::now_playing::{self, PlaybackState};
 use discord_rich_presence::{DiscordIpc, DiscordIpcClient, activity};
 use gpui::App;
 use parking_lot::Mutex;
@@ -11,12 +8,6 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct DiscordPresence {}
 
-struct CachedSongInfo {
-    title: String,
-    duration: i32,
-    artist_name: Option<String>,
-}
-
 impl DiscordPresence {
     pub fn init(cx: &mut App) {
         let app_id = Arc::new("1194990403963858984".to_string());
@@ -25,12 +16,9 @@ impl DiscordPresence {
 
         let client = Arc::clone(&client);
         let connected = Arc::clone(&connected);
-        let db = cx.global::<Database>().clone();
+        let now_playing = now_playing::subscribe();
 
         cx.spawn(async move |cx| {
-            let mut cached_song_id: Option<Cuid> = None;
-            let mut cached_song_info: Option<CachedSongInfo> = None;
-
             loop {
                 cx.background_executor()
                     .timer(std::time::Duration::from_secs(2))
@@ -66,51 +54,21 @@ impl DiscordPresence {
                     continue;
                 }
 
-                let song_id = cx.update(|app| {
-                    app.try_global::<Queue>()
-                        .and_then(|q| q.get_current_song_id())
-                });
-
-                if song_id != cached_song_id {
-                    cached_song_id = song_id.clone();
-
-                    if let Some(id) = song_id {
-                        let song = match db.get_song(&id) {
-                            Ok(Some(s)) => s,
-                            _ => {
-                                cached_song_info = None;
-                                continue;
-                            }
-                        };
-
-                        let artist_name = Some(song.artists.join(", ")).filter(|s| !s.is_empty());
-
-                        cached_song_info = Some(CachedSongInfo {
-                            title: song.title,
-                            duration: song.duration,
-                            artist_name,
-                        });
-                    } else {
-                        cached_song_info = None;
-                    }
-                }
-
-                let (position, is_paused) = cx.update(|app| {
-                    app.try_global::<Playback>()
-                        .map(|p| (p.get_position(), p.get_paused()))
-                        .unwrap_or((0.0f32, true))
-                });
-
-                let desired = match cached_song_info.as_ref() {
-                    Some(song) if !is_paused => {
-                        let total_secs = song.duration as i64;
-                        let elapsed_secs = position as i64;
-                        let remaining_secs = total_secs.saturating_sub(elapsed_secs);
-                        let end = unix_now_i64() + remaining_secs;
-                        let start = end - total_secs;
-                        Some((song.title.clone(), song.artist_name.clone(), start, end))
+                let desired = {
+                    let snapshot = now_playing.borrow();
+                    match (&snapshot.song, snapshot.state) {
+                        (Some(song), PlaybackState::Playing) => {
+                            let total_secs = song.duration as i64;
+                            let elapsed_secs = snapshot.position as i64;
+                            let remaining_secs = total_secs.saturating_sub(elapsed_secs);
+                            let end = unix_now_i64() + remaining_secs;
+                            let start = end - total_secs;
+                            let artist_name =
+                                Some(song.artists.join(", ")).filter(|s| !s.is_empty());
+                            Some((song.title.clone(), artist_name, start, end))
+                        }
+                        _ => None,
                     }
-                    _ => None,
                 };
 
                 let client = Arc::clone(&client);
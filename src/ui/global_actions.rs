@@ -4,14 +4,24 @@ use tracing::{debug, error, info};
 use crate::{
     data::{config::Config, db::repo::Database, scanner::Scanner},
     media::playback::Playback,
+    ui::{app::MainWindow, components::song_table::JumpToPlayingRequested},
     updater::{Updater, run_check_in_background},
 };
 
 actions!(
     vleer,
-    [Quit, ReloadConfig, Scan, ForceScan, CheckForUpdates]
+    [
+        Quit,
+        ReloadConfig,
+        Scan,
+        ForceScan,
+        CheckForUpdates,
+        FocusNext,
+        FocusPrevious,
+        FocusSearch
+    ]
 );
-actions!(player, [PlayPause, Next, Previous]);
+actions!(player, [PlayPause, Next, Previous, JumpToPlaying]);
 
 pub fn register_actions(cx: &mut App) {
     cx.on_action(quit);
@@ -19,10 +29,14 @@ pub fn register_actions(cx: &mut App) {
     cx.on_action(scan);
     cx.on_action(force_scan);
     cx.on_action(check_for_updates);
+    cx.on_action(focus_next);
+    cx.on_action(focus_previous);
+    cx.on_action(focus_search);
 
     cx.on_action(play_pause);
     cx.on_action(next);
     cx.on_action(previous);
+    cx.on_action(jump_to_playing);
 
     cx.bind_keys([KeyBinding::new("secondary-alt-r", ReloadConfig, None)]);
     cx.bind_keys([KeyBinding::new("secondary-w", Quit, None)]);
@@ -30,10 +44,20 @@ pub fn register_actions(cx: &mut App) {
     cx.bind_keys([KeyBinding::new("secondary-r", Scan, None)]);
     cx.bind_keys([KeyBinding::new("secondary-shift-r", ForceScan, None)]);
     cx.bind_keys([KeyBinding::new("secondary-u", CheckForUpdates, None)]);
+    cx.bind_keys([KeyBinding::new("tab", FocusNext, None)]);
+    cx.bind_keys([KeyBinding::new("shift-tab", FocusPrevious, None)]);
+    // Typing `/` in a text field should still insert it.
+    cx.bind_keys([KeyBinding::new("/", FocusSearch, Some("!TextInput"))]);
+    cx.bind_keys([KeyBinding::new(
+        "secondary-f",
+        FocusSearch,
+        Some("!TextInput"),
+    )]);
 
     cx.bind_keys([KeyBinding::new("alt-right", Next, None)]);
     cx.bind_keys([KeyBinding::new("alt-left", Previous, None)]);
     cx.bind_keys([KeyBinding::new("space", PlayPause, None)]);
+    cx.bind_keys([KeyBinding::new("secondary-j", JumpToPlaying, None)]);
 
     debug!("Actions: {:?}", cx.all_action_names());
 }
@@ -50,6 +74,33 @@ fn quit(_: &Quit, cx: &mut App) {
     cx.quit();
 }
 
+fn focus_next(_: &FocusNext, cx: &mut App) {
+    if let Some(window) = cx.active_window() {
+        window
+            .update(cx, |_, window, cx| window.focus_next(cx))
+            .ok();
+    }
+}
+
+fn focus_previous(_: &FocusPrevious, cx: &mut App) {
+    if let Some(window) = cx.active_window() {
+        window
+            .update(cx, |_, window, cx| window.focus_prev(cx))
+            .ok();
+    }
+}
+
+fn focus_search(_: &FocusSearch, cx: &mut App) {
+    if let Some(window) = cx
+        .active_window()
+        .and_then(|window| window.downcast::<MainWindow>())
+    {
+        window
+            .update(cx, |main, window, cx| main.focus_search(window, cx))
+            .ok();
+    }
+}
+
 fn play_pause(_: &PlayPause, cx: &mut App) {
     cx.update_global::<Playback, _>(|playback, cx| {
         playback.play_pause(cx);
@@ -68,6 +119,10 @@ fn next(_: &Next, cx: &mut App) {
     });
 }
 
+fn jump_to_playing(_: &JumpToPlaying, cx: &mut App) {
+    cx.set_global(JumpToPlayingRequested);
+}
+
 fn reload_config(_: &ReloadConfig, cx: &mut App) {
     cx.update_global::<Config, _>(|config, _cx| {
         if let Err(e) = config.reload() {
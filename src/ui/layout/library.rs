@@ -1,3 +1,4 @@
+use crate::data::config::{ClickBehavior, Config};
 use crate::data::db::repo::Database;
 use crate::data::models::{Cuid, PinnedItem};
 use crate::media::playback::Playback;
@@ -5,8 +6,10 @@ use crate::media::queue::Queue;
 use crate::ui::components::context_menu::LibraryDataChanged;
 use crate::ui::components::context_menu::{
     ContextMenu, PinnedItemsChanged, QueueChanged, album_context_menu_items,
-    artist_context_menu_items, playlist_context_menu_items, song_context_menu_items,
+    artist_context_menu_items, create_playlist, playlist_context_menu_items,
+    song_context_menu_items,
 };
+use crate::ui::components::cover::{Cover, image_uri};
 use crate::ui::components::div::flex_row;
 use crate::ui::components::icons::icon;
 use crate::ui::components::scrollbar::ScrollableElement;
@@ -25,7 +28,25 @@ use gpui::prelude::FluentBuilder;
 use gpui::*;
 use tracing::error;
 
-const SEARCH_RESULT_LIMIT: i64 = 20;
+/// Results shown under each search section before "See all".
+const SEARCH_SECTION_LIMIT: usize = 4;
+
+pub const SIDEBAR_WIDTH: f32 = 300.0;
+pub const COLLAPSED_SIDEBAR_WIDTH: f32 = 70.0;
+/// Window width below which the sidebar only shows icons.
+const SIDEBAR_COLLAPSE_BELOW: f32 = 1000.0;
+
+pub fn sidebar_collapsed(window_width: f32) -> bool {
+    window_width < SIDEBAR_COLLAPSE_BELOW
+}
+
+pub fn sidebar_width(window_width: f32) -> f32 {
+    if sidebar_collapsed(window_width) {
+        COLLAPSED_SIDEBAR_WIDTH
+    } else {
+        SIDEBAR_WIDTH
+    }
+}
 
 #[derive(Default)]
 pub struct Search {
@@ -112,7 +133,7 @@ impl Library {
             this._search_task = Some(cx.spawn(async move |this, cx: &mut AsyncApp| {
                 let (results, counts) = bg
                     .spawn(async move {
-                        let r = db.search_library(&query, SEARCH_RESULT_LIMIT);
+                        let r = db.search_library_grouped(&query, SEARCH_SECTION_LIMIT as i64 + 1);
                         let counts = db.get_search_match_counts(&query);
                         (r, counts)
                     })
@@ -167,48 +188,41 @@ impl Library {
             context_menu: cx.new(|_| ContextMenu::new()),
         }
     }
+
+    pub fn focus_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_input
+            .update(cx, |input, cx| input.focus_and_select_all(window, cx));
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn pinned_item(
     id: Cuid,
     name: String,
     image_id: Option<String>,
     item_type: String,
     variables: &Variables,
+    click_behavior: ClickBehavior,
     context_menu: Entity<ContextMenu>,
+    collapsed: bool,
 ) -> impl IntoElement {
     let is_artist = item_type == "Artist";
     let is_album = item_type == "Album";
     let is_playlist = item_type == "Playlist";
+    let is_song = item_type == "Song";
     let item_type_clone = item_type.clone();
     let item_type_for_ctx = item_type.clone();
     let id_clone = id.clone();
     let id_for_ctx = id.clone();
 
-    let cover_element = if let Some(uri) = image_id {
-        let image = img(format!("!image://{}", uri))
-            .size_full()
-            .object_fit(ObjectFit::Cover);
-        if is_artist {
-            image.rounded_full().into_any_element()
-        } else {
-            image.into_any_element()
-        }
-    } else {
-        let placeholder = div().size_full().bg(variables.border);
-        if is_artist {
-            placeholder.rounded_full().into_any_element()
-        } else {
-            placeholder.into_any_element()
-        }
-    };
+    let cover_element = Cover::new(image_id.as_deref().map(image_uri)).rounded(is_artist);
 
     flex_row()
         .group("pinned-item")
         .bg(variables.element)
         .hover(|s| s.bg(variables.element_hover))
         .gap(px(variables.padding_8))
-        .pr(px(variables.padding_8))
+        .when(!collapsed, |this| this.pr(px(variables.padding_8)))
         .when(is_artist, |this| {
             this.rounded_tl(px(18.0)).rounded_bl(px(18.0))
         })
@@ -224,6 +238,23 @@ fn pinned_item(
                     }
                 })
         })
+        .when(is_song && click_behavior != ClickBehavior::Cover, |div| {
+            let song_id = id.clone();
+            div.cursor_pointer()
+                .on_mouse_down(MouseButton::Left, move |event, _window, cx| {
+                    if !click_behavior.plays_on(event.click_count) {
+                        return;
+                    }
+                    cx.update_global::<Queue, _>(|queue, _| {
+                        queue.clear();
+                        queue.add_song(song_id.clone());
+                    });
+                    cx.update_global::<Playback, _>(|playback, cx| {
+                        playback.play_queue(cx);
+                    });
+                    cx.set_global(QueueChanged);
+                })
+        })
         .when(is_playlist, |div| {
             let playlist_id = id.clone();
             div.cursor_pointer()
@@ -326,13 +357,107 @@ fn pinned_item(
                     )
                 }),
         )
-        .child(
-            div()
-                .overflow_x_hidden()
-                .text_ellipsis()
-                .font_weight(FontWeight(500.0))
-                .child(name),
+        .when(!collapsed, |this| {
+            this.child(
+                div()
+                    .overflow_x_hidden()
+                    .text_ellipsis()
+                    .font_weight(FontWeight(500.0))
+                    .child(name),
+            )
+        })
+}
+
+fn search_section_header(
+    title: &'static str,
+    see_all: Option<(usize, AppView)>,
+    variables: &Variables,
+) -> impl IntoElement {
+    flex_row()
+        .justify_between()
+        .items_center()
+        .text_color(variables.text_secondary)
+        .child(title)
+        .when_some(see_all, |this, (count, view)| {
+            this.child(
+                div()
+                    .id(SharedString::from(format!("search-see-all-{title}")))
+                    .cursor_pointer()
+                    .hover(|s| s.text_color(variables.text))
+                    .child(format!("See all {count}"))
+                    .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+                        if let Some(Some(root)) = window.root::<MainWindow>() {
+                            root.update(cx, |main, cx| {
+                                main.set_current_view(view, window, cx);
+                            });
+                        }
+                    }),
+            )
+        })
+}
+
+/// Search results split into a top result followed by one capped section per item type.
+fn search_sections(
+    results: &[PinnedItem],
+    counts: (usize, usize, usize, usize),
+    variables: &Variables,
+    click_behavior: ClickBehavior,
+    context_menu: Entity<ContextMenu>,
+) -> impl IntoElement {
+    let (s_count, al_count, ar_count, p_count) = counts;
+    let item = |item: &PinnedItem| {
+        pinned_item(
+            item.id.clone(),
+            item.name.clone(),
+            item.image_id.clone(),
+            item.item_type.clone(),
+            variables,
+            click_behavior,
+            context_menu.clone(),
+            false,
         )
+    };
+
+    let mut sections = flex_col()
+        .gap(px(variables.padding_16))
+        .pr(px(variables.padding_16))
+        .py(px(variables.padding_16));
+
+    let Some((top, rest)) = results.split_first() else {
+        return sections;
+    };
+    sections = sections.child(
+        flex_col()
+            .gap(px(variables.padding_8))
+            .child(search_section_header("Top result", None, variables))
+            .child(item(top)),
+    );
+
+    for (item_type, title, count, view) in [
+        ("Song", "Songs", s_count, AppView::Songs),
+        ("Album", "Albums", al_count, AppView::Albums),
+        ("Artist", "Artists", ar_count, AppView::Artists),
+        ("Playlist", "Playlists", p_count, AppView::Playlists),
+    ] {
+        let items: Vec<&PinnedItem> = rest
+            .iter()
+            .filter(|i| i.item_type == item_type)
+            .take(SEARCH_SECTION_LIMIT)
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+        let shown = items.len() + usize::from(top.item_type == item_type);
+        let see_all = (count > shown).then_some((count, view));
+        sections = sections.child(
+            flex_col()
+                .gap(px(variables.padding_8))
+                .child(search_section_header(title, see_all, variables))
+                .children(items.into_iter().map(&item)),
+        );
+    }
+
+    sections
 }
 
 impl Render for Library {
@@ -341,6 +466,7 @@ impl Render for Library {
         let search = cx.global::<Search>();
         let query = search.query.trim().to_string();
         let is_searching = !query.is_empty();
+        let read_only = cx.global::<Database>().read_only();
 
         let (s_count, al_count, ar_count, p_count) = if is_searching {
             self.search_counts
@@ -349,19 +475,7 @@ impl Render for Library {
         };
 
         let displayed_items: Vec<PinnedItem> = if is_searching {
-            let song_names: std::collections::HashSet<String> = self
-                .search_results
-                .iter()
-                .filter(|i| i.item_type == "Song")
-                .map(|i| i.name.to_lowercase())
-                .collect();
-            self.search_results
-                .iter()
-                .filter(|i| {
-                    !(i.item_type == "Album" && song_names.contains(&i.name.to_lowercase()))
-                })
-                .cloned()
-                .collect()
+            self.search_results.clone()
         } else {
             self.pinned_items.clone()
         };
@@ -369,6 +483,68 @@ impl Render for Library {
         let has_display = !displayed_items.is_empty();
         let is_search_pending = is_searching && self.search_pending;
         let context_menu = self.context_menu.clone();
+        let click_behavior = cx.global::<Config>().get().interface.click_behavior;
+        let collapsed = sidebar_collapsed(window.bounds().size.width.into());
+
+        if collapsed {
+            return div()
+                .size_full()
+                .min_w_0()
+                .min_h_0()
+                .group("library")
+                .child(div().absolute().size_0().child(context_menu.clone()))
+                .child(
+                    flex_col()
+                        .size_full()
+                        .min_h_0()
+                        .items_center()
+                        .pt(px(variables.padding_16))
+                        .gap(px(variables.padding_16))
+                        .child(
+                            flex_col()
+                                .id("links")
+                                .items_center()
+                                .gap(px(variables.padding_16))
+                                .flex_shrink_0()
+                                .child(NavButton::new(icons::SONGS, None, None, AppView::Songs))
+                                .child(NavButton::new(icons::ALBUM, None, None, AppView::Albums))
+                                .child(NavButton::new(icons::ARTIST, None, None, AppView::Artists))
+                                .child(NavButton::new(
+                                    icons::PLAYLIST,
+                                    None,
+                                    None,
+                                    AppView::Playlists,
+                                )),
+                        )
+                        .when(has_display, |this| {
+                            this.child(
+                                div()
+                                    .flex_1()
+                                    .min_h_0()
+                                    .w_full()
+                                    .overflow_y_scrollbar()
+                                    .child(
+                                        flex_col()
+                                            .items_center()
+                                            .gap(px(variables.padding_8))
+                                            .pb(px(variables.padding_16))
+                                            .children(displayed_items.iter().map(|item| {
+                                                pinned_item(
+                                                    item.id.clone(),
+                                                    item.name.clone(),
+                                                    item.image_id.clone(),
+                                                    item.item_type.clone(),
+                                                    variables,
+                                                    click_behavior,
+                                                    context_menu.clone(),
+                                                    true,
+                                                )
+                                            })),
+                                    ),
+                            )
+                        }),
+                );
+        }
 
         div()
             .size_full()
@@ -457,6 +633,7 @@ impl Render for Library {
                                             .child(
                                                 div()
                                                     .id("create-playlist-btn")
+                                                    .when(read_only, |this| this.hidden())
                                                     .cursor_pointer()
                                                     .child(
                                                         icon(icons::PLUS)
@@ -469,36 +646,7 @@ impl Render for Library {
                                                         MouseButton::Left,
                                                         move |_event, window, cx| {
                                                             cx.stop_propagation();
-                                                            let new_id = Cuid::new();
-                                                            let db =
-                                                                cx.global::<Database>().clone();
-                                                            if let Err(e) = db.upsert_playlist(
-                                                                &new_id, "", None, None, false,
-                                                            ) {
-                                                                tracing::error!(
-                                                                    "Failed to create playlist: {}",
-                                                                    e
-                                                                );
-                                                                return;
-                                                            }
-                                                            cx.update_global::<SelectedPlaylist, _>(
-                                                                |sel, _| {
-                                                                    sel.id = Some(new_id);
-                                                                    sel.focus_title = true;
-                                                                },
-                                                            );
-                                                            cx.set_global(LibraryDataChanged);
-                                                            if let Some(Some(root)) =
-                                                                window.root::<MainWindow>()
-                                                            {
-                                                                root.update(cx, |view, cx| {
-                                                                    view.set_current_view(
-                                                                        AppView::Playlist,
-                                                                        window,
-                                                                        cx,
-                                                                    );
-                                                                });
-                                                            }
+                                                            create_playlist(window, cx);
                                                         },
                                                     ),
                                             ),
@@ -536,7 +684,16 @@ impl Render for Library {
                                         .flex_1()
                                         .min_h_0()
                                         .overflow_y_scrollbar()
-                                        .child(
+                                        .child(if is_searching {
+                                            search_sections(
+                                                &displayed_items,
+                                                self.search_counts,
+                                                variables,
+                                                click_behavior,
+                                                context_menu.clone(),
+                                            )
+                                            .into_any_element()
+                                        } else {
                                             flex_col()
                                                 .gap(px(variables.padding_8))
                                                 .pr(px(variables.padding_16))
@@ -548,10 +705,13 @@ impl Render for Library {
                                                         item.image_id.clone(),
                                                         item.item_type.clone(),
                                                         variables,
+                                                        click_behavior,
                                                         context_menu.clone(),
+                                                        false,
                                                     )
-                                                })),
-                                        )
+                                                }))
+                                                .into_any_element()
+                                        })
                                         .into_any_element()
                                 } else if is_search_pending {
                                     div()
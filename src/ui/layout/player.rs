@@ -1,27 +1,62 @@
 use gpui::*;
 use std::ops::Range;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 use crate::{
-    data::{config::Config, models::Cuid},
+    data::{config::Config, db::repo::Database, models::Cuid},
     media::{
+        now_playing,
         playback::Playback,
         queue::{Queue, RepeatMode},
+        visualizer::SPECTRUM_BARS,
     },
+    status::StatusColor,
     ui::{
         components::{
             button::Button,
             context_menu::{ContextMenu, QueueChanged, song_context_menu_items},
+            cover::{Cover, image_uri},
             div::{flex_col, flex_row},
             icons::{self, icon},
             progress_bar::progress_slider,
             slider::slider,
         },
-        layout::queue::QueueVisible,
+        layout::{navbar::status, queue::QueueVisible},
         variables::Variables,
     },
 };
 
+const PLAYBACK_ERROR_STATUS: &str = "playback.error";
+const PLAYBACK_ERROR_DURATION: Duration = Duration::from_secs(8);
+const REFRESH_INTERVAL: Duration = Duration::from_millis(100);
+/// The spectrum analyzer needs a faster refresh than the progress bar to look fluid.
+const SPECTRUM_REFRESH_INTERVAL: Duration = Duration::from_millis(33);
+const SPECTRUM_HEIGHT: f32 = 24.0;
+const UP_NEXT_COUNT: usize = 3;
+
+fn spectrum_visible(cx: &App) -> bool {
+    let audio = &cx.global::<Config>().get().audio;
+    audio.visualizer && audio.spectrum_analyzer
+}
+
+/// A small bar graph of the spectrum. Only reads the magnitudes the audio thread already
+/// computed, so drawing it costs no more than a few divs.
+fn spectrum_analyzer(bars: [f32; SPECTRUM_BARS], variables: &Variables) -> Div {
+    flex_row()
+        .flex_shrink_0()
+        .h(px(SPECTRUM_HEIGHT))
+        .items_end()
+        .gap(px(1.0))
+        .children(bars.into_iter().map(|value| {
+            div()
+                .w(px(2.0))
+                .h(px((value * SPECTRUM_HEIGHT).max(1.0)))
+                .bg(variables.accent)
+        }))
+}
+
 #[allow(dead_code)]
 struct CachedSong {
     id: Cuid,
@@ -31,8 +66,16 @@ struct CachedSong {
     album_id: Option<Cuid>,
 }
 
+/// A song in the up-next preview: its display index in the queue, id and "title · artist".
+struct UpNextSong {
+    display_idx: usize,
+    id: Cuid,
+    label: String,
+}
+
 pub struct Player {
     cached_song_data: Option<CachedSong>,
+    up_next: Vec<UpNextSong>,
     context_menu: Entity<ContextMenu>,
     hovered_artist: Option<usize>,
 }
@@ -51,9 +94,14 @@ impl Player {
 
         cx.spawn(async move |this, cx: &mut AsyncApp| {
             loop {
-                cx.background_executor()
-                    .timer(Duration::from_millis(100))
-                    .await;
+                let interval = cx.update(|cx| {
+                    if spectrum_visible(cx) {
+                        SPECTRUM_REFRESH_INTERVAL
+                    } else {
+                        REFRESH_INTERVAL
+                    }
+                });
+                cx.background_executor().timer(interval).await;
 
                 let should_refresh = cx.update(|cx| {
                     cx.try_global::<Playback>()
@@ -74,12 +122,89 @@ impl Player {
         })
         .detach();
 
+        Self::show_playback_errors(cx);
+
         Self {
             cached_song_data: None,
+            up_next: Vec::new(),
             context_menu: cx.new(|_| ContextMenu::new()),
             hovered_artist: None,
         }
     }
+
+    /// Refreshes the up-next preview when the songs after the current one changed. Only looks
+    /// the songs up again then, since this runs on every render.
+    fn refresh_up_next(&mut self, cx: &App) {
+        let queue = cx.global::<Queue>();
+        let upcoming = queue.upcoming(UP_NEXT_COUNT);
+        let unchanged = upcoming.len() == self.up_next.len()
+            && upcoming
+                .iter()
+                .zip(&self.up_next)
+                .all(|((idx, id), song)| *idx == song.display_idx && *id == song.id);
+        if unchanged {
+            return;
+        }
+
+        let ids: Vec<Cuid> = upcoming.iter().map(|(_, id)| id.clone()).collect();
+        let mut songs = cx
+            .global::<Database>()
+            .get_songs_by_ids(&ids)
+            .unwrap_or_default();
+        songs.extend(ids.iter().filter_map(|id| queue.transient_song(id)));
+
+        self.up_next = upcoming
+            .into_iter()
+            .filter_map(|(display_idx, id)| {
+                let song = songs.iter().find(|song| song.id == id)?;
+                let label = if song.artists.is_empty() {
+                    song.title.clone()
+                } else {
+                    format!("{} \u{00B7} {}", song.title, song.artists.join(", "))
+                };
+                Some(UpNextSong {
+                    display_idx,
+                    id,
+                    label,
+                })
+            })
+            .collect();
+    }
+
+    /// Shows tracks that failed to open or seek in the status bar, so a failure doesn't look
+    /// like the app ignoring the click.
+    fn show_playback_errors(cx: &mut Context<Self>) {
+        let mut errors = now_playing::subscribe_errors();
+        let executor = cx.background_executor().clone();
+        let generation = Arc::new(AtomicU64::new(0));
+        cx.background_executor()
+            .spawn(async move {
+                while errors.changed().await.is_ok() {
+                    let Some(error) = errors.borrow_and_update().clone() else {
+                        continue;
+                    };
+                    let current = generation.fetch_add(1, Ordering::Relaxed) + 1;
+                    status().set(
+                        PLAYBACK_ERROR_STATUS,
+                        format!("Couldn't play {}: {}", error.title, error.reason),
+                        None,
+                        StatusColor::Destructive,
+                    );
+
+                    let generation = generation.clone();
+                    let timer = executor.timer(PLAYBACK_ERROR_DURATION);
+                    executor
+                        .spawn(async move {
+                            timer.await;
+                            if generation.load(Ordering::Relaxed) == current {
+                                status().clear(PLAYBACK_ERROR_STATUS);
+                            }
+                        })
+                        .detach();
+                }
+            })
+            .detach();
+    }
 }
 
 impl Render for Player {
@@ -96,7 +221,7 @@ impl Render for Player {
             }
 
             let title = song.title.clone();
-            let cover = song.image_id.map(|id| format!("!image://{}", id));
+            let cover = song.image_id.as_deref().map(image_uri);
 
             let artists_vec = if song.artists.is_empty() {
                 vec!["Unknown Artist".to_string()]
@@ -137,6 +262,8 @@ impl Render for Player {
         };
 
         let is_playing = cx.global::<Playback>().get_playing();
+        let spectrum = (is_playing && spectrum_visible(cx))
+            .then(|| cx.global::<Playback>().get_spectrum_bars());
         let volume = cx.global::<Playback>().get_volume();
         let repeat_mode = cx.global::<Queue>().get_repeat_mode();
         let is_shuffle = cx.global::<Queue>().get_shuffle();
@@ -265,15 +392,12 @@ impl Render for Player {
                             });
                         }
                     })
-                    .child(if let Some(uri) = cover_uri {
-                        img(format!("{}?size=50", uri))
+                    .child(
+                        div()
                             .size(px(36.0))
-                            .object_fit(ObjectFit::Cover)
                             .flex_shrink_0()
-                            .into_any_element()
-                    } else {
-                        div().size(px(36.0)).bg(variables.border).into_any_element()
-                    })
+                            .child(Cover::new(cover_uri.map(|uri| format!("{}?size=50", uri)))),
+                    )
                     .child(
                         flex_col()
                             .gap(px(2.0))
@@ -305,6 +429,9 @@ impl Render for Player {
                                     .child(artist_line),
                             ),
                     )
+                    .when_some(spectrum, |row, bars| {
+                        row.child(spectrum_analyzer(bars, variables))
+                    })
                     .into_any_element()
             } else {
                 flex_row()
@@ -354,10 +481,44 @@ impl Render for Player {
                 cx.notify();
             }));
 
+        self.refresh_up_next(cx);
+        let up_next = flex_row()
+            .flex_1()
+            .min_w_0()
+            .gap(px(variables.padding_8))
+            .items_center()
+            .justify_end()
+            .overflow_hidden()
+            .text_sm()
+            .text_color(variables.text_secondary)
+            .when(!self.up_next.is_empty(), |row| row.child("Next"))
+            .children(self.up_next.iter().map(|song| {
+                let display_idx = song.display_idx;
+                div()
+                    .id(("up-next", display_idx))
+                    .max_w(px(160.0))
+                    .min_w_0()
+                    .truncate()
+                    .cursor_pointer()
+                    .hover(|s| s.text_color(variables.text))
+                    .child(song.label.clone())
+                    .on_click(move |_event, _window, cx| {
+                        cx.update_global::<Queue, _>(|queue, cx| {
+                            queue.set_current_index_display(display_idx, cx);
+                        });
+                        cx.update_global::<Playback, _>(|playback, cx| {
+                            playback.play_queue(cx);
+                        });
+                        cx.set_global(QueueChanged);
+                    })
+            }));
+
         let volume_display = flex_row()
+            .w_full()
             .gap(px(variables.padding_8))
             .items_center()
             .justify_end()
+            .child(up_next)
             .child(queue_button)
             .child(icon(volume_icon))
             .child(
@@ -413,10 +574,8 @@ impl Render for Player {
                         if duration > 0.0 {
                             let seek_time = value * duration;
 
-                            cx.update_global::<Playback, _>(|playback, _cx| {
-                                if let Err(e) = playback.seek(seek_time) {
-                                    tracing::error!("Failed to seek: {}", e);
-                                }
+                            cx.update_global::<Playback, _>(|playback, cx| {
+                                playback.seek_reporting_errors(seek_time, cx);
                             });
 
                             window.refresh();
@@ -6,7 +6,10 @@ use crate::data::db::repo::Database;
 use crate::data::models::{Cuid, Song};
 use crate::media::playback::Playback;
 use crate::media::queue::Queue;
-use crate::ui::components::context_menu::{ContextMenu, QueueChanged, song_context_menu_items};
+use crate::ui::components::context_menu::{
+    ContextMenu, ContextMenuItem, QueueChanged, song_context_menu_items,
+};
+use crate::ui::components::cover::{Cover, image_uri};
 use crate::ui::components::div::{flex_col, flex_row};
 use crate::ui::components::icons::{self, icon};
 use crate::ui::components::scrollbar::{Scrollbar, ScrollbarAxis};
@@ -21,6 +24,49 @@ pub struct QueueVisible(pub bool);
 
 impl Global for QueueVisible {}
 
+fn clear_upcoming(cx: &mut App) {
+    cx.update_global::<Queue, _>(|queue, _| queue.clear_after_current());
+    cx.set_global(QueueChanged);
+}
+
+fn clear_queue(cx: &mut App) {
+    cx.update_global::<Playback, _>(|playback, cx| playback.stop(cx));
+}
+
+fn clear_menu_items() -> [ContextMenuItem; 3] {
+    [
+        ContextMenuItem::separator(),
+        ContextMenuItem::entry("Clear upcoming", icons::X, |_, cx| clear_upcoming(cx)),
+        ContextMenuItem::destructive("Clear queue", icons::TRASH, |_, cx| clear_queue(cx)),
+    ]
+}
+
+fn clear_controls(variables: &Variables) -> impl IntoElement {
+    let variables = *variables;
+    let link = move |id: &'static str, label: &'static str, action: fn(&mut App)| {
+        div()
+            .id(id)
+            .cursor_pointer()
+            .hover(move |s| s.text_color(variables.text))
+            .child(label)
+            .on_click(move |_, _, cx| action(cx))
+    };
+
+    flex_row()
+        .flex_shrink_0()
+        .justify_end()
+        .gap(px(variables.padding_16))
+        .px(px(variables.padding_16))
+        .pb(px(variables.padding_8))
+        .text_color(variables.text_secondary)
+        .child(link(
+            "queue-clear-upcoming",
+            "Clear upcoming",
+            clear_upcoming,
+        ))
+        .child(link("queue-clear", "Clear queue", clear_queue))
+}
+
 #[derive(Clone)]
 struct QueueDragPayload {
     from_index: usize,
@@ -47,21 +93,17 @@ impl Render for QueueDragPayload {
             .map(|id| id == song.id)
             .unwrap_or(false);
 
-        let cover = if let Some(ref uri) = song.image_id {
-            img(format!("!image://{}", uri))
-                .size_full()
-                .object_fit(ObjectFit::Cover)
-                .into_any_element()
-        } else {
-            div().bg(variables.border).size_full().into_any_element()
-        };
+        let cover = Cover::new(song.image_id.as_deref().map(image_uri));
 
         let drag_w = QUEUE_WIDTH - variables.padding_16 * 2.0;
 
+        let interface = &cx.global::<Config>().get().interface;
+        let font_size = px(interface.ui_font_size());
+
         div()
-            .font_family("Feature Mono")
-            .text_size(px(14.0))
-            .line_height(px(14.0))
+            .font_family(interface.font_family.clone())
+            .text_size(font_size)
+            .line_height(font_size)
             .pl(pos.x - px(ROW_HEIGHT / 2.0))
             .pt(pos.y - px(ROW_HEIGHT / 2.0))
             .child(
@@ -171,12 +213,16 @@ impl QueuePane {
 
     fn reload_songs(&mut self, cx: &mut Context<Self>) {
         let items: Vec<Cuid> = cx.global::<Queue>().get_items();
+        let transient = cx.global::<Queue>().transient_songs();
         let db = cx.global::<Database>().clone();
 
         cx.spawn(async move |this, cx: &mut AsyncApp| {
             let fetched = db.get_songs_by_ids(&items).unwrap_or_default();
-            let id_to_song: std::collections::HashMap<_, _> =
-                fetched.into_iter().map(|s| (s.id.clone(), s)).collect();
+            let id_to_song: std::collections::HashMap<_, _> = fetched
+                .into_iter()
+                .chain(transient)
+                .map(|s| (s.id.clone(), s))
+                .collect();
             let songs: Vec<Song> = items
                 .iter()
                 .filter_map(|id| id_to_song.get(id).cloned())
@@ -224,14 +270,7 @@ fn render_row(
     let song = song.clone();
     let variables = *variables;
 
-    let cover = if let Some(ref uri) = song.image_id {
-        img(format!("!image://{}", uri))
-            .size_full()
-            .object_fit(ObjectFit::Cover)
-            .into_any_element()
-    } else {
-        div().bg(variables.border).size_full().into_any_element()
-    };
+    let cover = Cover::new(song.image_id.as_deref().map(image_uri));
 
     let drag_payload = QueueDragPayload {
         from_index: display_idx,
@@ -258,7 +297,8 @@ fn render_row(
                 .gap(px(variables.padding_8))
                 .pr(px(variables.padding_8))
                 .on_mouse_down(MouseButton::Right, move |event, _window, cx| {
-                    let items = song_context_menu_items(song_id_for_ctx.clone(), cx);
+                    let mut items = song_context_menu_items(song_id_for_ctx.clone(), cx);
+                    items.extend(clear_menu_items());
                     context_menu.update(cx, |menu, cx| {
                         menu.show(event.position, items, cx);
                     });
@@ -527,72 +567,79 @@ impl Render for QueuePane {
             .when(!is_empty, |this| {
                 let scroll_handle = self.scroll_handle.clone();
                 this.child(
-                    div()
+                    flex_col()
                         .size_full()
                         .min_h_0()
-                        .relative()
-                        .child(
-                            div().size_full().child(
-                                uniform_list(
-                                    ElementId::Name("queue-list".into()),
-                                    row_count,
-                                    move |range, _window, _cx| {
-                                        range
-                                            .map(|display_idx| {
-                                                let real_idx = display_order[display_idx];
-                                                let song = &songs[real_idx];
-                                                let is_current = current_song_id
-                                                    .as_ref()
-                                                    .map(|id| id == &song.id)
-                                                    .unwrap_or(false);
-
-                                                let is_slot = drag_from == Some(display_idx);
-
-                                                if is_slot {
-                                                    render_drop_slot(
-                                                        &view_handle,
-                                                        display_idx,
-                                                        &variables,
-                                                    )
-                                                    .into_any_element()
-                                                } else {
-                                                    render_row(
-                                                        &view_handle,
-                                                        RowState {
-                                                            display_idx,
-                                                            song,
-                                                            is_current,
-                                                            spectrum,
-                                                            visualizer_enabled,
-                                                        },
-                                                        &variables,
-                                                        context_menu.clone(),
-                                                    )
-                                                    .into_any_element()
-                                                }
-                                            })
-                                            .collect()
-                                    },
-                                )
-                                .track_scroll(&scroll_handle)
-                                .size_full()
-                                .pt(px(variables.padding_16))
-                                .pb(px(variables.padding_16 - variables.padding_8)),
-                            ),
-                        )
                         .child(
                             div()
-                                .absolute()
-                                .top_0()
-                                .right_0()
-                                .bottom_0()
-                                .left_0()
+                                .flex_1()
+                                .min_h_0()
+                                .relative()
                                 .child(
-                                    Scrollbar::new(&self.scroll_handle)
-                                        .axis(ScrollbarAxis::Vertical),
-                                ),
+                                    div().size_full().child(
+                                        uniform_list(
+                                            ElementId::Name("queue-list".into()),
+                                            row_count,
+                                            move |range, _window, _cx| {
+                                                range
+                                                    .map(|display_idx| {
+                                                        let real_idx = display_order[display_idx];
+                                                        let song = &songs[real_idx];
+                                                        let is_current = current_song_id
+                                                            .as_ref()
+                                                            .map(|id| id == &song.id)
+                                                            .unwrap_or(false);
+
+                                                        let is_slot =
+                                                            drag_from == Some(display_idx);
+
+                                                        if is_slot {
+                                                            render_drop_slot(
+                                                                &view_handle,
+                                                                display_idx,
+                                                                &variables,
+                                                            )
+                                                            .into_any_element()
+                                                        } else {
+                                                            render_row(
+                                                                &view_handle,
+                                                                RowState {
+                                                                    display_idx,
+                                                                    song,
+                                                                    is_current,
+                                                                    spectrum,
+                                                                    visualizer_enabled,
+                                                                },
+                                                                &variables,
+                                                                context_menu.clone(),
+                                                            )
+                                                            .into_any_element()
+                                                        }
+                                                    })
+                                                    .collect()
+                                            },
+                                        )
+                                        .track_scroll(&scroll_handle)
+                                        .size_full()
+                                        .pt(px(variables.padding_16))
+                                        .pb(px(variables.padding_16 - variables.padding_8)),
+                                    ),
+                                )
+                                .child(
+                                    div()
+                                        .absolute()
+                                        .top_0()
+                                        .right_0()
+                                        .bottom_0()
+                                        .left_0()
+                                        .child(
+                                            Scrollbar::new(&self.scroll_handle)
+                                                .axis(ScrollbarAxis::Vertical),
+                                        ),
+                                )
+                                .child(div().absolute().size_0().child(self.context_menu.clone())),
                         )
-                        .child(div().absolute().size_0().child(self.context_menu.clone())),
+                        .child(clear_controls(&variables)),
                 )
             })
     }
@@ -1,5 +1,6 @@
 pub mod app;
 pub mod assets;
+pub mod bidi;
 pub mod components;
 pub mod discord_presence;
 pub mod global_actions;
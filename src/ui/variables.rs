@@ -14,6 +14,7 @@ pub struct Variables {
     pub text_muted: Rgba,
     pub destructive: Rgba,
     pub warning: Rgba,
+    pub focus_ring: Rgba,
 
     pub padding_8: f32,
     pub padding_16: f32,
@@ -36,6 +37,7 @@ impl Default for Variables {
             text_muted: rgb(0x303030),
             destructive: rgb(0xFF5858),
             warning: rgb(0xFFAA2A),
+            focus_ring: rgb(0xA058FF),
 
             padding_8: 8.0,
             padding_16: 16.0,
@@ -1,39 +1,42 @@
 use gpui::*;
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::sync::Arc;
 
 use crate::{
     data::{
         db::repo::Database,
+        metadata::online::{self, PlannedRetag, Release},
         models::{Album, Cuid},
+        scanner::Scanner,
     },
-    media::{
-        playback::{Playback, play_album_now},
-        queue::Queue,
-    },
+    media::playback::{play_album_now, play_song_ids_shuffled},
     ui::{
         components::{
             button::Button,
-            context_menu::{
-                ContextMenu, LibraryDataChanged, QueueChanged, album_context_menu_items,
-            },
+            context_menu::{ContextMenu, LibraryDataChanged, album_context_menu_items},
+            cover::{self, Cover, image_uri},
             div::{flex_col, flex_row},
             icons,
-            song_table::{
-                GetRowCountHandler, GetRowHandler, QueueHandler, SongEntry, SongTable,
-                SongTableEvent, join_artists,
-            },
+            song_table::{JumpToPlayingRequested, SongTable, SongTableEvent, join_artists},
         },
         variables::Variables,
         views::{ActiveView, AppView, SelectedAlbum},
     },
 };
 
-type SongCache = Rc<RefCell<Vec<Arc<SongEntry>>>>;
-
 type ArtistInfo = (String, Option<String>);
 
+const RETAG_PREVIEW_ROWS: usize = 50;
+
+enum RetagState {
+    Idle,
+    LookingUp,
+    NotFound,
+    Failed,
+    Preview {
+        release: Release,
+        planned: Vec<PlannedRetag>,
+    },
+}
+
 pub struct AlbumView {
     album_id: Option<Cuid>,
     album: Option<Album>,
@@ -44,80 +47,43 @@ pub struct AlbumView {
     year: Option<String>,
     genres: Vec<String>,
     total_duration_secs: i32,
-    songs_cache: SongCache,
+    missing_tracks: Vec<(i32, i32)>,
     load_task: Option<Task<()>>,
     table: Entity<SongTable>,
     context_menu: Entity<ContextMenu>,
+    retag: RetagState,
 }
 
-fn song_entry_from_song(song: &crate::data::models::Song) -> Arc<SongEntry> {
-    let artists = if song.artists.is_empty() {
-        vec!["Unknown".to_string()]
-    } else {
-        song.artists.clone()
-    };
-    let (artist, artist_ranges) = join_artists(&artists);
-    let minutes = song.duration / 60;
-    let seconds = song.duration % 60;
-    Arc::new(SongEntry {
-        id: song.id.clone(),
-        title: song.title.clone(),
-        artist,
-        artist_ranges,
-        album: String::new(),
-        album_id: song.album_id.clone(),
-        duration: format!("{}:{:02}", minutes, seconds),
-        cover_uri: song.image_id.clone().map(|id| format!("!image://{}", id)),
-        track_number: song.track_number,
-        genre: String::new(),
-    })
+/// Lists missing tracks by number, prefixed with the disc when the album spans several.
+fn missing_tracks_line(missing: &[(i32, i32)]) -> Option<String> {
+    if missing.is_empty() {
+        return None;
+    }
+    let multi_disc = missing.iter().any(|(disc, _)| *disc != 1);
+    let tracks = missing
+        .iter()
+        .map(|(disc, track)| {
+            if multi_disc {
+                format!("{}-{}", disc, track)
+            } else {
+                track.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(format!("Incomplete \u{00B7} missing tracks {}", tracks))
 }
 
 impl AlbumView {
     pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
-        let songs_cache: SongCache = Rc::new(RefCell::new(Vec::new()));
-
-        let get_row_count: GetRowCountHandler = {
-            let cache = songs_cache.clone();
-            Rc::new(move |_cx, _sort| cache.borrow().len())
-        };
-
-        let get_row: GetRowHandler = {
-            let cache = songs_cache.clone();
-            Rc::new(move |_cx, idx, _sort| cache.borrow().get(idx).cloned())
-        };
+        let table = SongTable::for_album(cx);
 
-        let queue_handler: QueueHandler = {
-            let cache = songs_cache.clone();
-            Rc::new(move |cx, current_id, index, _sort| {
-                let rest: Vec<Cuid> = {
-                    let cache = cache.borrow();
-                    if cache.get(index).map(|e| &e.id) != Some(&current_id) {
-                        return;
-                    }
-                    cache.iter().skip(index + 1).map(|e| e.id.clone()).collect()
-                };
-                if rest.is_empty() {
-                    return;
-                }
-                cx.update_global::<Queue, _>(|q, _| {
-                    q.add_songs(rest);
-                });
-                cx.set_global(QueueChanged);
-            })
-        };
-
-        let table = SongTable::new(
-            cx,
-            get_row_count,
-            get_row,
-            Some(queue_handler),
-            None,
-            true,
-            false,
-            false,
-            false,
-        );
+        cx.subscribe(&table, |this, table, event: &SongTableEvent, cx| {
+            if let SongTableEvent::NewRows = event {
+                this.summarize_songs(&table, cx);
+            }
+        })
+        .detach();
 
         let mut view = Self {
             album_id: cx.global::<SelectedAlbum>().0.clone(),
@@ -129,10 +95,11 @@ impl AlbumView {
             year: None,
             genres: Vec::new(),
             total_duration_secs: 0,
-            songs_cache,
+            missing_tracks: Vec::new(),
             load_task: None,
             table,
             context_menu: cx.new(|_| ContextMenu::new()),
+            retag: RetagState::Idle,
         };
 
         if cx.global::<ActiveView>().0 == AppView::Album {
@@ -145,6 +112,7 @@ impl AlbumView {
                 return;
             }
             this.album_id = new_id;
+            this.retag = RetagState::Idle;
             this.reload(cx);
         })
         .detach();
@@ -155,6 +123,9 @@ impl AlbumView {
             }
             let new_id = cx.global::<SelectedAlbum>().0.clone();
             if new_id != this.album_id || this.album.is_none() {
+                if new_id != this.album_id {
+                    this.retag = RetagState::Idle;
+                }
                 this.album_id = new_id;
                 this.reload(cx);
             }
@@ -166,22 +137,27 @@ impl AlbumView {
         })
         .detach();
 
+        cx.observe_global::<JumpToPlayingRequested>(|this, cx| {
+            if cx.global::<ActiveView>().0 == AppView::Album {
+                this.table.update(cx, |table, cx| table.jump_to_playing(cx));
+            }
+        })
+        .detach();
+
         view
     }
 
     fn reload(&mut self, cx: &mut Context<Self>) {
-        let Some(album_id) = self.album_id.clone() else {
+        let album_id = self.album_id.clone();
+        self.table
+            .update(cx, |table, cx| table.load(album_id.clone(), cx));
+        let Some(album_id) = album_id else {
             self.album = None;
             self.artist_id = None;
             self.artist_name = None;
             self.artist_image_id = None;
             self.artists_data = Vec::new();
-            self.year = None;
-            self.genres = Vec::new();
-            self.total_duration_secs = 0;
-            self.songs_cache.borrow_mut().clear();
-            let table = self.table.clone();
-            cx.update_entity(&table, |_table, cx| cx.emit(SongTableEvent::NewRows));
+            self.missing_tracks = Vec::new();
             cx.notify();
             return;
         };
@@ -191,52 +167,30 @@ impl AlbumView {
 
         let task = cx.spawn(async move |this, cx: &mut AsyncApp| {
             let id_for = album_id.clone();
-            let (album, artist_id, artist_name, artist_image_id, year, songs, artists_data) = bg
+            let (album, artist_id, artist_name, artist_image_id, artists_data, missing_tracks) = bg
                 .spawn(async move {
                     let album = db.get_album(&id_for).ok().flatten();
-                    let songs = db.get_album_songs(&id_for).unwrap_or_default();
-                    let primary_artist_name = album
-                        .as_ref()
-                        .and_then(|a| a.artists.first().cloned())
-                        .or_else(|| songs.first().and_then(|s| s.artists.first().cloned()));
-                    let artist = primary_artist_name
-                        .as_deref()
-                        .and_then(|name| db.get_artist_by_name(name).ok().flatten());
-                    let artist_id = artist.as_ref().map(|a| a.id.clone());
-                    let artist_image_id = artist.as_ref().and_then(|a| a.image_id.clone());
-                    let artist_name = album
-                        .as_ref()
-                        .map(|a| {
-                            if a.artists.is_empty() {
-                                String::new()
-                            } else {
-                                join_artists(&a.artists).0
-                            }
-                        })
-                        .filter(|s| !s.is_empty())
-                        .or_else(|| {
-                            songs
-                                .first()
-                                .map(|s| {
-                                    if s.artists.is_empty() {
-                                        String::new()
-                                    } else {
-                                        join_artists(&s.artists).0
-                                    }
-                                })
-                                .filter(|s| !s.is_empty())
-                        });
+                    let missing_tracks = db.get_album_missing_tracks(&id_for).unwrap_or_default();
+                    // Albums without album artists are credited to their first song's artists.
                     let artist_names_list: Vec<String> = album
                         .as_ref()
                         .map(|a| a.artists.clone())
                         .filter(|a| !a.is_empty())
                         .or_else(|| {
-                            songs
-                                .first()
-                                .map(|s| s.artists.clone())
+                            db.get_album_songs(&id_for)
+                                .ok()
+                                .and_then(|songs| songs.into_iter().next())
+                                .map(|s| s.artists)
                                 .filter(|a| !a.is_empty())
                         })
                         .unwrap_or_default();
+                    let artist = artist_names_list
+                        .first()
+                        .and_then(|name| db.get_artist_by_name(name).ok().flatten());
+                    let artist_id = artist.as_ref().map(|a| a.id.clone());
+                    let artist_image_id = artist.as_ref().and_then(|a| a.image_id.clone());
+                    let artist_name =
+                        Some(join_artists(&artist_names_list).0).filter(|s| !s.is_empty());
                     let mut artists_data: Vec<ArtistInfo> = Vec::new();
                     for name in &artist_names_list {
                         let img = db
@@ -246,19 +200,13 @@ impl AlbumView {
                             .and_then(|a| a.image_id.clone());
                         artists_data.push((name.clone(), img));
                     }
-                    let year = songs
-                        .iter()
-                        .find_map(|s| s.date.clone())
-                        .map(|d| d.chars().take(4).collect::<String>())
-                        .filter(|y| !y.is_empty());
                     (
                         album,
                         artist_id,
                         artist_name,
                         artist_image_id,
-                        year,
-                        songs,
                         artists_data,
+                        missing_tracks,
                     )
                 })
                 .await;
@@ -273,24 +221,7 @@ impl AlbumView {
                     this.artist_name = artist_name;
                     this.artist_image_id = artist_image_id;
                     this.artists_data = artists_data;
-                    this.year = year;
-                    this.total_duration_secs = songs.iter().map(|s| s.duration).sum();
-                    {
-                        let mut seen = std::collections::BTreeSet::new();
-                        for s in &songs {
-                            for g in &s.genres {
-                                seen.insert(g.clone());
-                            }
-                        }
-                        this.genres = seen.into_iter().collect();
-                    }
-                    {
-                        let mut cache = this.songs_cache.borrow_mut();
-                        cache.clear();
-                        cache.extend(songs.iter().map(song_entry_from_song));
-                    }
-                    let table = this.table.clone();
-                    cx.update_entity(&table, |_t, cx| cx.emit(SongTableEvent::NewRows));
+                    this.missing_tracks = missing_tracks;
                     cx.notify();
                 })
             })
@@ -300,6 +231,228 @@ impl AlbumView {
         self.load_task = Some(task);
     }
 
+    /// Takes the year, genres and length shown in the header from the songs the table loaded.
+    fn summarize_songs(&mut self, table: &Entity<SongTable>, cx: &mut Context<Self>) {
+        let songs = table.read(cx).songs();
+        self.year = songs
+            .iter()
+            .find_map(|s| s.date.clone())
+            .map(|d| d.chars().take(4).collect::<String>())
+            .filter(|y| !y.is_empty());
+        self.total_duration_secs = songs.iter().map(|s| s.duration).sum();
+        let mut seen = std::collections::BTreeSet::new();
+        for s in songs {
+            for g in &s.genres {
+                seen.insert(g.clone());
+            }
+        }
+        self.genres = seen.into_iter().collect();
+        cx.notify();
+    }
+
+    fn look_up_release(&mut self, cx: &mut Context<Self>) {
+        let (Some(album), Some(album_id)) = (self.album.clone(), self.album_id.clone()) else {
+            return;
+        };
+        self.retag = RetagState::LookingUp;
+        cx.notify();
+
+        let db = cx.global::<Database>().clone();
+        let bg = cx.background_executor().clone();
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let looked_up_id = album_id.clone();
+            let lookup = bg
+                .spawn(async move {
+                    let songs = db.get_album_songs(&album_id)?;
+                    let artist = album.artists.first().map(String::as_str);
+                    let release = online::find_release(&album.title, artist, songs.len())?;
+                    anyhow::Ok(release.map(|release| {
+                        let planned = online::plan_retag(&songs, &release);
+                        (release, planned)
+                    }))
+                })
+                .await;
+
+            let state = match lookup {
+                Ok(Some((release, planned))) if !planned.is_empty() => {
+                    RetagState::Preview { release, planned }
+                }
+                Ok(_) => RetagState::NotFound,
+                Err(e) => {
+                    tracing::error!("Failed to look up album online: {}", e);
+                    RetagState::Failed
+                }
+            };
+
+            this.update(cx, |this, cx| {
+                if this.album_id.as_ref() == Some(&looked_up_id)
+                    && matches!(this.retag, RetagState::LookingUp)
+                {
+                    this.retag = state;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn apply_retag(&mut self, cx: &mut Context<Self>) {
+        let RetagState::Preview { planned, .. } =
+            std::mem::replace(&mut self.retag, RetagState::Idle)
+        else {
+            return;
+        };
+        cx.notify();
+
+        let scanner = cx.global::<Scanner>().clone();
+        let db = cx.global::<Database>().clone();
+        let bg = cx.background_executor().clone();
+        let first_path = planned
+            .first()
+            .map(|retag| retag.path.to_string_lossy().to_string());
+        cx.spawn(async move |_this, cx: &mut AsyncApp| {
+            // A new album title means a new album, so follow the songs there.
+            let moved_to = bg
+                .spawn(async move {
+                    scanner.apply_retag(&db, planned).await?;
+                    anyhow::Ok(
+                        first_path
+                            .and_then(|path| db.get_song_by_path(&path).ok().flatten())
+                            .and_then(|song| song.album_id),
+                    )
+                })
+                .await;
+            match moved_to {
+                Ok(Some(album_id)) => cx.update(|cx| {
+                    if cx.global::<SelectedAlbum>().0.as_ref() != Some(&album_id) {
+                        cx.set_global(SelectedAlbum(Some(album_id)));
+                    }
+                }),
+                Ok(None) => {}
+                Err(e) => tracing::error!("Failed to re-tag album: {}", e),
+            }
+        })
+        .detach();
+    }
+
+    fn retag_element(&self, cx: &Context<Self>) -> Option<AnyElement> {
+        let variables = cx.global::<Variables>();
+        let message = match &self.retag {
+            RetagState::Idle => return None,
+            RetagState::LookingUp => "Looking up album on MusicBrainz...",
+            RetagState::NotFound => "No matching release found on MusicBrainz",
+            RetagState::Failed => "Couldn't reach MusicBrainz",
+            RetagState::Preview { release, planned } => {
+                return Some(self.retag_preview(release, planned, cx));
+            }
+        };
+        Some(
+            div()
+                .text_color(variables.text_secondary)
+                .child(message)
+                .into_any_element(),
+        )
+    }
+
+    fn retag_preview(
+        &self,
+        release: &Release,
+        planned: &[PlannedRetag],
+        cx: &Context<Self>,
+    ) -> AnyElement {
+        let variables = cx.global::<Variables>();
+        let song_count = self.table.read(cx).songs().len();
+        let heading = match &release.date {
+            Some(date) => format!(
+                "{} \u{00B7} {} \u{00B7} {}",
+                release.title, release.artist, date
+            ),
+            None => format!("{} \u{00B7} {}", release.title, release.artist),
+        };
+
+        flex_col()
+            .items_start()
+            .gap(px(variables.padding_8))
+            .child(div().text_color(variables.text).child(heading))
+            .child(div().text_color(variables.text_secondary).child(format!(
+                "{} of {} songs matched",
+                planned.len(),
+                song_count
+            )))
+            .children(planned.iter().take(RETAG_PREVIEW_ROWS).map(|retag| {
+                flex_row()
+                    .max_w(px(650.0))
+                    .gap(px(variables.padding_8))
+                    .child(
+                        div()
+                            .flex_shrink_0()
+                            .text_color(variables.text_secondary)
+                            .child(format!("{}-{:02}", retag.disc_number, retag.track_number)),
+                    )
+                    .child(
+                        div()
+                            .min_w_0()
+                            .text_color(variables.text_secondary)
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .child(retag.old_title.clone()),
+                    )
+                    .child(
+                        div()
+                            .min_w_0()
+                            .text_color(variables.text)
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .child(format!(
+                                "\u{2192} {} \u{00B7} {}",
+                                retag.title, retag.artist
+                            )),
+                    )
+            }))
+            .when(planned.len() > RETAG_PREVIEW_ROWS, |this| {
+                this.child(
+                    div()
+                        .text_color(variables.text_secondary)
+                        .child(format!("and {} more", planned.len() - RETAG_PREVIEW_ROWS)),
+                )
+            })
+            .child(
+                flex_row()
+                    .gap(px(variables.padding_8))
+                    .child(
+                        div()
+                            .id("retag-apply")
+                            .cursor_pointer()
+                            .px(px(variables.padding_16))
+                            .py(px(variables.padding_8))
+                            .bg(variables.accent)
+                            .text_color(variables.background)
+                            .hover(|s| s.bg(variables.accent_hover))
+                            .child("Write tags")
+                            .on_click(cx.listener(|this, _event, _window, cx| {
+                                this.apply_retag(cx);
+                            })),
+                    )
+                    .child(
+                        div()
+                            .id("retag-cancel")
+                            .cursor_pointer()
+                            .px(px(variables.padding_16))
+                            .py(px(variables.padding_8))
+                            .bg(variables.element)
+                            .text_color(variables.text)
+                            .hover(|s| s.bg(variables.element_hover))
+                            .child("Cancel")
+                            .on_click(cx.listener(|this, _event, _window, cx| {
+                                this.retag = RetagState::Idle;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
+
     fn total_duration_string(&self) -> String {
         let total = self.total_duration_secs;
         let hours = total / 3600;
@@ -320,26 +473,19 @@ impl Render for AlbumView {
 
         let body = if let Some(album) = self.album.clone() {
             let cover_size = 220.0_f32;
-            let image: AnyElement = match album.image_id.clone() {
-                Some(uri) => img(format!("!image://{}", uri))
-                    .id("album-cover")
-                    .size(px(cover_size))
-                    .object_fit(ObjectFit::Cover)
-                    .into_any_element(),
-                None => div()
-                    .id("album-cover-placeholder")
-                    .size(px(cover_size))
-                    .bg(variables.border)
-                    .into_any_element(),
-            };
+            let image = div()
+                .id("album-cover")
+                .size(px(cover_size))
+                .flex_shrink_0()
+                .child(Cover::new(album.image_id.as_deref().map(image_uri)));
 
-            let song_count = self.songs_cache.borrow().len();
+            let song_count = self.table.read(cx).songs().len();
             let duration = self.total_duration_string();
             let year = self.year.clone();
             let album_id_play = album.id.clone();
             let album_id_menu = album.id.clone();
             let menu_for_button = context_menu.clone();
-            let songs_for_shuffle = self.songs_cache.clone();
+            let table_for_shuffle = self.table.clone();
             let _artist_id = self.artist_id.clone();
 
             let genres_str = if self.genres.is_empty() {
@@ -371,6 +517,15 @@ impl Render for AlbumView {
                         .child(album.title.clone()),
                 )
                 .child(div().text_color(variables.text_secondary).child(meta_line))
+                .children(missing_tracks_line(&self.missing_tracks).map(|line| {
+                    div()
+                        .px(px(variables.padding_8))
+                        .py(px(variables.padding_8))
+                        .border_1()
+                        .border_color(variables.warning)
+                        .text_color(variables.warning)
+                        .child(line)
+                }))
                 .child(
                     flex_row()
                         .gap(px(variables.padding_8))
@@ -394,23 +549,13 @@ impl Render for AlbumView {
                                 .items_center()
                                 .gap(px(variables.padding_8))
                                 .on_click(move |_event, _window, cx| {
-                                    let song_ids: Vec<Cuid> = songs_for_shuffle
-                                        .borrow()
+                                    let song_ids: Vec<Cuid> = table_for_shuffle
+                                        .read(cx)
+                                        .songs()
                                         .iter()
-                                        .map(|e| e.id.clone())
+                                        .map(|s| s.id.clone())
                                         .collect();
-                                    if song_ids.is_empty() {
-                                        return;
-                                    }
-                                    cx.update_global::<Queue, _>(|queue, _| {
-                                        queue.clear();
-                                        queue.add_songs(song_ids);
-                                        queue.set_shuffle(true);
-                                    });
-                                    cx.update_global::<Playback, _>(|playback, cx| {
-                                        playback.play_queue(cx);
-                                    });
-                                    cx.set_global(QueueChanged);
+                                    play_song_ids_shuffled(song_ids, cx);
                                 }),
                         )
                         .child(
@@ -424,8 +569,17 @@ impl Render for AlbumView {
                                         menu.show(event.position(), items, cx);
                                     });
                                 }),
+                        )
+                        .child(
+                            Button::new("album-retag-button")
+                                .items_center()
+                                .child("Fix tags online")
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.look_up_release(cx);
+                                })),
                         ),
-                );
+                )
+                .children(self.retag_element(cx));
 
             let artists_data = self.artists_data.clone();
 
@@ -449,18 +603,10 @@ impl Render for AlbumView {
                                         .rounded_full()
                                         .relative()
                                         .overflow_hidden()
-                                        .child(match image_uri {
-                                            Some(uri) => img(format!("!image://{}", uri))
-                                                .size_full()
-                                                .rounded_full()
-                                                .object_fit(ObjectFit::Cover)
-                                                .into_any_element(),
-                                            None => div()
-                                                .size_full()
-                                                .rounded_full()
-                                                .bg(variables.border)
-                                                .into_any_element(),
-                                        }),
+                                        .child(
+                                            Cover::new(image_uri.as_deref().map(cover::image_uri))
+                                                .rounded(true),
+                                        ),
                                 )
                                 .child(
                                     div()
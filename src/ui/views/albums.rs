@@ -4,17 +4,24 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use std::rc::Rc;
 
 use crate::{
-    data::{db::repo::Database, models::AlbumListItem},
+    data::{
+        db::repo::Database,
+        models::{AlbumListItem, AlbumSort},
+    },
     ui::{
         app::MainWindow,
         components::{
             card::{ArtistHoverHandler, CARD_GRID_GAP, Card, calculate_card_layout},
             context_menu::{ContextMenu, LibraryDataChanged, album_context_menu_items},
             div::{flex_col, flex_row},
+            icons::{self, icon},
             scrollbar::{Scrollbar, ScrollbarAxis, ScrollbarHandle},
             song_table::format_artist_line,
         },
-        layout::{library::Search, queue::QueueVisible},
+        layout::{
+            library::{Search, sidebar_width},
+            queue::QueueVisible,
+        },
         variables::Variables,
         views::{ActiveView, AppView, SelectedAlbum},
     },
@@ -35,6 +42,8 @@ pub struct AlbumsView {
     scroll_handle: UniformListScrollHandle,
     context_menu: Entity<ContextMenu>,
     hovered_artist: Option<(String, usize)>,
+    sort: AlbumSort,
+    ascending: bool,
 }
 
 impl AlbumsView {
@@ -48,6 +57,7 @@ impl AlbumsView {
         let db = cx.global::<Database>().clone();
         let bg = cx.background_executor().clone();
         let page_size = self.page_size as i64;
+        let (sort, ascending) = (self.sort, self.ascending);
 
         let task = cx.spawn(async move |this, cx: &mut AsyncApp| {
             let query_for_spawn = query.clone();
@@ -55,7 +65,7 @@ impl AlbumsView {
                 .spawn(async move {
                     let count = db.get_albums_count(&query_for_spawn).unwrap_or(0);
                     let first_page = if count > 0 {
-                        db.get_albums(&query_for_spawn, 0, page_size)
+                        db.get_albums(&query_for_spawn, sort, ascending, 0, page_size)
                             .unwrap_or_default()
                     } else {
                         Vec::new()
@@ -125,6 +135,8 @@ impl AlbumsView {
             scroll_handle: UniformListScrollHandle::default(),
             context_menu: cx.new(|_| ContextMenu::new()),
             hovered_artist: None,
+            sort: AlbumSort::default(),
+            ascending: true,
         };
 
         if cx.global::<ActiveView>().0 == AppView::Albums {
@@ -152,18 +164,34 @@ impl AlbumsView {
         .detach();
 
         cx.observe_global::<LibraryDataChanged>(|this, cx| {
-            this.page_cache.clear();
-            this.page_pending.clear();
-            this.pending_query = None;
-            this.query_version = this.query_version.wrapping_add(1);
-            let query = this.last_query.clone();
-            this.request_query(query, cx);
+            this.invalidate(cx);
         })
         .detach();
 
         view
     }
 
+    fn invalidate(&mut self, cx: &mut Context<Self>) {
+        self.page_cache.clear();
+        self.page_pending.clear();
+        self.pending_query = None;
+        self.query_version = self.query_version.wrapping_add(1);
+        let query = self.last_query.clone();
+        self.request_query(query, cx);
+    }
+
+    fn set_sort(&mut self, sort: AlbumSort, cx: &mut Context<Self>) {
+        if self.sort == sort {
+            self.ascending = !self.ascending;
+        } else {
+            self.sort = sort;
+            self.ascending = true;
+        }
+        self.scroll_handle.scroll_to_item(0, ScrollStrategy::Top);
+        self.invalidate(cx);
+        cx.notify();
+    }
+
     fn request_query(&mut self, query: String, cx: &mut Context<Self>) {
         if query == self.last_query && self.pending_query.is_none() && !self.page_cache.is_empty() {
             return;
@@ -193,12 +221,13 @@ impl AlbumsView {
         let query_version = self.query_version;
         let page_size = self.page_size;
         let offset = (page * page_size) as i64;
+        let (sort, ascending) = (self.sort, self.ascending);
 
         cx.spawn(async move |this, cx: &mut AsyncApp| {
             let query_for_spawn = query.clone();
             let albums = bg
                 .spawn(async move {
-                    db.get_albums(&query_for_spawn, offset, page_size as i64)
+                    db.get_albums(&query_for_spawn, sort, ascending, offset, page_size as i64)
                         .unwrap_or_default()
                 })
                 .await;
@@ -321,7 +350,7 @@ impl Render for AlbumsView {
 
         let bounds = window.bounds();
         let window_width: f32 = bounds.size.width.into();
-        let mut estimated_width = window_width - 300.0 - 98.0;
+        let mut estimated_width = window_width - sidebar_width(window_width) - 98.0;
         if queue_visible.0 {
             estimated_width -= 316.0;
         }
@@ -437,8 +466,45 @@ impl Render for AlbumsView {
                 .into_any_element()
         };
 
+        let sort_options = [
+            (AlbumSort::Title, "Title"),
+            (AlbumSort::Year, "Year"),
+            (AlbumSort::Artist, "Artist"),
+            (AlbumSort::DateAdded, "Date Added"),
+        ];
+        let sort_bar = flex_row()
+            .flex_shrink_0()
+            .px(px(variables.padding_24))
+            .pt(px(variables.padding_24))
+            .gap(px(variables.padding_16))
+            .text_color(variables.text_secondary)
+            .children(sort_options.into_iter().map(|(sort, label)| {
+                let is_sorted = self.sort == sort;
+                flex_row()
+                    .id(ElementId::Name(format!("albums-sort-{}", label).into()))
+                    .gap(px(variables.padding_8))
+                    .items_center()
+                    .cursor_pointer()
+                    .hover(|s| s.text_color(variables.text))
+                    .when(is_sorted, |this| {
+                        let arrow_icon = if self.ascending {
+                            icon(icons::ARROW_UP)
+                        } else {
+                            icon(icons::ARROW_DOWN)
+                        };
+                        this.text_color(variables.text)
+                            .child(label)
+                            .child(arrow_icon.text_color(variables.text))
+                    })
+                    .when(!is_sorted, |this| this.child(label))
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.set_sort(sort, cx);
+                    }))
+            }));
+
         flex_col()
             .size_full()
+            .child(sort_bar)
             .child(
                 div()
                     .id("albums-scroll-container")
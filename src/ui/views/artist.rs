@@ -0,0 +1,366 @@
+use gpui::{prelude::FluentBuilder, *};
+
+use crate::{
+    data::{
+        db::repo::Database,
+        models::{AlbumListItem, Artist, ArtistListItem, Cuid},
+    },
+    media::playback::{play_album_now, play_artist_now, shuffle_artist_now},
+    ui::{
+        app::MainWindow,
+        components::{
+            button::Button,
+            card::{CARD_MIN_IMAGE_SIZE, Card},
+            context_menu::{
+                ContextMenu, LibraryDataChanged, album_context_menu_items,
+                artist_context_menu_items,
+            },
+            cover::{Cover, image_uri},
+            div::{flex_col, flex_row},
+            icons,
+            song_table::{SongTable, SongTableEvent},
+        },
+        variables::Variables,
+        views::{ActiveView, AppView, SelectedAlbum, SelectedArtist},
+    },
+};
+
+pub struct ArtistView {
+    artist_id: Option<Cuid>,
+    artist: Option<Artist>,
+    albums: Vec<AlbumListItem>,
+    duplicates: Vec<ArtistListItem>,
+    load_task: Option<Task<()>>,
+    table: Entity<SongTable>,
+    context_menu: Entity<ContextMenu>,
+}
+
+impl ArtistView {
+    pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let table = SongTable::for_artist(cx);
+
+        cx.subscribe(&table, |_this, _table, event: &SongTableEvent, cx| {
+            if let SongTableEvent::NewRows = event {
+                cx.notify();
+            }
+        })
+        .detach();
+
+        let mut view = Self {
+            artist_id: cx.global::<SelectedArtist>().0.clone(),
+            artist: None,
+            albums: Vec::new(),
+            duplicates: Vec::new(),
+            load_task: None,
+            table,
+            context_menu: cx.new(|_| ContextMenu::new()),
+        };
+
+        if cx.global::<ActiveView>().0 == AppView::Artist {
+            view.reload(cx);
+        }
+
+        cx.observe_global::<SelectedArtist>(|this, cx| {
+            let new_id = cx.global::<SelectedArtist>().0.clone();
+            if new_id == this.artist_id {
+                return;
+            }
+            this.artist_id = new_id;
+            this.reload(cx);
+        })
+        .detach();
+
+        cx.observe_global::<ActiveView>(|this, cx| {
+            if cx.global::<ActiveView>().0 != AppView::Artist {
+                return;
+            }
+            let new_id = cx.global::<SelectedArtist>().0.clone();
+            if new_id != this.artist_id || this.artist.is_none() {
+                this.artist_id = new_id;
+                this.reload(cx);
+            }
+        })
+        .detach();
+
+        cx.observe_global::<LibraryDataChanged>(|this, cx| {
+            this.reload(cx);
+        })
+        .detach();
+
+        view
+    }
+
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        let artist_id = self.artist_id.clone();
+        self.table
+            .update(cx, |table, cx| table.load(artist_id.clone(), cx));
+        let Some(artist_id) = artist_id else {
+            self.artist = None;
+            self.albums = Vec::new();
+            self.duplicates = Vec::new();
+            cx.notify();
+            return;
+        };
+
+        let db = cx.global::<Database>().clone();
+        let bg = cx.background_executor().clone();
+
+        let task = cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let id_for = artist_id.clone();
+            let (artist, albums, duplicates) = bg
+                .spawn(async move {
+                    let artist = db.get_artist(&id_for).ok().flatten();
+                    let albums = db.get_artist_albums(&id_for).unwrap_or_default();
+                    let duplicates = db.get_duplicate_artists(&id_for).unwrap_or_default();
+                    (artist, albums, duplicates)
+                })
+                .await;
+
+            cx.update(|cx| {
+                this.update(cx, |this, cx| {
+                    if this.artist_id.as_ref() != Some(&artist_id) {
+                        return;
+                    }
+                    this.artist = artist;
+                    this.albums = albums;
+                    this.duplicates = duplicates;
+                    cx.notify();
+                })
+            })
+            .ok();
+        });
+
+        self.load_task = Some(task);
+    }
+}
+
+/// Folds `from` into `into`, for a duplicate the user confirmed is the same artist.
+fn merge_artist(from: &Cuid, into: &Cuid, cx: &mut App) {
+    let db = cx.global::<Database>().clone();
+    if let Err(e) = db.merge_artists(from, into) {
+        tracing::error!("Failed to merge artists: {}", e);
+        return;
+    }
+    cx.set_global(LibraryDataChanged);
+}
+
+fn duplicates_notice(
+    artist: &Artist,
+    duplicates: &[ArtistListItem],
+    variables: Variables,
+) -> impl IntoElement {
+    flex_col()
+        .w_full()
+        .flex_shrink_0()
+        .gap(px(variables.padding_8))
+        .px(px(variables.padding_8))
+        .py(px(variables.padding_8))
+        .border_1()
+        .border_color(variables.border)
+        .child(
+            div()
+                .text_color(variables.text_secondary)
+                .child("These may be the same artist"),
+        )
+        .children(duplicates.iter().enumerate().map(|(idx, duplicate)| {
+            let from = duplicate.id.clone();
+            let into = artist.id.clone();
+            flex_row()
+                .w_full()
+                .justify_between()
+                .gap(px(variables.padding_16))
+                .child(div().min_w_0().truncate().child(format!(
+                    "{} \u{00B7} {} songs",
+                    duplicate.name, duplicate.song_count
+                )))
+                .child(
+                    div()
+                        .id(("artist-merge", idx))
+                        .flex_shrink_0()
+                        .cursor_pointer()
+                        .underline()
+                        .child(format!("Merge into {}", artist.name))
+                        .on_click(move |_, _, cx| merge_artist(&from, &into, cx)),
+                )
+        }))
+}
+
+fn artist_album_tile(
+    idx: usize,
+    album: &AlbumListItem,
+    context_menu: Entity<ContextMenu>,
+) -> impl IntoElement {
+    let album_id = album.id.clone();
+    let play_album_id = album_id.clone();
+    let nav_album_id = album_id.clone();
+
+    Card::new(
+        format!("artist-album-{}", idx),
+        album.title.clone(),
+        CARD_MIN_IMAGE_SIZE,
+    )
+    .when_some(album.year.clone(), |card, year| card.subtitle(year))
+    .image_uri(album.image_id.clone())
+    .flex_shrink_0()
+    .on_play(move |_window, cx| {
+        play_album_now(play_album_id.clone(), cx);
+    })
+    .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+        cx.set_global(SelectedAlbum(Some(nav_album_id.clone())));
+        if let Some(Some(root)) = window.root::<MainWindow>() {
+            root.update(cx, |view, cx| {
+                view.set_current_view(AppView::Album, window, cx);
+            });
+        }
+    })
+    .on_mouse_down(MouseButton::Right, move |event, _window, cx| {
+        let items = album_context_menu_items(album_id.clone(), cx);
+        context_menu.update(cx, |menu, cx| {
+            menu.show(event.position, items, cx);
+        });
+    })
+}
+
+impl Render for ArtistView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let variables = *cx.global::<Variables>();
+        let context_menu = self.context_menu.clone();
+
+        let body = if let Some(artist) = self.artist.clone() {
+            let avatar_size = 96.0_f32;
+            let avatar = div()
+                .id("artist-avatar")
+                .size(px(avatar_size))
+                .flex_shrink_0()
+                .child(Cover::new(artist.image_id.as_deref().map(image_uri)).rounded(true));
+
+            let album_count = self.albums.len();
+            let featured_count = self.table.read(cx).songs().len();
+            let artist_id_play = artist.id.clone();
+            let artist_id_shuffle = artist.id.clone();
+            let artist_id_menu = artist.id.clone();
+            let menu_for_button = context_menu.clone();
+
+            let header = flex_row()
+                .w_full()
+                .flex_shrink_0()
+                .items_center()
+                .gap(px(variables.padding_16))
+                .child(avatar)
+                .child(
+                    flex_col()
+                        .flex_1()
+                        .min_w_0()
+                        .gap(px(variables.padding_8))
+                        .child(
+                            div()
+                                .font_weight(FontWeight::BOLD)
+                                .text_size(px(18.0))
+                                .line_height(px(22.0))
+                                .text_ellipsis()
+                                .overflow_x_hidden()
+                                .child(artist.name.clone()),
+                        )
+                        .child(div().text_color(variables.text_secondary).child(format!(
+                            "{} albums \u{00B7} appears on {} songs",
+                            album_count, featured_count
+                        )))
+                        .child(
+                            flex_row()
+                                .gap(px(variables.padding_8))
+                                .child(
+                                    Button::new("artist-play-button")
+                                        .icon(icons::PLAY)
+                                        .items_center()
+                                        .gap(px(variables.padding_8))
+                                        .bg_color(variables.accent)
+                                        .color(variables.background)
+                                        .hover_color(variables.background)
+                                        .hover(|s| s.bg(variables.accent_background))
+                                        .on_click(move |_event, _window, cx| {
+                                            play_artist_now(artist_id_play.clone(), cx);
+                                        }),
+                                )
+                                .child(
+                                    Button::new("artist-shuffle-button")
+                                        .icon(icons::SHUFFLE)
+                                        .items_center()
+                                        .gap(px(variables.padding_8))
+                                        .on_click(move |_event, _window, cx| {
+                                            shuffle_artist_now(artist_id_shuffle.clone(), cx);
+                                        }),
+                                )
+                                .child(
+                                    Button::new("artist-more-button")
+                                        .icon(icons::DOTS)
+                                        .items_center()
+                                        .gap(px(variables.padding_8))
+                                        .on_click(move |event, _window, cx| {
+                                            let items = artist_context_menu_items(
+                                                artist_id_menu.clone(),
+                                                cx,
+                                            );
+                                            menu_for_button.update(cx, |menu, cx| {
+                                                menu.show(event.position(), items, cx);
+                                            });
+                                        }),
+                                ),
+                        ),
+                );
+
+            let section_title = |title: &'static str| {
+                div()
+                    .flex_shrink_0()
+                    .text_color(variables.text)
+                    .font_weight(FontWeight::BOLD)
+                    .child(title)
+            };
+
+            flex_col()
+                .size_full()
+                .p(px(variables.padding_24))
+                .gap(px(variables.padding_24))
+                .child(header)
+                .when(!self.duplicates.is_empty(), |this| {
+                    this.child(duplicates_notice(&artist, &self.duplicates, variables))
+                })
+                .when(album_count > 0, |this| {
+                    this.child(section_title("Albums")).child(
+                        flex_row()
+                            .id("artist-albums")
+                            .flex_shrink_0()
+                            .w_full()
+                            .gap(px(variables.padding_16))
+                            .overflow_x_scroll()
+                            .children(self.albums.iter().enumerate().map(|(idx, album)| {
+                                artist_album_tile(idx, album, context_menu.clone())
+                            })),
+                    )
+                })
+                .when(featured_count > 0, |this| {
+                    this.child(section_title("Appears On"))
+                        .child(div().flex_1().min_h_0().child(self.table.clone()))
+                })
+                .into_any_element()
+        } else {
+            flex_row()
+                .id("artist-loading")
+                .w_full()
+                .p(px(variables.padding_24))
+                .text_color(variables.text_secondary)
+                .child("Loading...")
+                .into_any_element()
+        };
+
+        flex_col().size_full().child(
+            div()
+                .id("artist-scroll-container")
+                .flex_1()
+                .size_full()
+                .min_h_0()
+                .relative()
+                .child(div().id("artist-content").size_full().child(body))
+                .child(self.context_menu.clone()),
+        )
+    }
+}
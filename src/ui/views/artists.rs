@@ -2,17 +2,22 @@ use gpui::{Context, IntoElement, Render, prelude::FluentBuilder, *};
 use rustc_hash::{FxHashMap, FxHashSet};
 
 use crate::{
-    data::{db::repo::Database, models::ArtistListItem},
+    data::{config::Config, db::repo::Database, models::ArtistListItem},
     ui::{
+        app::MainWindow,
         components::{
             card::{CARD_GRID_GAP, Card, CardImageShape, calculate_card_layout},
             context_menu::{ContextMenu, LibraryDataChanged, artist_context_menu_items},
-            div::{flex_col, flex_row},
+            div::flex_row,
+            index_rail::{IndexRail, MIN_INDEXED_ITEMS, letter_offsets},
             scrollbar::{Scrollbar, ScrollbarAxis, ScrollbarHandle},
         },
-        layout::{library::Search, queue::QueueVisible},
+        layout::{
+            library::{Search, sidebar_width},
+            queue::QueueVisible,
+        },
         variables::Variables,
-        views::{ActiveView, AppView},
+        views::{ActiveView, AppView, SelectedArtist},
     },
 };
 
@@ -30,6 +35,9 @@ pub struct ArtistsView {
     container_width: Option<f32>,
     scroll_handle: UniformListScrollHandle,
     context_menu: Entity<ContextMenu>,
+    index_offsets: Vec<(char, usize)>,
+    index_token: u64,
+    index_rail_enabled: bool,
 }
 
 impl ArtistsView {
@@ -90,6 +98,7 @@ impl ArtistsView {
                     this.request_inflight = false;
 
                     if data_changed {
+                        this.refresh_index(cx);
                         cx.notify();
                     }
 
@@ -119,6 +128,9 @@ impl ArtistsView {
             container_width: None,
             scroll_handle: UniformListScrollHandle::default(),
             context_menu: cx.new(|_| ContextMenu::new()),
+            index_offsets: Vec::new(),
+            index_token: 0,
+            index_rail_enabled: cx.global::<Config>().get().interface.index_rail,
         };
 
         if cx.global::<ActiveView>().0 == AppView::Artists {
@@ -154,9 +166,56 @@ impl ArtistsView {
         })
         .detach();
 
+        cx.observe_global::<Config>(|this, cx| {
+            let enabled = cx.global::<Config>().get().interface.index_rail;
+            if enabled != this.index_rail_enabled {
+                this.index_rail_enabled = enabled;
+                this.refresh_index(cx);
+            }
+        })
+        .detach();
+
         view
     }
 
+    fn refresh_index(&mut self, cx: &mut Context<Self>) {
+        self.index_token = self.index_token.wrapping_add(1);
+        let token = self.index_token;
+
+        if !self.index_rail_enabled || self.total_count < MIN_INDEXED_ITEMS {
+            self.index_offsets.clear();
+            return;
+        }
+
+        let db = cx.global::<Database>().clone();
+        let query = self.last_query.clone();
+        let bg = cx.background_executor().clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let offsets = bg
+                .spawn(async move {
+                    let names = db.get_artist_names(&query).unwrap_or_default();
+                    letter_offsets(names.iter().map(String::as_str))
+                })
+                .await;
+
+            this.update(cx, |this, cx| {
+                if this.index_token == token {
+                    this.index_offsets = offsets;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn scroll_to_artist(&self, index: usize) {
+        let items_per_row = self.calculate_layout().1.max(1);
+        self.scroll_handle
+            .scroll_to_item(index / items_per_row, ScrollStrategy::Top);
+    }
+
     fn request_query(&mut self, query: String, cx: &mut Context<Self>) {
         if query == self.last_query && self.pending_query.is_none() && !self.page_cache.is_empty() {
             return;
@@ -261,14 +320,31 @@ fn artist_tile(
     context_menu: Entity<ContextMenu>,
 ) -> impl IntoElement {
     let artist_id = artist.id.clone();
+    let nav_artist_id = artist_id.clone();
+    let subtitle = format!(
+        "{} album{}, {} song{}",
+        artist.album_count,
+        if artist.album_count == 1 { "" } else { "s" },
+        artist.song_count,
+        if artist.song_count == 1 { "" } else { "s" },
+    );
 
     Card::new(
         format!("artist-item-{}", idx),
         artist.name.clone(),
         cover_size,
     )
+    .subtitle(subtitle)
     .image_uri(artist.image_id.clone())
     .image_shape(CardImageShape::Circle)
+    .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
+        cx.set_global(SelectedArtist(Some(nav_artist_id.clone())));
+        if let Some(Some(root)) = window.root::<MainWindow>() {
+            root.update(cx, |view, cx| {
+                view.set_current_view(AppView::Artist, window, cx);
+            });
+        }
+    })
     .on_mouse_down(MouseButton::Right, move |event, _window, cx| {
         let items = artist_context_menu_items(artist_id.clone(), cx);
         context_menu.update(cx, |menu, cx| {
@@ -284,7 +360,7 @@ impl Render for ArtistsView {
 
         let bounds = window.bounds();
         let window_width: f32 = bounds.size.width.into();
-        let mut estimated_width = window_width - 300.0 - 98.0;
+        let mut estimated_width = window_width - sidebar_width(window_width) - 98.0;
         if queue_visible.0 {
             estimated_width -= 316.0;
         }
@@ -382,37 +458,48 @@ impl Render for ArtistsView {
                 .into_any_element()
         };
 
-        flex_col()
+        let show_rail = self.index_rail_enabled && !self.index_offsets.is_empty();
+        let view = cx.entity();
+
+        flex_row()
             .size_full()
             .child(
                 div()
                     .id("artists-scroll-container")
                     .flex_1()
-                    .size_full()
+                    .h_full()
+                    .min_w_0()
                     .min_h_0()
                     .relative()
                     .child(div().id("artists-content").size_full().child(grid_content))
-                    .child(self.context_menu.clone()),
+                    .child(self.context_menu.clone())
+                    .when(row_count > 0, |this| {
+                        let scroll_handle = self.scroll_handle.clone();
+                        let padding_extra =
+                            px(variables.padding_24 + (variables.padding_24 - CARD_GRID_GAP));
+                        let mut content_size = scroll_handle.content_size();
+                        content_size.height += padding_extra;
+                        this.child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .right_0()
+                                .bottom_0()
+                                .left_0()
+                                .child(
+                                    Scrollbar::new(&scroll_handle)
+                                        .axis(ScrollbarAxis::Vertical)
+                                        .scroll_size(content_size),
+                                ),
+                        )
+                    }),
             )
-            .when(row_count > 0, |this| {
-                let scroll_handle = self.scroll_handle.clone();
-                let padding_extra =
-                    px(variables.padding_24 + (variables.padding_24 - CARD_GRID_GAP));
-                let mut content_size = scroll_handle.content_size();
-                content_size.height += padding_extra;
-                this.child(
-                    div()
-                        .absolute()
-                        .top_0()
-                        .right_0()
-                        .bottom_0()
-                        .left_0()
-                        .child(
-                            Scrollbar::new(&scroll_handle)
-                                .axis(ScrollbarAxis::Vertical)
-                                .scroll_size(content_size),
-                        ),
-                )
+            .when(show_rail, |this| {
+                this.child(IndexRail::new(
+                    "artists-index-rail",
+                    self.index_offsets.clone(),
+                    move |index, _window, cx| view.read(cx).scroll_to_artist(index),
+                ))
             })
     }
 }
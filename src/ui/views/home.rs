@@ -1,6 +1,11 @@
 use crate::media::playback::{play_album_now, play_song_now};
 use crate::{
-    data::{db::repo::Database, models::RecentItem},
+    data::{
+        config::Config,
+        db::repo::Database,
+        models::{RecentGroup, RecentItem},
+        scanner::{Scanner, add_scan_path},
+    },
     ui::{
         app::MainWindow,
         components::{
@@ -14,7 +19,7 @@ use crate::{
             scrollbar::ScrollableElement,
             song_table::format_artist_line,
         },
-        layout::queue::QueueVisible,
+        layout::{library::sidebar_width, queue::QueueVisible},
         variables::Variables,
         views::{AppView, SelectedAlbum},
     },
@@ -27,12 +32,28 @@ pub struct HomeView {
     recently_played_offset: usize,
     recently_added: Vec<RecentItem>,
     recently_added_offset: usize,
+    recently_added_groups: Vec<(RecentGroup, Vec<RecentItem>)>,
+    recently_added_group_offsets: [usize; 4],
+    recently_added_page: i64,
+    recently_added_limit: i64,
+    recently_added_exhausted: bool,
+    recently_added_loading: bool,
+    library_empty: bool,
     container_width: Option<f32>,
     context_menu: Entity<ContextMenu>,
     hovered_artist: Option<(String, usize)>,
 }
 
 const HOME_RECENT_ITEMS_LIMIT: i64 = 100;
+
+fn recently_added_page(cx: &App) -> i64 {
+    cx.global::<Config>()
+        .get()
+        .interface
+        .recently_added_limit
+        .max(1)
+}
+
 impl HomeView {
     pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
         let mut view = Self {
@@ -40,6 +61,13 @@ impl HomeView {
             recently_played_offset: 0,
             recently_added: Vec::new(),
             recently_added_offset: 0,
+            recently_added_groups: Vec::new(),
+            recently_added_group_offsets: [0; 4],
+            recently_added_page: recently_added_page(cx),
+            recently_added_limit: recently_added_page(cx),
+            recently_added_exhausted: false,
+            recently_added_loading: false,
+            library_empty: false,
             container_width: None,
             context_menu: cx.new(|_| ContextMenu::new()),
             hovered_artist: None,
@@ -55,6 +83,17 @@ impl HomeView {
         })
         .detach();
 
+        cx.observe_global::<Config>(|this, cx| {
+            let page = recently_added_page(cx);
+            if this.recently_added_page != page {
+                this.recently_added_page = page;
+                this.recently_added_limit = page;
+                this.recently_added_exhausted = false;
+                this.load_recently_added(cx);
+            }
+        })
+        .detach();
+
         view.load_recently_played(cx);
         view.load_recently_added(cx);
         view
@@ -81,15 +120,41 @@ impl HomeView {
 
     fn load_recently_added(&mut self, cx: &mut Context<Self>) {
         let db = cx.global::<Database>().clone();
+        let limit = self.recently_added_limit;
+        let previous_len = self.recently_added.len();
+        let is_more = self.recently_added_loading;
 
         cx.spawn(async move |this, cx: &mut AsyncApp| {
-            let items = db
-                .get_recently_added_items(HOME_RECENT_ITEMS_LIMIT)
-                .unwrap_or_default();
+            let dated_items = db.get_recently_added_items(limit).unwrap_or_default();
+            let library_empty =
+                dated_items.is_empty() && db.get_songs_count(None).unwrap_or(0) == 0;
+
+            let today = chrono::Local::now().date_naive();
+            let mut groups: Vec<(RecentGroup, Vec<RecentItem>)> = RecentGroup::ALL
+                .into_iter()
+                .map(|group| (group, Vec::new()))
+                .collect();
+            let mut items = Vec::with_capacity(dated_items.len());
+            for (item, date_added) in dated_items {
+                let group = RecentGroup::from_date_added(date_added.as_deref(), today);
+                if let Some((_, group_items)) = groups.iter_mut().find(|(g, _)| *g == group) {
+                    group_items.push(item.clone());
+                }
+                items.push(item);
+            }
+            groups.retain(|(_, group_items)| !group_items.is_empty());
 
             cx.update(|cx| {
                 this.update(cx, |this, cx| {
+                    this.recently_added_exhausted = is_more && items.len() <= previous_len;
+                    this.recently_added_loading = false;
+                    this.library_empty = library_empty;
                     this.recently_added = items;
+                    this.recently_added_groups = groups;
+                    if !is_more {
+                        this.recently_added_offset = 0;
+                        this.recently_added_group_offsets = [0; 4];
+                    }
                     cx.notify();
                 })
             })
@@ -98,6 +163,25 @@ impl HomeView {
         .detach();
     }
 
+    /// Pulls in another batch once the row is scrolled close to the end of what is loaded.
+    fn load_more_recently_added_if_needed(
+        &mut self,
+        offset: usize,
+        item_count: usize,
+        items_per_page: usize,
+        cx: &mut Context<Self>,
+    ) {
+        if self.recently_added_exhausted
+            || self.recently_added_loading
+            || offset + items_per_page * 2 < item_count
+        {
+            return;
+        }
+        self.recently_added_loading = true;
+        self.recently_added_limit += self.recently_added_page;
+        self.load_recently_added(cx);
+    }
+
     fn calculate_layout(&self) -> (f32, usize) {
         calculate_card_layout(self.container_width)
     }
@@ -148,6 +232,35 @@ impl HomeView {
             self.recently_added.len(),
             items_per_page,
         );
+        self.load_more_recently_added_if_needed(
+            self.recently_added_offset,
+            self.recently_added.len(),
+            items_per_page,
+            cx,
+        );
+        cx.notify();
+    }
+
+    fn scroll_added_group_left(&mut self, group_idx: usize, cx: &mut Context<Self>) {
+        let (_, items_per_page) = self.calculate_layout();
+        if let Some(offset) = self.recently_added_group_offsets.get_mut(group_idx) {
+            Self::scroll_offset_left(offset, items_per_page);
+        }
+        cx.notify();
+    }
+
+    fn scroll_added_group_right(&mut self, group_idx: usize, cx: &mut Context<Self>) {
+        let (_, items_per_page) = self.calculate_layout();
+        let item_count = self
+            .recently_added_groups
+            .get(group_idx)
+            .map(|(_, items)| items.len())
+            .unwrap_or(0);
+        if let Some(offset) = self.recently_added_group_offsets.get_mut(group_idx) {
+            Self::scroll_offset_right(offset, item_count, items_per_page);
+            let offset = *offset;
+            self.load_more_recently_added_if_needed(offset, item_count, items_per_page, cx);
+        }
         cx.notify();
     }
 }
@@ -235,6 +348,15 @@ fn recent_item_tile(
         })
 }
 
+fn recent_group_section_id(group: RecentGroup) -> &'static str {
+    match group {
+        RecentGroup::Today => "recently-added-today",
+        RecentGroup::ThisWeek => "recently-added-this-week",
+        RecentGroup::ThisMonth => "recently-added-this-month",
+        RecentGroup::Earlier => "recently-added-earlier",
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn recent_items_content(
     section_id: &'static str,
@@ -292,6 +414,64 @@ fn recent_items_content(
         .into_any_element()
 }
 
+fn add_music_folder(cx: &mut App) {
+    let options = PathPromptOptions {
+        files: false,
+        directories: true,
+        multiple: false,
+        prompt: None,
+    };
+    let receiver = cx.prompt_for_paths(options);
+    cx.spawn(async move |cx| {
+        let Ok(Ok(Some(paths))) = receiver.await else {
+            return;
+        };
+        let Some(path) = paths.into_iter().next() else {
+            return;
+        };
+        cx.update(|cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| add_scan_path(&mut s.scan.paths, &path));
+            });
+            let db = cx.global::<Database>().clone();
+            cx.global::<Scanner>().rescan_now(db);
+        });
+    })
+    .detach();
+}
+
+fn empty_library_welcome(variables: &Variables) -> impl IntoElement {
+    flex_col()
+        .id("home-empty-library")
+        .items_start()
+        .gap(px(variables.padding_16))
+        .child(
+            div()
+                .text_color(variables.text)
+                .text_xl()
+                .font_weight(FontWeight::BOLD)
+                .child("Welcome \u{2014} add your music folder"),
+        )
+        .child(div().text_color(variables.text_secondary).child(
+            "Vleer didn't find any songs yet. Pick the folder your music lives in to scan it.",
+        ))
+        .child(
+            flex_row()
+                .id("home-add-music-folder")
+                .cursor_pointer()
+                .gap(px(variables.padding_8))
+                .items_center()
+                .px(px(variables.padding_16))
+                .py(px(variables.padding_8))
+                .bg(variables.accent)
+                .text_color(variables.background)
+                .hover(|s| s.bg(variables.accent_hover))
+                .on_click(|_, _, cx| add_music_folder(cx))
+                .child(icon(icons::PLUS))
+                .child("Choose music folder"),
+        )
+}
+
 impl Render for HomeView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let variables = cx.global::<Variables>();
@@ -300,7 +480,7 @@ impl Render for HomeView {
 
         let bounds = window.bounds();
         let window_width: f32 = bounds.size.width.into();
-        let mut estimated_width = window_width - 300.0 - 98.0;
+        let mut estimated_width = window_width - sidebar_width(window_width) - 98.0;
         if queue_visible.0 {
             estimated_width -= 316.0;
         }
@@ -329,17 +509,105 @@ impl Render for HomeView {
             &self.hovered_artist,
             view_weak.clone(),
         );
-        let recently_added_content = recent_items_content(
-            "recently-added",
-            &self.recently_added,
-            self.recently_added_offset,
-            items_per_page,
-            cover_size,
-            variables,
-            context_menu.clone(),
-            &self.hovered_artist,
-            view_weak.clone(),
-        );
+        let grouped = cx.global::<Config>().get().interface.group_recently_added
+            && !self.recently_added_groups.is_empty();
+        let recently_added_content = if grouped {
+            flex_col()
+                .id("recently-added-groups")
+                .w_full()
+                .gap(px(variables.padding_16))
+                .children(self.recently_added_groups.iter().enumerate().map(
+                    |(group_idx, (group, items))| {
+                        let section_id = recent_group_section_id(*group);
+                        let offset = self.recently_added_group_offsets[group_idx];
+                        let can_left = offset > 0;
+                        let can_right = offset + items_per_page < items.len();
+                        flex_col()
+                            .id(ElementId::Name(format!("{section_id}-section").into()))
+                            .w_full()
+                            .gap(px(variables.padding_8))
+                            .child(
+                                flex_row()
+                                    .id(ElementId::Name(format!("{section_id}-header").into()))
+                                    .w_full()
+                                    .items_center()
+                                    .justify_between()
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .font_weight(FontWeight(600.0))
+                                            .child(group.label()),
+                                    )
+                                    .child(
+                                        flex_row()
+                                            .gap(px(variables.padding_8))
+                                            .child(
+                                                icon(icons::ARROW_LEFT)
+                                                    .when(can_left, |this| this.cursor_pointer())
+                                                    .on_mouse_down(
+                                                        MouseButton::Left,
+                                                        cx.listener(
+                                                            move |this, _event, _window, cx| {
+                                                                this.scroll_added_group_left(
+                                                                    group_idx, cx,
+                                                                );
+                                                            },
+                                                        ),
+                                                    )
+                                                    .text_color(if can_left {
+                                                        variables.text_secondary
+                                                    } else {
+                                                        variables.text_muted
+                                                    }),
+                                            )
+                                            .child(
+                                                icon(icons::ARROW_RIGHT)
+                                                    .when(can_right, |this| this.cursor_pointer())
+                                                    .on_mouse_down(
+                                                        MouseButton::Left,
+                                                        cx.listener(
+                                                            move |this, _event, _window, cx| {
+                                                                this.scroll_added_group_right(
+                                                                    group_idx, cx,
+                                                                );
+                                                            },
+                                                        ),
+                                                    )
+                                                    .text_color(if can_right {
+                                                        variables.text_secondary
+                                                    } else {
+                                                        variables.text_muted
+                                                    }),
+                                            ),
+                                    ),
+                            )
+                            .child(recent_items_content(
+                                section_id,
+                                items,
+                                offset,
+                                items_per_page,
+                                cover_size,
+                                variables,
+                                context_menu.clone(),
+                                &self.hovered_artist,
+                                view_weak.clone(),
+                            ))
+                    },
+                ))
+                .into_any_element()
+        } else {
+            recent_items_content(
+                "recently-added",
+                &self.recently_added,
+                self.recently_added_offset,
+                items_per_page,
+                cover_size,
+                variables,
+                context_menu.clone(),
+                &self.hovered_artist,
+                view_weak.clone(),
+            )
+        };
 
         let recently_played = flex_col()
             .id("recently-played-section")
@@ -441,51 +709,53 @@ impl Render for HomeView {
                             .font_weight(FontWeight(600.0))
                             .text_size(px(18.0)),
                     )
-                    .child(
-                        flex_row()
-                            .id("recently-added-arrows")
-                            .gap(px(variables.padding_8))
-                            .child(
-                                icon(icons::ARROW_LEFT)
-                                    .when(can_scroll_recently_added_left, |this| {
-                                        this.cursor_pointer()
-                                    })
-                                    .on_mouse_down(
-                                        MouseButton::Left,
-                                        cx.listener(|this, _event, _window, cx| {
-                                            this.scroll_recently_added_left(cx);
+                    .when(!grouped, |this| {
+                        this.child(
+                            flex_row()
+                                .id("recently-added-arrows")
+                                .gap(px(variables.padding_8))
+                                .child(
+                                    icon(icons::ARROW_LEFT)
+                                        .when(can_scroll_recently_added_left, |this| {
+                                            this.cursor_pointer()
+                                        })
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _event, _window, cx| {
+                                                this.scroll_recently_added_left(cx);
+                                            }),
+                                        )
+                                        .text_color(left_arrow_color)
+                                        .hover(|this| {
+                                            if can_scroll_recently_added_left {
+                                                this.text_color(variables.text)
+                                            } else {
+                                                this
+                                            }
                                         }),
-                                    )
-                                    .text_color(left_arrow_color)
-                                    .hover(|this| {
-                                        if can_scroll_recently_added_left {
-                                            this.text_color(variables.text)
-                                        } else {
-                                            this
-                                        }
-                                    }),
-                            )
-                            .child(
-                                icon(icons::ARROW_RIGHT)
-                                    .when(can_scroll_recently_added_right, |this| {
-                                        this.cursor_pointer()
-                                    })
-                                    .on_mouse_down(
-                                        MouseButton::Left,
-                                        cx.listener(|this, _event, _window, cx| {
-                                            this.scroll_recently_added_right(cx);
+                                )
+                                .child(
+                                    icon(icons::ARROW_RIGHT)
+                                        .when(can_scroll_recently_added_right, |this| {
+                                            this.cursor_pointer()
+                                        })
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(|this, _event, _window, cx| {
+                                                this.scroll_recently_added_right(cx);
+                                            }),
+                                        )
+                                        .text_color(right_arrow_color)
+                                        .hover(|this| {
+                                            if can_scroll_recently_added_right {
+                                                this.text_color(variables.text)
+                                            } else {
+                                                this
+                                            }
                                         }),
-                                    )
-                                    .text_color(right_arrow_color)
-                                    .hover(|this| {
-                                        if can_scroll_recently_added_right {
-                                            this.text_color(variables.text)
-                                        } else {
-                                            this
-                                        }
-                                    }),
-                            ),
-                    )
+                                ),
+                        )
+                    })
                     .items_center()
                     .justify_between(),
             )
@@ -525,8 +795,12 @@ impl Render for HomeView {
 |__/|__/\___/_/\___/\____/_/ /_/ /_/\___/ ",
                                     )),
                             )
-                            .child(recently_played)
-                            .child(recently_added),
+                            .when(self.library_empty, |this| {
+                                this.child(empty_library_welcome(variables))
+                            })
+                            .when(!self.library_empty, |this| {
+                                this.child(recently_played).child(recently_added)
+                            }),
                     )
                     .child(self.context_menu.clone()),
             )
@@ -1,5 +1,6 @@
 mod album;
 mod albums;
+mod artist;
 mod artists;
 mod home;
 mod playlist;
@@ -12,7 +13,7 @@ use std::collections::HashMap;
 
 use crate::data::models::Cuid;
 use crate::ui::views::{
-    album::AlbumView, albums::AlbumsView, artists::ArtistsView, home::HomeView,
+    album::AlbumView, albums::AlbumsView, artist::ArtistView, artists::ArtistsView, home::HomeView,
     playlist::PlaylistView, playlists::PlaylistsView, settings::SettingsView, songs::SongsView,
 };
 
@@ -25,6 +26,7 @@ pub enum AppView {
     Albums,
     Album,
     Artists,
+    Artist,
     Playlists,
     Playlist,
 }
@@ -38,6 +40,7 @@ impl AppView {
             AppView::Albums => "Albums",
             AppView::Album => "Album",
             AppView::Artists => "Artists",
+            AppView::Artist => "Artist",
             AppView::Playlists => "Playlists",
             AppView::Playlist => "Playlist",
         }
@@ -60,6 +63,11 @@ pub struct SelectedAlbum(pub Option<Cuid>);
 
 impl Global for SelectedAlbum {}
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SelectedArtist(pub Option<Cuid>);
+
+impl Global for SelectedArtist {}
+
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct SelectedPlaylist {
     pub id: Option<Cuid>,
@@ -101,6 +109,11 @@ impl ViewRegistry {
             cx.new(|cx| ArtistsView::new(window, cx)).into(),
         );
 
+        views.insert(
+            AppView::Artist,
+            cx.new(|cx| ArtistView::new(window, cx)).into(),
+        );
+
         views.insert(
             AppView::Playlists,
             cx.new(|cx| PlaylistsView::new(window, cx)).into(),
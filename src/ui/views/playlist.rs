@@ -1,13 +1,10 @@
 use gpui::*;
 use sha2::{Digest, Sha256};
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::sync::Arc;
 
 use crate::{
     data::{
         db::repo::Database,
-        models::{Cuid, Playlist, PlaylistTrack},
+        models::{Cuid, Playlist},
     },
     media::{
         playback::{Playback, play_playlist_now},
@@ -19,26 +16,21 @@ use crate::{
             context_menu::{
                 ContextMenu, LibraryDataChanged, QueueChanged, playlist_context_menu_items,
             },
+            cover::{Cover, image_uri},
             div::{flex_col, flex_row},
             icons,
-            input::{InputEvent, TextInput},
-            song_table::{
-                GetRowCountHandler, GetRowHandler, QueueHandler, SongEntry, SongTable,
-                SongTableEvent, join_artists,
-            },
+            input::{InputEvent, PastePolicy, TextInput},
+            song_table::{JumpToPlayingRequested, SongTable, SongTableEvent},
         },
         variables::Variables,
         views::{ActiveView, AppView, SelectedPlaylist},
     },
 };
 
-type SongCache = Rc<RefCell<Vec<Arc<SongEntry>>>>;
-
 pub struct PlaylistView {
     playlist_id: Option<Cuid>,
     playlist: Option<Playlist>,
     total_duration_secs: i32,
-    songs_cache: SongCache,
     load_task: Option<Task<()>>,
     table: Entity<SongTable>,
     title_input: Entity<TextInput>,
@@ -46,78 +38,21 @@ pub struct PlaylistView {
     pending_title_focus: bool,
 }
 
-fn song_entry_from_track(track: &PlaylistTrack) -> Arc<SongEntry> {
-    let song = &track.song;
-    let artists = if song.artists.is_empty() {
-        vec!["Unknown".to_string()]
-    } else {
-        song.artists.clone()
-    };
-    let (artist, artist_ranges) = join_artists(&artists);
-    let minutes = song.duration / 60;
-    let seconds = song.duration % 60;
-    Arc::new(SongEntry {
-        id: song.id.clone(),
-        title: song.title.clone(),
-        artist,
-        artist_ranges,
-        album: track.album_title.clone().unwrap_or_default(),
-        album_id: song.album_id.clone(),
-        duration: format!("{}:{:02}", minutes, seconds),
-        cover_uri: song.image_id.clone().map(|id| format!("!image://{}", id)),
-        track_number: song.track_number,
-        genre: String::new(),
-    })
-}
-
 impl PlaylistView {
     pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
-        let songs_cache: SongCache = Rc::new(RefCell::new(Vec::new()));
-
-        let get_row_count: GetRowCountHandler = {
-            let cache = songs_cache.clone();
-            Rc::new(move |_cx, _sort| cache.borrow().len())
-        };
-
-        let get_row: GetRowHandler = {
-            let cache = songs_cache.clone();
-            Rc::new(move |_cx, idx, _sort| cache.borrow().get(idx).cloned())
-        };
-
-        let queue_handler: QueueHandler = {
-            let cache = songs_cache.clone();
-            Rc::new(move |cx, current_id, index, _sort| {
-                let rest: Vec<Cuid> = {
-                    let cache = cache.borrow();
-                    if cache.get(index).map(|e| &e.id) != Some(&current_id) {
-                        return;
-                    }
-                    cache.iter().skip(index + 1).map(|e| e.id.clone()).collect()
-                };
-                if rest.is_empty() {
-                    return;
-                }
-                cx.update_global::<Queue, _>(|q, _| {
-                    q.add_songs(rest);
-                });
-                cx.set_global(QueueChanged);
-            })
-        };
+        let table = SongTable::for_playlist(cx);
 
-        let table = SongTable::new(
-            cx,
-            get_row_count,
-            get_row,
-            Some(queue_handler),
-            None,
-            false,
-            true,
-            true,
-            false,
-        );
+        cx.subscribe(&table, |this, table, event: &SongTableEvent, cx| {
+            if let SongTableEvent::NewRows = event {
+                this.total_duration_secs = table.read(cx).songs().iter().map(|s| s.duration).sum();
+                cx.notify();
+            }
+        })
+        .detach();
 
         let title_input = cx.new(|cx| {
             TextInput::new(cx, "Playlist name")
+                .with_paste_policy(PastePolicy::FirstLine)
                 .with_background(transparent_black())
                 .no_padding()
                 .with_height(px(22.0))
@@ -131,7 +66,6 @@ impl PlaylistView {
             playlist_id: initial_id,
             playlist: None,
             total_duration_secs: 0,
-            songs_cache,
             load_task: None,
             table,
             title_input: title_input.clone(),
@@ -215,16 +149,22 @@ impl PlaylistView {
         })
         .detach();
 
+        cx.observe_global::<JumpToPlayingRequested>(|this, cx| {
+            if cx.global::<ActiveView>().0 == AppView::Playlist {
+                this.table.update(cx, |table, cx| table.jump_to_playing(cx));
+            }
+        })
+        .detach();
+
         view
     }
 
     fn reload(&mut self, cx: &mut Context<Self>) {
-        let Some(playlist_id) = self.playlist_id.clone() else {
+        let playlist_id = self.playlist_id.clone();
+        self.table
+            .update(cx, |table, cx| table.load(playlist_id.clone(), cx));
+        let Some(playlist_id) = playlist_id else {
             self.playlist = None;
-            self.total_duration_secs = 0;
-            self.songs_cache.borrow_mut().clear();
-            let table = self.table.clone();
-            cx.update_entity(&table, |_t, cx| cx.emit(SongTableEvent::NewRows));
             cx.notify();
             return;
         };
@@ -235,12 +175,8 @@ impl PlaylistView {
 
         let task = cx.spawn(async move |this, cx: &mut AsyncApp| {
             let id_for = playlist_id.clone();
-            let (playlist, songs) = bg
-                .spawn(async move {
-                    let playlist = db.get_playlist(&id_for).ok().flatten();
-                    let songs = db.get_playlist_songs(&id_for).unwrap_or_default();
-                    (playlist, songs)
-                })
+            let playlist = bg
+                .spawn(async move { db.get_playlist(&id_for).ok().flatten() })
                 .await;
 
             cx.update(|cx| {
@@ -253,15 +189,7 @@ impl PlaylistView {
                         .map(|p| p.name.clone())
                         .unwrap_or_default();
                     title_input.update(cx, |inp, cx| inp.set_text(name, cx));
-                    this.total_duration_secs = songs.iter().map(|t| t.song.duration).sum();
-                    {
-                        let mut cache = this.songs_cache.borrow_mut();
-                        cache.clear();
-                        cache.extend(songs.iter().map(song_entry_from_track));
-                    }
                     this.playlist = playlist;
-                    let table = this.table.clone();
-                    cx.update_entity(&table, |_t, cx| cx.emit(SongTableEvent::NewRows));
                     cx.notify();
                 })
             })
@@ -310,11 +238,7 @@ impl Render for PlaylistView {
                     .overflow_hidden()
                     .relative()
                     .cursor_pointer()
-                    .child(
-                        img(format!("!image://{}", uri))
-                            .size_full()
-                            .object_fit(ObjectFit::Cover),
-                    )
+                    .child(Cover::new(Some(image_uri(&uri))))
                     .child(
                         div()
                             .absolute()
@@ -365,11 +289,11 @@ impl Render for PlaylistView {
                     .into_any_element(),
             };
 
-            let song_count = self.songs_cache.borrow().len();
+            let song_count = self.table.read(cx).songs().len();
             let duration = self.total_duration_string();
             let playlist_id_play = self.playlist_id.clone().unwrap_or_default();
             let playlist_id_menu = playlist_id_play.clone();
-            let songs_for_shuffle = self.songs_cache.clone();
+            let table_for_shuffle = self.table.clone();
             let menu_for_button = context_menu.clone();
 
             let meta_line = format!("{} songs \u{00B7} {}", song_count, duration);
@@ -412,10 +336,11 @@ impl Render for PlaylistView {
                                 .items_center()
                                 .gap(px(variables.padding_8))
                                 .on_click(move |_event, _window, cx| {
-                                    let song_ids: Vec<Cuid> = songs_for_shuffle
-                                        .borrow()
+                                    let song_ids: Vec<Cuid> = table_for_shuffle
+                                        .read(cx)
+                                        .songs()
                                         .iter()
-                                        .map(|e| e.id.clone())
+                                        .map(|s| s.id.clone())
                                         .collect();
                                     if song_ids.is_empty() {
                                         return;
@@ -1,4 +1,4 @@
-use crate::media::playback::play_playlist_now;
+use crate::media::playback::{play_playlist_now, play_playlist_shuffled};
 use gpui::{Context, IntoElement, Render, prelude::FluentBuilder, *};
 use rustc_hash::{FxHashMap, FxHashSet};
 
@@ -12,7 +12,10 @@ use crate::{
             div::{flex_col, flex_row},
             scrollbar::{Scrollbar, ScrollbarAxis, ScrollbarHandle},
         },
-        layout::{library::Search, queue::QueueVisible},
+        layout::{
+            library::{Search, sidebar_width},
+            queue::QueueVisible,
+        },
         variables::Variables,
         views::{ActiveView, AppView, SelectedPlaylist},
     },
@@ -256,6 +259,16 @@ impl PlaylistsView {
     }
 }
 
+fn format_playlist_duration(secs: i64) -> String {
+    let hours = secs / 3600;
+    let minutes = (secs % 3600) / 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{} min", minutes)
+    }
+}
+
 fn playlist_tile(
     idx: usize,
     playlist: &PlaylistListItem,
@@ -263,13 +276,15 @@ fn playlist_tile(
     context_menu: Entity<ContextMenu>,
 ) -> impl IntoElement {
     let subtitle = format!(
-        "{} song{}",
+        "{} song{} \u{00B7} {}",
         playlist.song_count,
-        if playlist.song_count == 1 { "" } else { "s" }
+        if playlist.song_count == 1 { "" } else { "s" },
+        format_playlist_duration(playlist.duration_secs)
     );
 
     let playlist_id = playlist.id.clone();
     let play_id = playlist_id.clone();
+    let shuffle_id = playlist_id.clone();
     let nav_id = playlist_id.clone();
 
     Card::new(
@@ -282,6 +297,9 @@ fn playlist_tile(
     .on_play(move |_window, cx| {
         play_playlist_now(play_id.clone(), cx);
     })
+    .on_shuffle(move |_window, cx| {
+        play_playlist_shuffled(shuffle_id.clone(), cx);
+    })
     .on_mouse_down(MouseButton::Left, move |_event, window, cx| {
         cx.update_global::<SelectedPlaylist, _>(|sel, _| {
             sel.id = Some(nav_id.clone());
@@ -308,7 +326,7 @@ impl Render for PlaylistsView {
 
         let bounds = window.bounds();
         let window_width: f32 = bounds.size.width.into();
-        let mut estimated_width = window_width - 300.0 - 98.0;
+        let mut estimated_width = window_width - sidebar_width(window_width) - 98.0;
         if queue_visible.0 {
             estimated_width -= 316.0;
         }
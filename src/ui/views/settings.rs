@@ -1,10 +1,25 @@
+use chrono::Datelike;
 use gpui::{Context, Entity, IntoElement, Render, prelude::FluentBuilder as _, *};
+use std::collections::HashMap;
+use std::rc::Rc;
 
-use crate::data::config::Config;
-use crate::media::playback::Playback;
+use crate::data::config::{
+    AudioFocusBehavior, ClickBehavior, Config, FallbackArtwork, HistoryRetention, ImportMode,
+    LoudnessSource, MAX_FONT_SIZE, MIN_FONT_SIZE, MissingSongsPolicy, PlayingSongClick,
+    ResamplerQuality, ResumeOnLaunch, RowDensity, ScrollbarClick, ScrollbarVisibility,
+};
+use crate::data::db::repo::Database;
+use crate::data::models::{LibraryTotals, ScanRun, SkippedSong};
+use crate::data::organize::PlannedMove;
+use crate::data::scanner::{Scanner, add_scan_path, collapse_tilde, expand_tilde, scan_path_error};
+use crate::media::playback::{MAX_PREAMP_DB, Playback};
+use crate::ui::app::purge_expired_history;
+use crate::ui::components::context_menu::{
+    BackgroundUiEvent, BackgroundUiNotifier, LibraryDataChanged,
+};
 use crate::ui::components::div::{flex_col, flex_row};
 use crate::ui::components::icons::{self, LINK, icon};
-use crate::ui::components::input::{InputEvent, TextInput};
+use crate::ui::components::input::{InputEvent, PastePolicy, TextInput};
 use crate::ui::components::scrollbar::ScrollableElement;
 use crate::ui::components::slider::slider;
 use crate::ui::components::switch::Switch;
@@ -12,7 +27,9 @@ use crate::ui::variables::Variables;
 use crate::updater::{UpdateStatus, Updater, is_managed_externally, run_check_in_background};
 
 #[derive(IntoElement)]
-struct ScanPathsSection;
+struct ScanPathsSection {
+    errors: HashMap<String, &'static str>,
+}
 
 impl RenderOnce for ScanPathsSection {
     fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
@@ -28,35 +45,71 @@ impl RenderOnce for ScanPathsSection {
                     .max_w(px(650.0))
                     .w_full()
                     .children(paths.into_iter().enumerate().map(move |(i, path)| {
+                        let error = self.errors.get(&path).copied();
                         flex_row()
                             .items_center()
                             .justify_between()
                             .w_full()
                             .p(px(variables.padding_16))
                             .bg(variables.element)
+                            .when(error.is_some(), |row| {
+                                row.border_1().border_color(variables.destructive)
+                            })
                             .child(
-                                div()
-                                    .text_color(variables.text)
-                                    .overflow_hidden()
-                                    .text_ellipsis()
-                                    .child(path.clone()),
+                                flex_col()
+                                    .min_w_0()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        div()
+                                            .text_color(variables.text)
+                                            .overflow_hidden()
+                                            .text_ellipsis()
+                                            .child(path.clone()),
+                                    )
+                                    .when_some(error, |col, error| {
+                                        col.child(
+                                            div().text_color(variables.destructive).child(error),
+                                        )
+                                    }),
                             )
                             .child(
-                                div()
-                                    .id(SharedString::from(format!("remove-path-{i}")))
-                                    .cursor_pointer()
+                                flex_row()
+                                    .flex_shrink_0()
+                                    .items_center()
+                                    .gap(px(variables.padding_16))
                                     .child(
-                                        icon(icons::X)
+                                        div()
+                                            .id(SharedString::from(format!("rescan-path-{i}")))
+                                            .cursor_pointer()
                                             .text_color(variables.text_secondary)
-                                            .hover(|s| s.text_color(variables.text)),
+                                            .hover(|s| s.text_color(variables.text))
+                                            .child("Rescan")
+                                            .on_click({
+                                                let path = path.clone();
+                                                move |_event, _window, cx| {
+                                                    let db = cx.global::<Database>().clone();
+                                                    cx.global::<Scanner>()
+                                                        .rescan_path_now(db, expand_tilde(&path));
+                                                }
+                                            }),
                                     )
-                                    .on_click(move |_event, _window, cx| {
-                                        cx.update_global::<Config, _>(|config, _cx| {
-                                            config.set(|s| {
-                                                s.scan.paths.retain(|p| p != &path);
-                                            });
-                                        });
-                                    }),
+                                    .child(
+                                        div()
+                                            .id(SharedString::from(format!("remove-path-{i}")))
+                                            .cursor_pointer()
+                                            .child(
+                                                icon(icons::X)
+                                                    .text_color(variables.text_secondary)
+                                                    .hover(|s| s.text_color(variables.text)),
+                                            )
+                                            .on_click(move |_event, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| {
+                                                        s.scan.paths.retain(|p| p != &path);
+                                                    });
+                                                });
+                                            }),
+                                    ),
                             )
                     })),
             )
@@ -93,15 +146,9 @@ impl RenderOnce for ScanPathsSection {
                         cx.spawn(async move |cx| {
                             if let Ok(Ok(Some(paths))) = receiver.await
                                 && let Some(path) = paths.into_iter().next()
-                                && let Some(path_str) = path.to_str()
                             {
-                                let path_str = path_str.to_string();
                                 cx.update_global::<Config, _>(|config: &mut Config, _cx| {
-                                    config.set(|s| {
-                                        if !s.scan.paths.contains(&path_str) {
-                                            s.scan.paths.push(path_str);
-                                        }
-                                    });
+                                    config.set(|s| add_scan_path(&mut s.scan.paths, &path));
                                 });
                             }
                         })
@@ -111,6 +158,916 @@ impl RenderOnce for ScanPathsSection {
     }
 }
 
+/// A label followed by a row of options, with `current` highlighted. Clicking an option hands
+/// its value to the `on_select` callback.
+#[derive(IntoElement)]
+struct OptionRow<T: Copy + PartialEq + 'static> {
+    id: &'static str,
+    label: &'static str,
+    current: T,
+    options: Vec<(T, SharedString)>,
+    on_select: Rc<dyn Fn(T, &mut App)>,
+}
+
+impl<T: Copy + PartialEq + 'static> OptionRow<T> {
+    fn new<L: Into<SharedString>>(
+        id: &'static str,
+        label: &'static str,
+        current: T,
+        options: impl IntoIterator<Item = (T, L)>,
+    ) -> Self {
+        Self {
+            id,
+            label,
+            current,
+            options: options
+                .into_iter()
+                .map(|(value, label)| (value, label.into()))
+                .collect(),
+            on_select: Rc::new(|_, _| {}),
+        }
+    }
+
+    fn on_select(mut self, on_select: impl Fn(T, &mut App) + 'static) -> Self {
+        self.on_select = Rc::new(on_select);
+        self
+    }
+}
+
+impl<T: Copy + PartialEq + 'static> RenderOnce for OptionRow<T> {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let OptionRow {
+            id,
+            label,
+            current,
+            options,
+            on_select,
+        } = self;
+
+        flex_row()
+            .items_center()
+            .gap(px(variables.padding_8))
+            .child(div().text_color(variables.text_secondary).child(label))
+            .children(options.into_iter().map(|(value, option_label)| {
+                let active = value == current;
+                let on_select = on_select.clone();
+                div()
+                    .id(SharedString::from(format!("{id}-{option_label}")))
+                    .cursor_pointer()
+                    .px(px(variables.padding_16))
+                    .py(px(variables.padding_8))
+                    .bg(if active {
+                        variables.element_hover
+                    } else {
+                        variables.element
+                    })
+                    .text_color(if active {
+                        variables.text
+                    } else {
+                        variables.text_secondary
+                    })
+                    .hover(|s| s.bg(variables.element_hover))
+                    .child(option_label)
+                    .on_click(move |_event, _window, cx| on_select(value, cx))
+            }))
+    }
+}
+
+#[derive(IntoElement)]
+struct ClickBehaviorSection;
+
+impl RenderOnce for ClickBehaviorSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().interface.click_behavior;
+        let options = [
+            (ClickBehavior::Cover, "Cover"),
+            (ClickBehavior::SingleClick, "Single click"),
+            (ClickBehavior::DoubleClick, "Double click"),
+        ];
+
+        OptionRow::new("click-behavior", "Play songs on", current, options).on_select(
+            |value, cx| {
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.interface.click_behavior = value);
+                });
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct PlayingSongClickSection;
+
+impl RenderOnce for PlayingSongClickSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().interface.playing_song_click;
+        let options = [
+            (PlayingSongClick::Restart, "Restart"),
+            (PlayingSongClick::Nothing, "Nothing"),
+            (PlayingSongClick::TogglePause, "Pause or resume"),
+        ];
+
+        OptionRow::new(
+            "playing-song-click",
+            "Playing the current song",
+            current,
+            options,
+        )
+        .on_select(|value, cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| s.interface.playing_song_click = value);
+            });
+        })
+    }
+}
+
+#[derive(IntoElement)]
+struct RowDensitySection;
+
+impl RenderOnce for RowDensitySection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().interface.row_density;
+        let options = [
+            (RowDensity::Comfortable, "Comfortable"),
+            (RowDensity::Compact, "Compact"),
+        ];
+
+        OptionRow::new("row-density", "Row density", current, options).on_select(|value, cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| s.interface.row_density = value);
+            });
+        })
+    }
+}
+
+#[derive(IntoElement)]
+struct FontSection {
+    font_input: Entity<TextInput>,
+}
+
+impl RenderOnce for FontSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let font_size = cx.global::<Config>().get().interface.ui_font_size();
+        let range = MAX_FONT_SIZE - MIN_FONT_SIZE;
+
+        flex_row()
+            .items_center()
+            .gap(px(variables.padding_8))
+            .child(div().text_color(variables.text_secondary).child("Font"))
+            .child(div().w(px(200.0)).child(self.font_input))
+            .child(
+                slider()
+                    .id("font-size-slider")
+                    .w(px(150.0))
+                    .h(px(16.0))
+                    .value(((font_size - MIN_FONT_SIZE) / range).clamp(0.0, 1.0))
+                    .on_change(move |val, _window, cx| {
+                        let new_size = (MIN_FONT_SIZE + val * range).round();
+                        cx.update_global::<Config, _>(|config, _cx| {
+                            config.set(|s| s.interface.font_size = new_size);
+                        });
+                    }),
+            )
+            .child(
+                div()
+                    .text_color(variables.text)
+                    .child(format!("{:.0} px", font_size)),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct FallbackArtworkSection;
+
+impl RenderOnce for FallbackArtworkSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let current = cx
+            .global::<Config>()
+            .get()
+            .interface
+            .fallback_artwork
+            .clone();
+        let custom_label = match &current {
+            FallbackArtwork::Custom(path) => path.clone(),
+            _ => "Custom image".to_string(),
+        };
+        let options = [
+            (Some(FallbackArtwork::None), "None".to_string()),
+            (Some(FallbackArtwork::Bundled), "Vleer logo".to_string()),
+            (None, custom_label),
+        ];
+
+        flex_row()
+            .items_center()
+            .gap(px(variables.padding_8))
+            .child(
+                div()
+                    .text_color(variables.text_secondary)
+                    .child("Missing artwork"),
+            )
+            .children(
+                options
+                    .into_iter()
+                    .enumerate()
+                    .map(|(ix, (artwork, label))| {
+                        let active = match &artwork {
+                            Some(artwork) => *artwork == current,
+                            None => matches!(current, FallbackArtwork::Custom(_)),
+                        };
+                        div()
+                            .id(("fallback-artwork", ix))
+                            .cursor_pointer()
+                            .max_w(px(240.0))
+                            .truncate()
+                            .px(px(variables.padding_16))
+                            .py(px(variables.padding_8))
+                            .bg(if active {
+                                variables.element_hover
+                            } else {
+                                variables.element
+                            })
+                            .text_color(if active {
+                                variables.text
+                            } else {
+                                variables.text_secondary
+                            })
+                            .hover(|s| s.bg(variables.element_hover))
+                            .child(label)
+                            .on_click(move |_event, _window, cx| {
+                                if let Some(artwork) = artwork.clone() {
+                                    cx.update_global::<Config, _>(|config, _cx| {
+                                        config.set(|s| s.interface.fallback_artwork = artwork);
+                                    });
+                                    return;
+                                }
+                                let options = PathPromptOptions {
+                                    files: true,
+                                    directories: false,
+                                    multiple: false,
+                                    prompt: None,
+                                };
+                                let receiver = cx.prompt_for_paths(options);
+                                cx.spawn(async move |cx| {
+                                    if let Ok(Ok(Some(paths))) = receiver.await
+                                        && let Some(path) = paths.into_iter().next()
+                                    {
+                                        let path_str = collapse_tilde(&path);
+                                        cx.update_global::<Config, _>(|config, _cx| {
+                                            config.set(|s| {
+                                                s.interface.fallback_artwork =
+                                                    FallbackArtwork::Custom(path_str)
+                                            });
+                                        });
+                                    }
+                                })
+                                .detach();
+                            })
+                    }),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct ScrollbarClickSection;
+
+impl RenderOnce for ScrollbarClickSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().interface.scrollbar_click;
+        let options = [
+            (ScrollbarClick::Page, "Page"),
+            (ScrollbarClick::Jump, "Jump to position"),
+        ];
+
+        OptionRow::new("scrollbar-click", "Scrollbar track click", current, options).on_select(
+            |value, cx| {
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.interface.scrollbar_click = value);
+                });
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct ScrollbarVisibilitySection;
+
+impl RenderOnce for ScrollbarVisibilitySection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().interface.scrollbar_visibility;
+        let options = [
+            (ScrollbarVisibility::System, "System"),
+            (ScrollbarVisibility::AutoHide, "Hide when idle"),
+            (ScrollbarVisibility::Always, "Always"),
+        ];
+
+        OptionRow::new("scrollbar-visibility", "Show scrollbars", current, options).on_select(
+            |value, cx| {
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.interface.scrollbar_visibility = value);
+                });
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct FocusBehaviorSection;
+
+impl RenderOnce for FocusBehaviorSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.focus_behavior;
+        let options = [
+            (AudioFocusBehavior::Ignore, "Keep playing"),
+            (AudioFocusBehavior::Duck, "Lower volume"),
+            (AudioFocusBehavior::Pause, "Pause"),
+        ];
+
+        OptionRow::new(
+            "focus-behavior",
+            "When other apps play audio",
+            current,
+            options,
+        )
+        .on_select(|value, cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| s.audio.focus_behavior = value);
+            });
+            cx.update_global::<Playback, _>(|playback, _cx| {
+                playback.set_focus_behavior(value);
+            });
+        })
+    }
+}
+
+#[derive(IntoElement)]
+struct ResumeOnLaunchSection;
+
+impl RenderOnce for ResumeOnLaunchSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.resume_on_launch;
+        let options = [
+            (ResumeOnLaunch::Paused, "Resume paused"),
+            (ResumeOnLaunch::Playing, "Resume playing"),
+            (ResumeOnLaunch::Stopped, "Stay stopped"),
+        ];
+
+        OptionRow::new("resume-on-launch", "On launch", current, options).on_select(|value, cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| s.audio.resume_on_launch = value);
+            });
+        })
+    }
+}
+
+#[derive(IntoElement)]
+struct ListeningTimeSection {
+    all_time: i64,
+    this_year: i64,
+}
+
+impl RenderOnce for ListeningTimeSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let goal_hours = cx.global::<Config>().get().yearly_listening_goal_hours;
+        let options = [
+            (None, "Off"),
+            (Some(100), "100 h"),
+            (Some(250), "250 h"),
+            (Some(500), "500 h"),
+            (Some(1000), "1000 h"),
+        ];
+
+        flex_col()
+            .gap(px(variables.padding_8))
+            .text_color(variables.text_secondary)
+            .child(format!(
+                "{} listened \u{00B7} {} this year",
+                format_total_duration(self.all_time),
+                format_total_duration(self.this_year)
+            ))
+            .when_some(goal_hours, |this, goal_hours| {
+                let progress = self.this_year as f32 / (goal_hours as f32 * 3600.0);
+                this.child(format!(
+                    "{} of {} hours this year ({:.0}%)",
+                    self.this_year / 3600,
+                    goal_hours,
+                    progress * 100.0
+                ))
+                .child(
+                    div().w(px(300.0)).h(px(4.0)).bg(variables.element).child(
+                        div()
+                            .h_full()
+                            .w(relative(progress.clamp(0.0, 1.0)))
+                            .bg(variables.accent),
+                    ),
+                )
+            })
+            .child(
+                OptionRow::new("listening-goal", "Yearly goal", goal_hours, options).on_select(
+                    |value, cx| {
+                        cx.update_global::<Config, _>(|config, _cx| {
+                            config.set(|s| s.yearly_listening_goal_hours = value);
+                        });
+                    },
+                ),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct HistoryRetentionSection;
+
+impl RenderOnce for HistoryRetentionSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let current = cx.global::<Config>().get().history_retention;
+        let options = [
+            (HistoryRetention::ThreeMonths, "90 days"),
+            (HistoryRetention::OneYear, "1 year"),
+            (HistoryRetention::Forever, "Forever"),
+        ];
+
+        flex_row()
+            .items_center()
+            .gap(px(variables.padding_8))
+            .child(
+                OptionRow::new(
+                    "history-retention",
+                    "Keep listening history",
+                    current,
+                    options,
+                )
+                .on_select(|value, cx| {
+                    cx.update_global::<Config, _>(|config, _cx| {
+                        config.set(|s| s.history_retention = value);
+                    });
+                    purge_expired_history(cx);
+                }),
+            )
+            .child(
+                div()
+                    .id("clear-listening-history")
+                    .cursor_pointer()
+                    .ml(px(variables.padding_16))
+                    .px(px(variables.padding_16))
+                    .py(px(variables.padding_8))
+                    .bg(variables.element)
+                    .text_color(variables.destructive)
+                    .hover(|s| s.bg(variables.element_hover))
+                    .child("Clear listening history")
+                    .on_click(|_event, window, cx| {
+                        let answer = window.prompt(
+                            PromptLevel::Warning,
+                            "Clear listening history?",
+                            Some("Every recorded play will be deleted. This can't be undone."),
+                            &["Clear History", "Cancel"],
+                            cx,
+                        );
+                        let db = cx.global::<Database>().clone();
+                        let background_ui = cx.global::<BackgroundUiNotifier>().clone();
+                        cx.background_executor()
+                            .spawn(async move {
+                                if answer.await != Ok(0) {
+                                    return;
+                                }
+                                match db.clear_history() {
+                                    Ok(()) => {
+                                        background_ui.notify(BackgroundUiEvent::HomeDataChanged)
+                                    }
+                                    Err(e) => {
+                                        tracing::error!("Failed to clear listening history: {}", e)
+                                    }
+                                }
+                            })
+                            .detach();
+                    }),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct MissingSongsSection;
+
+impl RenderOnce for MissingSongsSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().scan.remove_missing;
+        let options = [
+            (MissingSongsPolicy::Never, "Never"),
+            (MissingSongsPolicy::OnlyInScanPaths, "Only in scan paths"),
+            (MissingSongsPolicy::Always, "Always"),
+        ];
+
+        OptionRow::new("remove-missing", "Remove missing songs", current, options).on_select(
+            |value, cx| {
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.scan.remove_missing = value);
+                });
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct ScanConcurrencySection;
+
+impl RenderOnce for ScanConcurrencySection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().scan.concurrency;
+        let options = [None, Some(1), Some(2), Some(4), Some(8), Some(16), Some(32)]
+            .map(|n| (n, n.map_or("Auto".to_string(), |n| n.to_string())));
+
+        OptionRow::new("scan-concurrency", "Files read at once", current, options).on_select(
+            |value, cx| {
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.scan.concurrency = value);
+                });
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct LoudnessSourceSection;
+
+impl RenderOnce for LoudnessSourceSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.loudness_source;
+        let options = [
+            (LoudnessSource::TagsFirst, "ReplayGain tags"),
+            (LoudnessSource::MeasuredFirst, "Measured loudness"),
+        ];
+
+        let variables = cx.global::<Variables>();
+
+        flex_row()
+            .items_center()
+            .gap(px(variables.padding_8))
+            .child(
+                OptionRow::new("loudness-source", "Normalize using", current, options).on_select(
+                    |value, cx| {
+                        cx.update_global::<Config, _>(|config, _cx| {
+                            config.set(|s| s.audio.loudness_source = value);
+                        });
+                    },
+                ),
+            )
+            .child(
+                div()
+                    .id("measure-loudness")
+                    .cursor_pointer()
+                    .ml(px(variables.padding_16))
+                    .px(px(variables.padding_16))
+                    .py(px(variables.padding_8))
+                    .bg(variables.element)
+                    .text_color(variables.text)
+                    .hover(|s| s.bg(variables.element_hover))
+                    .child("Measure untagged songs")
+                    .on_click(|_event, _window, cx| {
+                        let db = cx.global::<Database>().clone();
+                        cx.global::<Scanner>().spawn_loudness_measurement(db);
+                    }),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct ResamplerQualitySection;
+
+impl RenderOnce for ResamplerQualitySection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.resampler_quality;
+        let options = [
+            (ResamplerQuality::Fast, "Fast"),
+            (ResamplerQuality::Balanced, "Balanced"),
+            (ResamplerQuality::High, "High"),
+        ];
+
+        OptionRow::new("resampler-quality", "Resampling", current, options).on_select(
+            |value, cx| {
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.audio.resampler_quality = value);
+                });
+                cx.update_global::<Playback, _>(|playback, _cx| {
+                    playback.set_resampler_quality(value);
+                });
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct SeekFadeSection;
+
+impl RenderOnce for SeekFadeSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.seek_fade_ms;
+        let options = [(0, "Off"), (5, "5 ms"), (10, "10 ms"), (20, "20 ms")];
+
+        OptionRow::new("seek-fade", "Fade when seeking", current, options).on_select(|value, cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| s.audio.seek_fade_ms = value);
+            });
+            cx.update_global::<Playback, _>(|playback, _cx| {
+                playback.set_seek_fade_ms(value);
+            });
+        })
+    }
+}
+
+#[derive(IntoElement)]
+struct OutputSampleRateSection;
+
+impl RenderOnce for OutputSampleRateSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.output_sample_rate;
+        let options = [
+            (None, "Automatic"),
+            (Some(44100), "44.1 kHz"),
+            (Some(48000), "48 kHz"),
+            (Some(96000), "96 kHz"),
+        ];
+
+        OptionRow::new("output-sample-rate", "Output sample rate", current, options).on_select(
+            |value, cx| {
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.audio.output_sample_rate = value);
+                });
+                cx.update_global::<Playback, _>(|playback, _cx| {
+                    playback.set_output_sample_rate(value);
+                });
+            },
+        )
+    }
+}
+
+#[derive(IntoElement)]
+struct ReleaseOutputSection;
+
+impl RenderOnce for ReleaseOutputSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.release_output_after_secs;
+        let options = [
+            (None, "Never"),
+            (Some(10), "10 s"),
+            (Some(60), "1 min"),
+            (Some(300), "5 min"),
+        ];
+
+        OptionRow::new(
+            "release-output",
+            "Release audio device when paused for",
+            current,
+            options,
+        )
+        .on_select(|value, cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| s.audio.release_output_after_secs = value);
+            });
+            cx.update_global::<Playback, _>(|playback, _cx| {
+                playback.set_release_output_after(value);
+            });
+        })
+    }
+}
+
+#[derive(IntoElement)]
+struct ShuffleMinDurationSection;
+
+impl RenderOnce for ShuffleMinDurationSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let current = cx.global::<Config>().get().audio.shuffle_min_duration_secs;
+        let options = [
+            (None, "Off"),
+            (Some(15), "15 s"),
+            (Some(30), "30 s"),
+            (Some(60), "1 min"),
+        ];
+
+        OptionRow::new(
+            "shuffle-min-duration",
+            "Skip shorter songs on shuffle",
+            current,
+            options,
+        )
+        .on_select(|value, cx| {
+            cx.update_global::<Config, _>(|config, _cx| {
+                config.set(|s| s.audio.shuffle_min_duration_secs = value);
+            });
+        })
+    }
+}
+
+#[derive(IntoElement)]
+struct NormalizationPreampSection;
+
+impl RenderOnce for NormalizationPreampSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let preamp_db = cx.global::<Config>().get().audio.normalization_preamp_db;
+        let range = MAX_PREAMP_DB * 2.0;
+
+        flex_row()
+            .items_center()
+            .gap(px(variables.padding_8))
+            .child(
+                div()
+                    .text_color(variables.text_secondary)
+                    .child("Normalization preamp"),
+            )
+            .child(
+                slider()
+                    .id("normalization-preamp-slider")
+                    .w(px(150.0))
+                    .h(px(16.0))
+                    .value(((preamp_db + MAX_PREAMP_DB) / range).clamp(0.0, 1.0))
+                    .on_change(move |val, _window, cx| {
+                        let new_preamp = ((val * range - MAX_PREAMP_DB) * 2.0).round() / 2.0;
+                        cx.update_global::<Config, _>(|config, _cx| {
+                            config.set(|s| s.audio.normalization_preamp_db = new_preamp);
+                        });
+                        cx.update_global::<Playback, _>(|playback, _cx| {
+                            playback.set_normalization_preamp(new_preamp);
+                        });
+                    }),
+            )
+            .child(
+                div()
+                    .text_color(variables.text)
+                    .child(format!("{:+.1} dB", preamp_db)),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct EqPreampSection;
+
+impl RenderOnce for EqPreampSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let preamp_db = cx.global::<Config>().get().equalizer.preamp_db;
+        let range = MAX_PREAMP_DB * 2.0;
+
+        flex_row()
+            .items_center()
+            .gap(px(variables.padding_8))
+            .child(
+                div()
+                    .text_color(variables.text_secondary)
+                    .child("EQ preamp"),
+            )
+            .child(
+                slider()
+                    .id("eq-preamp-slider")
+                    .w(px(150.0))
+                    .h(px(16.0))
+                    .value(((preamp_db + MAX_PREAMP_DB) / range).clamp(0.0, 1.0))
+                    .on_change(move |val, _window, cx| {
+                        let new_preamp = ((val * range - MAX_PREAMP_DB) * 2.0).round() / 2.0;
+                        cx.update_global::<Config, _>(|config, _cx| {
+                            config.set(|s| s.equalizer.preamp_db = new_preamp);
+                        });
+                        cx.update_global::<Playback, _>(|playback, _cx| {
+                            playback.set_eq_preamp(new_preamp);
+                        });
+                    }),
+            )
+            .child(
+                div()
+                    .text_color(variables.text)
+                    .child(format!("{:+.1} dB", preamp_db)),
+            )
+    }
+}
+
+#[derive(IntoElement)]
+struct ImportSection {
+    pattern_input: Entity<TextInput>,
+}
+
+impl RenderOnce for ImportSection {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let variables = cx.global::<Variables>();
+        let organize = cx.global::<Config>().get().organize.clone();
+        let read_only = cx.global::<Config>().read_only();
+        let modes = [
+            (ImportMode::InPlace, "Keep in place"),
+            (ImportMode::Copy, "Copy"),
+            (ImportMode::Move, "Move"),
+        ];
+
+        flex_col()
+            .items_start()
+            .gap(px(variables.padding_16))
+            .child(
+                OptionRow::new("import-mode", "Imported files", organize.import_mode, modes)
+                    .on_select(|value, cx| {
+                        cx.update_global::<Config, _>(|config, _cx| {
+                            config.set(|s| s.organize.import_mode = value);
+                        });
+                    }),
+            )
+            .child(
+                flex_row()
+                    .items_center()
+                    .gap(px(variables.padding_8))
+                    .child(
+                        div()
+                            .text_color(variables.text_secondary)
+                            .child("Library folder"),
+                    )
+                    .child(
+                        div()
+                            .id("organize-library-root")
+                            .cursor_pointer()
+                            .max_w(px(450.0))
+                            .px(px(variables.padding_16))
+                            .py(px(variables.padding_8))
+                            .bg(variables.element)
+                            .text_color(variables.text)
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .hover(|s| s.bg(variables.element_hover))
+                            .child(if organize.library_root.is_empty() {
+                                "Choose folder".to_string()
+                            } else {
+                                organize.library_root.clone()
+                            })
+                            .on_click(|_event, _window, cx| {
+                                let options = PathPromptOptions {
+                                    files: false,
+                                    directories: true,
+                                    multiple: false,
+                                    prompt: None,
+                                };
+                                let receiver = cx.prompt_for_paths(options);
+                                cx.spawn(async move |cx| {
+                                    if let Ok(Ok(Some(paths))) = receiver.await
+                                        && let Some(path) = paths.into_iter().next()
+                                    {
+                                        let path_str = collapse_tilde(&path);
+                                        cx.update_global::<Config, _>(|config, _cx| {
+                                            config.set(|s| s.organize.library_root = path_str);
+                                        });
+                                    }
+                                })
+                                .detach();
+                            }),
+                    ),
+            )
+            .child(
+                flex_row()
+                    .items_center()
+                    .gap(px(variables.padding_8))
+                    .child(div().text_color(variables.text_secondary).child("Pattern"))
+                    .child(div().w(px(300.0)).child(self.pattern_input)),
+            )
+            .when(!read_only, |this| {
+                this.child(
+                    div()
+                        .id("import-files")
+                        .cursor_pointer()
+                        .px(px(variables.padding_16))
+                        .py(px(variables.padding_8))
+                        .bg(variables.element)
+                        .text_color(variables.text)
+                        .hover(|s| s.bg(variables.element_hover))
+                        .child("Import files")
+                        .on_click(|_event, _window, cx| import_files(cx)),
+                )
+            })
+    }
+}
+
+fn import_files(cx: &mut App) {
+    let options = PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: true,
+        prompt: None,
+    };
+    let receiver = cx.prompt_for_paths(options);
+    let scanner = cx.global::<Scanner>().clone();
+    let db = cx.global::<Database>().clone();
+    let organize = cx.global::<Config>().get().organize.clone();
+    let bg = cx.background_executor().clone();
+
+    cx.spawn(async move |_cx| {
+        let Ok(Ok(Some(paths))) = receiver.await else {
+            return;
+        };
+        let result = bg
+            .spawn(async move { scanner.import_files(&db, paths, &organize).await })
+            .await;
+        if let Err(e) = result {
+            tracing::error!("Import failed: {}", e);
+        }
+    })
+    .detach();
+}
+
 #[derive(IntoElement)]
 struct EqSection {
     gain_inputs: Vec<Entity<TextInput>>,
@@ -260,6 +1217,47 @@ pub struct SettingsView {
     gain_inputs: Vec<Entity<TextInput>>,
     freq_inputs: Vec<Entity<TextInput>>,
     q_inputs: Vec<Entity<TextInput>>,
+    pattern_input: Entity<TextInput>,
+    font_input: Entity<TextInput>,
+    library_totals: Option<LibraryTotals>,
+    /// Seconds listened over all kept history and since the start of this year.
+    listen_time: Option<(i64, i64)>,
+    scan_runs: Vec<ScanRun>,
+    frequently_skipped: Vec<SkippedSong>,
+    organize_preview: Option<Vec<PlannedMove>>,
+    validated_scan_paths: Vec<String>,
+    scan_path_errors: HashMap<String, &'static str>,
+}
+
+const ORGANIZE_PREVIEW_ROWS: usize = 100;
+const SCAN_HISTORY_ROWS: i64 = 10;
+const FREQUENTLY_SKIPPED_MIN_SKIPS: i64 = 3;
+const FREQUENTLY_SKIPPED_ROWS: i64 = 10;
+
+fn format_total_duration(secs: i64) -> String {
+    let days = secs / 86_400;
+    let hours = (secs % 86_400) / 3600;
+    let minutes = (secs % 3600) / 60;
+    if days > 0 {
+        format!("{} days, {} hours", days, hours)
+    } else {
+        format!("{} hours, {} minutes", hours, minutes)
+    }
+}
+
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes.max(0) as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
 }
 
 impl SettingsView {
@@ -284,6 +1282,9 @@ impl SettingsView {
                     inp.set_text(format!("{:.2}", q), cx);
                 });
             }
+            if cx.global::<Config>().get().scan.paths != this.validated_scan_paths {
+                this.validate_scan_paths(cx);
+            }
             cx.notify();
         })
         .detach();
@@ -302,6 +1303,7 @@ impl SettingsView {
                         .with_text_color(text_secondary)
                         .with_height(px(24.0))
                         .centered()
+                        .with_paste_policy(PastePolicy::Strip)
                         .with_validator(|s| {
                             if s.is_empty() {
                                 return true;
@@ -324,6 +1326,7 @@ impl SettingsView {
                         .with_text_color(text_secondary)
                         .with_height(px(24.0))
                         .centered()
+                        .with_paste_policy(PastePolicy::Strip)
                         .with_validator(|s| {
                             if s.is_empty() {
                                 return true;
@@ -346,6 +1349,7 @@ impl SettingsView {
                         .with_text_color(text_secondary)
                         .with_height(px(24.0))
                         .centered()
+                        .with_paste_policy(PastePolicy::Strip)
                         .with_validator(|s| {
                             if s.is_empty() {
                                 return true;
@@ -429,14 +1433,279 @@ impl SettingsView {
                     });
                 }
             })
-            .detach();
-        }
+            .detach();
+        }
+
+        let pattern = cx.global::<Config>().get().organize.pattern.clone();
+        let pattern_input = cx.new(|cx| {
+            TextInput::new(cx, "{artist}/{album}/{track} {title}")
+                .with_paste_policy(PastePolicy::FirstLine)
+                .with_text(pattern)
+                .with_background(element_hover)
+                .with_text_color(text_secondary)
+                .with_height(px(32.0))
+        });
+        cx.subscribe(&pattern_input, |_this, _entity, event, cx| {
+            if let InputEvent::Submit(text) = event
+                && !text.trim().is_empty()
+            {
+                let pattern = text.trim().to_string();
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.organize.pattern = pattern);
+                });
+            }
+        })
+        .detach();
+
+        let font_family = cx.global::<Config>().get().interface.font_family.clone();
+        let font_input = cx.new(|cx| {
+            TextInput::new(cx, "Feature Mono")
+                .with_paste_policy(PastePolicy::FirstLine)
+                .with_text(font_family)
+                .with_background(element_hover)
+                .with_text_color(text_secondary)
+                .with_height(px(32.0))
+        });
+        cx.subscribe(&font_input, |_this, _entity, event, cx| {
+            if let InputEvent::Submit(text) = event
+                && !text.trim().is_empty()
+            {
+                let family = text.trim().to_string();
+                cx.update_global::<Config, _>(|config, _cx| {
+                    config.set(|s| s.interface.font_family = family);
+                });
+            }
+        })
+        .detach();
+
+        let mut view = Self {
+            gain_inputs,
+            freq_inputs,
+            q_inputs,
+            pattern_input,
+            font_input,
+            library_totals: None,
+            listen_time: None,
+            scan_runs: Vec::new(),
+            frequently_skipped: Vec::new(),
+            organize_preview: None,
+            validated_scan_paths: Vec::new(),
+            scan_path_errors: HashMap::new(),
+        };
+        view.refresh_library_totals(cx);
+        view.validate_scan_paths(cx);
+        view
+    }
+
+    fn validate_scan_paths(&mut self, cx: &mut Context<Self>) {
+        let paths = cx.global::<Config>().get().scan.paths.clone();
+        self.validated_scan_paths = paths.clone();
+        let bg = cx.background_executor().clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let checked = paths.clone();
+            let errors = bg
+                .spawn(async move {
+                    checked
+                        .into_iter()
+                        .filter_map(|path| {
+                            scan_path_error(&expand_tilde(&path)).map(|error| (path, error))
+                        })
+                        .collect::<HashMap<_, _>>()
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                if this.validated_scan_paths == paths {
+                    this.scan_path_errors = errors;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn preview_organize(&mut self, cx: &mut Context<Self>) {
+        let scanner = cx.global::<Scanner>().clone();
+        let db = cx.global::<Database>().clone();
+        let organize = cx.global::<Config>().get().organize.clone();
+        let bg = cx.background_executor().clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let preview = bg
+                .spawn(async move { scanner.preview_organize(&db, &organize).await })
+                .await;
+            let preview = match preview {
+                Ok(moves) => moves,
+                Err(e) => {
+                    tracing::error!("Failed to preview library organization: {}", e);
+                    return;
+                }
+            };
+
+            cx.update(|cx| {
+                this.update(cx, |this, cx| {
+                    this.organize_preview = Some(preview);
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn apply_organize(&mut self, cx: &mut Context<Self>) {
+        let Some(moves) = self.organize_preview.take() else {
+            return;
+        };
+        cx.notify();
+        if moves.is_empty() {
+            return;
+        }
+
+        let scanner = cx.global::<Scanner>().clone();
+        let db = cx.global::<Database>().clone();
+        cx.background_executor()
+            .spawn(async move {
+                if let Err(e) = scanner.apply_organize(&db, moves).await {
+                    tracing::error!("Failed to organize library: {}", e);
+                }
+            })
+            .detach();
+    }
+
+    fn organize_preview_element(&self, cx: &Context<Self>) -> AnyElement {
+        let variables = cx.global::<Variables>();
+        let read_only = cx.global::<Config>().read_only();
+        let Some(moves) = &self.organize_preview else {
+            return div()
+                .id("organize-preview")
+                .cursor_pointer()
+                .px(px(variables.padding_16))
+                .py(px(variables.padding_8))
+                .bg(variables.element)
+                .text_color(variables.text)
+                .hover(|s| s.bg(variables.element_hover))
+                .child("Organize library")
+                .on_click(cx.listener(|this, _event, _window, cx| {
+                    this.preview_organize(cx);
+                }))
+                .into_any_element();
+        };
+
+        let summary = if moves.is_empty() {
+            "All files already match the pattern".to_string()
+        } else {
+            format!(
+                "{} file{} will be moved",
+                moves.len(),
+                if moves.len() == 1 { "" } else { "s" }
+            )
+        };
+
+        flex_col()
+            .items_start()
+            .gap(px(variables.padding_8))
+            .child(div().text_color(variables.text).child(summary))
+            .children(moves.iter().take(ORGANIZE_PREVIEW_ROWS).map(|m| {
+                flex_col()
+                    .max_w(px(650.0))
+                    .child(
+                        div()
+                            .text_color(variables.text_secondary)
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .child(m.from.to_string_lossy().to_string()),
+                    )
+                    .child(
+                        div()
+                            .text_color(variables.text)
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .child(format!("\u{2192} {}", m.to.to_string_lossy())),
+                    )
+            }))
+            .when(moves.len() > ORGANIZE_PREVIEW_ROWS, |this| {
+                this.child(
+                    div()
+                        .text_color(variables.text_secondary)
+                        .child(format!("and {} more", moves.len() - ORGANIZE_PREVIEW_ROWS)),
+                )
+            })
+            .child(
+                flex_row()
+                    .gap(px(variables.padding_8))
+                    .when(!moves.is_empty() && !read_only, |this| {
+                        this.child(
+                            div()
+                                .id("organize-apply")
+                                .cursor_pointer()
+                                .px(px(variables.padding_16))
+                                .py(px(variables.padding_8))
+                                .bg(variables.accent)
+                                .text_color(variables.background)
+                                .hover(|s| s.bg(variables.accent_hover))
+                                .child("Apply")
+                                .on_click(cx.listener(|this, _event, _window, cx| {
+                                    this.apply_organize(cx);
+                                })),
+                        )
+                    })
+                    .child(
+                        div()
+                            .id("organize-cancel")
+                            .cursor_pointer()
+                            .px(px(variables.padding_16))
+                            .py(px(variables.padding_8))
+                            .bg(variables.element)
+                            .text_color(variables.text)
+                            .hover(|s| s.bg(variables.element_hover))
+                            .child("Cancel")
+                            .on_click(cx.listener(|this, _event, _window, cx| {
+                                this.organize_preview = None;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .into_any_element()
+    }
 
-        Self {
-            gain_inputs,
-            freq_inputs,
-            q_inputs,
-        }
+    fn refresh_library_totals(&mut self, cx: &mut Context<Self>) {
+        let db = cx.global::<Database>().clone();
+        let bg = cx.background_executor().clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let (totals, listen_time, scan_runs, frequently_skipped) = bg
+                .spawn(async move {
+                    let totals = db.get_library_totals().ok();
+                    let year_start = format!("{}-01-01 00:00:00", chrono::Local::now().year());
+                    let listen_time = db
+                        .get_total_listen_time(None)
+                        .ok()
+                        .zip(db.get_total_listen_time(Some(&year_start)).ok());
+                    let scan_runs = db.get_scan_runs(SCAN_HISTORY_ROWS).unwrap_or_default();
+                    let frequently_skipped = db
+                        .get_frequently_skipped(
+                            FREQUENTLY_SKIPPED_MIN_SKIPS,
+                            FREQUENTLY_SKIPPED_ROWS,
+                        )
+                        .unwrap_or_default();
+                    (totals, listen_time, scan_runs, frequently_skipped)
+                })
+                .await;
+
+            cx.update(|cx| {
+                this.update(cx, |this, cx| {
+                    this.library_totals = totals;
+                    this.listen_time = listen_time;
+                    this.scan_runs = scan_runs;
+                    this.frequently_skipped = frequently_skipped;
+                    cx.notify();
+                })
+            })
+            .ok();
+        })
+        .detach();
     }
 }
 
@@ -446,7 +1715,21 @@ impl Render for SettingsView {
         let telemetry = cx.global::<Config>().get().telemetry;
         let discord_rpc = cx.global::<Config>().get().discord_rpc;
         let visualizer_enabled = cx.global::<Config>().get().audio.visualizer;
+        let spectrum_analyzer = cx.global::<Config>().get().audio.spectrum_analyzer;
         let eq_enabled = cx.global::<Config>().get().equalizer.enabled;
+        let limiter_enabled = cx.global::<Config>().get().audio.limiter;
+        let pause_on_sleep = cx.global::<Config>().get().audio.pause_on_sleep;
+        let gapless = cx.global::<Config>().get().audio.gapless;
+        let clipping_warning = cx.global::<Config>().get().audio.clipping_warning;
+        let autoplay = cx.global::<Config>().get().audio.autoplay;
+        let detect_transcodes = cx.global::<Config>().get().scan.detect_transcodes;
+        let include_hidden = cx.global::<Config>().get().scan.include_hidden;
+        let group_recently_added = cx.global::<Config>().get().interface.group_recently_added;
+        let pin_new_playlists = cx.global::<Config>().get().interface.pin_new_playlists;
+        let index_rail = cx.global::<Config>().get().interface.index_rail;
+        let ignore_articles = cx.global::<Config>().get().interface.ignore_articles;
+        let momentum_scroll = cx.global::<Config>().get().interface.momentum_scroll;
+        let read_only = cx.global::<Config>().read_only();
 
         div()
             .flex_1()
@@ -469,6 +1752,13 @@ impl Render for SettingsView {
                                     .font_weight(FontWeight::BOLD)
                                     .child("General"),
                             )
+                            .when(read_only, |this| {
+                                this.child(
+                                    div()
+                                        .text_color(variables.warning)
+                                        .child("Read-only mode: changes here aren't saved"),
+                                )
+                            })
                             .child(
                                 flex_row()
                                     .items_center()
@@ -534,6 +1824,113 @@ impl Render for SettingsView {
                                             .text_color(variables.text_secondary)
                                             .child("Discord RPC"),
                                     ),
+                            )
+                            .child(HistoryRetentionSection)
+                            .child(ClickBehaviorSection)
+                            .child(PlayingSongClickSection)
+                            .child(RowDensitySection)
+                            .child(FontSection {
+                                font_input: self.font_input.clone(),
+                            })
+                            .child(FallbackArtworkSection)
+                            .child(ScrollbarClickSection)
+                            .child(ScrollbarVisibilitySection)
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new(
+                                            "group-recently-added-switch",
+                                            group_recently_added,
+                                        )
+                                        .on_change(move |value, _window, cx| {
+                                            cx.update_global::<Config, _>(|config, _cx| {
+                                                config.set(|s| {
+                                                    s.interface.group_recently_added = value
+                                                });
+                                            });
+                                        }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Group recently added by date"),
+                                    ),
+                            )
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("pin-new-playlists-switch", pin_new_playlists)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| {
+                                                        s.interface.pin_new_playlists = value
+                                                    });
+                                                });
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Pin new playlists to the sidebar"),
+                                    ),
+                            )
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(Switch::new("index-rail-switch", index_rail).on_change(
+                                        move |value, _window, cx| {
+                                            cx.update_global::<Config, _>(|config, _cx| {
+                                                config.set(|s| s.interface.index_rail = value);
+                                            });
+                                        },
+                                    ))
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Show an A–Z index on long lists"),
+                                    ),
+                            )
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("ignore-articles-switch", ignore_articles)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| {
+                                                        s.interface.ignore_articles = value
+                                                    });
+                                                });
+                                                cx.global::<Database>().set_ignore_articles(value);
+                                                cx.set_global(LibraryDataChanged);
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Ignore \"The\", \"A\" and \"An\" when sorting"),
+                                    ),
+                            )
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("momentum-scroll-switch", momentum_scroll)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| {
+                                                        s.interface.momentum_scroll = value
+                                                    });
+                                                });
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Keep scrolling briefly after the mouse wheel"),
+                                    ),
                             ),
                     )
                     .child(
@@ -567,22 +1964,152 @@ impl Render for SettingsView {
                                             .child("Visualizer"),
                                     ),
                             )
+                            .when(visualizer_enabled, |section| {
+                                section.child(
+                                    flex_row()
+                                        .gap(px(variables.padding_8))
+                                        .child(
+                                            Switch::new(
+                                                "spectrum-analyzer-switch",
+                                                spectrum_analyzer,
+                                            )
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| {
+                                                        s.audio.spectrum_analyzer = value
+                                                    });
+                                                });
+                                            }),
+                                        )
+                                        .child(
+                                            div()
+                                                .text_color(variables.text_secondary)
+                                                .child("Spectrum analyzer in the player"),
+                                        ),
+                                )
+                            })
+                            .child(NormalizationPreampSection)
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("clipping-warning-switch", clipping_warning)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config
+                                                        .set(|s| s.audio.clipping_warning = value);
+                                                });
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Warn when normalization makes a song clip"),
+                                    ),
+                            )
+                            .child(LoudnessSourceSection)
+                            .child(ResamplerQualitySection)
+                            .child(SeekFadeSection)
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(Switch::new("gapless-switch", gapless).on_change(
+                                        move |value, _window, cx| {
+                                            cx.update_global::<Config, _>(|config, _cx| {
+                                                config.set(|s| s.audio.gapless = value);
+                                            });
+                                            cx.update_global::<Playback, _>(|playback, _cx| {
+                                                playback.set_gapless(value);
+                                            });
+                                        },
+                                    ))
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Play consecutive songs without a gap"),
+                                    ),
+                            )
+                            .child(OutputSampleRateSection)
+                            .child(ReleaseOutputSection)
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("limiter-enabled-switch", limiter_enabled)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| s.audio.limiter = value);
+                                                });
+                                                cx.update_global::<Playback, _>(|playback, _cx| {
+                                                    playback.set_limiter_enabled(value);
+                                                });
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Limiter"),
+                                    ),
+                            )
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("pause-on-sleep-switch", pause_on_sleep)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| s.audio.pause_on_sleep = value);
+                                                });
+                                                cx.update_global::<Playback, _>(|playback, _cx| {
+                                                    playback.set_pause_on_sleep(value);
+                                                });
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Pause when the computer sleeps or locks"),
+                                    ),
+                            )
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(Switch::new("autoplay-switch", autoplay).on_change(
+                                        move |value, _window, cx| {
+                                            cx.update_global::<Config, _>(|config, _cx| {
+                                                config.set(|s| s.audio.autoplay = value);
+                                            });
+                                        },
+                                    ))
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Play similar songs when the queue ends"),
+                                    ),
+                            )
+                            .child(FocusBehaviorSection)
+                            .child(ResumeOnLaunchSection)
+                            .child(ShuffleMinDurationSection)
                             .child(
                                 flex_row()
                                     .gap(px(variables.padding_8))
                                     .child(Switch::new("eq-enabled-switch", eq_enabled).on_change(
                                         move |value, _window, cx| {
-                                            let (gains, q_values) =
+                                            let (gains, q_values, preamp_db) =
                                                 cx.update_global::<Config, _>(|config, _cx| {
                                                     config.set(|s| s.equalizer.enabled = value);
                                                     let eq = &config.get().equalizer;
-                                                    (eq.gains.clone(), eq.q_values.clone())
+                                                    (
+                                                        eq.gains.clone(),
+                                                        eq.q_values.clone(),
+                                                        eq.preamp_db,
+                                                    )
                                                 });
                                             cx.update_global::<Playback, _>(|playback, _cx| {
+                                                playback.set_eq_enabled(value);
                                                 if value {
                                                     playback.apply_eq_settings(&gains, &q_values);
-                                                } else {
-                                                    playback.set_eq_enabled(false);
+                                                    playback.set_eq_preamp(preamp_db);
                                                 }
                                             });
                                         },
@@ -593,6 +2120,7 @@ impl Render for SettingsView {
                                             .child("Equalizer"),
                                     ),
                             )
+                            .child(EqPreampSection)
                             .child(EqSection {
                                 gain_inputs: self.gain_inputs.clone(),
                                 freq_inputs: self.freq_inputs.clone(),
@@ -609,7 +2137,173 @@ impl Render for SettingsView {
                                     .font_weight(FontWeight::BOLD)
                                     .child("Scan Paths"),
                             )
-                            .child(ScanPathsSection),
+                            .child(ScanPathsSection {
+                                errors: self.scan_path_errors.clone(),
+                            })
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("detect-transcodes-switch", detect_transcodes)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| {
+                                                        s.scan.detect_transcodes = value
+                                                    });
+                                                });
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Flag lossless files that look like transcodes"),
+                                    ),
+                            )
+                            .child(
+                                flex_row()
+                                    .gap(px(variables.padding_8))
+                                    .child(
+                                        Switch::new("include-hidden-switch", include_hidden)
+                                            .on_change(move |value, _window, cx| {
+                                                cx.update_global::<Config, _>(|config, _cx| {
+                                                    config.set(|s| s.scan.include_hidden = value);
+                                                });
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_color(variables.text_secondary)
+                                            .child("Scan hidden files and folders"),
+                                    ),
+                            )
+                            .child(MissingSongsSection)
+                            .child(ScanConcurrencySection),
+                    )
+                    .child(
+                        flex_col()
+                            .items_start()
+                            .gap(px(variables.padding_16))
+                            .child(
+                                div()
+                                    .text_color(variables.text)
+                                    .text_xl()
+                                    .font_weight(FontWeight::BOLD)
+                                    .child("Import"),
+                            )
+                            .child(ImportSection {
+                                pattern_input: self.pattern_input.clone(),
+                            })
+                            .child(self.organize_preview_element(cx)),
+                    )
+                    .child(
+                        flex_col()
+                            .items_start()
+                            .gap(px(variables.padding_16))
+                            .child(
+                                div()
+                                    .text_color(variables.text)
+                                    .text_xl()
+                                    .font_weight(FontWeight::BOLD)
+                                    .child("Library"),
+                            )
+                            .child(match &self.library_totals {
+                                Some(totals) => flex_col()
+                                    .gap(px(variables.padding_8))
+                                    .text_color(variables.text_secondary)
+                                    .child(format!(
+                                        "{} songs \u{00B7} {} albums \u{00B7} {} artists",
+                                        totals.songs, totals.albums, totals.artists
+                                    ))
+                                    .child(format!("{} playlists", totals.playlists))
+                                    .child(format!(
+                                        "{} of music",
+                                        format_total_duration(totals.duration_secs)
+                                    ))
+                                    .child(format!(
+                                        "{} on disk \u{00B7} {} database",
+                                        format_bytes(totals.file_size),
+                                        format_bytes(totals.db_size)
+                                    ))
+                                    .into_any_element(),
+                                None => div()
+                                    .text_color(variables.text_secondary)
+                                    .child("Loading...")
+                                    .into_any_element(),
+                            })
+                            .when_some(self.listen_time, |this, (all_time, this_year)| {
+                                this.child(ListeningTimeSection {
+                                    all_time,
+                                    this_year,
+                                })
+                            })
+                            .when(!self.scan_runs.is_empty(), |this| {
+                                this.child(
+                                    flex_col()
+                                        .gap(px(variables.padding_8))
+                                        .child(
+                                            div().text_color(variables.text).child("Recent scans"),
+                                        )
+                                        .children(self.scan_runs.iter().map(|run| {
+                                            div().text_color(variables.text_secondary).child(
+                                                format!(
+                                                    "{} \u{00B7} {} scanned, {} added, {} updated, \
+                                                     {} removed, {} missing",
+                                                    run.finished_at,
+                                                    run.scanned,
+                                                    run.added,
+                                                    run.updated,
+                                                    run.removed,
+                                                    run.missing
+                                                ),
+                                            )
+                                        })),
+                                )
+                            })
+                            .when(!self.frequently_skipped.is_empty(), |this| {
+                                this.child(
+                                    flex_col()
+                                        .gap(px(variables.padding_8))
+                                        .child(
+                                            div()
+                                                .text_color(variables.text)
+                                                .child("Frequently skipped"),
+                                        )
+                                        .children(self.frequently_skipped.iter().map(|skipped| {
+                                            let song = &skipped.song;
+                                            let title = if song.artists.is_empty() {
+                                                song.title.clone()
+                                            } else {
+                                                format!(
+                                                    "{} \u{2014} {}",
+                                                    song.artists.join(", "),
+                                                    song.title
+                                                )
+                                            };
+                                            div().text_color(variables.text_secondary).child(
+                                                format!(
+                                                    "{} \u{00B7} skipped {} of {} plays",
+                                                    title,
+                                                    skipped.skips,
+                                                    skipped.plays
+                                                ),
+                                            )
+                                        })),
+                                )
+                            })
+                            .child(
+                                div()
+                                    .id("library-stats-refresh")
+                                    .cursor_pointer()
+                                    .px(px(variables.padding_16))
+                                    .py(px(variables.padding_8))
+                                    .bg(variables.element)
+                                    .text_color(variables.text)
+                                    .hover(|s| s.bg(variables.element_hover))
+                                    .child("Refresh")
+                                    .on_click(cx.listener(|this, _event, _window, cx| {
+                                        this.refresh_library_totals(cx);
+                                    })),
+                            ),
                     )
                     .child(UpdatesSection),
             )
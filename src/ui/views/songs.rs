@@ -1,4 +1,5 @@
-use gpui::*;
+use chrono::{Local, NaiveDateTime};
+use gpui::{prelude::FluentBuilder as _, *};
 use rustc_hash::FxHashMap;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -7,6 +8,7 @@ use tracing::error;
 
 use crate::{
     data::{
+        config::Config,
         db::repo::Database,
         models::{SongListItem, SongSort},
     },
@@ -14,10 +16,13 @@ use crate::{
     ui::{
         components::{
             context_menu::{LibraryDataChanged, QueueChanged},
-            div::flex_col,
+            cover::image_uri,
+            div::{flex_col, flex_row},
+            index_rail::{IndexRail, MIN_INDEXED_ITEMS, letter_offsets},
             song_table::{
-                GetRowCountHandler, GetRowHandler, QueueHandler, SongColumn, SongEntry, SongTable,
-                SongTableEvent, TableSort, format_artist_line,
+                GetRowCountHandler, GetRowHandler, JumpToPlayingRequested, LocateHandler,
+                QueueHandler, SongColumn, SongEntry, SongTable, SongTableEvent, TableSort,
+                format_artist_line,
             },
         },
         layout::library::Search,
@@ -46,10 +51,29 @@ fn map_sort(sort: Option<TableSort>) -> (SongSort, bool) {
             column: SongColumn::Genre,
             ascending,
         }) => (SongSort::Genre, ascending),
+        Some(TableSort {
+            column: SongColumn::LastPlayed,
+            ascending,
+        }) => (SongSort::LastPlayed, ascending),
         _ => (SongSort::Default, false),
     }
 }
 
+/// The day a song was last played, from its UTC timestamp shown in local time.
+fn format_last_played(timestamp: Option<&str>) -> String {
+    let Some(timestamp) = timestamp else {
+        return "Never".to_string();
+    };
+    match NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S") {
+        Ok(utc) => utc
+            .and_utc()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d")
+            .to_string(),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
 fn song_entry_from_list_item(item: SongListItem) -> Arc<SongEntry> {
     let (artist, artist_ranges) = format_artist_line(&item.artist_name);
     let album = item.album_title.unwrap_or_else(|| "Unknown".to_string());
@@ -64,9 +88,11 @@ fn song_entry_from_list_item(item: SongListItem) -> Arc<SongEntry> {
         album,
         album_id: item.album_id,
         duration: format!("{}:{:02}", minutes, seconds),
-        cover_uri: item.image_id.map(|id| format!("!image://{}", id)),
+        cover_uri: item.image_id.as_deref().map(image_uri),
         track_number: None,
         genre: item.genres.unwrap_or_default(),
+        last_played: format_last_played(item.last_played.as_deref()),
+        unavailable: item.unavailable,
     })
 }
 
@@ -241,7 +267,9 @@ fn spawn_page_fetch(
     cx.spawn(async move |cx: &mut AsyncApp| {
         let q = query.clone();
         let items = match bg
-            .spawn(async move { db.get_songs(Some(&q), sort, ascending, offset, limit) })
+            .spawn(async move {
+                db.get_songs_with_last_played(Some(&q), sort, ascending, offset, limit)
+            })
             .await
         {
             Ok(items) => items,
@@ -285,9 +313,53 @@ pub struct SongsView {
     table: Entity<SongTable>,
     last_query: String,
     cache: CacheHandle,
+    index_offsets: Vec<(char, usize)>,
+    index_len: usize,
+    index_token: u64,
+    index_rail_enabled: bool,
 }
 
 impl SongsView {
+    fn refresh_index(&mut self, cx: &mut Context<Self>) {
+        self.index_token = self.index_token.wrapping_add(1);
+        let token = self.index_token;
+
+        if !cx.global::<Config>().get().interface.index_rail {
+            self.index_offsets.clear();
+            self.index_len = 0;
+            return;
+        }
+
+        let db = cx.global::<Database>().clone();
+        let query = cx.global::<Search>().query.trim().to_string();
+        let (sort, ascending) = map_sort(self.table.read(cx).sort_method(cx));
+        let bg = cx.background_executor().clone();
+
+        cx.spawn(async move |this, cx: &mut AsyncApp| {
+            let (offsets, len) = bg
+                .spawn(async move {
+                    match db.get_song_index_keys(&query, sort, ascending) {
+                        Ok(keys) => (letter_offsets(keys.iter().map(String::as_str)), keys.len()),
+                        Err(e) => {
+                            error!("song index keys query failed: {}", e);
+                            (Vec::new(), 0)
+                        }
+                    }
+                })
+                .await;
+
+            this.update(cx, |this, cx| {
+                if this.index_token == token {
+                    this.index_offsets = offsets;
+                    this.index_len = len;
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     pub fn new(_window: &mut Window, cx: &mut Context<Self>) -> Self {
         let initial_query = cx.global::<Search>().query.trim().to_string();
         let cache: CacheHandle = Rc::new(RefCell::new(SongPageCache::new(SONG_PAGE_SIZE)));
@@ -402,6 +474,22 @@ impl SongsView {
             .detach();
         });
 
+        let locate: LocateHandler = Rc::new(move |cx, song_id, sort| {
+            let db = cx.global::<Database>().clone();
+            let query = cx.global::<Search>().query.trim().to_string();
+            let (sort, ascending) = map_sort(sort);
+            let song_id = song_id.clone();
+            cx.background_executor().spawn(async move {
+                match db.get_song_ids_from_offset(&query, sort, ascending, 0) {
+                    Ok(ids) => ids.iter().position(|id| *id == song_id),
+                    Err(e) => {
+                        error!("locate song: {}", e);
+                        None
+                    }
+                }
+            })
+        });
+
         let table = SongTable::new(
             cx,
             get_row_count,
@@ -412,8 +500,13 @@ impl SongsView {
             true,
             true,
             true,
+            true,
         );
         *table_weak.borrow_mut() = Some(table.downgrade());
+        table.update(cx, |table, cx| {
+            table.set_sort_key("songs", cx);
+            table.set_locate(locate);
+        });
 
         if cx.global::<ActiveView>().0 == AppView::Songs {
             let table_handle = table.clone();
@@ -437,6 +530,7 @@ impl SongsView {
             cx.update_entity(&table_handle, |_table, cx| {
                 cx.emit(SongTableEvent::NewRows);
             });
+            this.refresh_index(cx);
         })
         .detach();
 
@@ -454,6 +548,7 @@ impl SongsView {
             cx.update_entity(&table_handle, |_table, cx| {
                 cx.emit(SongTableEvent::NewRows);
             });
+            this.refresh_index(cx);
         })
         .detach();
 
@@ -463,14 +558,46 @@ impl SongsView {
             cx.update_entity(&table_handle, |_table, cx| {
                 cx.emit(SongTableEvent::NewRows);
             });
+            this.refresh_index(cx);
         })
         .detach();
 
-        Self {
+        cx.subscribe(&table, |this, _table, event: &SongTableEvent, cx| {
+            if matches!(event, SongTableEvent::SortChanged) {
+                this.refresh_index(cx);
+            }
+        })
+        .detach();
+
+        cx.observe_global::<JumpToPlayingRequested>(|this, cx| {
+            if cx.global::<ActiveView>().0 == AppView::Songs {
+                this.table.update(cx, |table, cx| table.jump_to_playing(cx));
+            }
+        })
+        .detach();
+
+        cx.observe_global::<Config>(|this, cx| {
+            let enabled = cx.global::<Config>().get().interface.index_rail;
+            if enabled != this.index_rail_enabled {
+                this.index_rail_enabled = enabled;
+                this.refresh_index(cx);
+            }
+        })
+        .detach();
+
+        let mut view = Self {
             table,
             last_query: initial_query,
             cache,
+            index_offsets: Vec::new(),
+            index_len: 0,
+            index_token: 0,
+            index_rail_enabled: cx.global::<Config>().get().interface.index_rail,
+        };
+        if cx.global::<ActiveView>().0 == AppView::Songs {
+            view.refresh_index(cx);
         }
+        view
     }
 }
 
@@ -478,10 +605,26 @@ impl Render for SongsView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let variables = cx.global::<Variables>();
 
-        flex_col()
-            .id("songs-border")
+        let show_rail = self.index_len >= MIN_INDEXED_ITEMS && !self.index_offsets.is_empty();
+        let table = self.table.clone();
+
+        flex_row()
             .size_full()
-            .p(px(variables.padding_24))
-            .child(self.table.clone())
+            .child(
+                flex_col()
+                    .id("songs-border")
+                    .flex_1()
+                    .min_w_0()
+                    .h_full()
+                    .p(px(variables.padding_24))
+                    .child(self.table.clone()),
+            )
+            .when(show_rail, |this| {
+                this.child(IndexRail::new(
+                    "songs-index-rail",
+                    self.index_offsets.clone(),
+                    move |row, _window, cx| table.read(cx).scroll_to_row(row),
+                ))
+            })
     }
 }
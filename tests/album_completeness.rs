@@ -0,0 +1,50 @@
+use vleer::data::db::repo::Database;
+use vleer::data::models::{Cuid, SongUpsert};
+
+mod common;
+
+fn add_track(db: &Database, album_id: &Cuid, disc: i32, track: i32, total: Option<i32>) {
+    let file = format!("/music/{}/{}-{}.flac", album_id, disc, track);
+    db.upsert_song(&SongUpsert {
+        title: &file,
+        artists: &["Artist"],
+        album_id: Some(album_id),
+        file_path: &file,
+        duration: 180,
+        track_number: Some(track),
+        track_total: total,
+        disc_number: Some(disc),
+        file_size: 1_000,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[test]
+fn reports_gaps_per_disc_against_the_declared_total() {
+    let (db, path) = common::temp_db("album_completeness");
+    let gappy = db.upsert_album("Gappy", &["Artist"], None).unwrap();
+    let complete = db.upsert_album("Complete", &["Artist"], None).unwrap();
+    let untagged = db.upsert_album("Untagged", &["Artist"], None).unwrap();
+
+    for track in [1, 2, 4, 5] {
+        add_track(&db, &gappy, 1, track, Some(5));
+    }
+    add_track(&db, &gappy, 2, 1, Some(2));
+    for track in [1, 2, 3] {
+        add_track(&db, &complete, 1, track, Some(3));
+    }
+    for track in [1, 3] {
+        add_track(&db, &untagged, 1, track, None);
+    }
+
+    assert_eq!(
+        db.get_album_missing_tracks(&gappy).unwrap(),
+        vec![(1, 3), (2, 2)]
+    );
+    assert!(db.get_album_missing_tracks(&complete).unwrap().is_empty());
+    assert!(db.get_album_missing_tracks(&untagged).unwrap().is_empty());
+    assert_eq!(db.get_incomplete_albums().unwrap(), vec![gappy]);
+
+    common::cleanup(&path);
+}
@@ -0,0 +1,47 @@
+use vleer::data::{db::repo::Database, models::AlbumSort};
+
+mod common;
+
+fn artist_names(db: &Database) -> Vec<String> {
+    db.get_artists("", 0, 10)
+        .unwrap()
+        .into_iter()
+        .map(|a| a.name)
+        .collect()
+}
+
+fn album_titles(db: &Database) -> Vec<String> {
+    db.get_albums("", AlbumSort::Title, true, 0, 10)
+        .unwrap()
+        .into_iter()
+        .map(|a| a.title)
+        .collect()
+}
+
+#[test]
+fn leading_articles_are_skipped_only_when_enabled() {
+    let (db, path) = common::temp_db("article_sort");
+    db.upsert_album("The Dark Side", &["The Beatles"], None)
+        .unwrap();
+    db.upsert_album("Meddle", &["Coldplay"], None).unwrap();
+    db.upsert_album("A Saucerful", &["Abba"], None).unwrap();
+
+    assert_eq!(artist_names(&db), ["Abba", "Coldplay", "The Beatles"]);
+    assert_eq!(
+        album_titles(&db),
+        ["A Saucerful", "Meddle", "The Dark Side"]
+    );
+
+    db.set_ignore_articles(true);
+    assert_eq!(artist_names(&db), ["Abba", "The Beatles", "Coldplay"]);
+    assert_eq!(
+        album_titles(&db),
+        ["The Dark Side", "Meddle", "A Saucerful"]
+    );
+    assert_eq!(
+        db.get_artist_names("").unwrap(),
+        ["Abba", "Beatles", "Coldplay"]
+    );
+
+    common::cleanup(&path);
+}
@@ -0,0 +1,24 @@
+mod common;
+
+#[test]
+fn artist_list_counts_albums_and_songs() {
+    let (db, path) = common::temp_db("artist_counts");
+
+    let first = db.upsert_album("First", &["Band"], None).unwrap();
+    let second = db.upsert_album("Second", &["Band"], None).unwrap();
+    common::add_song(&db, &["Band"], &first, "/music/a.flac");
+    common::add_song(&db, &["Band"], &first, "/music/b.flac");
+    common::add_song(&db, &["Band", "Guest"], &second, "/music/c.flac");
+
+    let artists = db.get_artists("", 0, 10).unwrap();
+    let counts = |name: &str| {
+        artists
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| (a.album_count, a.song_count))
+    };
+    assert_eq!(counts("Band"), Some((2, 3)));
+    assert_eq!(counts("Guest"), Some((0, 1)));
+
+    common::cleanup(&path);
+}
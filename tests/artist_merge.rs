@@ -0,0 +1,40 @@
+use vleer::data::models::artist_match_key;
+
+mod common;
+
+#[test]
+fn match_key_ignores_case_punctuation_and_leading_the() {
+    assert_eq!(artist_match_key("The Beatles"), artist_match_key("beatles"));
+    assert_eq!(artist_match_key("AC/DC"), artist_match_key("ac-dc"));
+    assert_ne!(artist_match_key("Theatre"), artist_match_key("atre"));
+}
+
+#[test]
+fn merge_moves_songs_and_survives_a_rescan() {
+    let (db, path) = common::temp_db("artist_merge");
+
+    let abbey = db
+        .upsert_album("Abbey Road", &["The Beatles"], None)
+        .unwrap();
+    let help = db.upsert_album("Help!", &["Beatles"], None).unwrap();
+    common::add_song(&db, &["The Beatles"], &abbey, "/music/a.flac");
+    common::add_song(&db, &["Beatles"], &help, "/music/b.flac");
+
+    let into = db.get_artist_by_name("The Beatles").unwrap().unwrap().id;
+    let from = db.get_artist_by_name("Beatles").unwrap().unwrap().id;
+    let duplicates = db.get_duplicate_artists(&into).unwrap();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].id, from);
+
+    db.merge_artists(&from, &into).unwrap();
+    assert!(db.get_artist(&from).unwrap().is_none());
+    assert_eq!(db.get_artist_song_ids(&into).unwrap().len(), 2);
+    assert_eq!(db.get_artist_albums(&into).unwrap().len(), 2);
+
+    common::add_song(&db, &["Beatles"], &help, "/music/b.flac");
+    assert!(db.get_artist_by_name("Beatles").unwrap().is_none());
+    assert_eq!(db.get_artist_song_ids(&into).unwrap().len(), 2);
+    assert!(db.get_duplicate_artists(&into).unwrap().is_empty());
+
+    common::cleanup(&path);
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use vleer::data::db::repo::Database;
+use vleer::data::models::{Cuid, SongUpsert};
+
+/// Opens a fresh database under /tmp, named so parallel tests don't share one.
+pub fn temp_db(name: &str) -> (Database, PathBuf) {
+    let path = PathBuf::from(format!("/tmp/vleer_{name}_{}.db", std::process::id()));
+    let db = Database::new(&path).expect("failed to create test db");
+    (db, path)
+}
+
+pub fn cleanup(path: &PathBuf) {
+    let _ = std::fs::remove_file(path);
+    let _ = std::fs::remove_file(format!("{}-wal", path.display()));
+    let _ = std::fs::remove_file(format!("{}-shm", path.display()));
+}
+
+/// Adds a song to `album_id` with placeholder tags, keyed and titled by its file path.
+#[allow(dead_code)]
+pub fn add_song(db: &Database, artists: &[&str], album_id: &Cuid, file: &str) {
+    db.upsert_song(&SongUpsert {
+        title: file,
+        artists,
+        album_id: Some(album_id),
+        file_path: file,
+        duration: 180,
+        file_size: 1_000,
+        ..Default::default()
+    })
+    .unwrap();
+}
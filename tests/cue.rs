@@ -0,0 +1,108 @@
+use std::path::Path;
+use vleer::data::cue;
+use vleer::data::models::{SongSegment, SongUpsert};
+
+mod common;
+
+const SHEET: &str = "\u{feff}REM GENRE Live
+PERFORMER \"The Band\"
+TITLE \"Live at the Hall\"
+FILE \"Live at the Hall.wav\" WAVE
+  TRACK 01 AUDIO
+    TITLE \"Intro\"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE \"Opener\"
+    PERFORMER \"The Band feat. Guest\"
+    INDEX 00 03:58:00
+    INDEX 01 04:00:30
+  TRACK 03 AUDIO
+    TITLE \"Encore\"
+    INDEX 01 10:15:00
+";
+
+#[test]
+fn parses_tracks_with_their_start_and_end() {
+    let sheet = cue::parse(SHEET);
+    assert_eq!(sheet.title.as_deref(), Some("Live at the Hall"));
+    assert_eq!(sheet.performer.as_deref(), Some("The Band"));
+
+    let tracks = sheet
+        .tracks_for(Path::new("/music/Live at the Hall.flac"))
+        .unwrap();
+    assert_eq!(tracks.len(), 3);
+    assert_eq!(tracks[0].title.as_deref(), Some("Intro"));
+    assert_eq!(tracks[0].start_secs, 0.0);
+    assert_eq!(tracks[0].end_secs, Some(240.4));
+    assert_eq!(tracks[1].performer.as_deref(), Some("The Band feat. Guest"));
+    assert_eq!(tracks[1].start_secs, 240.4);
+    assert_eq!(tracks[2].number, 3);
+    assert_eq!(tracks[2].end_secs, None);
+}
+
+#[test]
+fn cue_tracks_share_one_file() {
+    let (db, path) = common::temp_db("cue");
+    let file = "/music/Live at the Hall.flac";
+
+    for (cue_track, title, start_secs, end_secs) in
+        [(1, "Intro", 0.0, Some(240.4)), (2, "Opener", 240.4, None)]
+    {
+        db.upsert_song(&SongUpsert {
+            title,
+            artists: &["The Band"],
+            file_path: file,
+            duration: 200,
+            track_number: Some(cue_track),
+            file_size: 1_000,
+            segment: SongSegment {
+                cue_track,
+                start_secs,
+                end_secs,
+            },
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    let segments = db.get_song_segments(file).unwrap();
+    assert_eq!(segments.len(), 2);
+    let opener = db
+        .get_song(&segments.iter().find(|(_, n)| *n == 2).unwrap().0)
+        .unwrap()
+        .unwrap();
+    assert_eq!(opener.title, "Opener");
+    assert_eq!(opener.start_secs, 240.4);
+    assert_eq!(opener.end_secs, None);
+    assert_eq!(db.get_song_paths().unwrap(), vec![file.to_string()]);
+
+    let titles: Vec<String> = db
+        .get_songs_by_path(file)
+        .unwrap()
+        .into_iter()
+        .map(|song| song.title)
+        .collect();
+    assert_eq!(titles, ["Intro", "Opener"]);
+    assert!(opener.is_cue_track());
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn sheet_maps_back_to_the_audio_it_splits() {
+    let dir = std::env::temp_dir().join(format!("vleer_cue_sheet_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("Live at the Hall.flac"), b"").unwrap();
+    std::fs::write(dir.join("Other.flac"), b"").unwrap();
+    let sheet = dir.join("Live at the Hall.cue");
+    std::fs::write(&sheet, SHEET).unwrap();
+
+    let audio = cue::audio_for(&sheet);
+    assert!(audio.contains(&dir.join("Live at the Hall.flac")));
+    assert!(audio.contains(&dir.join("Live at the Hall.wav")));
+    assert!(!audio.contains(&dir.join("Other.flac")));
+    assert!(cue::sheet_modified(&dir.join("Live at the Hall.flac")).is_some());
+    assert!(cue::sheet_modified(&dir.join("Other.flac")).is_none());
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -1,21 +1,11 @@
 use std::time::Instant;
-use vleer::data::{db::repo::Database, models::SongSort};
+use vleer::data::models::{AlbumSort, SongSort, SongUpsert};
 
-fn temp_db() -> (Database, std::path::PathBuf) {
-    let path = std::path::PathBuf::from(format!("/tmp/vleer_bench_{}.db", std::process::id()));
-    let db = Database::new(&path).expect("failed to create test db");
-    (db, path)
-}
-
-fn cleanup(path: &std::path::PathBuf) {
-    let _ = std::fs::remove_file(path);
-    let _ = std::fs::remove_file(format!("{}-wal", path.display()));
-    let _ = std::fs::remove_file(format!("{}-shm", path.display()));
-}
+mod common;
 
 #[test]
 fn bench_sqlite_operations() {
-    let (db, path) = temp_db();
+    let (db, path) = common::temp_db("bench");
 
     const SONGS: usize = 10_000;
     const ARTISTS: usize = 100;
@@ -38,20 +28,18 @@ fn bench_sqlite_operations() {
     for i in 0..SONGS {
         let album_id = &album_ids[i % album_ids.len()];
         let artist = &artist_names[i % ARTISTS];
-        db.upsert_song(
-            &format!("Song Title {i}"),
-            &[artist.as_str()],
-            Some(album_id),
-            &format!("/music/song_{i}.mp3"),
-            180 + (i as i32 % 300),
-            Some((i as i32 % 20) + 1),
-            Some(2000 + (i as i32 % 24)),
-            &[],
-            None,
-            1_000_000,
-            i as i64,
-            None,
-        )
+        db.upsert_song(&SongUpsert {
+            title: &format!("Song Title {i}"),
+            artists: &[artist.as_str()],
+            album_id: Some(album_id),
+            file_path: &format!("/music/song_{i}.mp3"),
+            duration: 180 + (i as i32 % 300),
+            track_number: Some((i as i32 % 20) + 1),
+            year: Some(2000 + (i as i32 % 24)),
+            file_size: 1_000_000,
+            file_modified: i as i64,
+            ..Default::default()
+        })
         .unwrap();
     }
     println!("insert {SONGS} songs:         {:>10?}", t.elapsed());
@@ -86,7 +74,7 @@ fn bench_sqlite_operations() {
 
     let t = Instant::now();
     for i in 0..100 {
-        db.get_albums("", i * 5, 5).unwrap();
+        db.get_albums("", AlbumSort::Title, true, i * 5, 5).unwrap();
     }
     println!("get_albums paginated  x100:  {:>10?}", t.elapsed());
 
@@ -96,5 +84,5 @@ fn bench_sqlite_operations() {
     }
     println!("get_albums_count      x100:  {:>10?}", t.elapsed());
 
-    cleanup(&path);
+    common::cleanup(&path);
 }
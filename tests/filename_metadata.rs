@@ -0,0 +1,33 @@
+use vleer::data::db::repo::Database;
+use vleer::data::models::{Cuid, SongUpsert};
+
+mod common;
+
+const FILE: &str = "/music/Artist - Song.mp3";
+
+fn upsert(db: &Database, title: &str, metadata_from_filename: bool) -> Cuid {
+    db.upsert_song(&SongUpsert {
+        title,
+        artists: &["Artist"],
+        file_path: FILE,
+        duration: 180,
+        file_size: 1_000,
+        metadata_from_filename,
+        ..Default::default()
+    })
+    .unwrap();
+    db.get_song_by_path(FILE).unwrap().unwrap().id
+}
+
+#[test]
+fn filename_flag_is_cleared_when_the_song_is_rescanned() {
+    let (db, path) = common::temp_db("filename_metadata");
+
+    let id = upsert(&db, "Song", true);
+    assert!(db.get_metadata_from_filename(&id).unwrap());
+
+    let id = upsert(&db, "Tagged Song", false);
+    assert!(!db.get_metadata_from_filename(&id).unwrap());
+
+    common::cleanup(&path);
+}
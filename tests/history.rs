@@ -0,0 +1,159 @@
+use vleer::data::{
+    db::repo::Database,
+    models::{Cuid, EventType, SongSort, SongUpsert},
+};
+
+mod common;
+
+fn play(db: &Database) -> (Cuid, Cuid) {
+    let context = db.insert_event_context(None, None).unwrap();
+    let event = db.insert_event(EventType::Play, Some(&context)).unwrap();
+    (event, context)
+}
+
+fn backdate(path: &std::path::Path, event: &Cuid, days: u32) {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute(
+        "UPDATE events SET timestamp = DATETIME('now', ?1) WHERE id = ?2",
+        rusqlite::params![format!("-{days} days"), event],
+    )
+    .unwrap();
+}
+
+#[test]
+fn purge_drops_only_events_past_retention() {
+    let (db, path) = common::temp_db("history_purge");
+    let (old_event, old_context) = play(&db);
+    let (recent_event, recent_context) = play(&db);
+    backdate(&path, &old_event, 120);
+    backdate(&path, &recent_event, 30);
+
+    assert_eq!(db.purge_history_older_than(90).unwrap(), 1);
+
+    let remaining: Vec<Cuid> = db
+        .get_events_by_type(EventType::Play)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.id)
+        .collect();
+    assert_eq!(remaining, [recent_event]);
+    assert!(db.get_event_context(&old_context).unwrap().is_none());
+    assert!(db.get_event_context(&recent_context).unwrap().is_some());
+
+    assert_eq!(db.purge_history_older_than(90).unwrap(), 0);
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn clear_history_removes_everything() {
+    let (db, path) = common::temp_db("history_clear");
+    play(&db);
+    play(&db);
+
+    db.clear_history().unwrap();
+
+    assert!(db.get_events_by_type(EventType::Play).unwrap().is_empty());
+    assert!(db.get_recently_played_items(10).unwrap().is_empty());
+
+    common::cleanup(&path);
+}
+
+fn set_timestamp(path: &std::path::Path, event: &Cuid, timestamp: &str) {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute(
+        "UPDATE events SET timestamp = ?1 WHERE id = ?2",
+        rusqlite::params![timestamp, event],
+    )
+    .unwrap();
+}
+
+#[test]
+fn listen_time_skips_pauses_and_caps_plays_at_song_length() {
+    let (db, path) = common::temp_db("history_listen_time");
+    db.upsert_song(&SongUpsert {
+        title: "Song",
+        artists: &["Artist"],
+        file_path: "/music/listen.flac",
+        duration: 180,
+        file_size: 1_000,
+        ..Default::default()
+    })
+    .unwrap();
+    let song_id = db.get_songs(None, SongSort::Default, true, 0, 1).unwrap()[0]
+        .id
+        .clone();
+    let context = db.insert_event_context(Some(&song_id), None).unwrap();
+    let events = [
+        (EventType::Play, "2026-01-01 10:00:00"),
+        (EventType::Pause, "2026-01-01 10:01:00"),
+        (EventType::Resume, "2026-01-01 10:01:40"),
+        (EventType::Stop, "2026-01-01 10:02:10"),
+        (EventType::Play, "2026-01-01 11:00:00"),
+        (EventType::Play, "2026-01-01 12:00:00"),
+    ];
+    for (event_type, timestamp) in events {
+        let event = db.insert_event(event_type, Some(&context)).unwrap();
+        set_timestamp(&path, &event, timestamp);
+    }
+
+    assert_eq!(db.get_total_listen_time(None).unwrap(), 90 + 180);
+    assert_eq!(
+        db.get_total_listen_time(Some("2026-01-01 10:30:00"))
+            .unwrap(),
+        180
+    );
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn frequently_skipped_needs_skips_on_most_plays() {
+    let (db, path) = common::temp_db("history_skips");
+    for (title, file) in [
+        ("Skipped", "/music/skipped.flac"),
+        ("Liked", "/music/liked.flac"),
+    ] {
+        db.upsert_song(&SongUpsert {
+            title,
+            artists: &["Artist"],
+            file_path: file,
+            duration: 180,
+            file_size: 1_000,
+            ..Default::default()
+        })
+        .unwrap();
+    }
+    let song_id = |file: &str| db.get_song_by_path(file).unwrap().unwrap().id;
+    let skipped = song_id("/music/skipped.flac");
+    let liked = song_id("/music/liked.flac");
+
+    let listen = |song: &Cuid, end: EventType| {
+        let context = db.insert_event_context(Some(song), None).unwrap();
+        db.insert_event(EventType::Play, Some(&context)).unwrap();
+        db.insert_event(end, Some(&context)).unwrap();
+    };
+    for _ in 0..3 {
+        listen(&skipped, EventType::Skip);
+    }
+    listen(&skipped, EventType::Stop);
+    for _ in 0..3 {
+        listen(&liked, EventType::Skip);
+    }
+    for _ in 0..3 {
+        listen(&liked, EventType::Stop);
+    }
+
+    assert_eq!(db.get_play_and_skip_counts(&skipped).unwrap(), (4, 3));
+    assert_eq!(db.get_play_and_skip_counts(&liked).unwrap(), (6, 3));
+    assert_eq!(db.get_events_by_type(EventType::Skip).unwrap().len(), 6);
+
+    let frequent = db.get_frequently_skipped(3, 10).unwrap();
+    assert_eq!(frequent.len(), 1);
+    assert_eq!(frequent[0].song.id, skipped);
+    assert_eq!(frequent[0].song.artists, ["Artist"]);
+    assert_eq!((frequent[0].skips, frequent[0].plays), (3, 4));
+    assert!(db.get_frequently_skipped(4, 10).unwrap().is_empty());
+
+    common::cleanup(&path);
+}
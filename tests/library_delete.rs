@@ -0,0 +1,101 @@
+use std::path::Path;
+use vleer::data::models::SongUpsert;
+
+mod common;
+
+#[test]
+fn deleting_songs_drops_orphaned_albums_and_artists() {
+    let (db, path) = common::temp_db("delete");
+    let lonely = db.upsert_album("Lonely", &["Solo"], None).unwrap();
+    let shared = db.upsert_album("Shared", &["Duo"], None).unwrap();
+
+    for (title, artist, album, file) in [
+        ("A", "Solo", &lonely, "/music/a.flac"),
+        ("B", "Duo", &shared, "/music/b.flac"),
+        ("C", "Duo", &shared, "/music/c.flac"),
+    ] {
+        db.upsert_song(&SongUpsert {
+            title,
+            artists: &[artist],
+            album_id: Some(album),
+            file_path: file,
+            duration: 180,
+            file_size: 1_000,
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    let doomed: Vec<_> = ["/music/a.flac", "/music/b.flac"]
+        .iter()
+        .map(|p| db.get_song_by_path(p).unwrap().unwrap().id)
+        .collect();
+    assert_eq!(db.delete_songs(&doomed).unwrap(), 2);
+
+    assert!(db.get_song_by_path("/music/c.flac").unwrap().is_some());
+    assert!(db.get_album(&lonely).unwrap().is_none());
+    assert!(db.get_album(&shared).unwrap().is_some());
+    assert!(db.get_artist_by_name("Solo").unwrap().is_none());
+    assert!(db.get_artist_by_name("Duo").unwrap().is_some());
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn removing_missing_paths_clears_links_even_when_read_only() {
+    let (db, path) = common::temp_db("delete_by_path");
+    let album = db.upsert_album("Gone", &["Ghost"], None).unwrap();
+    db.upsert_song(&SongUpsert {
+        title: "Haunt",
+        artists: &["Ghost"],
+        album_id: Some(&album),
+        file_path: "/music/haunt.flac",
+        duration: 180,
+        file_size: 1_000,
+        ..Default::default()
+    })
+    .unwrap();
+
+    db.set_read_only(true);
+    assert_eq!(
+        db.delete_songs_by_path(&["/music/haunt.flac".to_string()])
+            .unwrap(),
+        1
+    );
+
+    assert!(db.get_song_by_path("/music/haunt.flac").unwrap().is_none());
+    assert!(db.get_album(&album).unwrap().is_none());
+    assert!(db.get_artist_by_name("Ghost").unwrap().is_none());
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn scoped_rescan_only_clears_unavailable_inside_its_folder() {
+    let (db, path) = common::temp_db("unavailable_scope");
+    for file in ["/music/a/one.flac", "/music/b/two.flac"] {
+        db.upsert_song(&SongUpsert {
+            title: file,
+            artists: &["Artist"],
+            file_path: file,
+            duration: 180,
+            file_size: 1_000,
+            ..Default::default()
+        })
+        .unwrap();
+    }
+    let missing = [
+        "/music/a/one.flac".to_string(),
+        "/music/b/two.flac".to_string(),
+    ];
+    db.set_unavailable_songs(&missing, None).unwrap();
+
+    db.set_unavailable_songs(&[], Some(Path::new("/music/a")))
+        .unwrap();
+
+    let unavailable = |file: &str| db.get_song_by_path(file).unwrap().unwrap().unavailable;
+    assert!(!unavailable("/music/a/one.flac"));
+    assert!(unavailable("/music/b/two.flac"));
+
+    common::cleanup(&path);
+}
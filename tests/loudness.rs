@@ -0,0 +1,53 @@
+use std::f32::consts::TAU;
+use vleer::media::loudness::LoudnessMeter;
+
+const SAMPLE_RATE: u32 = 48000;
+
+fn measure_sine(amplitude: f32, channels: u16, secs: f32) -> Option<f32> {
+    let mut meter = LoudnessMeter::new(SAMPLE_RATE, channels);
+    let frames = (SAMPLE_RATE as f32 * secs) as usize;
+    for frame in 0..frames {
+        let sample = amplitude * (TAU * 997.0 * frame as f32 / SAMPLE_RATE as f32).sin();
+        for _ in 0..channels {
+            meter.push(sample);
+        }
+    }
+    meter.integrated()
+}
+
+fn assert_lufs(measured: Option<f32>, expected: f32) {
+    let measured = measured.expect("signal is above the gate");
+    assert!(
+        (measured - expected).abs() < 0.1,
+        "measured {measured} LUFS, expected {expected}"
+    );
+}
+
+#[test]
+fn full_scale_sine_reads_as_reference_level() {
+    assert_lufs(measure_sine(1.0, 1, 3.0), -3.01);
+    assert_lufs(measure_sine(1.0, 2, 3.0), 0.0);
+}
+
+#[test]
+fn loudness_tracks_amplitude() {
+    assert_lufs(measure_sine(0.1, 2, 3.0), -20.0);
+    assert_lufs(measure_sine(0.01, 2, 3.0), -40.0);
+}
+
+#[test]
+fn silence_and_short_clips_are_not_measured() {
+    assert_eq!(measure_sine(0.0, 2, 3.0), None);
+    assert_eq!(measure_sine(1.0, 2, 0.3), None);
+}
+
+#[test]
+fn peak_is_the_largest_absolute_sample() {
+    let mut meter = LoudnessMeter::new(SAMPLE_RATE, 2);
+    for sample in [0.1, -0.6, 0.4, 0.25] {
+        meter.push(sample);
+    }
+    let measurement = meter.measurement();
+    assert_eq!(measurement.peak, 0.6);
+    assert_eq!(measurement.lufs, None);
+}
@@ -0,0 +1,98 @@
+use vleer::data::models::{Cuid, Song};
+use vleer::media::now_playing::{self, NowPlaying, PlaybackError, PlaybackState};
+use vleer::media::queue::{Queue, RepeatMode};
+
+fn song(id: &Cuid) -> Song {
+    Song {
+        id: id.clone(),
+        title: "Track".to_string(),
+        artists: vec!["Artist".to_string()],
+        album_id: None,
+        file_path: "/music/track.flac".to_string(),
+        file_size: 1_000,
+        file_modified: 0,
+        genres: Vec::new(),
+        date: None,
+        duration: 200,
+        image_id: None,
+        track_number: None,
+        disc_number: None,
+        favorite: false,
+        lufs: None,
+        track_peak: None,
+        computed_lufs: None,
+        computed_peak: None,
+        gain_offset_db: 0.0,
+        pinned: false,
+        date_added: String::new(),
+        date_updated: String::new(),
+        start_secs: 0.0,
+        end_secs: None,
+        unavailable: false,
+    }
+}
+
+#[test]
+fn capture_reflects_queue_and_playback() {
+    let ids: Vec<Cuid> = (0..3).map(|_| Cuid::new()).collect();
+    let mut queue = Queue::new();
+    queue.clear_and_queue_songs(ids.clone(), 1);
+    queue.cycle_repeat_mode();
+
+    let playing = NowPlaying::capture(&queue, Some(song(&ids[1])), 12.5, false);
+    assert_eq!(playing.state, PlaybackState::Playing);
+    assert_eq!(playing.position, 12.5);
+    assert_eq!(playing.duration, 200.0);
+    assert_eq!(playing.repeat, RepeatMode::All);
+    assert!(!playing.shuffle);
+    assert_eq!(playing.queue_index, Some(1));
+    assert_eq!(playing.queue_len, 3);
+    assert!(playing.has_next && playing.has_previous);
+
+    let paused = NowPlaying::capture(&queue, Some(song(&ids[1])), 12.5, true);
+    assert_eq!(paused.state, PlaybackState::Paused);
+
+    let stopped = NowPlaying::capture(&Queue::new(), None, 3.0, false);
+    assert_eq!(stopped.state, PlaybackState::Stopped);
+    assert_eq!(stopped.position, 0.0);
+    assert_eq!(stopped.queue_len, 0);
+}
+
+#[test]
+fn subscribers_only_see_changed_snapshots() {
+    let id = Cuid::new();
+    let mut queue = Queue::new();
+    queue.clear_and_queue_songs(vec![id.clone()], 0);
+
+    let mut rx = now_playing::subscribe();
+    rx.mark_unchanged();
+
+    now_playing::publish(NowPlaying::capture(&queue, Some(song(&id)), 1.0, false));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(rx.borrow_and_update().position, 1.0);
+
+    now_playing::publish(NowPlaying::capture(&queue, Some(song(&id)), 1.0, false));
+    assert!(!rx.has_changed().unwrap());
+
+    now_playing::publish(NowPlaying::capture(&queue, Some(song(&id)), 1.0, true));
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(rx.borrow_and_update().state, PlaybackState::Paused);
+}
+
+#[test]
+fn reported_errors_reach_subscribers() {
+    let mut rx = now_playing::subscribe_errors();
+    rx.mark_unchanged();
+
+    let error = PlaybackError {
+        title: "Track".to_string(),
+        path: "/music/track.flac".to_string(),
+        reason: "No such file or directory".to_string(),
+    };
+    now_playing::report_error(error.clone());
+    assert!(rx.has_changed().unwrap());
+    assert_eq!(rx.borrow_and_update().as_ref(), Some(&error));
+
+    now_playing::report_error(error.clone());
+    assert!(rx.has_changed().unwrap());
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "null-audio")]
+
+use rodio::Player;
+use rodio::source::{SineWave, Source};
+use std::num::NonZero;
+use std::time::Duration;
+use vleer::media::null_output::NullOutput;
+
+fn output() -> NullOutput {
+    NullOutput::new(NonZero::new(44100).unwrap(), NonZero::new(2).unwrap())
+}
+
+fn assert_position(player: &Player, expected_ms: u64) {
+    let pos = player.get_pos().as_millis() as i64;
+    assert!(
+        (pos - expected_ms as i64).abs() <= 20,
+        "position {pos}ms, expected about {expected_ms}ms"
+    );
+}
+
+#[test]
+fn position_follows_simulated_clock() {
+    let output = output();
+    let player = Player::connect_new(output.mixer());
+    player.append(SineWave::new(440.0).take_duration(Duration::from_secs(2)));
+
+    assert_position(&player, 0);
+    output.advance(Duration::from_millis(500));
+    assert_position(&player, 500);
+    output.advance(Duration::from_millis(250));
+    assert_position(&player, 750);
+    assert!(!player.empty());
+}
+
+#[test]
+fn paused_player_does_not_advance() {
+    let output = output();
+    let player = Player::connect_new(output.mixer());
+    player.append(SineWave::new(440.0).take_duration(Duration::from_secs(2)));
+
+    output.advance(Duration::from_millis(300));
+    player.pause();
+    output.advance(Duration::from_secs(1));
+    assert_position(&player, 300);
+
+    player.play();
+    output.advance(Duration::from_millis(200));
+    assert_position(&player, 500);
+}
+
+#[test]
+fn player_empties_at_end_of_source() {
+    let output = output();
+    let player = Player::connect_new(output.mixer());
+    player.append(SineWave::new(440.0).take_duration(Duration::from_secs(1)));
+
+    output.advance(Duration::from_millis(900));
+    assert!(!player.empty());
+    output.advance(Duration::from_millis(200));
+    assert!(player.empty());
+}
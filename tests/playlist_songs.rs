@@ -0,0 +1,40 @@
+use vleer::data::models::{Cuid, SongUpsert};
+
+mod common;
+
+#[test]
+fn re_adding_a_song_keeps_its_position() {
+    let (db, path) = common::temp_db("playlist_songs");
+    for file in ["/music/a.flac", "/music/b.flac"] {
+        db.upsert_song(&SongUpsert {
+            title: "Song",
+            artists: &["Artist"],
+            file_path: file,
+            duration: 180,
+            file_size: 1_000,
+            ..Default::default()
+        })
+        .unwrap();
+    }
+    let a = db.get_song_by_path("/music/a.flac").unwrap().unwrap().id;
+    let b = db.get_song_by_path("/music/b.flac").unwrap().unwrap().id;
+    let playlist = Cuid::new();
+    db.upsert_playlist(&playlist, "Mix", None, None, false)
+        .unwrap();
+
+    assert!(!db.playlist_contains(&playlist, &a).unwrap());
+    db.upsert_playlist_song(&playlist, &a).unwrap();
+    db.upsert_playlist_song(&playlist, &b).unwrap();
+    assert!(db.playlist_contains(&playlist, &a).unwrap());
+
+    db.upsert_playlist_song(&playlist, &a).unwrap();
+    let order: Vec<Cuid> = db
+        .get_playlist_songs(&playlist)
+        .unwrap()
+        .into_iter()
+        .map(|track| track.song.id)
+        .collect();
+    assert_eq!(order, vec![a, b]);
+
+    common::cleanup(&path);
+}
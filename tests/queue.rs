@@ -0,0 +1,338 @@
+use vleer::data::models::Cuid;
+use vleer::media::queue::{Queue, RepeatMode};
+
+fn song_ids(count: usize) -> Vec<Cuid> {
+    (0..count).map(|_| Cuid::new()).collect()
+}
+
+#[test]
+fn shuffle_keeps_clicked_song_first() {
+    let ids = song_ids(20);
+
+    for clicked in [0, 7, 19] {
+        for _ in 0..20 {
+            let mut queue = Queue::new();
+            queue.set_shuffle(true);
+            queue.clear_and_queue_songs(ids.clone(), clicked);
+
+            assert_eq!(queue.get_current_song_id().as_ref(), Some(&ids[clicked]));
+            let order = queue.get_items();
+            assert_eq!(order.len(), ids.len());
+            assert_eq!(order[0], ids[clicked]);
+
+            let mut sorted = order.clone();
+            sorted.sort_by_key(|id| ids.iter().position(|x| x == id));
+            assert_eq!(sorted, ids);
+        }
+    }
+}
+
+#[test]
+fn shuffle_on_click_then_queue_rest() {
+    let ids = song_ids(12);
+    let clicked = 4;
+
+    for _ in 0..20 {
+        let mut queue = Queue::new();
+        queue.set_shuffle(true);
+        queue.clear_and_queue_songs(vec![ids[clicked].clone()], 0);
+        queue.add_songs(ids[clicked + 1..].to_vec());
+
+        assert_eq!(queue.get_current_song_id().as_ref(), Some(&ids[clicked]));
+        assert_eq!(queue.get_items()[0], ids[clicked]);
+
+        let mut played = Vec::new();
+        while let Some(id) = queue.next() {
+            played.push(id);
+        }
+        assert_eq!(played.len(), ids.len() - clicked - 1);
+        assert!(!played.contains(&ids[clicked]));
+    }
+}
+
+fn queue_with(ids: &[Cuid], mode: RepeatMode) -> Queue {
+    let mut queue = Queue::new();
+    queue.add_songs(ids.to_vec());
+    while queue.get_repeat_mode() != mode {
+        queue.cycle_repeat_mode();
+    }
+    queue
+}
+
+#[test]
+fn repeat_mode_cycles_off_all_one() {
+    let mut queue = Queue::new();
+    assert_eq!(queue.get_repeat_mode(), RepeatMode::Off);
+    queue.cycle_repeat_mode();
+    assert_eq!(queue.get_repeat_mode(), RepeatMode::All);
+    queue.cycle_repeat_mode();
+    assert_eq!(queue.get_repeat_mode(), RepeatMode::One);
+    queue.cycle_repeat_mode();
+    assert_eq!(queue.get_repeat_mode(), RepeatMode::Off);
+}
+
+#[test]
+fn empty_queue_has_nothing_to_play() {
+    for mode in [RepeatMode::Off, RepeatMode::All, RepeatMode::One] {
+        let mut queue = queue_with(&[], mode);
+        assert_eq!(queue.get_current_song_id(), None);
+        assert_eq!(queue.next(), None);
+        assert_eq!(queue.previous(), None);
+        assert!(!queue.has_next());
+        assert!(!queue.has_previous());
+    }
+}
+
+#[test]
+fn repeat_off_stops_at_end_of_queue() {
+    let ids = song_ids(3);
+    let mut queue = queue_with(&ids, RepeatMode::Off);
+
+    assert_eq!(queue.get_current_song_id().as_ref(), Some(&ids[0]));
+    assert!(!queue.has_previous());
+    assert_eq!(queue.previous(), None);
+
+    assert_eq!(queue.next().as_ref(), Some(&ids[1]));
+    assert_eq!(queue.next().as_ref(), Some(&ids[2]));
+    assert!(!queue.has_next());
+    assert_eq!(queue.next(), None);
+    assert_eq!(queue.next_manual(), None);
+    assert_eq!(queue.get_current_song_id().as_ref(), Some(&ids[2]));
+
+    assert_eq!(queue.previous().as_ref(), Some(&ids[1]));
+}
+
+#[test]
+fn repeat_all_wraps_both_ways() {
+    let ids = song_ids(3);
+    let mut queue = queue_with(&ids, RepeatMode::All);
+
+    assert!(queue.has_previous());
+    assert_eq!(queue.previous().as_ref(), Some(&ids[2]));
+    assert_eq!(queue.next().as_ref(), Some(&ids[0]));
+
+    let played: Vec<Cuid> = (0..6).filter_map(|_| queue.next()).collect();
+    let expected: Vec<Cuid> = ids.iter().cycle().skip(1).take(6).cloned().collect();
+    assert_eq!(played, expected);
+}
+
+#[test]
+fn repeat_one_holds_until_manual_skip() {
+    let ids = song_ids(3);
+    let mut queue = queue_with(&ids, RepeatMode::One);
+
+    for _ in 0..3 {
+        assert_eq!(queue.next().as_ref(), Some(&ids[0]));
+        assert_eq!(queue.previous().as_ref(), Some(&ids[0]));
+    }
+
+    assert_eq!(queue.next_manual().as_ref(), Some(&ids[1]));
+    assert_eq!(queue.next().as_ref(), Some(&ids[1]));
+    assert_eq!(queue.previous_manual().as_ref(), Some(&ids[0]));
+    assert_eq!(queue.get_repeat_mode(), RepeatMode::One);
+
+    queue.next_manual();
+    queue.next_manual();
+    assert_eq!(queue.next_manual(), None);
+    assert_eq!(queue.get_current_song_id().as_ref(), Some(&ids[2]));
+}
+
+#[test]
+fn shuffle_plays_every_song_once_then_stops_or_wraps() {
+    let ids = song_ids(8);
+
+    let mut queue = queue_with(&ids, RepeatMode::Off);
+    queue.set_shuffle(true);
+    let first = queue
+        .get_current_song_id()
+        .expect("queue has a current song");
+    let mut played = vec![first];
+    while let Some(id) = queue.next() {
+        played.push(id);
+    }
+    assert_eq!(played.len(), ids.len());
+    played.sort_by_key(|id| ids.iter().position(|x| x == id));
+    assert_eq!(played, ids);
+    assert!(!queue.has_next());
+
+    let mut queue = queue_with(&ids, RepeatMode::All);
+    queue.set_shuffle(true);
+    let order = queue.get_items();
+    let start = queue
+        .get_current_display_index()
+        .expect("shuffle has a position");
+    for step in 1..=ids.len() * 2 {
+        let expected = &order[(start + step) % order.len()];
+        assert_eq!(queue.next().as_ref(), Some(expected));
+    }
+}
+
+#[test]
+fn shuffle_passes_over_skipped_songs_until_only_they_are_left() {
+    let ids = song_ids(8);
+    let short: Vec<Cuid> = ids[..3].to_vec();
+    let is_short = |id: &Cuid| short.contains(id);
+
+    let mut queue = Queue::new();
+    queue.set_shuffle(true);
+    queue.clear_and_queue_songs(ids.clone(), 0);
+
+    let mut played = Vec::new();
+    while let Some(id) = queue.next_skipping(is_short) {
+        played.push(id);
+    }
+    let long_played = played.iter().take_while(|id| !is_short(id)).count();
+    assert_eq!(long_played, ids.len() - short.len());
+    assert!(played[long_played..].iter().all(is_short));
+
+    let mut queue = Queue::new();
+    queue.clear_and_queue_songs(ids.clone(), 0);
+    assert_eq!(queue.next_skipping(is_short).as_ref(), Some(&ids[1]));
+}
+
+#[test]
+fn radio_refills_only_when_running_low() {
+    let seed = Cuid::new();
+    let mut queue = Queue::new();
+    queue.start_radio(seed.clone());
+    assert_eq!(queue.get_items(), [seed.clone()]);
+
+    assert_eq!(queue.begin_radio_refill(), Some(seed.clone()));
+    assert_eq!(
+        queue.begin_radio_refill(),
+        None,
+        "a lookup is already in flight"
+    );
+
+    let candidates = song_ids(8);
+    let mut with_repeat = vec![seed.clone()];
+    with_repeat.extend(candidates.iter().cloned());
+    assert_eq!(queue.finish_radio_refill(with_repeat, 5), 5);
+    assert_eq!(queue.get_items()[1..], candidates[..5]);
+
+    assert_eq!(
+        queue.begin_radio_refill(),
+        None,
+        "five songs left is plenty"
+    );
+    for _ in 0..3 {
+        queue.next();
+    }
+    assert_eq!(queue.begin_radio_refill(), Some(seed));
+}
+
+#[test]
+fn replacing_the_queue_stops_radio() {
+    let mut queue = Queue::new();
+    queue.start_radio(Cuid::new());
+    queue.clear_and_queue_songs(song_ids(2), 0);
+
+    assert_eq!(queue.begin_radio_refill(), None);
+    assert_eq!(queue.finish_radio_refill(song_ids(3), 3), 0);
+    assert_eq!(queue.get_items().len(), 2);
+}
+
+#[test]
+fn autoplay_continues_with_unplayed_candidates() {
+    let played = song_ids(2);
+    let mut queue = Queue::new();
+    queue.clear_and_queue_songs(played.clone(), 1);
+    assert_eq!(queue.next(), None);
+
+    assert_eq!(queue.autoplay(played.clone(), 5), None);
+
+    let fresh = song_ids(4);
+    let mut candidates = played.clone();
+    candidates.extend(fresh.iter().cloned());
+    assert_eq!(queue.autoplay(candidates, 3), Some(fresh[0].clone()));
+    assert_eq!(queue.get_items()[2..], fresh[..3]);
+    assert_eq!(queue.get_current_song_id(), Some(fresh[0].clone()));
+}
+
+#[test]
+fn clear_after_current_keeps_played_songs() {
+    let ids = song_ids(6);
+    let mut queue = Queue::new();
+    queue.clear_and_queue_songs(ids.clone(), 2);
+
+    queue.clear_after_current();
+
+    assert_eq!(queue.get_items(), ids[..3]);
+    assert_eq!(queue.get_current_song_id(), Some(ids[2].clone()));
+    assert!(!queue.has_next());
+    assert!(queue.has_previous());
+}
+
+#[test]
+fn clear_after_current_follows_shuffle_order() {
+    let ids = song_ids(10);
+    let mut queue = Queue::new();
+    queue.set_shuffle(true);
+    queue.clear_and_queue_songs(ids.clone(), 3);
+    queue.next();
+    queue.next();
+    let order = queue.get_items();
+
+    queue.clear_after_current();
+
+    assert_eq!(queue.get_items(), order[..3]);
+    assert_eq!(queue.get_current_song_id(), Some(order[2].clone()));
+    assert_eq!(queue.get_current_display_index(), Some(2));
+    assert!(!queue.has_next());
+}
+
+#[test]
+fn clear_after_current_ends_radio() {
+    let mut queue = Queue::new();
+    queue.start_radio(Cuid::new());
+
+    queue.clear_after_current();
+
+    assert_eq!(queue.begin_radio_refill(), None);
+    assert_eq!(queue.get_items().len(), 1);
+}
+
+#[test]
+fn upcoming_follows_play_order_and_wraps_only_on_repeat_all() {
+    let ids = song_ids(4);
+    let mut queue = queue_with(&ids, RepeatMode::Off);
+    queue.next();
+    queue.next();
+
+    let upcoming: Vec<(usize, Cuid)> = queue.upcoming(3);
+    assert_eq!(upcoming, [(3, ids[3].clone())]);
+
+    queue.cycle_repeat_mode();
+    let upcoming = queue.upcoming(3);
+    assert_eq!(
+        upcoming,
+        [
+            (3, ids[3].clone()),
+            (0, ids[0].clone()),
+            (1, ids[1].clone())
+        ]
+    );
+
+    queue.set_shuffle(true);
+    let order = queue.get_items();
+    let upcoming: Vec<Cuid> = queue.upcoming(2).into_iter().map(|(_, id)| id).collect();
+    assert_eq!(upcoming, order[1..3]);
+}
+
+#[test]
+fn peeking_at_the_next_song_matches_moving_there() {
+    let ids = song_ids(6);
+    let short: Vec<Cuid> = ids[..2].to_vec();
+    let is_short = |id: &Cuid| short.contains(id);
+
+    for mode in [RepeatMode::Off, RepeatMode::All, RepeatMode::One] {
+        for shuffle in [false, true] {
+            let mut queue = queue_with(&ids, mode);
+            queue.set_shuffle(shuffle);
+            for _ in 0..ids.len() + 2 {
+                let peeked = queue.peek_next_skipping(is_short);
+                assert_eq!(queue.next_skipping(is_short), peeked);
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use vleer::data::db::repo::Database;
+use vleer::data::models::{Cuid, RecentItem, SongUpsert};
+
+mod common;
+
+fn add_song(db: &Database, title: &str, album_id: Option<&Cuid>, file: &str, image_id: &str) {
+    db.upsert_song(&SongUpsert {
+        title,
+        artists: &["Artist"],
+        album_id,
+        file_path: file,
+        duration: 180,
+        image_id: Some(image_id),
+        file_size: 1_000,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[test]
+fn singles_sharing_a_cover_are_not_grouped() {
+    let (db, path) = common::temp_db("recently_added");
+    db.upsert_image("shared", &[1, 2, 3]).unwrap();
+    db.upsert_image("album", &[4, 5, 6]).unwrap();
+
+    for i in 0..3 {
+        add_song(
+            &db,
+            &format!("Single {i}"),
+            None,
+            &format!("/music/single{i}.flac"),
+            "shared",
+        );
+    }
+    let album = db.upsert_album("Record", &["Artist"], None).unwrap();
+    for i in 0..2 {
+        add_song(
+            &db,
+            &format!("Track {i}"),
+            Some(&album),
+            &format!("/music/track{i}.flac"),
+            "album",
+        );
+    }
+
+    let items: Vec<RecentItem> = db
+        .get_recently_added_items(10)
+        .unwrap()
+        .into_iter()
+        .map(|(item, _)| item)
+        .collect();
+    let songs = items
+        .iter()
+        .filter(|item| matches!(item, RecentItem::Song { .. }))
+        .count();
+    let albums: Vec<&Cuid> = items
+        .iter()
+        .filter_map(|item| match item {
+            RecentItem::Album { id, .. } => Some(id),
+            RecentItem::Song { .. } => None,
+        })
+        .collect();
+    assert_eq!(songs, 3);
+    assert_eq!(albums, vec![&album]);
+
+    common::cleanup(&path);
+}
@@ -0,0 +1,106 @@
+use rodio::buffer::SamplesBuffer;
+use std::f32::consts::TAU;
+use std::num::NonZero;
+use vleer::data::config::ResamplerQuality;
+use vleer::media::resampler::Resampler;
+
+const QUALITIES: [ResamplerQuality; 3] = [
+    ResamplerQuality::Fast,
+    ResamplerQuality::Balanced,
+    ResamplerQuality::High,
+];
+
+fn sine(sample_rate: u32, channels: u16, freq: f32, secs: f32) -> SamplesBuffer {
+    let frames = (sample_rate as f32 * secs) as usize;
+    let samples: Vec<f32> = (0..frames)
+        .flat_map(|i| {
+            let sample = 0.5 * (TAU * freq * i as f32 / sample_rate as f32).sin();
+            std::iter::repeat_n(sample, channels as usize)
+        })
+        .collect();
+    SamplesBuffer::new(
+        NonZero::new(channels).unwrap(),
+        NonZero::new(sample_rate).unwrap(),
+        samples,
+    )
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+#[test]
+fn same_rate_passes_through_untouched() {
+    let input: Vec<f32> = sine(44100, 2, 440.0, 0.1).collect();
+    for quality in QUALITIES {
+        let output: Vec<f32> = Resampler::new(
+            sine(44100, 2, 440.0, 0.1),
+            NonZero::new(44100).unwrap(),
+            quality,
+        )
+        .collect();
+        assert_eq!(output, input);
+    }
+}
+
+#[test]
+fn output_length_follows_rate_ratio() {
+    for quality in QUALITIES {
+        for (from, to) in [(44100, 48000), (48000, 44100), (96000, 44100)] {
+            let output: Vec<f32> = Resampler::new(
+                sine(from, 2, 1000.0, 1.0),
+                NonZero::new(to).unwrap(),
+                quality,
+            )
+            .collect();
+            let frames = output.len() / 2;
+            assert_eq!(output.len() % 2, 0);
+            assert!(
+                frames.abs_diff(to as usize) <= 2,
+                "{from} -> {to}: {frames} frames"
+            );
+        }
+    }
+}
+
+#[test]
+fn tone_level_and_pitch_survive_conversion() {
+    for quality in QUALITIES {
+        let output: Vec<f32> = Resampler::new(
+            sine(44100, 1, 1000.0, 1.0),
+            NonZero::new(48000).unwrap(),
+            quality,
+        )
+        .collect();
+        let steady = &output[2000..46000];
+
+        let expected_rms = 0.5 / 2f32.sqrt();
+        assert!((rms(steady) - expected_rms).abs() < 0.01, "{quality:?}");
+
+        let crossings = steady
+            .windows(2)
+            .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+            .count();
+        let expected = 2.0 * 1000.0 * steady.len() as f32 / 48000.0;
+        assert!(
+            (crossings as f32 - expected).abs() <= 2.0,
+            "{quality:?}: {crossings}"
+        );
+    }
+}
+
+#[test]
+fn high_quality_keeps_treble_that_cheaper_modes_dull() {
+    let level = |quality| {
+        let output: Vec<f32> = Resampler::new(
+            sine(44100, 1, 15000.0, 1.0),
+            NonZero::new(48000).unwrap(),
+            quality,
+        )
+        .collect();
+        rms(&output[2000..46000])
+    };
+    let expected_rms = 0.5 / 2f32.sqrt();
+    assert!((level(ResamplerQuality::High) - expected_rms).abs() < 0.01);
+    assert!(level(ResamplerQuality::Fast) < level(ResamplerQuality::High));
+}
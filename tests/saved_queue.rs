@@ -0,0 +1,36 @@
+use vleer::data::models::{Cuid, SavedQueue, SavedQueueItem};
+
+mod common;
+
+fn item(file_path: Option<&str>) -> SavedQueueItem {
+    SavedQueueItem {
+        song_id: Cuid::new(),
+        file_path: file_path.map(str::to_string),
+    }
+}
+
+#[test]
+fn saved_queue_round_trips_and_replaces_previous() {
+    let (db, path) = common::temp_db("saved_queue_round_trip");
+    assert_eq!(db.load_queue().unwrap(), None);
+
+    let first = SavedQueue {
+        items: vec![item(None), item(None), item(None)],
+        current_index: Some(2),
+        position_secs: 12.0,
+    };
+    db.save_queue(&first).unwrap();
+
+    let second = SavedQueue {
+        items: vec![item(None), item(Some("/tmp/outside.flac"))],
+        current_index: Some(1),
+        position_secs: 42.5,
+    };
+    db.save_queue(&second).unwrap();
+    assert_eq!(db.load_queue().unwrap(), Some(second));
+
+    db.save_queue(&SavedQueue::default()).unwrap();
+    assert_eq!(db.load_queue().unwrap(), None);
+
+    common::cleanup(&path);
+}
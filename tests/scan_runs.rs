@@ -0,0 +1,29 @@
+mod common;
+
+#[test]
+fn scan_runs_are_listed_newest_first_and_trimmed() {
+    let (db, path) = common::temp_db("scan_runs_history");
+    assert!(db.get_scan_runs(10).unwrap().is_empty());
+
+    for i in 0..60 {
+        db.record_scan_run(100, i, 0, 0, 0).unwrap();
+    }
+    db.record_scan_run(90, 0, 2, 10, 3).unwrap();
+
+    let runs = db.get_scan_runs(100).unwrap();
+    assert_eq!(runs.len(), 50);
+    assert_eq!(
+        (
+            runs[0].scanned,
+            runs[0].updated,
+            runs[0].removed,
+            runs[0].missing
+        ),
+        (90, 2, 10, 3)
+    );
+    assert_eq!(runs[1].added, 59);
+    assert!(!runs[0].finished_at.is_empty());
+    assert_eq!(db.get_scan_runs(3).unwrap().len(), 3);
+
+    common::cleanup(&path);
+}
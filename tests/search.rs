@@ -0,0 +1,63 @@
+use vleer::data::db::repo::Database;
+use vleer::data::models::SongUpsert;
+
+mod common;
+
+fn add_song(db: &Database, title: &str, file_path: &str) {
+    db.upsert_song(&SongUpsert {
+        title,
+        artists: &["Someone"],
+        file_path,
+        duration: 180,
+        file_size: 1_000,
+        ..Default::default()
+    })
+    .unwrap();
+}
+
+#[test]
+fn path_matches_rank_below_tag_matches() {
+    let (db, path) = common::temp_db("search_path_rank");
+    add_song(&db, "Track 01", "/music/Bootlegs 1997/track01.mp3");
+    add_song(&db, "Bootlegs", "/music/misc/b.mp3");
+    add_song(&db, "Unrelated", "/music/misc/c.mp3");
+
+    let names: Vec<String> = db
+        .search_library("bootlegs", 10)
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.item_type == "Song")
+        .map(|r| r.name)
+        .collect();
+    assert_eq!(names, ["Bootlegs", "Track 01"]);
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn grouped_search_keeps_each_type_visible() {
+    let (db, path) = common::temp_db("search_grouped");
+    for i in 1..=5 {
+        add_song(&db, &format!("Rain {i}"), &format!("/music/rain{i}.mp3"));
+    }
+    db.upsert_song(&SongUpsert {
+        title: "Other",
+        artists: &["Rainbow"],
+        file_path: "/music/other.mp3",
+        duration: 180,
+        file_size: 1_000,
+        ..Default::default()
+    })
+    .unwrap();
+
+    let results = db.search_library_grouped("rain", 2).unwrap();
+    let songs = results.iter().filter(|r| r.item_type == "Song").count();
+    assert_eq!(songs, 2);
+    assert!(
+        results
+            .iter()
+            .any(|r| r.item_type == "Artist" && r.name == "Rainbow")
+    );
+
+    common::cleanup(&path);
+}
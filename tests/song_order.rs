@@ -0,0 +1,192 @@
+use vleer::data::{
+    db::repo::Database,
+    models::{Cuid, EventType, SongSort, SongUpsert},
+};
+
+mod common;
+
+fn visible_ids(db: &Database, query: Option<&str>, sort: SongSort, ascending: bool) -> Vec<Cuid> {
+    db.get_songs(query, sort, ascending, 0, i64::MAX)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect()
+}
+
+#[test]
+fn queue_from_offset_matches_visible_order() {
+    let (db, path) = common::temp_db("order_queue");
+
+    let genres = ["Rock", "Jazz", "Ambient"];
+    let mut album_ids = Vec::new();
+    for i in 0..4 {
+        let artist = format!("Artist {i}");
+        let id = db
+            .upsert_album(&format!("Album {}", 4 - i), &[artist.as_str()], None)
+            .unwrap();
+        album_ids.push(id);
+    }
+
+    for i in 0..40 {
+        let artist = format!("Artist {}", i % 4);
+        db.upsert_song(&SongUpsert {
+            title: &format!("Song {}", (i * 7) % 40),
+            artists: &[artist.as_str()],
+            album_id: Some(&album_ids[i % album_ids.len()]),
+            file_path: &format!("/music/order_{i}.flac"),
+            duration: 120 + ((i as i32 * 13) % 90),
+            track_number: Some((i as i32 % 10) + 1),
+            year: Some(2000 + i as i32 % 5),
+            genres: &[genres[i % genres.len()]],
+            file_size: 1_000,
+            file_modified: i as i64,
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    let sorts = [
+        SongSort::Default,
+        SongSort::Title,
+        SongSort::Album,
+        SongSort::Duration,
+        SongSort::Genre,
+        SongSort::LastPlayed,
+    ];
+
+    for query in ["", "Song", "Artist 2"] {
+        for sort in sorts {
+            for ascending in [true, false] {
+                let visible = visible_ids(&db, Some(query), sort, ascending);
+                assert!(!visible.is_empty(), "no rows for {query:?} {sort:?}");
+
+                for clicked in [0, visible.len() / 2, visible.len() - 1] {
+                    let queued = db
+                        .get_song_ids_from_offset(query, sort, ascending, (clicked + 1) as i64)
+                        .unwrap();
+                    assert_eq!(
+                        queued,
+                        visible[clicked + 1..],
+                        "queue order differs for {query:?} {sort:?} asc={ascending} at {clicked}"
+                    );
+                }
+            }
+        }
+    }
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn last_played_sorts_recent_first_and_never_played_last() {
+    let (db, path) = common::temp_db("order_last_played");
+    for name in ["old", "recent", "never"] {
+        db.upsert_song(&SongUpsert {
+            title: name,
+            artists: &["Artist"],
+            file_path: &format!("/music/{name}.flac"),
+            duration: 180,
+            file_size: 1_000,
+            ..Default::default()
+        })
+        .unwrap();
+    }
+    let id = |name: &str| {
+        db.get_song_by_path(&format!("/music/{name}.flac"))
+            .unwrap()
+            .unwrap()
+            .id
+    };
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    for (name, timestamp) in [
+        ("old", "2025-01-01 10:00:00"),
+        ("recent", "2026-01-01 10:00:00"),
+        ("old", "2025-06-01 10:00:00"),
+    ] {
+        let context = db.insert_event_context(Some(&id(name)), None).unwrap();
+        let event = db.insert_event(EventType::Play, Some(&context)).unwrap();
+        conn.execute(
+            "UPDATE events SET timestamp = ?1 WHERE id = ?2",
+            rusqlite::params![timestamp, event],
+        )
+        .unwrap();
+    }
+
+    let newest_first = visible_ids(&db, None, SongSort::LastPlayed, false);
+    assert_eq!(newest_first, [id("recent"), id("old"), id("never")]);
+    let oldest_first = visible_ids(&db, None, SongSort::LastPlayed, true);
+    assert_eq!(oldest_first, [id("never"), id("old"), id("recent")]);
+
+    let songs = db
+        .get_songs(None, SongSort::LastPlayed, false, 0, 10)
+        .unwrap();
+    assert_eq!(songs[1].last_played.as_deref(), Some("2025-06-01 10:00:00"));
+    assert_eq!(songs[2].last_played, None);
+
+    let by_title = db.get_songs(None, SongSort::Title, true, 0, 10).unwrap();
+    assert!(by_title.iter().all(|song| song.last_played.is_none()));
+    let by_title = db
+        .get_songs_with_last_played(None, SongSort::Title, true, 0, 10)
+        .unwrap();
+    assert_eq!(
+        by_title[1].last_played.as_deref(),
+        Some("2025-06-01 10:00:00")
+    );
+
+    let neglected = db
+        .get_song_ids_not_played_since("2025-07-01 00:00:00")
+        .unwrap();
+    assert_eq!(neglected, [id("never"), id("old")]);
+
+    common::cleanup(&path);
+}
+
+#[test]
+fn album_plays_disc_then_track_then_untracked_by_filename() {
+    let (db, path) = common::temp_db("order_album");
+    let album_id = db.upsert_album("Double Album", &["Artist"], None).unwrap();
+
+    // (title, file, track, disc), inserted out of order on purpose.
+    let songs = [
+        ("Disc 2 Track 1", "/music/2cd/d2t1.flac", Some(1), Some(2)),
+        ("Bonus B", "/music/2cd/zz_bonus.flac", None, None),
+        ("Disc 1 Track 2", "/music/2cd/d1t2.flac", Some(2), Some(1)),
+        ("Disc 2 Track 2", "/music/2cd/d2t2.flac", Some(2), Some(2)),
+        ("Bonus A", "/music/2cd/aa_bonus.flac", None, Some(2)),
+        ("Disc 1 Track 1", "/music/2cd/d1t1.flac", Some(1), None),
+    ];
+    for (title, file, track, disc) in songs {
+        db.upsert_song(&SongUpsert {
+            title,
+            artists: &["Artist"],
+            album_id: Some(&album_id),
+            file_path: file,
+            duration: 200,
+            track_number: track,
+            disc_number: disc,
+            file_size: 1_000,
+            ..Default::default()
+        })
+        .unwrap();
+    }
+
+    let titles: Vec<String> = db
+        .get_album_songs(&album_id)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.title)
+        .collect();
+    assert_eq!(
+        titles,
+        [
+            "Disc 1 Track 1",
+            "Disc 1 Track 2",
+            "Disc 2 Track 1",
+            "Disc 2 Track 2",
+            "Bonus A",
+            "Bonus B",
+        ]
+    );
+
+    common::cleanup(&path);
+}